@@ -205,6 +205,7 @@ async fn benchmark_table(client: &Arc<ClientHandle>, prefix: u64, client_num: u6
     let res = client
         .create_table(CreateTableReq {
             if_not_exists: true,
+            table_id: None,
             name_ident: tb_name_ident(),
             table_meta: Default::default(),
         })
@@ -245,6 +246,7 @@ async fn benchmark_table(client: &Arc<ClientHandle>, prefix: u64, client_num: u6
     let res = client
         .create_table(CreateTableReq {
             if_not_exists: true,
+            table_id: None,
             name_ident: tb_name_ident(),
             table_meta: Default::default(),
         })
@@ -12,6 +12,9 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::sync::atomic::AtomicUsize;
+use std::sync::atomic::Ordering;
+use std::sync::Arc;
 use std::time::Duration;
 
 use async_trait::async_trait;
@@ -54,6 +57,34 @@ async fn test_pool() -> anyhow::Result<()> {
     Ok(())
 }
 
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn test_pool_concurrent_get() -> anyhow::Result<()> {
+    let p = Arc::new(Pool::new(
+        CountingMgr {
+            built: AtomicUsize::new(0),
+        },
+        Duration::from_millis(10),
+    ));
+
+    let mut handles = vec![];
+    for i in 0..100 {
+        let p = p.clone();
+        handles.push(tokio::spawn(async move { p.get(&(i % 2)).await }));
+    }
+    for h in handles {
+        h.await??;
+    }
+
+    assert_eq!(
+        2,
+        p.item_manager().built.load(Ordering::Relaxed),
+        "concurrent get() share one item per key"
+    );
+    assert_eq!(2, p.items.lock().unwrap().len());
+
+    Ok(())
+}
+
 #[derive(Debug)]
 struct FooMgr {}
 
@@ -85,3 +116,27 @@ impl ItemManager for FooMgr {
         }
     }
 }
+
+/// Counts the items it builds.
+#[derive(Debug)]
+struct CountingMgr {
+    built: AtomicUsize,
+}
+
+#[async_trait]
+impl ItemManager for CountingMgr {
+    type Key = u32;
+    type Item = u32;
+    type Error = anyhow::Error;
+
+    async fn build(&self, key: &Self::Key) -> Result<Self::Item, Self::Error> {
+        // Building a connection takes a while.
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        self.built.fetch_add(1, Ordering::Relaxed);
+        Ok(*key)
+    }
+
+    async fn check(&self, item: Self::Item) -> Result<Self::Item, Self::Error> {
+        Ok(item)
+    }
+}
@@ -263,6 +263,10 @@ build_exceptions! {
     CatalogNotFound(2320),
     /// data mask error codes
     DatamaskAlreadyExists(2321),
+    /// `ReadOnly` should be raised when a catalog is set to read only and a mutation is requested.
+    ReadOnly(2322),
+    /// `TableIdInUse` should be raised when creating a table with a reserved id that is used by another table.
+    TableIdInUse(2323),
 
 
     // Cluster error codes.
@@ -341,6 +345,7 @@ build_exceptions! {
     IllegalTenantQuotaFormat(2901),
     TenantQuotaUnknown(2902),
     TenantQuotaExceeded(2903),
+    DatabaseQuotaExceeded(2904),
 
 }
 
@@ -14,16 +14,60 @@
 
 use std::time::Duration;
 
+/// TLS config for connecting to a gRPC server.
+///
+/// A channel built with it only speaks TLS: it never falls back to a plaintext connection.
 #[derive(Clone, Debug, Default)]
 pub struct RpcClientTlsConfig {
+    /// Path to the PEM encoded CA certificate to verify the server certificate with.
     pub rpc_tls_server_root_ca_cert: String,
+
+    /// The name to verify the server certificate against, instead of the host of the address.
     pub domain_name: String,
+
+    /// Path to the PEM encoded client certificate for mutual TLS.
+    ///
+    /// Mutual TLS is enabled only when both of it and `rpc_tls_client_key` are not empty.
+    pub rpc_tls_client_cert: String,
+
+    /// Path to the PEM encoded private key of `rpc_tls_client_cert`.
+    pub rpc_tls_client_key: String,
 }
 
 impl RpcClientTlsConfig {
     pub fn enabled(&self) -> bool {
         !self.rpc_tls_server_root_ca_cert.is_empty() && !self.domain_name.is_empty()
     }
+
+    /// Whether the client presents a certificate to the server.
+    pub fn mtls_enabled(&self) -> bool {
+        !self.rpc_tls_client_cert.is_empty() && !self.rpc_tls_client_key.is_empty()
+    }
+
+    /// Set the client certificate and private key for mutual TLS.
+    pub fn with_client_identity(mut self, cert: impl ToString, key: impl ToString) -> Self {
+        self.rpc_tls_client_cert = cert.to_string();
+        self.rpc_tls_client_key = key.to_string();
+        self
+    }
+}
+
+/// HTTP/2 keep-alive of a client channel.
+///
+/// A PING frame is sent every `interval`, even if there is no in-flight request,
+/// and the connection is closed if the PING is not acknowledged within `timeout`.
+/// It keeps an idle connection from being dropped by a load balancer or a NAT,
+/// and detects a dead connection before a request is sent on it.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct KeepAliveConf {
+    pub interval: Duration,
+    pub timeout: Duration,
+}
+
+impl KeepAliveConf {
+    pub fn new(interval: Duration, timeout: Duration) -> Self {
+        Self { interval, timeout }
+    }
 }
 
 #[derive(Clone, Debug, Default)]
@@ -39,6 +83,8 @@ pub struct RpcClientConf {
     /// None disables auto-sync.
     pub auto_sync_interval: Option<Duration>,
     pub unhealthy_endpoint_evict_time: Duration,
+    /// None disables keep-alive.
+    pub keep_alive: Option<KeepAliveConf>,
 }
 
 impl RpcClientConf {
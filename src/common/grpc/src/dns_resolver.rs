@@ -38,8 +38,10 @@ use tonic::transport::Certificate;
 use tonic::transport::Channel;
 use tonic::transport::ClientTlsConfig;
 use tonic::transport::Endpoint;
+use tonic::transport::Identity;
 use trust_dns_resolver::TokioAsyncResolver;
 
+use crate::KeepAliveConf;
 use crate::RpcClientTlsConfig;
 
 pub struct DNSResolver {
@@ -148,11 +150,30 @@ impl ConnectionFactory {
         timeout: Option<Duration>,
         rpc_client_config: Option<RpcClientTlsConfig>,
     ) -> std::result::Result<Channel, GrpcConnectionError> {
-        let endpoint = Self::create_rpc_endpoint(addr, timeout, rpc_client_config)?;
+        Self::create_rpc_channel_with_keep_alive(addr, timeout, rpc_client_config, None).await
+    }
+
+    /// Create a channel like [`Self::create_rpc_channel`], and keep the connection alive with `keep_alive`.
+    ///
+    /// With `keep_alive`, both HTTP/2 PING and TCP keep-alive are enabled with the same interval.
+    pub async fn create_rpc_channel_with_keep_alive(
+        addr: impl ToString,
+        timeout: Option<Duration>,
+        rpc_client_config: Option<RpcClientTlsConfig>,
+        keep_alive: Option<KeepAliveConf>,
+    ) -> std::result::Result<Channel, GrpcConnectionError> {
+        let mut endpoint = Self::create_rpc_endpoint(addr, timeout, rpc_client_config)?;
+
+        if let Some(keep_alive) = keep_alive {
+            endpoint = endpoint
+                .http2_keep_alive_interval(keep_alive.interval)
+                .keep_alive_timeout(keep_alive.timeout)
+                .keep_alive_while_idle(true);
+        }
 
         let mut inner_connector = HttpConnector::new_with_resolver(DNSService);
         inner_connector.set_nodelay(true);
-        inner_connector.set_keepalive(None);
+        inner_connector.set_keepalive(keep_alive.map(|k| k.interval));
         inner_connector.enforce_http(false);
 
         // check connection immediately
@@ -213,9 +234,16 @@ impl ConnectionFactory {
         let server_root_ca_cert = std::fs::read(conf.rpc_tls_server_root_ca_cert.as_str())?;
         let server_root_ca_cert = Certificate::from_pem(server_root_ca_cert);
 
-        let tls = ClientTlsConfig::new()
+        let mut tls = ClientTlsConfig::new()
             .domain_name(conf.domain_name.to_string())
             .ca_certificate(server_root_ca_cert);
+
+        if conf.mtls_enabled() {
+            let cert = std::fs::read(conf.rpc_tls_client_cert.as_str())?;
+            let key = std::fs::read(conf.rpc_tls_client_key.as_str())?;
+            tls = tls.identity(Identity::from_pem(cert, key));
+        }
+
         Ok(tls)
     }
 }
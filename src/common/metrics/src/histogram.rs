@@ -32,6 +32,10 @@ pub static BUCKET_MILLISECONDS: [f64; 15] = [
     300000.0, 600000.0, 1800000.0,
 ];
 
+pub static BUCKET_BYTES: [f64; 10] = [
+    256.0, 1024.0, 4096.0, 16384.0, 65536.0, 262144.0, 1048576.0, 4194304.0, 16777216.0, 67108864.0,
+];
+
 /// Histogram is a port of prometheus-client's Histogram. The only difference is that
 /// we can reset the histogram.
 #[derive(Debug)]
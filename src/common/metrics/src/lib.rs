@@ -43,6 +43,7 @@ pub use registry::register_gauge;
 pub use registry::register_gauge_family;
 pub use registry::register_histogram_family_in_milliseconds;
 pub use registry::register_histogram_family_in_seconds;
+pub use registry::register_histogram_in_bytes;
 pub use registry::register_histogram_in_milliseconds;
 pub use registry::register_histogram_in_seconds;
 pub use registry::render_prometheus_metrics;
@@ -27,6 +27,7 @@ use prometheus_client::registry::Registry;
 
 use crate::counter::Counter;
 use crate::histogram::Histogram;
+use crate::histogram::BUCKET_BYTES;
 use crate::histogram::BUCKET_MILLISECONDS;
 use crate::histogram::BUCKET_SECONDS;
 
@@ -148,6 +149,10 @@ pub fn register_histogram_in_milliseconds(name: &str) -> Histogram {
     register_histogram(name, BUCKET_MILLISECONDS.iter().copied())
 }
 
+pub fn register_histogram_in_bytes(name: &str) -> Histogram {
+    register_histogram(name, BUCKET_BYTES.iter().copied())
+}
+
 pub fn register_histogram_in_seconds(name: &str) -> Histogram {
     register_histogram(name, BUCKET_SECONDS.iter().copied())
 }
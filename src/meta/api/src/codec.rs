@@ -0,0 +1,155 @@
+// Copyright 2021 Datafuse Labs
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Pluggable encoding of the metadata values stored in the meta-service.
+//!
+//! A value is stored in one of the formats:
+//! - Protobuf: the message built by `FromToProto`, without a header.
+//!   This is the format meta-api reads and writes, thus the existing values are all in it.
+//! - JSON: a [`HEADER_JSON`] byte followed by the JSON of the value, e.g., written by an external tool.
+//!
+//! A protobuf message never starts with a byte in `0x00..=0x07`, which is a tag of the reserved field number 0.
+//! Thus a byte in this range identifies a non-protobuf format, and decoding detects the format of every value,
+//! so that a store with values in mixed formats can be read during a migration.
+//!
+//! SchemaApi reads and writes only protobuf values.
+//! Thus JSON is only decoded, and the catalogs write with [`ProtobufCodec`].
+
+use databend_common_meta_types::anyerror::AnyError;
+use databend_common_meta_types::InvalidReply;
+use databend_common_meta_types::MetaNetworkError;
+use databend_common_proto_conv::FromToProto;
+use serde::de::DeserializeOwned;
+
+use crate::deserialize_struct;
+use crate::serialize_struct;
+
+/// The first byte of a value encoded in JSON.
+pub const HEADER_JSON: u8 = 0x01;
+
+/// The bytes that a protobuf message never starts with, reserved for format headers.
+const RESERVED_HEADERS: std::ops::RangeInclusive<u8> = 0x00..=0x07;
+
+/// Encode and decode a metadata value.
+///
+/// `decode()` accepts a value in any format, whichever format `encode()` writes.
+pub trait MetaCodec {
+    fn encode<T>(&self, value: &T) -> Result<Vec<u8>, MetaNetworkError>
+    where
+        T: FromToProto + 'static,
+        T::PB: databend_common_protos::prost::Message;
+
+    fn decode<T>(&self, buf: &[u8]) -> Result<T, MetaNetworkError>
+    where
+        T: FromToProto + DeserializeOwned,
+        T::PB: databend_common_protos::prost::Message + Default,
+    {
+        decode_any(buf)
+    }
+}
+
+/// Encode values in protobuf, the format every reader of the meta-service understands.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct ProtobufCodec;
+
+impl MetaCodec for ProtobufCodec {
+    fn encode<T>(&self, value: &T) -> Result<Vec<u8>, MetaNetworkError>
+    where
+        T: FromToProto + 'static,
+        T::PB: databend_common_protos::prost::Message,
+    {
+        serialize_struct(value)
+    }
+}
+
+/// Decode a value in any format, detected by its first byte.
+pub fn decode_any<T>(buf: &[u8]) -> Result<T, MetaNetworkError>
+where
+    T: FromToProto + DeserializeOwned,
+    T::PB: databend_common_protos::prost::Message + Default,
+{
+    match buf.first() {
+        Some(&HEADER_JSON) => serde_json::from_slice(&buf[1..]).map_err(|e| {
+            let inv = InvalidReply::new("decode json value", &e);
+            MetaNetworkError::InvalidReply(inv)
+        }),
+        Some(h) if RESERVED_HEADERS.contains(h) => {
+            let e = AnyError::error(format!("unknown value format header: {:#04x}", h));
+            Err(MetaNetworkError::InvalidReply(InvalidReply::new(
+                "decode value",
+                &e,
+            )))
+        }
+        // An empty buffer is an empty protobuf message.
+        _ => deserialize_struct(buf),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use databend_common_meta_app::schema::DBIdTableName;
+
+    use crate::codec::decode_any;
+    use crate::codec::MetaCodec;
+    use crate::codec::ProtobufCodec;
+    use crate::codec::HEADER_JSON;
+    use crate::serialize_struct;
+
+    fn name() -> DBIdTableName {
+        DBIdTableName {
+            db_id: 3,
+            table_name: "t1".to_string(),
+        }
+    }
+
+    fn json_value() -> Vec<u8> {
+        let mut buf = vec![HEADER_JSON];
+        buf.extend_from_slice(br#"{"db_id":3,"table_name":"t1"}"#);
+        buf
+    }
+
+    #[test]
+    fn test_protobuf_codec() -> anyhow::Result<()> {
+        let buf = ProtobufCodec.encode(&name())?;
+
+        // Compatible with the values written by meta-api.
+        assert_eq!(serialize_struct(&name())?, buf);
+        assert!(buf[0] > 0x07);
+
+        assert_eq!(name(), ProtobufCodec.decode::<DBIdTableName>(&buf)?);
+        Ok(())
+    }
+
+    #[test]
+    fn test_decode_json() -> anyhow::Result<()> {
+        assert_eq!(
+            name(),
+            ProtobufCodec.decode::<DBIdTableName>(&json_value())?
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_decode_detects_format() -> anyhow::Result<()> {
+        let values = [ProtobufCodec.encode(&name())?, json_value()];
+        for buf in values {
+            assert_eq!(name(), decode_any::<DBIdTableName>(&buf)?);
+        }
+
+        // A reserved header of an unknown format
+        let res = decode_any::<DBIdTableName>(&[0x02, b'{', b'}']);
+        assert!(res.is_err());
+        Ok(())
+    }
+}
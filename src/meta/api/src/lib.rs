@@ -23,6 +23,7 @@ mod background_api;
 mod background_api_impl;
 mod background_api_keys;
 mod background_api_test_suite;
+pub mod codec;
 mod data_mask_api;
 mod data_mask_api_impl;
 mod data_mask_api_keys;
@@ -56,6 +57,7 @@ pub use util::assert_table_exist;
 pub use util::convert_share_meta_to_spec;
 pub use util::db_has_to_exist;
 pub use util::deserialize_struct;
+pub use util::deserialize_u64;
 pub use util::fetch_id;
 pub use util::get_object_shared_by_share_ids;
 pub use util::get_pb_value;
@@ -71,6 +73,7 @@ pub use util::is_db_need_to_be_remove;
 pub use util::list_keys;
 pub use util::list_u64_value;
 pub use util::remove_db_from_share;
+pub use util::reserve_ids;
 pub use util::send_txn;
 pub use util::serialize_struct;
 pub use util::serialize_u64;
@@ -79,3 +82,4 @@ pub use util::txn_op_del;
 pub use util::txn_op_put;
 pub use util::txn_op_put_with_expire;
 pub use util::DEFAULT_MGET_SIZE;
+pub use util::MAX_RESERVE_IDS;
@@ -12,6 +12,7 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::ops::Range;
 use std::sync::Arc;
 
 use databend_common_meta_app::schema::CatalogInfo;
@@ -66,10 +67,13 @@ use databend_common_meta_app::schema::ListTableReq;
 use databend_common_meta_app::schema::ListVirtualColumnsReq;
 use databend_common_meta_app::schema::LockInfo;
 use databend_common_meta_app::schema::LockMeta;
+use databend_common_meta_app::schema::MGetTableReq;
 use databend_common_meta_app::schema::RenameDatabaseReply;
 use databend_common_meta_app::schema::RenameDatabaseReq;
 use databend_common_meta_app::schema::RenameTableReply;
 use databend_common_meta_app::schema::RenameTableReq;
+use databend_common_meta_app::schema::SetDatabaseQuotaReply;
+use databend_common_meta_app::schema::SetDatabaseQuotaReq;
 use databend_common_meta_app::schema::SetLVTReply;
 use databend_common_meta_app::schema::SetLVTReq;
 use databend_common_meta_app::schema::SetTableColumnMaskPolicyReply;
@@ -94,6 +98,7 @@ use databend_common_meta_app::schema::UpsertTableOptionReply;
 use databend_common_meta_app::schema::UpsertTableOptionReq;
 use databend_common_meta_app::schema::VirtualColumnMeta;
 use databend_common_meta_types::MetaId;
+use databend_common_meta_types::TxnRequest;
 
 use crate::kv_app_error::KVAppError;
 
@@ -131,6 +136,15 @@ pub trait SchemaApi: Send + Sync {
         req: ListDatabaseReq,
     ) -> Result<Vec<Arc<DatabaseInfo>>, KVAppError>;
 
+    /// Set or remove the max number of tables a database can hold.
+    ///
+    /// An existing database with more tables than the new limit is left as is,
+    /// only creating a new table is rejected.
+    async fn set_database_quota(
+        &self,
+        req: SetDatabaseQuotaReq,
+    ) -> Result<SetDatabaseQuotaReply, KVAppError>;
+
     // index
 
     async fn create_index(&self, req: CreateIndexReq) -> Result<CreateIndexReply, KVAppError>;
@@ -182,6 +196,13 @@ pub trait SchemaApi: Send + Sync {
 
     async fn create_table(&self, req: CreateTableReq) -> Result<CreateTableReply, KVAppError>;
 
+    /// Reserve `n` contiguous table ids by updating the id generator once, to create tables with by `CreateTableReq.table_id`.
+    ///
+    /// The ids are never allocated to another table, whether they are used or not.
+    /// `None` if the meta-service does not support it, see [`reserve_ids`](crate::reserve_ids).
+    /// `n` must be in `1..=MAX_RESERVE_IDS`.
+    async fn reserve_table_ids(&self, n: u64) -> Result<Option<Range<MetaId>>, KVAppError>;
+
     /// List all tables belonging to every db and every tenant.
     ///
     /// I.e.: all tables found in key-space: `__fd_table_by_id/`.
@@ -192,6 +213,14 @@ pub trait SchemaApi: Send + Sync {
 
     async fn drop_table_by_id(&self, req: DropTableByIdReq) -> Result<DropTableReply, KVAppError>;
 
+    /// Build the transaction that `drop_table_by_id` would submit, without submitting it.
+    ///
+    /// It returns `None` if there is nothing to drop, i.e., the table does not exist and `req.if_exists` is true.
+    async fn plan_drop_table_by_id(
+        &self,
+        req: DropTableByIdReq,
+    ) -> Result<Option<TxnRequest>, KVAppError>;
+
     async fn undrop_table(&self, req: UndropTableReq) -> Result<UndropTableReply, KVAppError>;
 
     async fn rename_table(&self, req: RenameTableReq) -> Result<RenameTableReply, KVAppError>;
@@ -203,6 +232,36 @@ pub trait SchemaApi: Send + Sync {
 
     async fn list_tables(&self, req: ListTableReq) -> Result<Vec<Arc<TableInfo>>, KVAppError>;
 
+    /// List at most `limit` tables in a db whose names are after `after`, in the same order as `list_tables`.
+    ///
+    /// It returns the tables and the cursor to pass as `after` to get the next page,
+    /// which is `None` if there are no more tables.
+    /// A page may have less than `limit` tables even if it is not the last one, if a table is dropped while listing.
+    ///
+    /// A db created from a share is not paged: all of its tables are returned in the first page.
+    async fn list_tables_paged(
+        &self,
+        req: ListTableReq,
+        after: Option<String>,
+        limit: usize,
+    ) -> Result<(Vec<Arc<TableInfo>>, Option<String>), KVAppError>;
+
+    /// List the tables in a db whose names start with `name_prefix`, sorted by name.
+    ///
+    /// Only the table names with the prefix are scanned, instead of listing all the tables and filtering them.
+    /// An empty `name_prefix` lists all tables.
+    async fn list_tables_by_prefix(
+        &self,
+        req: ListTableReq,
+        name_prefix: &str,
+    ) -> Result<Vec<Arc<TableInfo>>, KVAppError>;
+
+    /// Get the tables of the given names in a database, the same as calling `get_table` for every name.
+    ///
+    /// The table ids and then the table metas are fetched with `mget`, instead of one request per table.
+    /// A table that does not exist is skipped.
+    async fn mget_tables(&self, req: MGetTableReq) -> Result<Vec<Arc<TableInfo>>, KVAppError>;
+
     async fn get_table_by_id(
         &self,
         table_id: MetaId,
@@ -17,6 +17,7 @@ use std::collections::BTreeMap;
 use std::collections::HashMap;
 use std::collections::HashSet;
 use std::fmt::Display;
+use std::ops::Range;
 use std::sync::Arc;
 
 use chrono::DateTime;
@@ -27,6 +28,7 @@ use databend_common_meta_app::app_error::CreateDatabaseWithDropTime;
 use databend_common_meta_app::app_error::CreateIndexWithDropTime;
 use databend_common_meta_app::app_error::CreateTableWithDropTime;
 use databend_common_meta_app::app_error::DatabaseAlreadyExists;
+use databend_common_meta_app::app_error::DatabaseQuotaExceeded;
 use databend_common_meta_app::app_error::DropDbWithDropTime;
 use databend_common_meta_app::app_error::DropIndexWithDropTime;
 use databend_common_meta_app::app_error::DropTableWithDropTime;
@@ -37,6 +39,7 @@ use databend_common_meta_app::app_error::ShareHasNoGrantedPrivilege;
 use databend_common_meta_app::app_error::StreamAlreadyExists;
 use databend_common_meta_app::app_error::StreamVersionMismatched;
 use databend_common_meta_app::app_error::TableAlreadyExists;
+use databend_common_meta_app::app_error::TableIdInUse;
 use databend_common_meta_app::app_error::TableLockExpired;
 use databend_common_meta_app::app_error::TableVersionMismatched;
 use databend_common_meta_app::app_error::UndropDbHasNoHistory;
@@ -129,10 +132,13 @@ use databend_common_meta_app::schema::ListTableReq;
 use databend_common_meta_app::schema::ListVirtualColumnsReq;
 use databend_common_meta_app::schema::LockInfo;
 use databend_common_meta_app::schema::LockMeta;
+use databend_common_meta_app::schema::MGetTableReq;
 use databend_common_meta_app::schema::RenameDatabaseReply;
 use databend_common_meta_app::schema::RenameDatabaseReq;
 use databend_common_meta_app::schema::RenameTableReply;
 use databend_common_meta_app::schema::RenameTableReq;
+use databend_common_meta_app::schema::SetDatabaseQuotaReply;
+use databend_common_meta_app::schema::SetDatabaseQuotaReq;
 use databend_common_meta_app::schema::SetLVTReply;
 use databend_common_meta_app::schema::SetLVTReq;
 use databend_common_meta_app::schema::SetTableColumnMaskPolicyAction;
@@ -168,6 +174,7 @@ use databend_common_meta_app::schema::VirtualColumnMeta;
 use databend_common_meta_app::schema::VirtualColumnNameIdent;
 use databend_common_meta_app::share::ShareGrantObject;
 use databend_common_meta_app::share::ShareNameIdent;
+use databend_common_meta_app::share::ShareSpec;
 use databend_common_meta_app::share::ShareTableInfoMap;
 use databend_common_meta_kvapi::kvapi;
 use databend_common_meta_kvapi::kvapi::Key;
@@ -215,6 +222,7 @@ use crate::kv_app_error::KVAppError;
 use crate::list_keys;
 use crate::list_u64_value;
 use crate::remove_db_from_share;
+use crate::reserve_ids;
 use crate::send_txn;
 use crate::serialize_struct;
 use crate::serialize_u64;
@@ -230,6 +238,7 @@ use crate::util::deserialize_u64;
 use crate::util::get_index_metas_by_ids;
 use crate::util::get_table_by_id_or_err;
 use crate::util::get_table_names_by_ids;
+use crate::util::get_tableinfos_by_ids;
 use crate::util::get_virtual_column_by_id_or_err;
 use crate::util::list_tables_from_share_db;
 use crate::util::list_tables_from_unshare_db;
@@ -372,7 +381,10 @@ impl<KV: kvapi::KVApi<Error = MetaError> + ?Sized> SchemaApi for KV {
                 Err(e) => {
                     if let KVAppError::AppError(AppError::UnknownDatabase(_)) = e {
                         if req.if_exists {
-                            return Ok(DropDatabaseReply { spec_vec: None });
+                            return Ok(DropDatabaseReply {
+                                spec_vec: None,
+                                db_id: None,
+                            });
                         }
                     }
 
@@ -534,6 +546,7 @@ impl<KV: kvapi::KVApi<Error = MetaError> + ?Sized> SchemaApi for KV {
                     } else {
                         Some(spec_vec)
                     },
+                    db_id: Some(db_id),
                 });
             }
         }
@@ -778,6 +791,49 @@ impl<KV: kvapi::KVApi<Error = MetaError> + ?Sized> SchemaApi for KV {
         }
     }
 
+    #[logcall::logcall("debug")]
+    #[minitrace::trace]
+    async fn set_database_quota(
+        &self,
+        req: SetDatabaseQuotaReq,
+    ) -> Result<SetDatabaseQuotaReply, KVAppError> {
+        debug!(req = as_debug!(&req); "SchemaApi: {}", func_name!());
+
+        let name_key = &req.name_ident;
+
+        let mut trials = txn_backoff(None, func_name!());
+        loop {
+            trials.next().unwrap()?.await;
+
+            let (_db_id_seq, db_id, db_meta_seq, mut db_meta) =
+                get_db_or_err(self, name_key, format!("set_database_quota: {}", name_key)).await?;
+
+            db_meta.max_tables = req.max_tables;
+
+            let dbid = DatabaseId { db_id };
+
+            let txn_req = TxnRequest {
+                condition: vec![txn_cond_seq(&dbid, Eq, db_meta_seq)],
+                if_then: vec![
+                    txn_op_put(&dbid, serialize_struct(&db_meta)?), // (db_id) -> db_meta
+                ],
+                else_then: vec![],
+            };
+
+            let (succ, _responses) = send_txn(self, txn_req).await?;
+
+            debug!(
+                name_key = as_debug!(name_key),
+                succ = succ;
+                "set_database_quota"
+            );
+
+            if succ {
+                return Ok(SetDatabaseQuotaReply {});
+            }
+        }
+    }
+
     #[logcall::logcall("debug")]
     #[minitrace::trace]
     async fn get_database(&self, req: GetDatabaseReq) -> Result<Arc<DatabaseInfo>, KVAppError> {
@@ -1251,10 +1307,10 @@ impl<KV: kvapi::KVApi<Error = MetaError> + ?Sized> SchemaApi for KV {
     ) -> Result<Vec<(u64, String, IndexMeta)>, KVAppError> {
         debug!(req = as_debug!(&req); "SchemaApi: {}", func_name!());
 
-        // Get index id list by `prefix_list` "<prefix>/<tenant>"
+        // Get index id list by `prefix_list` "<prefix>/<tenant>/"
         let prefix_key = kvapi::KeyBuilder::new_prefixed(IndexNameIdent::PREFIX)
             .push_str(&req.tenant)
-            .done();
+            .done_prefix();
 
         let id_list = self.prefix_list_kv(&prefix_key).await?;
         let mut id_name_list = Vec::with_capacity(id_list.len());
@@ -1298,10 +1354,10 @@ impl<KV: kvapi::KVApi<Error = MetaError> + ?Sized> SchemaApi for KV {
     ) -> Result<Vec<u64>, KVAppError> {
         debug!(req = as_debug!(&req); "SchemaApi: {}", func_name!());
 
-        // Get index id list by `prefix_list` "<prefix>/<tenant>"
+        // Get index id list by `prefix_list` "<prefix>/<tenant>/"
         let prefix_key = kvapi::KeyBuilder::new_prefixed(IndexNameIdent::PREFIX)
             .push_str(&req.tenant)
-            .done();
+            .done_prefix();
 
         let id_list = self.prefix_list_kv(&prefix_key).await?;
         let mut id_name_list = Vec::with_capacity(id_list.len());
@@ -1339,10 +1395,10 @@ impl<KV: kvapi::KVApi<Error = MetaError> + ?Sized> SchemaApi for KV {
     ) -> Result<Vec<(u64, String, IndexMeta)>, KVAppError> {
         debug!(req = as_debug!(&req); "SchemaApi: {}", func_name!());
 
-        // Get index id list by `prefix_list` "<prefix>/<tenant>"
+        // Get index id list by `prefix_list` "<prefix>/<tenant>/"
         let prefix_key = kvapi::KeyBuilder::new_prefixed(IndexNameIdent::PREFIX)
             .push_str(&req.tenant)
-            .done();
+            .done_prefix();
 
         let id_list = self.prefix_list_kv(&prefix_key).await?;
         let mut id_name_list = Vec::with_capacity(id_list.len());
@@ -1575,10 +1631,10 @@ impl<KV: kvapi::KVApi<Error = MetaError> + ?Sized> SchemaApi for KV {
             }
         }
 
-        // Get virtual columns list by `prefix_list` "<prefix>/<tenant>"
+        // Get virtual columns list by `prefix_list` "<prefix>/<tenant>/"
         let prefix_key = kvapi::KeyBuilder::new_prefixed(VirtualColumnNameIdent::PREFIX)
             .push_str(&req.tenant)
-            .done();
+            .done_prefix();
 
         let list = self.prefix_list_kv(&prefix_key).await?;
         let mut virtual_column_list = Vec::with_capacity(list.len());
@@ -1742,6 +1798,26 @@ impl<KV: kvapi::KVApi<Error = MetaError> + ?Sized> SchemaApi for KV {
                 }
             }
 
+            // The table count is consistent with db_meta.seq,
+            // because creating a table updates db_meta, which is guarded by the txn condition.
+            if let Some(max_tables) = db_meta.data.max_tables {
+                let (names, _ids) = list_u64_value(self, &DBIdTableName {
+                    db_id: db_id.data,
+                    table_name: "".to_string(),
+                })
+                .await?;
+
+                if names.len() as u64 >= max_tables {
+                    return Err(KVAppError::AppError(AppError::DatabaseQuotaExceeded(
+                        DatabaseQuotaExceeded::new(
+                            &tenant_dbname.db_name,
+                            max_tables,
+                            format!("create_table: {}", tenant_dbname_tbname),
+                        ),
+                    )));
+                }
+            }
+
             let mut tb_id_list = {
                 let d = data.remove(0);
                 let (k, v) = deserialize_struct_get_response::<TableIdListKey, TableIdList>(d)?;
@@ -1758,6 +1834,36 @@ impl<KV: kvapi::KVApi<Error = MetaError> + ?Sized> SchemaApi for KV {
                 v.unwrap_or_default()
             };
 
+            if let Some(id) = req.table_id {
+                // An id that the table id generator has not issued yet may be issued to another table later.
+                // The generator is updated with the last issued id, and its seq never decreases.
+                let issued = self
+                    .get_kv(&IdGenerator::table_id().to_string_key())
+                    .await?
+                    .map_or(0, |seq_v| seq_v.seq);
+                if id > issued {
+                    return Err(KVAppError::AppError(AppError::UnknownTableId(
+                        UnknownTableId::new(
+                            id,
+                            format!(
+                                "create_table: {}: not issued, the last issued is {}",
+                                tenant_dbname_tbname, issued
+                            ),
+                        ),
+                    )));
+                }
+
+                // A reserved id must not be used by another table.
+                // Checked in every loop, the txn fails if the id is used since the last check.
+                let table_id = TableId { table_id: id };
+                if self.has_kv(&table_id.to_string_key()).await? {
+                    return Err(KVAppError::AppError(AppError::TableIdInUse(
+                        TableIdInUse::new(id, format!("create_table: {}", tenant_dbname_tbname)),
+                    )));
+                }
+                key_table_id = Some(table_id);
+            }
+
             // Table id is unique and does not need to re-generate in every loop.
             if key_table_id.is_none() {
                 let id = fetch_id(self, IdGenerator::table_id()).await?;
@@ -1786,6 +1892,8 @@ impl<KV: kvapi::KVApi<Error = MetaError> + ?Sized> SchemaApi for KV {
                         txn_cond_seq(&key_dbid, Eq, db_meta.seq),
                         // no other table with the same name is inserted.
                         txn_cond_seq(&key_dbid_tbname, Eq, 0),
+                        // no other table with the same id is inserted, in case the id is reserved.
+                        txn_cond_seq(key_table_id.as_ref().unwrap(), Eq, 0),
                         // no other table id with the same name is append.
                         txn_cond_seq(&key_table_id_list, Eq, tb_id_list.seq),
                         // update table count atomically
@@ -1832,6 +1940,14 @@ impl<KV: kvapi::KVApi<Error = MetaError> + ?Sized> SchemaApi for KV {
         }
     }
 
+    #[logcall::logcall("debug")]
+    #[minitrace::trace]
+    async fn reserve_table_ids(&self, n: u64) -> Result<Option<Range<MetaId>>, KVAppError> {
+        debug!(n = n; "SchemaApi: {}", func_name!());
+
+        reserve_ids(self, IdGenerator::table_id(), n).await
+    }
+
     /// List all tables belonging to every db and every tenant.
     ///
     /// It returns a list of (table-id, table-meta-seq, table-meta).
@@ -2449,6 +2565,203 @@ impl<KV: kvapi::KVApi<Error = MetaError> + ?Sized> SchemaApi for KV {
         Ok(tb_infos)
     }
 
+    #[logcall::logcall("debug")]
+    #[minitrace::trace]
+    async fn list_tables_paged(
+        &self,
+        req: ListTableReq,
+        after: Option<String>,
+        limit: usize,
+    ) -> Result<(Vec<Arc<TableInfo>>, Option<String>), KVAppError> {
+        debug!(req = as_debug!(&req); "SchemaApi: {}", func_name!());
+
+        let tenant_dbname = &req.inner;
+
+        // Get db by name to ensure presence
+        let (_db_id_seq, db_id, _db_meta_seq, db_meta) = get_db_or_err(
+            self,
+            tenant_dbname,
+            format!("list_tables_paged: {}", &tenant_dbname),
+        )
+        .await?;
+
+        if let Some(share) = db_meta.from_share {
+            if after.is_some() {
+                return Ok((vec![], None));
+            }
+            let tb_infos = list_tables_from_share_db(self, share, tenant_dbname).await?;
+            return Ok((tb_infos, None));
+        }
+
+        // List a page of table ids by tenant, db_id, table_name.
+
+        let prefix = DBIdTableName {
+            db_id,
+            // Use empty name to scan all tables
+            table_name: "".to_string(),
+        }
+        .to_string_key();
+
+        let after = after.map(|table_name| DBIdTableName { db_id, table_name }.to_string_key());
+
+        let res = self
+            .prefix_list_kv_paged(&prefix, after.as_deref(), limit)
+            .await?;
+
+        let mut dbid_tbnames = Vec::with_capacity(res.len());
+        let mut ids = Vec::with_capacity(res.len());
+
+        for (str_key, seq_v) in res.iter() {
+            let dbid_tbname = DBIdTableName::from_str_key(str_key).map_err(|e| {
+                let inv = InvalidReply::new("list_tables_paged", &e);
+                let meta_net_err = MetaNetworkError::InvalidReply(inv);
+                MetaError::NetworkError(meta_net_err)
+            })?;
+
+            ids.push(*deserialize_u64(&seq_v.data)?);
+            dbid_tbnames.push(dbid_tbname);
+        }
+
+        let next = if res.len() < limit {
+            None
+        } else {
+            dbid_tbnames.last().map(|x| x.table_name.clone())
+        };
+
+        let tb_infos = get_tableinfos_by_ids(
+            self,
+            &ids,
+            tenant_dbname,
+            Some(dbid_tbnames),
+            DatabaseType::NormalDB,
+        )
+        .await?;
+
+        Ok((tb_infos, next))
+    }
+
+    #[logcall::logcall("debug")]
+    #[minitrace::trace]
+    async fn list_tables_by_prefix(
+        &self,
+        req: ListTableReq,
+        name_prefix: &str,
+    ) -> Result<Vec<Arc<TableInfo>>, KVAppError> {
+        debug!(req = as_debug!(&req); "SchemaApi: {}", func_name!());
+
+        let tenant_dbname = &req.inner;
+
+        // Get db by name to ensure presence
+        let (_db_id_seq, db_id, _db_meta_seq, db_meta) = get_db_or_err(
+            self,
+            tenant_dbname,
+            format!("list_tables_by_prefix: {}", &tenant_dbname),
+        )
+        .await?;
+
+        let mut tb_infos = match db_meta.from_share {
+            None => {
+                // The table name is the last segment of the key,
+                // thus the names with the prefix are exactly the keys with the prefix.
+                let dbid_tbname = DBIdTableName {
+                    db_id,
+                    table_name: name_prefix.to_string(),
+                };
+
+                let (dbid_tbnames, ids) = list_u64_value(self, &dbid_tbname).await?;
+
+                get_tableinfos_by_ids(
+                    self,
+                    &ids,
+                    tenant_dbname,
+                    Some(dbid_tbnames),
+                    DatabaseType::NormalDB,
+                )
+                .await?
+            }
+            Some(share) => {
+                let mut tb_infos = list_tables_from_share_db(self, share, tenant_dbname).await?;
+                tb_infos.retain(|tb_info| tb_info.name.starts_with(name_prefix));
+                tb_infos
+            }
+        };
+
+        // The keys are sorted by the escaped name, which may differ from the name order.
+        tb_infos.sort_by(|a, b| a.name.cmp(&b.name));
+
+        Ok(tb_infos)
+    }
+
+    #[logcall::logcall("debug")]
+    #[minitrace::trace]
+    async fn mget_tables(&self, req: MGetTableReq) -> Result<Vec<Arc<TableInfo>>, KVAppError> {
+        debug!(req = as_debug!(&req); "SchemaApi: {}", func_name!());
+
+        let tenant_dbname = &req.inner;
+
+        // Get db by name to ensure presence
+        let (_db_id_seq, db_id, _db_meta_seq, db_meta) = get_db_or_err(
+            self,
+            tenant_dbname,
+            format!("mget_tables: {}", &tenant_dbname),
+        )
+        .await?;
+
+        if db_meta.from_share.is_some() {
+            // A table in a shared db is resolved through the share, one by one.
+            let mut tb_infos = Vec::with_capacity(req.table_names.len());
+            for table_name in req.table_names.iter() {
+                let get_req =
+                    GetTableReq::new(&tenant_dbname.tenant, &tenant_dbname.db_name, table_name);
+                match self.get_table(get_req).await {
+                    Ok(tb_info) => tb_infos.push(tb_info),
+                    Err(KVAppError::AppError(AppError::UnknownTable(_))) => {}
+                    Err(e) => return Err(e),
+                }
+            }
+            return Ok(tb_infos);
+        }
+
+        // mget() table ids by tenant, db_id, table_name.
+
+        let dbid_tbnames = req
+            .table_names
+            .iter()
+            .map(|table_name| DBIdTableName {
+                db_id,
+                table_name: table_name.clone(),
+            })
+            .collect::<Vec<_>>();
+
+        let mut ids = Vec::with_capacity(dbid_tbnames.len());
+        let mut found = Vec::with_capacity(dbid_tbnames.len());
+
+        for c in dbid_tbnames.chunks(DEFAULT_MGET_SIZE) {
+            let keys = c.iter().map(|k| k.to_string_key()).collect::<Vec<_>>();
+            let seq_ids = self.mget_kv(&keys).await?;
+
+            for (dbid_tbname, seq_id) in c.iter().zip(seq_ids) {
+                if let Some(seq_id) = seq_id {
+                    ids.push(*deserialize_u64(&seq_id.data)?);
+                    found.push(dbid_tbname.clone());
+                }
+            }
+        }
+
+        // mget() table metas by table ids, a table just dropped is skipped too.
+
+        let tb_infos = get_tableinfos_by_ids(
+            self,
+            &ids,
+            tenant_dbname,
+            Some(found),
+            DatabaseType::NormalDB,
+        )
+        .await?;
+
+        Ok(tb_infos)
+    }
+
     #[logcall::logcall("debug")]
     #[minitrace::trace]
     async fn get_table_by_id(
@@ -2523,217 +2836,52 @@ impl<KV: kvapi::KVApi<Error = MetaError> + ?Sized> SchemaApi for KV {
         let table_id = req.tb_id;
         debug!(req = as_debug!(&table_id); "SchemaApi: {}", func_name!());
 
-        let mut tbcount_found = false;
-        let mut tb_count = 0;
-        let mut tb_count_seq;
         let tbid = TableId { table_id };
         let tenant = &req.tenant;
+        let mut tb_count_cache = None;
 
         let mut trials = txn_backoff(None, func_name!());
         loop {
             trials.next().unwrap()?.await;
 
-            // Check if table exists.
-            let (tb_meta_seq, tb_meta): (_, Option<TableMeta>) = get_pb_value(self, &tbid).await?;
-            if tb_meta_seq == 0 || tb_meta.is_none() {
-                return Err(KVAppError::AppError(AppError::UnknownTableId(
-                    UnknownTableId::new(table_id, "drop_table_by_id failed to find valid tb_meta"),
-                )));
-            }
-
-            // Get db name, tenant name and related info for tx.
-            let table_id_to_name = TableIdToName { table_id };
-            let (_, table_name_opt): (_, Option<DBIdTableName>) =
-                get_pb_value(self, &table_id_to_name).await?;
-
-            let dbid_tbname = if let Some(db_id_table_name) = table_name_opt {
-                db_id_table_name
-            } else {
-                let dbid_tbname = DBIdTableName {
-                    db_id: req.db_id,
-                    table_name: req.table_name.clone(),
-                };
-                warn!(
-                    "drop_table_by_id cannot find {:?}, use {:?} instead",
-                    table_id_to_name, dbid_tbname
-                );
-
-                dbid_tbname
-            };
-
-            let db_id = dbid_tbname.db_id;
-            let tbname = dbid_tbname.table_name.clone();
-            let (tb_id_seq, _) = get_u64_value(self, &dbid_tbname).await?;
-            if tb_id_seq == 0 {
-                return if req.if_exists {
-                    Ok(DropTableReply { spec_vec: None })
-                } else {
-                    return Err(KVAppError::AppError(AppError::UnknownTable(
-                        UnknownTable::new(tbname, "drop_table_by_id"),
-                    )));
-                };
-            }
-
-            // get current table count from _fd_table_count/<tenant>
-            let tb_count_key = CountTablesKey {
-                tenant: tenant.clone(),
-            };
-            (tb_count_seq, tb_count) = {
-                let (seq, count) = get_u64_value(self, &tb_count_key).await?;
-                if seq > 0 {
-                    (seq, count)
-                } else if !tbcount_found {
-                    // only count_tables for the first time.
-                    tbcount_found = true;
-                    (0, count_tables(self, &tb_count_key).await?)
-                } else {
-                    (0, tb_count)
-                }
+            let Some(drop_txn) =
+                build_drop_table_by_id_txn(self, &req, &mut tb_count_cache).await?
+            else {
+                return Ok(DropTableReply { spec_vec: None });
             };
 
-            let (db_meta_seq, db_meta) =
-                get_db_by_id_or_err(self, db_id, "drop_table_by_id").await?;
-
-            // cannot operate on shared database
-            if let Some(from_share) = db_meta.from_share {
-                return Err(KVAppError::AppError(AppError::ShareHasNoGrantedPrivilege(
-                    ShareHasNoGrantedPrivilege::new(&from_share.tenant, &from_share.share_name),
-                )));
-            }
+            let (succ, _responses) = send_txn(self, drop_txn.txn).await?;
 
             debug!(
-                ident = as_display!(&tbid),
-                tenant = as_display!(&tenant);
-                "drop table by id"
+                tenant = as_display!(&tenant),
+                id = as_debug!(&tbid),
+                succ = succ;
+                "drop_table_by_id"
             );
-
-            {
-                let mut tb_meta = tb_meta.unwrap();
-                // drop a table with drop_on time
-                if tb_meta.drop_on.is_some() {
-                    return Err(KVAppError::AppError(AppError::DropTableWithDropTime(
-                        DropTableWithDropTime::new(&dbid_tbname.table_name),
-                    )));
-                }
-
-                tb_meta.drop_on = Some(Utc::now());
-
-                // There must NOT be concurrent txn(b) that list-then-delete tables:
-                // Otherwise, (b) may not delete all of the tables, if this txn(a) is operating on some table.
-                // We guarantee there is no `(b)` so we do not have to assert db seq.
-                let mut condition = vec![
-                    // assert db_meta seq so that no other txn can delete this db
-                    txn_cond_seq(&DatabaseId { db_id }, Eq, db_meta_seq),
-                    // still this table id
-                    txn_cond_seq(&dbid_tbname, Eq, tb_id_seq),
-                    // table is not changed
-                    txn_cond_seq(&tbid, Eq, tb_meta_seq),
-                ];
-
-                let mut if_then = vec![
-                    // update db_meta seq so that no other txn can delete this db
-                    txn_op_put(&DatabaseId { db_id }, serialize_struct(&db_meta)?), /* (db_id) -> db_meta */
-                    txn_op_del(&dbid_tbname), // (db_id, tb_name) -> tb_id
-                    txn_op_put(&tbid, serialize_struct(&tb_meta)?), /* (tenant, db_id, tb_id) -> tb_meta */
-                ];
-
-                // update table count atomically
-                condition.push(txn_cond_seq(&tb_count_key, Eq, tb_count_seq));
-                // _fd_table_count/tenant -> tb_count
-                if_then.push(txn_op_put(&tb_count_key, serialize_u64(tb_count - 1)?));
-
-                // remove table from share
-                let mut spec_vec = Vec::with_capacity(db_meta.shared_by.len());
-                let mut mut_share_table_info = Vec::with_capacity(db_meta.shared_by.len());
-                for share_id in &db_meta.shared_by {
-                    let res = remove_table_from_share(
-                        self,
-                        *share_id,
-                        table_id,
-                        tenant.clone(),
-                        &mut condition,
-                        &mut if_then,
-                    )
-                    .await;
-
-                    match res {
-                        Ok((share_name, share_meta, share_table_info)) => {
-                            spec_vec.push(
-                                convert_share_meta_to_spec(
-                                    self,
-                                    &share_name,
-                                    *share_id,
-                                    share_meta,
-                                )
-                                .await?,
-                            );
-                            mut_share_table_info.push((share_name.to_string(), share_table_info));
-                        }
-                        Err(e) => match e {
-                            // ignore UnknownShareId error
-                            KVAppError::AppError(AppError::UnknownShareId(_)) => {
-                                error!(
-                                    "UnknownShareId {} when drop_table_by_id tenant:{} table_id:{} shared by",
-                                    share_id, tenant, table_id
-                                );
-                            }
-                            _ => return Err(e),
-                        },
-                    }
-                }
-
-                // add TableIdListKey if not exist
-                {
-                    // get table id list from _fd_table_id_list/db_id/table_name
-                    let dbid_tbname_idlist = TableIdListKey {
-                        db_id,
-                        table_name: dbid_tbname.table_name.clone(),
-                    };
-                    let (tb_id_list_seq, _tb_id_list_opt): (_, Option<TableIdList>) =
-                        get_pb_value(self, &dbid_tbname_idlist).await?;
-                    if tb_id_list_seq == 0 {
-                        let mut tb_id_list = TableIdList::new();
-                        tb_id_list.append(table_id);
-
-                        warn!(
-                            "drop table:{:?}, table_id:{:?} has no TableIdList",
-                            dbid_tbname, table_id
-                        );
-
-                        condition.push(txn_cond_seq(&dbid_tbname_idlist, Eq, tb_id_list_seq));
-                        if_then.push(txn_op_put(
-                            &dbid_tbname_idlist,
-                            serialize_struct(&tb_id_list)?,
-                        ));
-                    }
-                }
-                let txn_req = TxnRequest {
-                    condition,
-                    if_then,
-                    else_then: vec![],
-                };
-
-                let (succ, _responses) = send_txn(self, txn_req).await?;
-
-                debug!(
-                    tenant = as_display!(&tenant),
-                    id = as_debug!(&tbid),
-                    succ = succ;
-                    "drop_table_by_id"
-                );
-                if succ {
-                    return Ok(DropTableReply {
-                        spec_vec: if spec_vec.is_empty() {
-                            None
-                        } else {
-                            Some((spec_vec, mut_share_table_info))
-                        },
-                    });
-                }
+            if succ {
+                return Ok(DropTableReply {
+                    spec_vec: if drop_txn.spec_vec.is_empty() {
+                        None
+                    } else {
+                        Some((drop_txn.spec_vec, drop_txn.share_table_info))
+                    },
+                });
             }
         }
     }
 
+    #[logcall::logcall("debug")]
+    #[minitrace::trace]
+    async fn plan_drop_table_by_id(
+        &self,
+        req: DropTableByIdReq,
+    ) -> Result<Option<TxnRequest>, KVAppError> {
+        debug!(req = as_debug!(&req.tb_id); "SchemaApi: {}", func_name!());
+
+        let drop_txn = build_drop_table_by_id_txn(self, &req, &mut None).await?;
+        Ok(drop_txn.map(|x| x.txn))
+    }
+
     #[minitrace::trace]
     async fn get_table_copied_file_info(
         &self,
@@ -4242,6 +4390,209 @@ async fn count_tables(
     Ok(count)
 }
 
+/// The transaction submitted by `drop_table_by_id`, and the share info to reply once it is committed.
+struct DropTableByIdTxn {
+    txn: TxnRequest,
+    spec_vec: Vec<ShareSpec>,
+    share_table_info: Vec<ShareTableInfoMap>,
+}
+
+/// Build the transaction to drop a table by id, without submitting it.
+///
+/// It returns `None` if the table name does not exist and `req.if_exists` is true.
+///
+/// `tb_count_cache` keeps the table count computed by listing tables, when it is not in the kv space,
+/// so that it is computed only once when the transaction is retried.
+async fn build_drop_table_by_id_txn(
+    kv_api: &(impl kvapi::KVApi<Error = MetaError> + ?Sized),
+    req: &DropTableByIdReq,
+    tb_count_cache: &mut Option<u64>,
+) -> Result<Option<DropTableByIdTxn>, KVAppError> {
+    let table_id = req.tb_id;
+    let tbid = TableId { table_id };
+    let tenant = &req.tenant;
+
+    // Check if table exists.
+    let (tb_meta_seq, tb_meta): (_, Option<TableMeta>) = get_pb_value(kv_api, &tbid).await?;
+    if tb_meta_seq == 0 || tb_meta.is_none() {
+        return Err(KVAppError::AppError(AppError::UnknownTableId(
+            UnknownTableId::new(table_id, "drop_table_by_id failed to find valid tb_meta"),
+        )));
+    }
+
+    // Get db name, tenant name and related info for tx.
+    let table_id_to_name = TableIdToName { table_id };
+    let (_, table_name_opt): (_, Option<DBIdTableName>) =
+        get_pb_value(kv_api, &table_id_to_name).await?;
+
+    let dbid_tbname = if let Some(db_id_table_name) = table_name_opt {
+        db_id_table_name
+    } else {
+        let dbid_tbname = DBIdTableName {
+            db_id: req.db_id,
+            table_name: req.table_name.clone(),
+        };
+        warn!(
+            "drop_table_by_id cannot find {:?}, use {:?} instead",
+            table_id_to_name, dbid_tbname
+        );
+
+        dbid_tbname
+    };
+
+    let db_id = dbid_tbname.db_id;
+    let tbname = dbid_tbname.table_name.clone();
+    let (tb_id_seq, _) = get_u64_value(kv_api, &dbid_tbname).await?;
+    if tb_id_seq == 0 {
+        return if req.if_exists {
+            Ok(None)
+        } else {
+            Err(KVAppError::AppError(AppError::UnknownTable(
+                UnknownTable::new(tbname, "drop_table_by_id"),
+            )))
+        };
+    }
+
+    // get current table count from _fd_table_count/<tenant>
+    let tb_count_key = CountTablesKey {
+        tenant: tenant.clone(),
+    };
+    let (tb_count_seq, tb_count) = {
+        let (seq, count) = get_u64_value(kv_api, &tb_count_key).await?;
+        if seq > 0 {
+            (seq, count)
+        } else {
+            // only count_tables for the first time.
+            let count = match tb_count_cache {
+                Some(count) => *count,
+                None => *tb_count_cache.insert(count_tables(kv_api, &tb_count_key).await?),
+            };
+            (0, count)
+        }
+    };
+
+    let (db_meta_seq, db_meta) = get_db_by_id_or_err(kv_api, db_id, "drop_table_by_id").await?;
+
+    // cannot operate on shared database
+    if let Some(from_share) = db_meta.from_share {
+        return Err(KVAppError::AppError(AppError::ShareHasNoGrantedPrivilege(
+            ShareHasNoGrantedPrivilege::new(&from_share.tenant, &from_share.share_name),
+        )));
+    }
+
+    debug!(
+        ident = as_display!(&tbid),
+        tenant = as_display!(&tenant);
+        "drop table by id"
+    );
+
+    let mut tb_meta = tb_meta.unwrap();
+    // drop a table with drop_on time
+    if tb_meta.drop_on.is_some() {
+        return Err(KVAppError::AppError(AppError::DropTableWithDropTime(
+            DropTableWithDropTime::new(&dbid_tbname.table_name),
+        )));
+    }
+
+    tb_meta.drop_on = Some(Utc::now());
+
+    // There must NOT be concurrent txn(b) that list-then-delete tables:
+    // Otherwise, (b) may not delete all of the tables, if this txn(a) is operating on some table.
+    // We guarantee there is no `(b)` so we do not have to assert db seq.
+    let mut condition = vec![
+        // assert db_meta seq so that no other txn can delete this db
+        txn_cond_seq(&DatabaseId { db_id }, Eq, db_meta_seq),
+        // still this table id
+        txn_cond_seq(&dbid_tbname, Eq, tb_id_seq),
+        // table is not changed
+        txn_cond_seq(&tbid, Eq, tb_meta_seq),
+    ];
+
+    let mut if_then = vec![
+        // update db_meta seq so that no other txn can delete this db
+        txn_op_put(&DatabaseId { db_id }, serialize_struct(&db_meta)?), // (db_id) -> db_meta
+        txn_op_del(&dbid_tbname), // (db_id, tb_name) -> tb_id
+        txn_op_put(&tbid, serialize_struct(&tb_meta)?), // (tenant, db_id, tb_id) -> tb_meta
+    ];
+
+    // update table count atomically
+    condition.push(txn_cond_seq(&tb_count_key, Eq, tb_count_seq));
+    // _fd_table_count/tenant -> tb_count
+    if_then.push(txn_op_put(&tb_count_key, serialize_u64(tb_count - 1)?));
+
+    // remove table from share
+    let mut spec_vec = Vec::with_capacity(db_meta.shared_by.len());
+    let mut share_table_info = Vec::with_capacity(db_meta.shared_by.len());
+    for share_id in &db_meta.shared_by {
+        let res = remove_table_from_share(
+            kv_api,
+            *share_id,
+            table_id,
+            tenant.clone(),
+            &mut condition,
+            &mut if_then,
+        )
+        .await;
+
+        match res {
+            Ok((share_name, share_meta, table_info)) => {
+                spec_vec.push(
+                    convert_share_meta_to_spec(kv_api, &share_name, *share_id, share_meta).await?,
+                );
+                share_table_info.push((share_name.to_string(), table_info));
+            }
+            Err(e) => match e {
+                // ignore UnknownShareId error
+                KVAppError::AppError(AppError::UnknownShareId(_)) => {
+                    error!(
+                        "UnknownShareId {} when drop_table_by_id tenant:{} table_id:{} shared by",
+                        share_id, tenant, table_id
+                    );
+                }
+                _ => return Err(e),
+            },
+        }
+    }
+
+    // add TableIdListKey if not exist
+    {
+        // get table id list from _fd_table_id_list/db_id/table_name
+        let dbid_tbname_idlist = TableIdListKey {
+            db_id,
+            table_name: dbid_tbname.table_name.clone(),
+        };
+        let (tb_id_list_seq, _tb_id_list_opt): (_, Option<TableIdList>) =
+            get_pb_value(kv_api, &dbid_tbname_idlist).await?;
+        if tb_id_list_seq == 0 {
+            let mut tb_id_list = TableIdList::new();
+            tb_id_list.append(table_id);
+
+            warn!(
+                "drop table:{:?}, table_id:{:?} has no TableIdList",
+                dbid_tbname, table_id
+            );
+
+            condition.push(txn_cond_seq(&dbid_tbname_idlist, Eq, tb_id_list_seq));
+            if_then.push(txn_op_put(
+                &dbid_tbname_idlist,
+                serialize_struct(&tb_id_list)?,
+            ));
+        }
+    }
+
+    let txn = TxnRequest {
+        condition,
+        if_then,
+        else_then: vec![],
+    };
+
+    Ok(Some(DropTableByIdTxn {
+        txn,
+        spec_vec,
+        share_table_info,
+    }))
+}
+
 async fn get_share_table_info_map(
     kv_api: &(impl kvapi::KVApi<Error = MetaError> + ?Sized),
     table_meta: &TableMeta,
@@ -4832,10 +5183,10 @@ async fn gc_dropped_table_index(
     table_id: u64,
     if_then: &mut Vec<TxnOp>,
 ) -> Result<(), KVAppError> {
-    // Get index id list by `prefix_list` "<prefix>/<tenant>"
+    // Get index id list by `prefix_list` "<prefix>/<tenant>/"
     let prefix_key = kvapi::KeyBuilder::new_prefixed(IndexNameIdent::PREFIX)
         .push_str(tenant)
-        .done();
+        .done_prefix();
 
     let id_list = kv_api.prefix_list_kv(&prefix_key).await?;
     let mut id_name_list = Vec::with_capacity(id_list.len());
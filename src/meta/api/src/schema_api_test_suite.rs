@@ -83,6 +83,7 @@ use databend_common_meta_app::schema::ListVirtualColumnsReq;
 use databend_common_meta_app::schema::LockKey;
 use databend_common_meta_app::schema::RenameDatabaseReq;
 use databend_common_meta_app::schema::RenameTableReq;
+use databend_common_meta_app::schema::SetDatabaseQuotaReq;
 use databend_common_meta_app::schema::SetLVTReq;
 use databend_common_meta_app::schema::SetTableColumnMaskPolicyAction;
 use databend_common_meta_app::schema::SetTableColumnMaskPolicyReq;
@@ -233,6 +234,7 @@ async fn upsert_test_data(
             seq: MatchSeq::GE(0),
             value: Operation::Update(value),
             value_meta: None,
+            idempotency_token: None,
         })
         .await?;
 
@@ -250,6 +252,7 @@ async fn delete_test_data(
             seq: MatchSeq::GE(0),
             value: Operation::Delete,
             value_meta: None,
+            idempotency_token: None,
         })
         .await?;
 
@@ -276,6 +279,7 @@ impl SchemaApiTestSuite {
         suite.database_list(&b.build().await).await?;
         suite.database_list_in_diff_tenant(&b.build().await).await?;
         suite.database_rename(&b.build().await).await?;
+        suite.database_quota(&b.build().await).await?;
         suite
             .database_drop_undrop_list_history(&b.build().await)
             .await?;
@@ -297,6 +301,7 @@ impl SchemaApiTestSuite {
         suite.table_upsert_option(&b.build().await).await?;
         suite.table_list(&b.build().await).await?;
         suite.table_list_many(&b.build().await).await?;
+        suite.table_create_concurrently(&b.build().await).await?;
         suite.table_list_all(&b.build().await).await?;
         suite
             .table_drop_undrop_list_history(&b.build().await)
@@ -453,6 +458,7 @@ impl SchemaApiTestSuite {
 
         let req = CreateTableReq {
             if_not_exists: false,
+            table_id: None,
             name_ident: db_table_name_ident.clone(),
             table_meta: table_meta(created_on),
         };
@@ -1015,6 +1021,154 @@ impl SchemaApiTestSuite {
         Ok(())
     }
 
+    #[minitrace::trace]
+    async fn database_quota<MT: SchemaApi + kvapi::AsKVApi<Error = MetaError>>(
+        &self,
+        mt: &MT,
+    ) -> anyhow::Result<()> {
+        let tenant = "tenant1";
+        let db_name = "db1";
+
+        let set_quota = |max_tables: Option<u64>| SetDatabaseQuotaReq {
+            name_ident: DatabaseNameIdent {
+                tenant: tenant.to_string(),
+                db_name: db_name.to_string(),
+            },
+            max_tables,
+        };
+
+        info!("--- set quota of unknown db");
+        {
+            let res = mt.set_database_quota(set_quota(Some(2))).await;
+            assert_eq!(
+                ErrorCode::UnknownDatabase("").code(),
+                ErrorCode::from(res.unwrap_err()).code()
+            );
+        }
+
+        let mut util = Util::new(mt, tenant, db_name, "tb1", "JSON");
+        util.create_db().await?;
+
+        info!("--- create tables up to the quota");
+        {
+            mt.set_database_quota(set_quota(Some(2))).await?;
+
+            let db = mt
+                .get_database(GetDatabaseReq::new(tenant, db_name))
+                .await?;
+            assert_eq!(Some(2), db.meta.max_tables);
+
+            util.create_table().await?;
+            util.table_name = "tb2".to_string();
+            util.create_table().await?;
+
+            util.table_name = "tb3".to_string();
+            let res = util.create_table().await;
+            let err = res.unwrap_err().downcast::<KVAppError>()?;
+            assert_eq!(
+                ErrorCode::DatabaseQuotaExceeded("").code(),
+                ErrorCode::from(err).code()
+            );
+        }
+
+        info!("--- an existing name at the quota is not a quota error");
+        {
+            util.table_name = "tb1".to_string();
+            let req = CreateTableReq {
+                if_not_exists: true,
+                table_id: None,
+                name_ident: TableNameIdent {
+                    tenant: util.tenant(),
+                    db_name: util.db_name(),
+                    table_name: util.tbl_name(),
+                },
+                table_meta: util.table_meta(),
+            };
+
+            let reply = mt.create_table(req.clone()).await?;
+            assert!(!reply.new_table);
+
+            let res = mt
+                .create_table(CreateTableReq {
+                    if_not_exists: false,
+                    ..req
+                })
+                .await;
+            assert_eq!(
+                ErrorCode::TableAlreadyExists("").code(),
+                ErrorCode::from(res.unwrap_err()).code()
+            );
+        }
+
+        info!("--- a dropped table does not count");
+        {
+            util.table_name = "tb2".to_string();
+            util.drop_table_by_id().await?;
+
+            util.table_name = "tb3".to_string();
+            util.create_table().await?;
+
+            util.table_name = "tb4".to_string();
+            let res = util.create_table().await;
+            assert!(res.is_err());
+        }
+
+        info!("--- remove the quota");
+        {
+            mt.set_database_quota(set_quota(None)).await?;
+
+            util.create_table().await?;
+        }
+
+        info!("--- concurrent creations do not exceed the quota");
+        {
+            let db2_name = "db2";
+            let mut util2 = Util::new(mt, tenant, db2_name, "tb1", "JSON");
+            util2.create_db().await?;
+
+            mt.set_database_quota(SetDatabaseQuotaReq {
+                name_ident: DatabaseNameIdent {
+                    tenant: tenant.to_string(),
+                    db_name: db2_name.to_string(),
+                },
+                max_tables: Some(1),
+            })
+            .await?;
+
+            let n = 8;
+            let futs = (0..n).map(|i| {
+                let req = CreateTableReq {
+                    if_not_exists: false,
+                    table_id: None,
+                    name_ident: TableNameIdent {
+                        tenant: util2.tenant(),
+                        db_name: util2.db_name(),
+                        table_name: format!("tb_{}", i),
+                    },
+                    table_meta: util2.table_meta(),
+                };
+                mt.create_table(req)
+            });
+
+            let results = futures::future::join_all(futs).await;
+
+            let created = results.iter().filter(|r| r.is_ok()).count();
+            assert_eq!(1, created);
+
+            for res in results.into_iter().filter_map(|r| r.err()) {
+                assert_eq!(
+                    ErrorCode::DatabaseQuotaExceeded("").code(),
+                    ErrorCode::from(res).code()
+                );
+            }
+
+            let tables = mt.list_tables(ListTableReq::new(tenant, db2_name)).await?;
+            assert_eq!(1, tables.len());
+        }
+
+        Ok(())
+    }
+
     #[minitrace::trace]
     async fn database_rename<MT: SchemaApi>(&self, mt: &MT) -> anyhow::Result<()> {
         let tenant = "tenant1";
@@ -1458,6 +1612,7 @@ impl SchemaApiTestSuite {
 
         let req = CreateTableReq {
             if_not_exists: false,
+            table_id: None,
             name_ident: TableNameIdent {
                 tenant: tenant.to_string(),
                 db_name: db.to_string(),
@@ -1534,6 +1689,7 @@ impl SchemaApiTestSuite {
             let created_on = Utc::now();
             let req = CreateTableReq {
                 if_not_exists: false,
+                table_id: None,
                 name_ident: name_ident.clone(),
                 table_meta: table_meta(created_on),
             };
@@ -1618,6 +1774,7 @@ impl SchemaApiTestSuite {
 
             let req = CreateTableReq {
                 if_not_exists: false,
+                table_id: None,
                 name_ident: TableNameIdent {
                     tenant: tenant.to_string(),
                     db_name: db_name.to_string(),
@@ -1681,6 +1838,7 @@ impl SchemaApiTestSuite {
 
         let mut req = CreateTableReq {
             if_not_exists: false,
+            table_id: None,
             name_ident: TableNameIdent {
                 tenant: tenant.to_string(),
                 db_name: db_name.to_string(),
@@ -1791,6 +1949,7 @@ impl SchemaApiTestSuite {
 
             let req = CreateTableReq {
                 if_not_exists: false,
+                table_id: None,
                 name_ident: TableNameIdent {
                     tenant: tenant.to_string(),
                     db_name: db_name.to_string(),
@@ -2096,6 +2255,7 @@ impl SchemaApiTestSuite {
         let created_on = Utc::now();
         let create_tb2_req = CreateTableReq {
             if_not_exists: false,
+            table_id: None,
             name_ident: TableNameIdent {
                 tenant: tenant.to_string(),
                 db_name: db1_name.to_string(),
@@ -2319,6 +2479,7 @@ impl SchemaApiTestSuite {
 
             let req = CreateTableReq {
                 if_not_exists: false,
+                table_id: None,
                 name_ident: TableNameIdent {
                     tenant: tenant.to_string(),
                     db_name: db_name.to_string(),
@@ -2585,6 +2746,7 @@ impl SchemaApiTestSuite {
         {
             let req = CreateTableReq {
                 if_not_exists: false,
+                table_id: None,
                 name_ident: TableNameIdent {
                     tenant: tenant.to_string(),
                     db_name: db_name.to_string(),
@@ -2596,6 +2758,7 @@ impl SchemaApiTestSuite {
 
             let req = CreateTableReq {
                 if_not_exists: false,
+                table_id: None,
                 name_ident: TableNameIdent {
                     tenant: tenant.to_string(),
                     db_name: db_name.to_string(),
@@ -2908,6 +3071,7 @@ impl SchemaApiTestSuite {
 
             let req = CreateTableReq {
                 if_not_exists: false,
+                table_id: None,
                 name_ident: TableNameIdent {
                     tenant: tenant.to_string(),
                     db_name: db_name.to_string(),
@@ -3240,6 +3404,7 @@ impl SchemaApiTestSuite {
 
         let req = CreateTableReq {
             if_not_exists: false,
+            table_id: None,
             name_ident,
             table_meta: create_table_meta.clone(),
         };
@@ -3474,6 +3639,7 @@ impl SchemaApiTestSuite {
 
         let req = CreateTableReq {
             if_not_exists: false,
+            table_id: None,
             name_ident: tbl_name_ident,
             table_meta: create_table_meta.clone(),
         };
@@ -3708,6 +3874,7 @@ impl SchemaApiTestSuite {
         {
             let req = CreateTableReq {
                 if_not_exists: false,
+                table_id: None,
                 name_ident: tbl_name_ident.clone(),
                 table_meta: create_table_meta.clone(),
             };
@@ -3794,6 +3961,7 @@ impl SchemaApiTestSuite {
 
             let req = CreateTableReq {
                 if_not_exists: false,
+                table_id: None,
                 name_ident: TableNameIdent {
                     tenant: tenant.to_string(),
                     db_name: "db1".to_string(),
@@ -3842,6 +4010,7 @@ impl SchemaApiTestSuite {
                 };
                 let req = CreateTableReq {
                     if_not_exists: false,
+                    table_id: None,
                     name_ident: table_name.clone(),
                     table_meta: table_meta(created_on),
                 };
@@ -3867,6 +4036,7 @@ impl SchemaApiTestSuite {
                 let mut table_meta = table_meta(created_on);
                 let req = CreateTableReq {
                     if_not_exists: false,
+                    table_id: None,
                     name_ident: TableNameIdent {
                         tenant: tenant.to_string(),
                         db_name: "db2".to_string(),
@@ -3895,6 +4065,7 @@ impl SchemaApiTestSuite {
             {
                 let req = CreateTableReq {
                     if_not_exists: false,
+                    table_id: None,
                     name_ident: TableNameIdent {
                         tenant: tenant.to_string(),
                         db_name: "db2".to_string(),
@@ -3943,6 +4114,7 @@ impl SchemaApiTestSuite {
             {
                 let req = CreateTableReq {
                     if_not_exists: false,
+                    table_id: None,
                     name_ident: TableNameIdent {
                         tenant: tenant.to_string(),
                         db_name: "db3".to_string(),
@@ -3969,6 +4141,7 @@ impl SchemaApiTestSuite {
                 let mut table_meta = table_meta(created_on);
                 let req = CreateTableReq {
                     if_not_exists: false,
+                    table_id: None,
                     name_ident: TableNameIdent {
                         tenant: tenant.to_string(),
                         db_name: "db3".to_string(),
@@ -3998,6 +4171,7 @@ impl SchemaApiTestSuite {
             {
                 let req = CreateTableReq {
                     if_not_exists: false,
+                    table_id: None,
                     name_ident: TableNameIdent {
                         tenant: tenant.to_string(),
                         db_name: "db3".to_string(),
@@ -4144,6 +4318,7 @@ impl SchemaApiTestSuite {
                 let table_name = format!("tb{:?}", i);
                 let req = CreateTableReq {
                     if_not_exists: false,
+                    table_id: None,
                     name_ident: TableNameIdent {
                         tenant: tenant.to_string(),
                         db_name: db.to_string(),
@@ -4380,6 +4555,7 @@ impl SchemaApiTestSuite {
         {
             let req = CreateTableReq {
                 if_not_exists: false,
+                table_id: None,
                 name_ident: tbl_name_ident.clone(),
                 table_meta: create_table_meta.clone(),
             };
@@ -4502,6 +4678,7 @@ impl SchemaApiTestSuite {
             let res = mt
                 .create_table(CreateTableReq {
                     if_not_exists: false,
+                    table_id: None,
                     name_ident: tbl_name_ident.clone(),
                     table_meta: create_table_meta.clone(),
                 })
@@ -4598,6 +4775,7 @@ impl SchemaApiTestSuite {
             // first create drop table2
             let req = CreateTableReq {
                 if_not_exists: false,
+                table_id: None,
                 name_ident: new_tbl_name_ident.clone(),
                 table_meta: create_table_meta.clone(),
             };
@@ -4759,6 +4937,7 @@ impl SchemaApiTestSuite {
 
             let req = CreateTableReq {
                 if_not_exists: false,
+                table_id: None,
                 name_ident: TableNameIdent {
                     tenant: tenant.to_string(),
                     db_name: db_name.to_string(),
@@ -4867,6 +5046,7 @@ impl SchemaApiTestSuite {
 
             let req = CreateTableReq {
                 if_not_exists: false,
+                table_id: None,
                 name_ident: TableNameIdent {
                     tenant: tenant.to_string(),
                     db_name: db_name.to_string(),
@@ -5017,6 +5197,7 @@ impl SchemaApiTestSuite {
 
             let req = CreateTableReq {
                 if_not_exists: false,
+                table_id: None,
                 name_ident: TableNameIdent {
                     tenant: tenant.to_string(),
                     db_name: db_name.to_string(),
@@ -5231,6 +5412,7 @@ impl SchemaApiTestSuite {
             for tb_name in tb_names {
                 let req = CreateTableReq {
                     if_not_exists: false,
+                    table_id: None,
                     name_ident: tb_name.clone(),
                     table_meta: table_meta(create_on),
                 };
@@ -5348,6 +5530,7 @@ impl SchemaApiTestSuite {
 
             let mut req = CreateTableReq {
                 if_not_exists: false,
+                table_id: None,
                 name_ident: TableNameIdent {
                     tenant: tenant.to_string(),
                     db_name: db_name.to_string(),
@@ -5415,6 +5598,7 @@ impl SchemaApiTestSuite {
                 let table_meta = util.table_meta();
                 let req = CreateTableReq {
                     if_not_exists: false,
+                    table_id: None,
                     name_ident: TableNameIdent {
                         tenant: util.tenant(),
                         db_name: util.db_name(),
@@ -5431,11 +5615,90 @@ impl SchemaApiTestSuite {
         }
 
         info!("--- get_tables");
+        let all = {
+            let res = mt
+                .list_tables(ListTableReq::new(util.tenant(), util.db_name()))
+                .await?;
+            assert_eq!(n, res.len());
+            res.iter().map(|t| t.name.clone()).collect::<Vec<_>>()
+        };
+
+        info!("--- list_tables_paged in the same order as list_tables");
+        {
+            let mut got = vec![];
+            let mut after = None;
+            loop {
+                let (page, next) = mt
+                    .list_tables_paged(ListTableReq::new(util.tenant(), util.db_name()), after, 100)
+                    .await?;
+                assert!(page.len() <= 100);
+                got.extend(page.iter().map(|t| t.name.clone()));
+
+                if next.is_none() {
+                    break;
+                }
+                after = next;
+            }
+            assert_eq!(all, got);
+        }
+
+        Ok(())
+    }
+
+    /// Test creating tables in one db concurrently.
+    ///
+    /// Every create_table() updates the db meta with a CAS transaction and retries on conflict,
+    /// thus no table is lost.
+    #[minitrace::trace]
+    async fn table_create_concurrently<MT>(&self, mt: &MT) -> anyhow::Result<()>
+    where MT: SchemaApi + kvapi::AsKVApi<Error = MetaError> {
+        let n = 10;
+
+        let mut util = Util::new(mt, "tenant1", "db1", "tb1", "eng1");
+
+        info!("--- prepare db");
+        let old_db = {
+            util.create_db().await?;
+            mt.get_database(Self::req_get_db(util.tenant(), util.db_name()))
+                .await?
+        };
+
+        info!("--- create {} tables concurrently", n);
+        {
+            let futs = (0..n).map(|i| {
+                let req = CreateTableReq {
+                    if_not_exists: false,
+                    table_id: None,
+                    name_ident: TableNameIdent {
+                        tenant: util.tenant(),
+                        db_name: util.db_name(),
+                        table_name: format!("tb_{}", i),
+                    },
+                    table_meta: util.table_meta(),
+                };
+                mt.create_table(req)
+            });
+
+            let replies = futures::future::try_join_all(futs).await?;
+            assert!(replies.iter().all(|r| r.new_table));
+        }
+
+        info!("--- all of the tables are created");
         {
             let res = mt
                 .list_tables(ListTableReq::new(util.tenant(), util.db_name()))
                 .await?;
             assert_eq!(n, res.len());
+
+            let tb_count = mt
+                .count_tables(Self::req_count_table(util.tenant()))
+                .await?;
+            assert_eq!(n as u64, tb_count.count);
+
+            let cur_db = mt
+                .get_database(Self::req_get_db(util.tenant(), util.db_name()))
+                .await?;
+            assert!(old_db.ident.seq < cur_db.ident.seq);
         }
 
         Ok(())
@@ -5465,6 +5728,7 @@ impl SchemaApiTestSuite {
 
             let mut req = CreateTableReq {
                 if_not_exists: false,
+                table_id: None,
                 name_ident: TableNameIdent {
                     tenant: tenant.to_string(),
                     db_name: db1_name.to_string(),
@@ -6282,6 +6546,7 @@ impl SchemaApiTestSuite {
             for tb in tables {
                 let req = CreateTableReq {
                     if_not_exists: false,
+                    table_id: None,
                     name_ident: TableNameIdent {
                         tenant: tenant.to_string(),
                         db_name: db_name.to_string(),
@@ -6355,6 +6620,7 @@ impl SchemaApiTestSuite {
 
             let req = CreateTableReq {
                 if_not_exists: false,
+                table_id: None,
                 name_ident: TableNameIdent {
                     tenant: tenant.to_string(),
                     db_name: db_name.to_string(),
@@ -6450,6 +6716,7 @@ impl SchemaApiTestSuite {
 
             let req = CreateTableReq {
                 if_not_exists: false,
+                table_id: None,
                 name_ident: TableNameIdent {
                     tenant: tenant.to_string(),
                     db_name: db_name.to_string(),
@@ -6704,6 +6971,7 @@ where MT: SchemaApi + kvapi::AsKVApi<Error = MetaError>
         let table_meta = self.table_meta();
         let req = CreateTableReq {
             if_not_exists: false,
+            table_id: None,
             name_ident: TableNameIdent {
                 tenant: self.tenant(),
                 db_name: self.db_name(),
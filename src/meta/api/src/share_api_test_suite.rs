@@ -732,6 +732,7 @@ impl ShareApiTestSuite {
 
             let req = CreateTableReq {
                 if_not_exists: false,
+                table_id: None,
                 name_ident: TableNameIdent {
                     tenant: tenant.to_string(),
                     db_name: db_name.to_string(),
@@ -755,6 +756,7 @@ impl ShareApiTestSuite {
 
             let req = CreateTableReq {
                 if_not_exists: false,
+                table_id: None,
                 name_ident: TableNameIdent {
                     tenant: tenant.to_string(),
                     db_name: db_name.to_string(),
@@ -771,6 +773,7 @@ impl ShareApiTestSuite {
 
             let req = CreateTableReq {
                 if_not_exists: false,
+                table_id: None,
                 name_ident: TableNameIdent {
                     tenant: tenant.to_string(),
                     db_name: db2_name.to_string(),
@@ -1179,6 +1182,7 @@ impl ShareApiTestSuite {
 
             let req = CreateTableReq {
                 if_not_exists: false,
+                table_id: None,
                 name_ident: TableNameIdent {
                     tenant: tenant.to_string(),
                     db_name: db_name.to_string(),
@@ -1332,6 +1336,7 @@ impl ShareApiTestSuite {
 
             let req = CreateTableReq {
                 if_not_exists: false,
+                table_id: None,
                 name_ident: TableNameIdent {
                     tenant: tenant1.to_string(),
                     db_name: db_name.to_string(),
@@ -1583,6 +1588,7 @@ impl ShareApiTestSuite {
 
             let req = CreateTableReq {
                 if_not_exists: false,
+                table_id: None,
                 name_ident: TableNameIdent {
                     tenant: tenant.to_string(),
                     db_name: db_name.to_string(),
@@ -16,6 +16,7 @@ use std::any::type_name;
 use std::collections::BTreeMap;
 use std::collections::HashSet;
 use std::fmt::Display;
+use std::ops::Range;
 use std::sync::Arc;
 
 use databend_common_meta_app::app_error::AppError;
@@ -52,7 +53,9 @@ use databend_common_meta_app::share::*;
 use databend_common_meta_kvapi::kvapi;
 use databend_common_meta_kvapi::kvapi::Key;
 use databend_common_meta_kvapi::kvapi::UpsertKVReq;
+use databend_common_meta_types::anyerror::AnyError;
 use databend_common_meta_types::txn_condition::Target;
+use databend_common_meta_types::txn_op_response;
 use databend_common_meta_types::ConditionResult;
 use databend_common_meta_types::InvalidArgument;
 use databend_common_meta_types::InvalidReply;
@@ -280,6 +283,7 @@ pub async fn fetch_id<T: kvapi::Key>(
             seq: MatchSeq::GE(0),
             value: Operation::Update(b"".to_vec()),
             value_meta: None,
+            idempotency_token: None,
         })
         .await?;
 
@@ -288,6 +292,62 @@ pub async fn fetch_id<T: kvapi::Key>(
     Ok(seq_v.seq)
 }
 
+/// The max number of ids [`reserve_ids`] reserves at a time.
+pub const MAX_RESERVE_IDS: u64 = 1024;
+
+/// Reserve `n` contiguous ids from `generator` by updating it once.
+///
+/// An id is a seq of the generator record, as [`fetch_id`] returns.
+/// A `ReserveSeq` operation advances the seq by `n` and updates the generator with the last one,
+/// thus the `n` seqs ending with it are taken by this reservation,
+/// and never returned by another `fetch_id()` or `reserve_ids()`.
+///
+/// It returns `None` if the meta-service does not apply `ReserveSeq`,
+/// i.e., the state machine feature `reserve_seq` is not enabled.
+///
+/// `n` must be in `1..=MAX_RESERVE_IDS`, otherwise an `InvalidArgument` error is returned.
+pub async fn reserve_ids<T: kvapi::Key>(
+    kv_api: &(impl kvapi::KVApi<Error = MetaError> + ?Sized),
+    generator: T,
+    n: u64,
+) -> Result<Option<Range<u64>>, KVAppError> {
+    if n == 0 || n > MAX_RESERVE_IDS {
+        let inv = InvalidArgument::new(
+            AnyError::error(format!(
+                "the number of ids to reserve must be in 1..={}, got {}",
+                MAX_RESERVE_IDS, n
+            )),
+            "reserve_ids",
+        );
+        return Err(KVAppError::MetaError(MetaError::from(inv)));
+    }
+
+    let key = generator.to_string_key();
+
+    let txn = TxnRequest::unconditional(vec![TxnOp::reserve_seq(&key, n)]);
+    let (_succ, responses) = send_txn(kv_api, txn).await?;
+
+    let reserved = responses.iter().find_map(|r| match &r.response {
+        Some(txn_op_response::Response::ReserveSeq(reserved)) => Some(reserved),
+        _ => None,
+    });
+
+    let Some(reserved) = reserved else {
+        return Ok(None);
+    };
+
+    let got = reserved.end.saturating_sub(reserved.start);
+    if got != n {
+        let inv = InvalidReply::new(
+            format!("reserve_ids: reserved {} ids of {}", got, key),
+            &AnyError::error(format!("expect {} ids", n)),
+        );
+        return Err(KVAppError::MetaError(MetaError::from(inv)));
+    }
+
+    Ok(Some(reserved.start..reserved.end))
+}
+
 pub fn serialize_struct<T>(value: &T) -> Result<Vec<u8>, MetaNetworkError>
 where
     T: FromToProto + 'static,
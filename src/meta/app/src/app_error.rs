@@ -162,6 +162,22 @@ impl TableAlreadyExists {
     }
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, thiserror::Error)]
+#[error("TableIdInUse: {table_id} while {context}")]
+pub struct TableIdInUse {
+    table_id: u64,
+    context: String,
+}
+
+impl TableIdInUse {
+    pub fn new(table_id: u64, context: impl Into<String>) -> Self {
+        Self {
+            table_id,
+            context: context.into(),
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, thiserror::Error)]
 #[error("ViewAlreadyExists: {view_name} while {context}")]
 pub struct ViewAlreadyExists {
@@ -350,6 +366,30 @@ impl UnknownDatabase {
             context: context.into(),
         }
     }
+
+    pub fn db_name(&self) -> &str {
+        &self.db_name
+    }
+}
+
+#[derive(thiserror::Error, Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[error(
+    "DatabaseQuotaExceeded: `{db_name}` can hold at most {max_tables} tables while `{context}`"
+)]
+pub struct DatabaseQuotaExceeded {
+    db_name: String,
+    max_tables: u64,
+    context: String,
+}
+
+impl DatabaseQuotaExceeded {
+    pub fn new(db_name: impl Into<String>, max_tables: u64, context: impl Into<String>) -> Self {
+        Self {
+            db_name: db_name.into(),
+            max_tables,
+            context: context.into(),
+        }
+    }
 }
 
 #[derive(thiserror::Error, Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
@@ -479,6 +519,10 @@ impl UnknownTable {
             context: context.into(),
         }
     }
+
+    pub fn table_name(&self) -> &str {
+        &self.table_name
+    }
 }
 
 #[derive(thiserror::Error, Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
@@ -885,6 +929,9 @@ pub enum AppError {
     #[error(transparent)]
     TableAlreadyExists(#[from] TableAlreadyExists),
 
+    #[error(transparent)]
+    TableIdInUse(#[from] TableIdInUse),
+
     #[error(transparent)]
     ViewAlreadyExists(#[from] ViewAlreadyExists),
 
@@ -924,6 +971,9 @@ pub enum AppError {
     #[error(transparent)]
     UnknownDatabase(#[from] UnknownDatabase),
 
+    #[error(transparent)]
+    DatabaseQuotaExceeded(#[from] DatabaseQuotaExceeded),
+
     #[error(transparent)]
     UnknownCatalog(#[from] UnknownCatalog),
 
@@ -1052,6 +1102,15 @@ impl AppErrorMessage for UnknownDatabase {
     }
 }
 
+impl AppErrorMessage for DatabaseQuotaExceeded {
+    fn message(&self) -> String {
+        format!(
+            "Database '{}' can hold at most {} tables",
+            self.db_name, self.max_tables
+        )
+    }
+}
+
 impl AppErrorMessage for UnknownCatalog {
     fn message(&self) -> String {
         format!("Unknown catalog '{}'", self.catalog_name)
@@ -1112,6 +1171,12 @@ impl AppErrorMessage for TableAlreadyExists {
     }
 }
 
+impl AppErrorMessage for TableIdInUse {
+    fn message(&self) -> String {
+        format!("Table id {} is in use by another table", self.table_id)
+    }
+}
+
 impl AppErrorMessage for ViewAlreadyExists {
     fn message(&self) -> String {
         format!("'{}' as view Already Exists", self.view_name)
@@ -1366,6 +1431,7 @@ impl From<AppError> for ErrorCode {
             AppError::UnknownTableId(err) => ErrorCode::UnknownTableId(err.message()),
             AppError::UnknownTable(err) => ErrorCode::UnknownTable(err.message()),
             AppError::UnknownCatalog(err) => ErrorCode::UnknownCatalog(err.message()),
+            AppError::DatabaseQuotaExceeded(err) => ErrorCode::DatabaseQuotaExceeded(err.message()),
             AppError::DatabaseAlreadyExists(err) => ErrorCode::DatabaseAlreadyExists(err.message()),
             AppError::CatalogAlreadyExists(err) => ErrorCode::CatalogAlreadyExists(err.message()),
             AppError::CreateDatabaseWithDropTime(err) => {
@@ -1381,6 +1447,7 @@ impl From<AppError> for ErrorCode {
                 ErrorCode::UndropDbWithNoDropTime(err.message())
             }
             AppError::TableAlreadyExists(err) => ErrorCode::TableAlreadyExists(err.message()),
+            AppError::TableIdInUse(err) => ErrorCode::TableIdInUse(err.message()),
             AppError::ViewAlreadyExists(err) => ErrorCode::ViewAlreadyExists(err.message()),
             AppError::CreateTableWithDropTime(err) => {
                 ErrorCode::CreateTableWithDropTime(err.message())
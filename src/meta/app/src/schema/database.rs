@@ -113,6 +113,11 @@ pub struct DatabaseMeta {
     // shared by share_id
     pub shared_by: BTreeSet<u64>,
     pub from_share: Option<ShareNameIdent>,
+
+    /// The max number of tables this database can hold, `None` for no limit.
+    ///
+    /// It is checked when a table is created.
+    pub max_tables: Option<u64>,
 }
 
 impl Default for DatabaseMeta {
@@ -127,6 +132,7 @@ impl Default for DatabaseMeta {
             drop_on: None,
             shared_by: BTreeSet::new(),
             from_share: None,
+            max_tables: None,
         }
     }
 }
@@ -231,6 +237,27 @@ impl Display for RenameDatabaseReq {
 #[derive(serde::Serialize, serde::Deserialize, Clone, Debug, PartialEq, Eq)]
 pub struct RenameDatabaseReply {}
 
+#[derive(serde::Serialize, serde::Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct SetDatabaseQuotaReq {
+    pub name_ident: DatabaseNameIdent,
+
+    /// The max number of tables the database can hold, `None` to remove the limit.
+    pub max_tables: Option<u64>,
+}
+
+impl Display for SetDatabaseQuotaReq {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "set_database_quota:{}/{}: max_tables={:?}",
+            self.name_ident.tenant, self.name_ident.db_name, self.max_tables
+        )
+    }
+}
+
+#[derive(serde::Serialize, serde::Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct SetDatabaseQuotaReply {}
+
 #[derive(serde::Serialize, serde::Deserialize, Clone, Debug, PartialEq, Eq)]
 pub struct DropDatabaseReq {
     pub if_exists: bool,
@@ -250,6 +277,9 @@ impl Display for DropDatabaseReq {
 #[derive(serde::Serialize, serde::Deserialize, Clone, Debug, PartialEq, Eq)]
 pub struct DropDatabaseReply {
     pub spec_vec: Option<Vec<ShareSpec>>,
+    /// The id of the dropped database, `None` if it does not exist and `if_exists` is set.
+    #[serde(default)]
+    pub db_id: Option<u64>,
 }
 
 #[derive(serde::Serialize, serde::Deserialize, Clone, Debug, PartialEq, Eq)]
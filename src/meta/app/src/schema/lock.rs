@@ -20,6 +20,7 @@ use chrono::DateTime;
 use chrono::Utc;
 use databend_common_exception::Result;
 use databend_common_meta_kvapi::kvapi::Key;
+use databend_common_meta_kvapi::kvapi::KeyBuilder;
 use databend_common_meta_kvapi::kvapi::KeyError;
 
 #[derive(serde::Serialize, serde::Deserialize, Clone, Debug, Eq, PartialEq)]
@@ -98,7 +99,9 @@ impl LockKey {
 
     pub fn gen_prefix(&self) -> String {
         match self {
-            LockKey::Table { table_id } => format!("{}/{}", TableLockKey::PREFIX, table_id),
+            LockKey::Table { table_id } => KeyBuilder::new_prefixed(TableLockKey::PREFIX)
+                .push_u64(*table_id)
+                .done_prefix(),
         }
     }
 
@@ -40,6 +40,8 @@ pub use database::GetDatabaseReq;
 pub use database::ListDatabaseReq;
 pub use database::RenameDatabaseReply;
 pub use database::RenameDatabaseReq;
+pub use database::SetDatabaseQuotaReply;
+pub use database::SetDatabaseQuotaReq;
 pub use database::UndropDatabaseReply;
 pub use database::UndropDatabaseReq;
 pub use index::*;
@@ -80,6 +82,7 @@ pub use table::GetTableReq;
 pub use table::ListDroppedTableReq;
 pub use table::ListDroppedTableResp;
 pub use table::ListTableReq;
+pub use table::MGetTableReq;
 pub use table::RenameTableReply;
 pub use table::RenameTableReq;
 pub use table::SetTableColumnMaskPolicyAction;
@@ -242,6 +242,8 @@ pub struct TableMeta {
     // shared by share_id
     pub shared_by: BTreeSet<u64>,
     pub column_mask_policy: Option<BTreeMap<String, String>>,
+    // Bumped every time the schema is altered, to let a reader detect a schema change cheaply.
+    pub schema_version: u64,
 }
 
 impl TableMeta {
@@ -255,7 +257,7 @@ impl TableMeta {
 
         let mut new_schema = self.schema.as_ref().to_owned();
         new_schema.add_column(field, index)?;
-        self.schema = Arc::new(new_schema);
+        self.update_schema(Arc::new(new_schema));
         self.field_comments.insert(index, comment.to_owned());
         Ok(())
     }
@@ -266,10 +268,16 @@ impl TableMeta {
         let mut new_schema = self.schema.as_ref().to_owned();
         let index = new_schema.drop_column(column)?;
         self.field_comments.remove(index);
-        self.schema = Arc::new(new_schema);
+        self.update_schema(Arc::new(new_schema));
         Ok(())
     }
 
+    /// Replace the schema and bump the `schema_version`.
+    pub fn update_schema(&mut self, schema: Arc<TableSchema>) {
+        self.schema = schema;
+        self.schema_version += 1;
+    }
+
     /// To fix the field comments panic.
     pub fn fill_field_comments(&mut self) {
         let num_fields = self.schema.num_fields();
@@ -356,6 +364,7 @@ impl Default for TableMeta {
             statistics: Default::default(),
             shared_by: BTreeSet::new(),
             column_mask_policy: None,
+            schema_version: 0,
         }
     }
 }
@@ -445,6 +454,10 @@ impl Display for TableIdList {
 #[derive(serde::Serialize, serde::Deserialize, Clone, Debug, PartialEq, Eq)]
 pub struct CreateTableReq {
     pub if_not_exists: bool,
+    /// The id of the table to create, reserved by `reserve_table_ids()`.
+    ///
+    /// If it is `None`, a new id is allocated.
+    pub table_id: Option<MetaId>,
     pub name_ident: TableNameIdent,
     pub table_meta: TableMeta,
 }
@@ -731,6 +744,39 @@ impl ListTableReq {
     }
 }
 
+/// Get the tables of the given names in a database, in a batch.
+///
+/// A table that does not exist is not included in the reply.
+#[derive(serde::Serialize, serde::Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct MGetTableReq {
+    pub inner: DatabaseNameIdent,
+    pub table_names: Vec<String>,
+}
+
+impl Deref for MGetTableReq {
+    type Target = DatabaseNameIdent;
+
+    fn deref(&self) -> &Self::Target {
+        &self.inner
+    }
+}
+
+impl MGetTableReq {
+    pub fn new(
+        tenant: impl Into<String>,
+        db_name: impl Into<String>,
+        table_names: Vec<String>,
+    ) -> MGetTableReq {
+        MGetTableReq {
+            inner: DatabaseNameIdent {
+                tenant: tenant.into(),
+                db_name: db_name.into(),
+            },
+            table_names,
+        }
+    }
+}
+
 #[derive(serde::Serialize, serde::Deserialize, Clone, Debug, PartialEq, Eq)]
 pub enum TableInfoFilter {
     // if datatime is some, filter only dropped tables which drop time before that,
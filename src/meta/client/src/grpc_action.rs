@@ -18,8 +18,10 @@ use std::fmt::Debug;
 
 use databend_common_meta_kvapi::kvapi::GetKVReply;
 use databend_common_meta_kvapi::kvapi::GetKVReq;
+use databend_common_meta_kvapi::kvapi::HasKVReq;
 use databend_common_meta_kvapi::kvapi::ListKVReply;
 use databend_common_meta_kvapi::kvapi::ListKVReq;
+use databend_common_meta_kvapi::kvapi::ListKeysReq;
 use databend_common_meta_kvapi::kvapi::MGetKVReply;
 use databend_common_meta_kvapi::kvapi::MGetKVReq;
 use databend_common_meta_kvapi::kvapi::UpsertKVReply;
@@ -119,6 +121,12 @@ pub enum MetaGrpcReadReq {
     GetKV(GetKVReq),
     MGetKV(MGetKVReq),
     ListKV(ListKVReq),
+
+    /// Returns at most one item, for an existent key, whose value has an empty `data`.
+    HasKV(HasKVReq),
+
+    /// Returns an item without value for every key with the prefix.
+    ListKeys(ListKeysReq),
 }
 
 // All Read requests returns a stream of KV pairs.
@@ -132,6 +140,8 @@ impl From<MetaGrpcReadReq> for MetaGrpcReq {
             MetaGrpcReadReq::GetKV(v) => MetaGrpcReq::GetKV(v),
             MetaGrpcReadReq::MGetKV(v) => MetaGrpcReq::MGetKV(v),
             MetaGrpcReadReq::ListKV(v) => MetaGrpcReq::ListKV(v),
+            MetaGrpcReadReq::HasKV(v) => MetaGrpcReq::GetKV(GetKVReq::new(v.key)),
+            MetaGrpcReadReq::ListKeys(v) => MetaGrpcReq::ListKV(ListKVReq::new(v.prefix)),
         }
     }
 }
@@ -192,6 +202,14 @@ impl RequestFor for Streamed<ListKVReq> {
     type Reply = BoxStream<StreamItem>;
 }
 
+impl RequestFor for Streamed<HasKVReq> {
+    type Reply = BoxStream<StreamItem>;
+}
+
+impl RequestFor for Streamed<ListKeysReq> {
+    type Reply = BoxStream<StreamItem>;
+}
+
 impl RequestFor for UpsertKVReq {
     type Reply = UpsertKVReply;
 }
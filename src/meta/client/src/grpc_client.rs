@@ -30,6 +30,7 @@ use databend_common_base::base::tokio::sync::mpsc::Sender;
 use databend_common_base::base::tokio::sync::oneshot;
 use databend_common_base::base::tokio::sync::oneshot::Receiver as OneRecv;
 use databend_common_base::base::tokio::sync::oneshot::Sender as OneSend;
+use databend_common_base::base::tokio::sync::Semaphore;
 use databend_common_base::base::tokio::time::sleep;
 use databend_common_base::containers::ItemManager;
 use databend_common_base::containers::Pool;
@@ -40,9 +41,11 @@ use databend_common_base::runtime::UnlimitedFuture;
 use databend_common_base::GLOBAL_TASK;
 use databend_common_grpc::ConnectionFactory;
 use databend_common_grpc::GrpcConnectionError;
+use databend_common_grpc::KeepAliveConf;
 use databend_common_grpc::RpcClientConf;
 use databend_common_grpc::RpcClientTlsConfig;
 use databend_common_meta_api::reply::reply_to_api_result;
+use databend_common_meta_kvapi::kvapi::BackoffPolicy;
 use databend_common_meta_types::anyerror::AnyError;
 use databend_common_meta_types::protobuf as pb;
 use databend_common_meta_types::protobuf::meta_service_client::MetaServiceClient;
@@ -62,6 +65,7 @@ use databend_common_meta_types::MetaClientError;
 use databend_common_meta_types::MetaError;
 use databend_common_meta_types::MetaHandshakeError;
 use databend_common_meta_types::MetaNetworkError;
+use databend_common_meta_types::RequestTimeout;
 use databend_common_meta_types::TxnReply;
 use databend_common_meta_types::TxnRequest;
 use futures::stream::StreamExt;
@@ -99,6 +103,7 @@ use crate::to_digit_ver;
 use crate::ClientWorkerRequest;
 use crate::MetaGrpcReadReq;
 use crate::MetaGrpcReq;
+use crate::RequestTimeouts;
 use crate::METACLI_COMMIT_SEMVER;
 use crate::MIN_METASRV_SEMVER;
 
@@ -114,14 +119,30 @@ pub struct MetaChannelManager {
     timeout: Option<Duration>,
     tls_config: Option<RpcClientTlsConfig>,
 
+    /// The keep-alive of every channel, `None` disables it.
+    ///
+    /// A connection whose PING is not acknowledged is closed,
+    /// and the client built on it is rebuilt by the pool when it is used next time.
+    keep_alive: Option<KeepAliveConf>,
+
     /// The endpoints of the meta-service cluster.
     ///
     /// The endpoints will be added to a built client item
     /// and will be updated when a error or successful response is received.
     endpoints: Arc<Mutex<Endpoints>>,
+
+    /// The backoff between the attempts to establish a client to an endpoint.
+    ///
+    /// Jitter spreads the reconnects of many clients when a meta-service node restarts.
+    reconnect_backoff: BackoffPolicy,
 }
 
 impl MetaChannelManager {
+    /// The max number of attempts to establish a client in one `build()`.
+    const RECONNECT_ATTEMPTS: u32 = 3;
+    const RECONNECT_BASE_BACKOFF: Duration = Duration::from_millis(50);
+    const RECONNECT_MAX_BACKOFF: Duration = Duration::from_secs(1);
+
     pub fn new(
         username: impl ToString,
         password: impl ToString,
@@ -134,10 +155,23 @@ impl MetaChannelManager {
             password: password.to_string(),
             timeout,
             tls_config,
+            keep_alive: None,
             endpoints,
+            reconnect_backoff: BackoffPolicy::new(
+                Self::RECONNECT_BASE_BACKOFF,
+                2.0,
+                Self::RECONNECT_MAX_BACKOFF,
+            )
+            .with_jitter(true),
         }
     }
 
+    /// Keep every channel alive with `keep_alive`, `None` disables it.
+    pub fn with_keep_alive(mut self, keep_alive: Option<KeepAliveConf>) -> Self {
+        self.keep_alive = keep_alive;
+        self
+    }
+
     async fn new_established_client(
         &self,
         addr: &String,
@@ -187,19 +221,24 @@ impl MetaChannelManager {
     async fn build_channel(&self, addr: &String) -> Result<Channel, MetaNetworkError> {
         info!("build channel to {}", addr);
 
-        let ch = ConnectionFactory::create_rpc_channel(addr, self.timeout, self.tls_config.clone())
-            .await
-            .map_err(|e| match e {
-                GrpcConnectionError::InvalidUri { .. } => MetaNetworkError::BadAddressFormat(
-                    AnyError::new(&e).add_context(|| "while creating rpc channel"),
-                ),
-                GrpcConnectionError::TLSConfigError { .. } => MetaNetworkError::TLSConfigError(
-                    AnyError::new(&e).add_context(|| "while creating rpc channel"),
-                ),
-                GrpcConnectionError::CannotConnect { .. } => MetaNetworkError::ConnectionError(
-                    ConnectionError::new(e, "while creating rpc channel"),
-                ),
-            })?;
+        let ch = ConnectionFactory::create_rpc_channel_with_keep_alive(
+            addr,
+            self.timeout,
+            self.tls_config.clone(),
+            self.keep_alive,
+        )
+        .await
+        .map_err(|e| match e {
+            GrpcConnectionError::InvalidUri { .. } => MetaNetworkError::BadAddressFormat(
+                AnyError::new(&e).add_context(|| "while creating rpc channel"),
+            ),
+            GrpcConnectionError::TLSConfigError { .. } => MetaNetworkError::TLSConfigError(
+                AnyError::new(&e).add_context(|| "while creating rpc channel"),
+            ),
+            GrpcConnectionError::CannotConnect { .. } => MetaNetworkError::ConnectionError(
+                ConnectionError::new(e, "while creating rpc channel"),
+            ),
+        })?;
         Ok(ch)
     }
 }
@@ -213,7 +252,28 @@ impl ItemManager for MetaChannelManager {
     #[logcall::logcall(err = "debug")]
     #[minitrace::trace]
     async fn build(&self, addr: &Self::Key) -> Result<Self::Item, Self::Error> {
-        self.new_established_client(addr).await
+        let mut backoff = self.reconnect_backoff.clone();
+        backoff.reset();
+
+        let mut attempt = 1;
+        loop {
+            let err = match self.new_established_client(addr).await {
+                Ok(client) => return Ok(client),
+                Err(e) => e,
+            };
+
+            if attempt >= Self::RECONNECT_ATTEMPTS {
+                return Err(err);
+            }
+
+            let delay = backoff.next_delay();
+            warn!(
+                "failed to connect to {} at {}th attempt: {}; retry after {:?}",
+                addr, attempt, err, delay
+            );
+            sleep(delay).await;
+            attempt += 1;
+        }
     }
 
     #[logcall::logcall(err = "debug")]
@@ -222,7 +282,15 @@ impl ItemManager for MetaChannelManager {
         // The underlying `tonic::transport::channel::Channel` reconnects when server is down.
         // But we still need to assert the readiness, e.g., when handshake token expires
         // If there was an error occurred, the channel will be closed.
+        // The pool then rebuilds it, with the backoff of this manager, while holding the lock of this endpoint,
+        // thus concurrent requests to a broken endpoint trigger only one reconnect.
         if let Some(e) = ch.take_error() {
+            let endpoint = ch.target_endpoint();
+            info!(
+                "reconnect to {}, the channel is broken by error: {:?}",
+                endpoint, e
+            );
+            grpc_metrics::incr_meta_grpc_client_reconnect(endpoint);
             return Err(MetaNetworkError::from(e).into());
         }
         Ok(ch)
@@ -238,8 +306,16 @@ pub struct ClientHandle {
     /// `oneshot::Receiver` impl `Drop` by sending a closed notification to the `Sender` half.
     #[allow(dead_code)]
     cancel_auto_sync_rx: OneRecv<()>,
+    /// Every in-flight request holds a permit.
+    /// It is closed by `close()` to reject new requests.
+    inflight: Semaphore,
+    /// The deadline of every request, by the request name, and the idle timeout of a returned stream.
+    request_timeouts: RequestTimeouts,
 }
 
+/// The max number of requests a [`ClientHandle`] sends concurrently.
+const MAX_INFLIGHT_REQUESTS: u32 = u32::MAX;
+
 impl ClientHandle {
     /// Send a request to the internal worker task, which may be running in another runtime.
     #[minitrace::trace]
@@ -253,12 +329,20 @@ impl ClientHandle {
     {
         static META_REQUEST_ID: AtomicU64 = AtomicU64::new(1);
 
+        let req: message::Request = req.into();
+        let req_name = req.name();
+        let timeout = self
+            .request_timeouts
+            .timeout_of(req_name)
+            .map(|t| (t, tokio::time::Instant::now() + t));
+        let stream_idle_timeout = self.request_timeouts.stream_idle_timeout();
+
         let request_future = async move {
             let (tx, rx) = oneshot::channel();
             let req = message::ClientWorkerRequest {
                 request_id: META_REQUEST_ID.fetch_add(1, Ordering::Relaxed),
                 resp_tx: tx,
-                req: req.into(),
+                req,
                 span: Span::enter_with_local_parent(std::any::type_name::<
                     message::ClientWorkerRequest,
                 >()),
@@ -269,6 +353,13 @@ impl ClientHandle {
                 "Meta ClientHandle send request to meta client worker"
             );
 
+            let _permit = self.inflight.acquire().await.map_err(|e| {
+                let cli_err = MetaClientError::ClientRuntimeError(
+                    AnyError::new(&e).add_context(|| "when sending req to a closed MetaGrpcClient"),
+                );
+                E::from(cli_err)
+            })?;
+
             grpc_metrics::incr_meta_grpc_client_request_inflight(1);
 
             let res = self.req_tx.send(req).await.map_err(|e| {
@@ -289,7 +380,28 @@ impl ClientHandle {
                 return Err(err);
             }
 
-            let res = rx.await.map_err(|e| {
+            let res = match timeout {
+                None => rx.await,
+                Some((timeout, deadline)) => {
+                    let Ok(res) = tokio::time::timeout_at(deadline, rx).await else {
+                        grpc_metrics::incr_meta_grpc_client_request_inflight(-1);
+
+                        warn!(
+                            "Meta ClientHandle request {} does not finish in {:?}",
+                            req_name, timeout
+                        );
+
+                        // Dropping `rx` lets the worker know no one is waiting for the response.
+                        let timeout_err = RequestTimeout::new(timeout, req_name);
+                        return Err(
+                            MetaClientError::from(MetaNetworkError::from(timeout_err)).into()
+                        );
+                    };
+                    res
+                }
+            };
+
+            let res = res.map_err(|e| {
                 grpc_metrics::incr_meta_grpc_client_request_inflight(-1);
 
                 error!(
@@ -303,6 +415,12 @@ impl ClientHandle {
             })?;
 
             grpc_metrics::incr_meta_grpc_client_request_inflight(-1);
+
+            let res = match stream_idle_timeout {
+                Some(idle) => res.with_stream_idle_timeout(idle),
+                None => res,
+            };
+
             let res: Result<Req::Reply, E> = res
                 .try_into()
                 .map_err(|e| {
@@ -336,6 +454,22 @@ impl ClientHandle {
     pub async fn get_cached_endpoints(&self) -> Result<Vec<String>, MetaError> {
         self.request(message::GetEndpoints {}).await
     }
+
+    /// Stop accepting new requests and wait for the in-flight ones to complete.
+    ///
+    /// A request sent after `close()` fails with a `ClientRuntimeError`.
+    /// It is idempotent: every call returns after the handle is closed.
+    ///
+    /// The worker and its connections are released when the last handle is dropped.
+    pub async fn close(&self) {
+        // Holding all of the permits means no request is in flight.
+        // A concurrent `close()` gets an error here after the first one closes the semaphore.
+        if let Ok(permits) = self.inflight.acquire_many(MAX_INFLIGHT_REQUESTS).await {
+            permits.forget();
+            self.inflight.close();
+            info!("MetaGrpcClient handle closed");
+        }
+    }
 }
 
 // TODO: maybe it just needs a runtime, not a MetaGrpcClientWorker.
@@ -376,6 +510,7 @@ impl MetaGrpcClient {
     ///
     /// It creates a new `Runtime` and spawn a background worker task in it that do all the RPC job.
     /// A client-handle is returned to communicate with the worker.
+    /// The handle applies the default [`RequestTimeouts`], i.e., no request has a deadline.
     ///
     /// Thus the real work is done in the dedicated runtime to avoid the client spawning tasks in the caller's runtime, which potentially leads to a deadlock if the caller has blocking calls to other components
     /// Because `tower` and `hyper` will spawn tasks when handling RPCs.
@@ -383,14 +518,7 @@ impl MetaGrpcClient {
     /// The worker is a singleton and the returned handle is cheap to clone.
     /// When all handles are dropped the worker will quit, then the runtime will be destroyed.
     pub fn try_new(conf: &RpcClientConf) -> Result<Arc<ClientHandle>, MetaClientError> {
-        Self::try_create(
-            conf.get_endpoints(),
-            &conf.username,
-            &conf.password,
-            conf.timeout,
-            conf.auto_sync_interval,
-            conf.tls_conf.clone(),
-        )
+        Self::try_new_with_timeouts(conf, RequestTimeouts::default())
     }
 
     #[minitrace::trace]
@@ -402,12 +530,68 @@ impl MetaGrpcClient {
         auto_sync_interval: Option<Duration>,
         tls_config: Option<RpcClientTlsConfig>,
     ) -> Result<Arc<ClientHandle>, MetaClientError> {
+        Self::try_create_with_timeouts(
+            endpoints,
+            username,
+            password,
+            timeout,
+            auto_sync_interval,
+            tls_config,
+            RequestTimeouts::default(),
+        )
+    }
+
+    /// Create a new client of metasrv whose handle applies `request_timeouts` to every request.
+    ///
+    /// `timeout` applies to every RPC on the underlying channel,
+    /// while `request_timeouts` makes a request fail with a [`RequestTimeout`] error,
+    /// which can be told apart from an error returned by the meta-service.
+    #[minitrace::trace]
+    pub fn try_create_with_timeouts(
+        endpoints: Vec<String>,
+        username: &str,
+        password: &str,
+        timeout: Option<Duration>,
+        auto_sync_interval: Option<Duration>,
+        tls_config: Option<RpcClientTlsConfig>,
+        request_timeouts: RequestTimeouts,
+    ) -> Result<Arc<ClientHandle>, MetaClientError> {
+        let conf = RpcClientConf {
+            endpoints,
+            username: username.to_string(),
+            password: password.to_string(),
+            tls_conf: tls_config,
+            timeout,
+            auto_sync_interval,
+            ..Default::default()
+        };
+
+        Self::try_new_with_timeouts(&conf, request_timeouts)
+    }
+
+    /// Create a new client of metasrv with `conf`, whose handle applies `request_timeouts` to every request.
+    ///
+    /// The connections to the meta-service are kept alive with `conf.keep_alive` if it is set.
+    #[minitrace::trace]
+    pub fn try_new_with_timeouts(
+        conf: &RpcClientConf,
+        request_timeouts: RequestTimeouts,
+    ) -> Result<Arc<ClientHandle>, MetaClientError> {
+        let endpoints = conf.get_endpoints();
+        let auto_sync_interval = conf.auto_sync_interval;
+
         Self::endpoints_non_empty(&endpoints)?;
 
         let endpoints = Arc::new(Mutex::new(Endpoints::new(endpoints)));
 
-        let mgr =
-            MetaChannelManager::new(username, password, timeout, tls_config, endpoints.clone());
+        let mgr = MetaChannelManager::new(
+            &conf.username,
+            &conf.password,
+            conf.timeout,
+            conf.tls_conf.clone(),
+            endpoints.clone(),
+        )
+        .with_keep_alive(conf.keep_alive);
 
         let rt =
             Runtime::with_worker_threads(1, Some("meta-client-rt".to_string())).map_err(|e| {
@@ -425,10 +609,13 @@ impl MetaGrpcClient {
         let handle = Arc::new(ClientHandle {
             req_tx: tx,
             cancel_auto_sync_rx: one_rx,
+            inflight: Semaphore::new(MAX_INFLIGHT_REQUESTS as usize),
+            request_timeouts,
         });
 
         let worker = Arc::new(Self {
-            conn_pool: Pool::new(mgr, Duration::from_millis(50)),
+            // `MetaChannelManager::build()` retries with its own backoff.
+            conn_pool: Pool::new(mgr, Duration::from_millis(50)).with_retries(1),
             endpoints,
             auto_sync_interval,
             rt: rt.clone(),
@@ -524,6 +711,23 @@ impl MetaGrpcClient {
                     .await;
                 message::Response::StreamMGet(strm)
             }
+            message::Request::StreamHasKV(r) => {
+                let strm = self
+                    .kv_read_v1(MetaGrpcReadReq::HasKV(r.into_inner()))
+                    .timed_ge(threshold(), info_spent("MetaGrpcClient::kv_read_v1(HasKV)"))
+                    .await;
+                message::Response::StreamMGet(strm)
+            }
+            message::Request::StreamListKeys(r) => {
+                let strm = self
+                    .kv_read_v1(MetaGrpcReadReq::ListKeys(r.into_inner()))
+                    .timed_ge(
+                        threshold(),
+                        info_spent("MetaGrpcClient::kv_read_v1(ListKeys)"),
+                    )
+                    .await;
+                message::Response::StreamMGet(strm)
+            }
             message::Request::Upsert(r) => {
                 let resp = self
                     .kv_api(r)
@@ -1088,6 +1292,13 @@ impl MetaGrpcClient {
             Err(s) => {
                 if status_is_retryable(&s) {
                     self.choose_next_endpoint();
+                }
+
+                // A transaction that may have been applied is sent again only if it is read-only.
+                // `Unauthenticated` is returned before the transaction is applied.
+                let safe_to_retry = s.code() == Code::Unauthenticated || txn_is_read_only(&txn);
+
+                if status_is_retryable(&s) && safe_to_retry {
                     let mut client = self.make_established_client().await?;
                     let req: Request<TxnRequest> = Request::new(txn);
                     let req = databend_common_tracing::inject_span_to_tonic_request(req);
@@ -1133,6 +1344,18 @@ fn status_is_retryable(status: &Status) -> bool {
     )
 }
 
+/// Whether a transaction consists of only `get` operations, thus it is safe to send again.
+///
+/// A transaction that writes is not retried, even an unconditional one:
+/// applied twice, a put increases the seq twice and may overwrite a write by another client in between,
+/// and a transaction does not carry an idempotency token, as an `UpsertKV` does, to de-duplicate it.
+fn txn_is_read_only(txn: &TxnRequest) -> bool {
+    txn.if_then
+        .iter()
+        .chain(txn.else_then.iter())
+        .all(|op| matches!(op.request, Some(pb::txn_op::Request::Get(_))))
+}
+
 /// Fill in auth token into request metadata.
 ///
 /// The token is stored in a `OnceCell`, which is fill in when handshake is done.
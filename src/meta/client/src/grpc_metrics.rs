@@ -42,6 +42,9 @@ pub static META_GRPC_CLIENT_REQUEST_FAILED: LazyLock<Family<VecLabels, Counter>>
 pub static META_GRPC_MAKE_CLIENT_FAIL: LazyLock<Family<VecLabels, Counter>> =
     LazyLock::new(|| register_counter_family("meta_grpc_make_client_fail"));
 
+pub static META_GRPC_CLIENT_RECONNECT: LazyLock<Family<VecLabels, Counter>> =
+    LazyLock::new(|| register_counter_family("meta_grpc_client_reconnect"));
+
 const LABEL_ENDPOINT: &str = "endpoint";
 const LABEL_REQUEST: &str = "request";
 const LABEL_ERROR: &str = "error";
@@ -91,3 +94,8 @@ pub fn incr_meta_grpc_make_client_fail(endpoint: &str) {
     let labels = vec![(LABEL_ENDPOINT, endpoint.to_string())];
     META_GRPC_MAKE_CLIENT_FAIL.get_or_create(&labels).inc();
 }
+
+pub fn incr_meta_grpc_client_reconnect(endpoint: &str) {
+    let labels = vec![(LABEL_ENDPOINT, endpoint.to_string())];
+    META_GRPC_CLIENT_RECONNECT.get_or_create(&labels).inc();
+}
@@ -13,14 +13,22 @@
 // limitations under the License.
 
 use databend_common_meta_kvapi::kvapi;
+use databend_common_meta_kvapi::kvapi::HasKVReq;
 use databend_common_meta_kvapi::kvapi::KVStream;
 use databend_common_meta_kvapi::kvapi::ListKVReq;
+use databend_common_meta_kvapi::kvapi::ListKeysReq;
 use databend_common_meta_kvapi::kvapi::MGetKVReq;
 use databend_common_meta_kvapi::kvapi::UpsertKVReply;
 use databend_common_meta_kvapi::kvapi::UpsertKVReq;
+use databend_common_meta_kvapi::kvapi::WatchEvent;
+use databend_common_meta_types::protobuf::watch_request::FilterType;
+use databend_common_meta_types::protobuf::WatchRequest;
+use databend_common_meta_types::InvalidArgument;
 use databend_common_meta_types::MetaError;
+use databend_common_meta_types::MetaNetworkError;
 use databend_common_meta_types::TxnReply;
 use databend_common_meta_types::TxnRequest;
+use futures::stream::BoxStream;
 use futures::StreamExt;
 use futures::TryStreamExt;
 
@@ -37,6 +45,34 @@ impl kvapi::KVApi for ClientHandle {
         Ok(reply)
     }
 
+    /// Ping with the `GetClientInfo` RPC, which does not access the key-value store.
+    #[minitrace::trace]
+    async fn ping(&self) -> Result<(), Self::Error> {
+        self.get_client_info().await?;
+        Ok(())
+    }
+
+    /// Check the existence with a `HasKV` read request, for which the meta-service does not send back the value.
+    ///
+    /// A meta-service that does not support `HasKV` rejects it as an invalid argument,
+    /// in which case it falls back to `get_kv()`.
+    #[minitrace::trace]
+    async fn has_kv(&self, key: &str) -> Result<bool, Self::Error> {
+        let res: Result<_, MetaError> = self.request(Streamed(HasKVReq::new(key))).await;
+
+        let mut strm = match res {
+            Ok(strm) => strm,
+            Err(MetaError::NetworkError(MetaNetworkError::InvalidArgument(_))) => {
+                let got = self.get_kv(key).await?;
+                return Ok(got.is_some());
+            }
+            Err(e) => return Err(e),
+        };
+
+        let item = strm.try_next().await.map_err(MetaError::from)?;
+        Ok(item.and_then(|x| x.value).is_some())
+    }
+
     #[minitrace::trace]
     async fn get_kv_stream(&self, keys: &[String]) -> Result<KVStream<Self::Error>, Self::Error> {
         let keys = keys.to_vec();
@@ -57,9 +93,63 @@ impl kvapi::KVApi for ClientHandle {
         Ok(strm.boxed())
     }
 
+    /// List with a `ListKeys` read request, for which the meta-service does not send back the values.
+    ///
+    /// A meta-service that does not support `ListKeys` rejects it as an invalid argument,
+    /// in which case it falls back to dropping the values from `list_kv()`.
+    #[minitrace::trace]
+    async fn prefix_list_keys(&self, prefix: &str) -> Result<Vec<String>, Self::Error> {
+        let res: Result<_, MetaError> = self.request(Streamed(ListKeysReq::new(prefix))).await;
+
+        let strm = match res {
+            Ok(strm) => strm,
+            Err(MetaError::NetworkError(MetaNetworkError::InvalidArgument(_))) => {
+                let strm = self.list_kv(prefix).await?;
+                let keys = strm.map_ok(|x| x.key).try_collect::<Vec<_>>().await?;
+                return Ok(keys);
+            }
+            Err(e) => return Err(e),
+        };
+
+        let keys = strm
+            .map_ok(|x| x.key)
+            .try_collect::<Vec<_>>()
+            .await
+            .map_err(MetaError::from)?;
+        Ok(keys)
+    }
+
     #[minitrace::trace]
     async fn transaction(&self, txn: TxnRequest) -> Result<TxnReply, Self::Error> {
         let reply = self.request(txn).await?;
         Ok(reply)
     }
+
+    /// The meta-service waits for a slow consumer only for a bounded time:
+    /// if the buffer of the watch stream is still full, the following events are dropped,
+    /// and a `WatchEventKind::Lagged(n)` event is delivered in place of them.
+    #[minitrace::trace]
+    async fn watch(
+        &self,
+        prefix: &str,
+    ) -> Result<BoxStream<'_, Result<WatchEvent, Self::Error>>, Self::Error> {
+        // An empty `key_end` of a prefix without upper bound, such as "", watches all keys since `key`.
+        let (key, key_end) = kvapi::prefix_to_range(prefix)
+            .map_err(|e| MetaError::from(InvalidArgument::new(e, "watch")))?;
+
+        let req = WatchRequest {
+            key,
+            key_end: Some(key_end),
+            filter_type: FilterType::All.into(),
+            report_lag: true,
+        };
+
+        let strm = self.request(req).await?;
+
+        let strm = strm
+            .map_err(MetaError::from)
+            .try_filter_map(|resp| futures::future::ready(Ok(WatchEvent::from_response(resp))));
+
+        Ok(strm.boxed())
+    }
 }
@@ -24,6 +24,7 @@ mod grpc_client;
 mod grpc_metrics;
 mod kv_api_impl;
 mod message;
+mod request_timeouts;
 
 use std::sync::LazyLock;
 
@@ -36,6 +37,7 @@ pub use grpc_client::MetaChannelManager;
 pub use grpc_client::MetaGrpcClient;
 pub use message::ClientWorkerRequest;
 pub use message::Streamed;
+pub use request_timeouts::RequestTimeouts;
 use semver::BuildMetadata;
 use semver::Prerelease;
 use semver::Version;
@@ -93,12 +95,24 @@ pub static METACLI_COMMIT_SEMVER: LazyLock<Version> = LazyLock::new(|| {
 ///   client: remove using MetaGrpcReq::GetKV/MGetKV/ListKV;
 ///   client: remove falling back kv_read_v1(Streamed(List)) to kv_api(List), added in `2023-10-20: since 1.2.176`;
 ///
+/// - 2026-10-14: since 1.2.288:
+///   Meta service: add: kv_read_v1() request `HasKV`, which does not send back the value;
+///   Meta service: add: kv_read_v1() request `ListKeys`, which does not send back the values;
+///   Meta client: `has_kv()` falls back to `get_kv()` if the server rejects `HasKV`;
+///   Meta client: `prefix_list_keys()` falls back to `list_kv()` if the server rejects `ListKeys`;
+///   Meta service: add: watch request with an empty `key_end` watches all keys since `key`;
+///   Meta client: `watch("")` sends an empty `key_end`, an older server watches only the key "".
+///   `MIN_METASRV_SEMVER` is not changed, because the client falls back if the server is older.
+///
 /// Server feature set:
 /// ```yaml
 /// server_features:
 ///   txn_delete_match_seq: ["2023-05-07", "1.1.32", ]
 ///   pb_seqv_meta:         ["2023-10-11", "1.2.153", ]
 ///   kv_read_v1:           ["2023-10-17", "1.2.163", ]
+///   kv_read_v1_has_kv:    ["2026-10-14", "1.2.288", ]
+///   kv_read_v1_list_keys: ["2026-10-14", "1.2.288", ]
+///   watch_open_range:     ["2026-10-14", "1.2.288", ]
 /// ```
 pub static MIN_METASRV_SEMVER: Version = Version {
     major: 1,
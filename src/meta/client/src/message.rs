@@ -14,9 +14,12 @@
 
 use std::fmt;
 use std::fmt::Formatter;
+use std::time::Duration;
 
 use databend_common_base::base::tokio::sync::oneshot::Sender;
+use databend_common_meta_kvapi::kvapi::HasKVReq;
 use databend_common_meta_kvapi::kvapi::ListKVReq;
+use databend_common_meta_kvapi::kvapi::ListKeysReq;
 use databend_common_meta_kvapi::kvapi::MGetKVReq;
 use databend_common_meta_kvapi::kvapi::UpsertKVReply;
 use databend_common_meta_kvapi::kvapi::UpsertKVReq;
@@ -34,6 +37,7 @@ use minitrace::Span;
 use tonic::codegen::BoxStream;
 
 use crate::established_client::EstablishedClient;
+use crate::request_timeouts::with_idle_timeout;
 
 /// A request that is sent by a meta-client handle to its worker.
 pub struct ClientWorkerRequest {
@@ -77,6 +81,12 @@ pub enum Request {
     /// List KVs by key prefix, returning a stream.
     StreamList(Streamed<ListKVReq>),
 
+    /// Check if a KV exists, returning a stream of at most one item without the value.
+    StreamHasKV(Streamed<HasKVReq>),
+
+    /// List keys by key prefix, returning a stream of items without values.
+    StreamListKeys(Streamed<ListKeysReq>),
+
     /// Update or insert KV
     Upsert(UpsertKVReq),
 
@@ -107,6 +117,8 @@ impl Request {
         match self {
             Request::StreamMGet(_) => "StreamMGet",
             Request::StreamList(_) => "StreamList",
+            Request::StreamHasKV(_) => "StreamHasKV",
+            Request::StreamListKeys(_) => "StreamListKeys",
             Request::Upsert(_) => "Upsert",
             Request::Txn(_) => "Txn",
             Request::Watch(_) => "Watch",
@@ -134,6 +146,21 @@ pub enum Response {
     GetClientInfo(Result<ClientInfo, MetaError>),
 }
 
+impl Response {
+    /// Make a returned `StreamMGet` or `StreamList` stream fail if it does not yield the next item in `idle`.
+    pub(crate) fn with_stream_idle_timeout(self, idle: Duration) -> Self {
+        match self {
+            Response::StreamMGet(Ok(strm)) => {
+                Response::StreamMGet(Ok(with_idle_timeout(strm, idle, "StreamMGet")))
+            }
+            Response::StreamList(Ok(strm)) => {
+                Response::StreamList(Ok(with_idle_timeout(strm, idle, "StreamList")))
+            }
+            other => other,
+        }
+    }
+}
+
 impl fmt::Debug for Response {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
         match self {
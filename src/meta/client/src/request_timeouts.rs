@@ -0,0 +1,156 @@
+// Copyright 2021 Datafuse Labs
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::BTreeMap;
+use std::time::Duration;
+
+use databend_common_base::base::tokio;
+use futures::StreamExt;
+use tonic::codegen::BoxStream;
+use tonic::Status;
+
+/// The deadlines of the requests a [`ClientHandle`](crate::ClientHandle) sends to the worker.
+///
+/// A deadline is looked up by the request name, such as `"Upsert"`, `"Txn"` or `"StreamList"`,
+/// and falls back to the default one if the request is not configured.
+/// By default no request has a deadline.
+///
+/// For a streaming request, such as `"StreamList"` used by `list_kv()` and `prefix_list_kv()`,
+/// the deadline applies to opening the stream only, since reading a big listing takes unbounded time.
+/// Instead, the stream idle timeout bounds the wait for every item of a `"StreamMGet"` or `"StreamList"` stream.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RequestTimeouts {
+    default: Option<Duration>,
+    requests: BTreeMap<&'static str, Duration>,
+    stream_idle: Option<Duration>,
+}
+
+impl RequestTimeouts {
+    /// Create a timeout config that applies `default` to every request.
+    pub fn new(default: Duration) -> Self {
+        Self {
+            default: Some(default),
+            ..Self::default()
+        }
+    }
+
+    /// Override the deadline of the request named `name`.
+    pub fn with_request(mut self, name: &'static str, timeout: Duration) -> Self {
+        self.requests.insert(name, timeout);
+        self
+    }
+
+    /// Fail a stream returned by a request if the next item does not arrive in `timeout`.
+    pub fn with_stream_idle_timeout(mut self, timeout: Duration) -> Self {
+        self.stream_idle = Some(timeout);
+        self
+    }
+
+    /// Returns the deadline that applies to the request named `name`, or `None` if there is no deadline.
+    pub fn timeout_of(&self, name: &str) -> Option<Duration> {
+        self.requests.get(name).copied().or(self.default)
+    }
+
+    /// Returns the max time to wait for the next item of a stream, or `None` if it is not bounded.
+    pub fn stream_idle_timeout(&self) -> Option<Duration> {
+        self.stream_idle
+    }
+}
+
+/// Wrap `strm` so that it yields a `DeadlineExceeded` error and ends
+/// if the next item does not arrive in `idle`.
+pub(crate) fn with_idle_timeout<T>(
+    strm: BoxStream<T>,
+    idle: Duration,
+    name: &'static str,
+) -> BoxStream<T>
+where
+    T: Send + 'static,
+{
+    let strm = futures::stream::unfold(Some(strm), move |strm| async move {
+        let mut strm = strm?;
+
+        match tokio::time::timeout(idle, strm.next()).await {
+            Ok(Some(item)) => Some((item, Some(strm))),
+            Ok(None) => None,
+            Err(_elapsed) => {
+                let status = Status::deadline_exceeded(format!(
+                    "{} stream yields no item in {:?}",
+                    name, idle
+                ));
+                Some((Err(status), None))
+            }
+        }
+    });
+
+    Box::pin(strm)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use databend_common_base::base::tokio;
+    use futures::StreamExt;
+    use tonic::Code;
+
+    use crate::request_timeouts::with_idle_timeout;
+    use crate::RequestTimeouts;
+
+    #[test]
+    fn test_request_timeouts() {
+        let t = RequestTimeouts::new(Duration::from_secs(1))
+            .with_request("StreamList", Duration::from_secs(10))
+            .with_request("Upsert", Duration::from_secs(2));
+
+        assert_eq!(Some(Duration::from_secs(10)), t.timeout_of("StreamList"));
+        assert_eq!(Some(Duration::from_secs(2)), t.timeout_of("Upsert"));
+        assert_eq!(Some(Duration::from_secs(1)), t.timeout_of("Txn"));
+        assert_eq!(None, t.stream_idle_timeout());
+
+        // No deadline by default
+        let t = RequestTimeouts::default();
+        assert_eq!(None, t.timeout_of("Txn"));
+
+        let t = RequestTimeouts::default().with_request("Upsert", Duration::from_secs(2));
+        assert_eq!(Some(Duration::from_secs(2)), t.timeout_of("Upsert"));
+        assert_eq!(None, t.timeout_of("Txn"));
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+    async fn test_with_idle_timeout() {
+        let idle = Duration::from_millis(100);
+
+        // A stream that ends in time
+        let strm = futures::stream::iter([Ok(1), Ok(2)]).boxed();
+        let got = with_idle_timeout(strm, idle, "test")
+            .collect::<Vec<_>>()
+            .await;
+        assert_eq!(
+            vec![1, 2],
+            got.into_iter().map(|x| x.unwrap()).collect::<Vec<_>>()
+        );
+
+        // A stream that stalls after the first item
+        let strm = futures::stream::iter([Ok(1)])
+            .chain(futures::stream::pending())
+            .boxed();
+        let got = with_idle_timeout(strm, idle, "test")
+            .collect::<Vec<_>>()
+            .await;
+        assert_eq!(2, got.len());
+        assert_eq!(1, *got[0].as_ref().unwrap());
+        assert_eq!(Code::DeadlineExceeded, got[1].as_ref().unwrap_err().code());
+    }
+}
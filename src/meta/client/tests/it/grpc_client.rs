@@ -14,6 +14,7 @@
 
 use std::sync::Arc;
 use std::time::Duration;
+use std::time::Instant;
 
 use databend_common_base::base::tokio;
 use databend_common_exception::ErrorCode;
@@ -21,12 +22,14 @@ use databend_common_grpc::ConnectionFactory;
 use databend_common_meta_client::ClientHandle;
 use databend_common_meta_client::MetaChannelManager;
 use databend_common_meta_client::MetaGrpcClient;
+use databend_common_meta_client::RequestTimeouts;
 use databend_common_meta_client::Streamed;
 use databend_common_meta_client::MIN_METASRV_SEMVER;
 use databend_common_meta_kvapi::kvapi::MGetKVReq;
 use databend_common_meta_types::protobuf::StreamItem;
 use databend_common_meta_types::MetaClientError;
 use databend_common_meta_types::MetaError;
+use databend_common_meta_types::MetaNetworkError;
 use databend_common_meta_types::UpsertKV;
 use futures::StreamExt;
 use log::info;
@@ -59,6 +62,95 @@ async fn test_grpc_client_timeout() -> anyhow::Result<()> {
     Ok(())
 }
 
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_grpc_client_request_timeouts() -> anyhow::Result<()> {
+    // The mock server sleeps 2 seconds in handshake and 60 seconds in kv_api,
+    // and replies kv_read_v1 at once.
+    let (srv_addr, _shutdown, _task_handle) = start_grpc_server();
+
+    let expect_timeout = |err: MetaError, want: Duration| match err {
+        MetaError::ClientError(MetaClientError::NetworkError(
+            MetaNetworkError::RequestTimeout(t),
+        )) => assert_eq!(want, t.timeout()),
+        _ => panic!("expect RequestTimeout, got: {:?}", err),
+    };
+
+    info!("--- every request honors its own deadline");
+    {
+        let timeouts = RequestTimeouts::new(Duration::from_secs(1))
+            .with_request("StreamMGet", Duration::from_secs(10))
+            .with_request("Upsert", Duration::from_secs(3));
+        let client = new_client_with_timeouts(&srv_addr, timeouts)?;
+
+        let res: Result<BoxStream<StreamItem>, MetaError> =
+            client.request(Streamed(MGetKVReq::new(["foo"]))).await;
+        assert!(res.is_ok(), "handshake within 10 seconds: {:?}", res.err());
+
+        let start = Instant::now();
+        let res = client.request(UpsertKV::insert("foo", b"foo")).await;
+        expect_timeout(res.unwrap_err(), Duration::from_secs(3));
+        assert!(start.elapsed() >= Duration::from_secs(3));
+        assert!(start.elapsed() < Duration::from_secs(10));
+    }
+
+    info!("--- a request not configured uses the default deadline");
+    {
+        let timeouts = RequestTimeouts::new(Duration::from_millis(500))
+            .with_request("Upsert", Duration::from_secs(3));
+        let client = new_client_with_timeouts(&srv_addr, timeouts)?;
+
+        // The handshake does not finish in 500 ms
+        let res: Result<BoxStream<StreamItem>, MetaError> =
+            client.request(Streamed(MGetKVReq::new(["foo"]))).await;
+        expect_timeout(res.err().unwrap(), Duration::from_millis(500));
+    }
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_grpc_client_stream_idle_timeout() -> anyhow::Result<()> {
+    // The mock server replies one item to kv_read_v1 then stalls.
+    let (srv_addr, _shutdown, _task_handle) = start_grpc_server();
+
+    info!("--- a stalled stream is not bounded by default");
+    {
+        let client = new_client_with_timeouts(&srv_addr, RequestTimeouts::default())?;
+
+        let res: Result<BoxStream<StreamItem>, MetaError> =
+            client.request(Streamed(MGetKVReq::new(["foo"]))).await;
+        let mut strm = res?;
+        assert!(strm.next().await.unwrap().is_ok());
+
+        let res = tokio::time::timeout(Duration::from_secs(1), strm.next()).await;
+        assert!(res.is_err(), "still waiting for the next item");
+    }
+
+    info!("--- a stalled stream fails after the idle timeout");
+    {
+        let timeouts =
+            RequestTimeouts::default().with_stream_idle_timeout(Duration::from_millis(500));
+        let client = new_client_with_timeouts(&srv_addr, timeouts)?;
+
+        let res: Result<BoxStream<StreamItem>, MetaError> =
+            client.request(Streamed(MGetKVReq::new(["foo"]))).await;
+        let mut strm = res?;
+        assert!(strm.next().await.unwrap().is_ok(), "the first item in time");
+
+        let start = Instant::now();
+        let err = strm.next().await.unwrap().unwrap_err();
+        assert_eq!(tonic::Code::DeadlineExceeded, err.code());
+        assert!(start.elapsed() >= Duration::from_millis(500));
+
+        assert!(
+            strm.next().await.is_none(),
+            "the stream ends after the timeout"
+        );
+    }
+
+    Ok(())
+}
+
 #[tokio::test(flavor = "multi_thread", worker_threads = 1)]
 async fn test_grpc_client_handshake_timeout() {
     let (srv_addr, _shutdown, _task_handle) = start_grpc_server();
@@ -162,8 +254,53 @@ async fn test_grpc_client_reconnect() -> anyhow::Result<()> {
     Ok(())
 }
 
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_grpc_client_close() -> anyhow::Result<()> {
+    let (srv_addr, _shutdown, _task_handle) = start_grpc_server();
+    let client = new_client(&srv_addr, Some(Duration::from_secs(3)))?;
+
+    info!("--- close without in-flight request");
+    client.close().await;
+
+    info!("--- no request is accepted after close");
+    {
+        let res: Result<Vec<String>, MetaError> = client.get_cached_endpoints().await;
+        let err = res.unwrap_err();
+        assert!(
+            matches!(
+                err,
+                MetaError::ClientError(MetaClientError::ClientRuntimeError(_))
+            ),
+            "got: {:?}",
+            err
+        );
+    }
+
+    info!("--- close is idempotent");
+    client.close().await;
+
+    Ok(())
+}
+
 fn new_client(addr: impl ToString, timeout: Option<Duration>) -> anyhow::Result<Arc<ClientHandle>> {
     let client = MetaGrpcClient::try_create(vec![addr.to_string()], "", "", timeout, None, None)?;
 
     Ok(client)
 }
+
+fn new_client_with_timeouts(
+    addr: impl ToString,
+    timeouts: RequestTimeouts,
+) -> anyhow::Result<Arc<ClientHandle>> {
+    let client = MetaGrpcClient::try_create_with_timeouts(
+        vec![addr.to_string()],
+        "",
+        "",
+        None,
+        None,
+        None,
+        timeouts,
+    )?;
+
+    Ok(client)
+}
@@ -38,6 +38,7 @@ use databend_common_meta_types::protobuf::TxnRequest;
 use databend_common_meta_types::protobuf::WatchRequest;
 use databend_common_meta_types::protobuf::WatchResponse;
 use futures::Stream;
+use futures::StreamExt;
 use rand::Rng;
 use tonic::codegen::BoxStream;
 use tonic::transport::Server;
@@ -80,8 +81,9 @@ impl MetaService for GrpcServiceForTestImpl {
         &self,
         _request: Request<RaftRequest>,
     ) -> Result<Response<Self::KvReadV1Stream>, Status> {
+        // Reply one item, then stall without ending the stream, for the stream idle timeout test.
         let itm = StreamItem::new("kv_read_v1".to_string(), None);
-        let output = futures::stream::once(async { Ok(itm) });
+        let output = futures::stream::once(async { Ok(itm) }).chain(futures::stream::pending());
         Ok(Response::new(Box::pin(output)))
     }
 
@@ -34,6 +34,11 @@ use log::warn;
 /// Since `StateMachine` is backed with sled::Tree, this impl has the same limitation as metasrv:
 /// - A sled::Db has to be a singleton, according to sled doc.
 /// - Every unit test has to generate a unique sled::Tree name to create a `MetaEmbedded`.
+///
+/// A local kvapi::KVApi impl backed by an embedded sled db, for single node deployment and tests.
+///
+/// Data is persisted in the process-wise sled db and survives a restart if it is initialized with a path.
+/// Operations are serialized by a lock, thus a `transaction` is isolated from other operations.
 #[derive(Clone)]
 pub struct MetaEmbedded {
     pub(crate) inner: Arc<Mutex<StateMachine>>,
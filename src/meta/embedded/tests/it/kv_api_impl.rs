@@ -15,6 +15,9 @@
 use databend_common_base::base::tokio;
 use databend_common_meta_embedded::MetaEmbedded;
 use databend_common_meta_kvapi::kvapi;
+use databend_common_meta_kvapi::kvapi::KVApi;
+use databend_common_meta_kvapi::kvapi::UpsertKVReq;
+use databend_common_meta_sled_store::get_sled_db;
 
 #[tokio::test(flavor = "multi_thread")]
 async fn test_kv_write_read() -> anyhow::Result<()> {
@@ -28,6 +31,30 @@ async fn test_kv_delete() -> anyhow::Result<()> {
     kvapi::TestSuite {}.kv_delete(&kv).await
 }
 
+#[tokio::test(flavor = "multi_thread")]
+async fn test_kv_delete_kv() -> anyhow::Result<()> {
+    let kv = MetaEmbedded::new_temp().await?;
+    kvapi::TestSuite {}.kv_delete_kv(&kv).await
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_kv_has_kv() -> anyhow::Result<()> {
+    let kv = MetaEmbedded::new_temp().await?;
+    kvapi::TestSuite {}.kv_has_kv(&kv).await
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_kv_ping() -> anyhow::Result<()> {
+    let kv = MetaEmbedded::new_temp().await?;
+    kvapi::TestSuite {}.kv_ping(&kv).await
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_kv_delete_prefix_kv() -> anyhow::Result<()> {
+    let kv = MetaEmbedded::new_temp().await?;
+    kvapi::TestSuite {}.kv_delete_prefix_kv(&kv).await
+}
+
 #[tokio::test(flavor = "multi_thread")]
 async fn test_kv_update() -> anyhow::Result<()> {
     let kv = MetaEmbedded::new_temp().await?;
@@ -40,6 +67,54 @@ async fn test_kv_timeout() -> anyhow::Result<()> {
     kvapi::TestSuite {}.kv_timeout(&kv).await
 }
 
+#[tokio::test(flavor = "multi_thread")]
+async fn test_kv_ext_upsert_with_ttl() -> anyhow::Result<()> {
+    let kv = MetaEmbedded::new_temp().await?;
+    kvapi::TestSuite {}.kv_ext_upsert_with_ttl(&kv).await
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_kv_ext_mget_map() -> anyhow::Result<()> {
+    let kv = MetaEmbedded::new_temp().await?;
+    kvapi::TestSuite {}.kv_ext_mget_map(&kv).await
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_kv_ext_mget_ordered() -> anyhow::Result<()> {
+    let kv = MetaEmbedded::new_temp().await?;
+    kvapi::TestSuite {}.kv_ext_mget_ordered(&kv).await
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_kv_list_rev() -> anyhow::Result<()> {
+    let kv = MetaEmbedded::new_temp().await?;
+    kvapi::TestSuite {}.kv_list_rev(&kv).await
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_kv_transaction_chunked() -> anyhow::Result<()> {
+    let kv = MetaEmbedded::new_temp().await?;
+    kvapi::TestSuite {}.kv_transaction_chunked(&kv).await
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_kv_transaction_many() -> anyhow::Result<()> {
+    let kv = MetaEmbedded::new_temp().await?;
+    kvapi::TestSuite {}.kv_transaction_many(&kv).await
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_kv_transaction_reply_by_key() -> anyhow::Result<()> {
+    let kv = MetaEmbedded::new_temp().await?;
+    kvapi::TestSuite {}.kv_transaction_reply_by_key(&kv).await
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_kv_ext_get_if_newer() -> anyhow::Result<()> {
+    let kv = MetaEmbedded::new_temp().await?;
+    kvapi::TestSuite {}.kv_ext_get_if_newer(&kv).await
+}
+
 #[tokio::test(flavor = "multi_thread")]
 async fn test_kv_meta() -> anyhow::Result<()> {
     let kv = MetaEmbedded::new_temp().await?;
@@ -52,8 +127,109 @@ async fn test_kv_list() -> anyhow::Result<()> {
     kvapi::TestSuite {}.kv_list(&kv).await
 }
 
+#[tokio::test(flavor = "multi_thread")]
+async fn test_kv_list_keys() -> anyhow::Result<()> {
+    let kv = MetaEmbedded::new_temp().await?;
+    kvapi::TestSuite {}.kv_list_keys(&kv).await
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_kv_count_prefix() -> anyhow::Result<()> {
+    let kv = MetaEmbedded::new_temp().await?;
+    kvapi::TestSuite {}.kv_count_prefix(&kv).await
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_kv_list_paged() -> anyhow::Result<()> {
+    let kv = MetaEmbedded::new_temp().await?;
+    kvapi::TestSuite {}.kv_list_paged(&kv).await
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_kv_list_stream() -> anyhow::Result<()> {
+    let kv = MetaEmbedded::new_temp().await?;
+    kvapi::TestSuite {}.kv_list_stream(&kv).await
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_kv_range_list() -> anyhow::Result<()> {
+    let kv = MetaEmbedded::new_temp().await?;
+    kvapi::TestSuite {}.kv_range_list(&kv).await
+}
+
 #[tokio::test(flavor = "multi_thread")]
 async fn test_kv_mget() -> anyhow::Result<()> {
     let kv = MetaEmbedded::new_temp().await?;
     kvapi::TestSuite {}.kv_mget(&kv).await
 }
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_kv_mset() -> anyhow::Result<()> {
+    let kv = MetaEmbedded::new_temp().await?;
+    kvapi::TestSuite {}.kv_mset(&kv).await
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_kv_increment() -> anyhow::Result<()> {
+    let kv = MetaEmbedded::new_temp().await?;
+    kvapi::TestSuite {}.kv_increment(&kv).await
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_kv_ext_prefix_list_json() -> anyhow::Result<()> {
+    let kv = MetaEmbedded::new_temp().await?;
+    kvapi::TestSuite {}.kv_ext_prefix_list_json(&kv).await
+}
+
+/// The phase of `test_kv_reopen` a child process runs: `write` or `read`.
+const REOPEN_PHASE: &str = "META_EMBEDDED_TEST_REOPEN_PHASE";
+
+/// The on-disk path of the sled db a child process of `test_kv_reopen` opens.
+const REOPEN_PATH: &str = "META_EMBEDDED_TEST_REOPEN_PATH";
+
+/// Data written by a `MetaEmbedded` is loaded from disk by another one.
+///
+/// The process-wise sled db can not be closed,
+/// thus the write and the read are run by two processes, one after another, on the same path.
+#[test]
+fn test_kv_reopen() -> anyhow::Result<()> {
+    let dir = tempfile::tempdir()?;
+    let path = dir.path().join("meta");
+
+    for phase in ["write", "read"] {
+        let status = std::process::Command::new(std::env::current_exe()?)
+            .args(["--exact", "kv_api_impl::test_kv_reopen_phase"])
+            .env(REOPEN_PHASE, phase)
+            .env(REOPEN_PATH, &path)
+            .status()?;
+
+        assert!(status.success(), "phase {} failed: {}", phase, status);
+    }
+
+    Ok(())
+}
+
+/// One phase of `test_kv_reopen`; it does nothing unless run by `test_kv_reopen` in a child process.
+#[tokio::test(flavor = "multi_thread")]
+async fn test_kv_reopen_phase() -> anyhow::Result<()> {
+    let Ok(phase) = std::env::var(REOPEN_PHASE) else {
+        return Ok(());
+    };
+    let path = std::env::var(REOPEN_PATH)?;
+
+    MetaEmbedded::init_global_meta_store(path).await?;
+    let kv = MetaEmbedded::get_meta().await?;
+
+    match phase.as_str() {
+        "write" => {
+            kv.upsert_kv(UpsertKVReq::update("k1", b"v1")).await?;
+            get_sled_db().flush_async().await?;
+        }
+        "read" => {
+            assert_eq!(Some(b"v1".to_vec()), kv.get_kv("k1").await?.map(|x| x.data));
+        }
+        _ => anyhow::bail!("unknown phase: {}", phase),
+    }
+
+    Ok(())
+}
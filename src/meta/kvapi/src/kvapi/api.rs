@@ -12,25 +12,40 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::collections::VecDeque;
 use std::ops::Deref;
+use std::time::Duration;
 
+use anyerror::AnyError;
 use async_trait::async_trait;
 use databend_common_meta_types::errors;
 use databend_common_meta_types::protobuf::StreamItem;
+use databend_common_meta_types::txn_op_response;
+use databend_common_meta_types::InvalidArgument;
 use databend_common_meta_types::SeqV;
+use databend_common_meta_types::TxnOp;
 use databend_common_meta_types::TxnReply;
 use databend_common_meta_types::TxnRequest;
 use futures_util::stream::BoxStream;
 use futures_util::StreamExt;
 use futures_util::TryStreamExt;
 use log::debug;
+use tokio::time::Instant;
 
 use crate::kvapi;
+use crate::kvapi::mset;
 use crate::kvapi::GetKVReply;
 use crate::kvapi::ListKVReply;
 use crate::kvapi::MGetKVReply;
 use crate::kvapi::UpsertKVReply;
 use crate::kvapi::UpsertKVReq;
+use crate::kvapi::WatchEvent;
+
+/// How long `ApiBuilder::build_cluster_ready()` waits for a cluster to be ready.
+const CLUSTER_READY_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// The interval `ApiBuilder::build_cluster_ready()` pings a node that is not ready yet.
+const CLUSTER_READY_POLL_INTERVAL: Duration = Duration::from_millis(50);
 
 /// Build an API impl instance or a cluster of API impl
 #[async_trait]
@@ -40,11 +55,51 @@ pub trait ApiBuilder<T>: Clone {
 
     /// Create a cluster of T
     async fn build_cluster(&self) -> Vec<T>;
+
+    /// Create a cluster of T and wait until every node of it is ready to serve.
+    ///
+    /// The default implementation pings every node until it succeeds,
+    /// and returns the last error if a node is not ready in 10 seconds.
+    /// Membership is not visible through `KVApi`,
+    /// thus a builder that has access to it should override this method to check that all nodes agree on it.
+    async fn build_cluster_ready(&self) -> anyhow::Result<Vec<T>>
+    where T: kvapi::KVApi + 'async_trait {
+        let cluster = self.build_cluster().await;
+
+        let deadline = Instant::now() + CLUSTER_READY_TIMEOUT;
+
+        for (i, kv) in cluster.iter().enumerate() {
+            loop {
+                let res = tokio::time::timeout_at(deadline, kv.ping()).await;
+
+                let err = match res {
+                    Ok(Ok(())) => break,
+                    Ok(Err(e)) => anyhow::Error::new(e),
+                    Err(elapsed) => anyhow::Error::new(elapsed),
+                };
+
+                if Instant::now() >= deadline {
+                    return Err(err.context(format!(
+                        "node-{} is not ready in {:?}",
+                        i, CLUSTER_READY_TIMEOUT
+                    )));
+                }
+
+                debug!("node-{} is not ready: {}, retry", i, err);
+                tokio::time::sleep(CLUSTER_READY_POLL_INTERVAL).await;
+            }
+        }
+
+        Ok(cluster)
+    }
 }
 
 /// A stream of key-value records that are returned by stream based API such as mget and list.
 pub type KVStream<E> = BoxStream<'static, Result<StreamItem, E>>;
 
+/// The key read by the default `KVApi::ping()`. No record is written to it.
+const PING_KEY: &str = "__fd_ping";
+
 /// API of a key-value store.
 #[async_trait]
 pub trait KVApi: Send + Sync {
@@ -53,11 +108,75 @@ pub trait KVApi: Send + Sync {
     /// Depends on the implementation the error could be different.
     /// E.g., a remove kvapi::KVApi impl returns network error or remote storage error.
     /// A local kvapi::KVApi impl just returns storage error.
-    type Error: std::error::Error + From<errors::IncompleteStream> + Send + Sync + 'static;
+    type Error: std::error::Error
+        + From<errors::IncompleteStream>
+        + From<InvalidArgument>
+        + Send
+        + Sync
+        + 'static;
 
     /// Update or insert a key-value record.
     async fn upsert_kv(&self, req: UpsertKVReq) -> Result<UpsertKVReply, Self::Error>;
 
+    /// Delete a key-value record by key.
+    ///
+    /// The returned `prev` is the value before deletion, `None` if the key does not exist.
+    ///
+    /// This method has a default implementation by calling `upsert_kv()` with a delete operation.
+    async fn delete_kv(&self, key: &str) -> Result<UpsertKVReply, Self::Error> {
+        self.upsert_kv(UpsertKVReq::delete(key)).await
+    }
+
+    /// Delete all of the key-value records whose key starts with `prefix`, and return the number of deleted records.
+    ///
+    /// The records are removed by a single `DeleteByPrefix` operation in one `transaction()`,
+    /// i.e., all keys in the range `prefix_to_range(prefix)` are removed atomically.
+    /// A record whose key only shares part of the prefix, such as `a/bc` for prefix `a/b/`, is not affected.
+    ///
+    /// An empty `prefix` matches every key, it is rejected with an `InvalidArgument` error.
+    async fn delete_prefix_kv(&self, prefix: &str) -> Result<u64, Self::Error> {
+        if prefix.is_empty() {
+            return Err(InvalidArgument::new(
+                AnyError::error("prefix must not be empty"),
+                "delete_prefix_kv",
+            )
+            .into());
+        }
+
+        let txn = TxnRequest::unconditional(vec![TxnOp::delete_by_prefix(prefix)]);
+        let reply = self.transaction(txn).await?;
+
+        let count = reply
+            .responses
+            .iter()
+            .find_map(|r| match &r.response {
+                Some(txn_op_response::Response::DeleteByPrefix(d)) => Some(d.count as u64),
+                _ => None,
+            })
+            .unwrap_or_default();
+
+        Ok(count)
+    }
+
+    /// Apply a batch of upserts in one request, and return one reply for every request in request order.
+    ///
+    /// The default implementation folds them into a single `transaction()`, thus they are applied atomically.
+    /// The `seq` of every request is checked against the state before the batch is applied:
+    /// if any of them does not match, none of the upserts is applied, and every reply has `prev == result`.
+    /// There is no partial failure: when an error is returned, whether the whole batch is applied is unknown.
+    ///
+    /// `Operation::AsIs` can not be expressed in a transaction, the default implementation returns an `InvalidArgument` error for it.
+    async fn mset_kv(&self, reqs: Vec<UpsertKVReq>) -> Result<Vec<UpsertKVReply>, Self::Error> {
+        if reqs.is_empty() {
+            return Ok(vec![]);
+        }
+
+        let txn = mset::mset_txn(&reqs)?;
+        let reply = self.transaction(txn).await?;
+
+        Ok(mset::mset_replies(reply))
+    }
+
     /// Get a key-value record by key.
     // TODO: #[deprecated(note = "use get_kv_stream() instead")]
     async fn get_kv(&self, key: &str) -> Result<GetKVReply, Self::Error> {
@@ -73,6 +192,28 @@ pub trait KVApi: Send + Sync {
         Ok(reply)
     }
 
+    /// Check if a key-value record exists, without returning the value.
+    ///
+    /// It provides no more guarantee about the visibility of concurrent writes than `get_kv()`.
+    ///
+    /// This method has a default implementation by calling `get_kv()`.
+    /// An implementation should override it if the backend supports a cheaper existence check.
+    async fn has_kv(&self, key: &str) -> Result<bool, Self::Error> {
+        let got = self.get_kv(key).await?;
+        Ok(got.is_some())
+    }
+
+    /// Check if the store is reachable, without reading or writing any real data.
+    ///
+    /// It never changes the state of the store.
+    ///
+    /// This method has a default implementation by reading a reserved key with `get_kv()`.
+    /// An implementation should override it if the backend provides a native health check.
+    async fn ping(&self) -> Result<(), Self::Error> {
+        self.get_kv(PING_KEY).await?;
+        Ok(())
+    }
+
     /// Get several key-values by keys.
     // TODO: #[deprecated(note = "use get_kv_stream() instead")]
     async fn mget_kv(&self, keys: &[String]) -> Result<MGetKVReply, Self::Error> {
@@ -127,8 +268,208 @@ pub trait KVApi: Send + Sync {
         Ok(v)
     }
 
+    /// List the keys that starts with the specified prefix, without values.
+    ///
+    /// The keys are returned in the same order as `prefix_list_kv()`.
+    ///
+    /// This method has a default implementation by dropping the values from `list_kv()` when they are received.
+    /// An implementation should override it if the backend is able to list keys only.
+    async fn prefix_list_keys(&self, prefix: &str) -> Result<Vec<String>, Self::Error> {
+        let strm = self.list_kv(prefix).await?;
+
+        let keys = strm.map_ok(|x| x.key).try_collect::<Vec<_>>().await?;
+
+        Ok(keys)
+    }
+
+    /// Count the key-value records that starts with the specified prefix.
+    ///
+    /// This method has a default implementation by counting the keys returned by `prefix_list_keys()`.
+    /// An implementation should override it if the backend is able to count without listing.
+    async fn count_prefix_kv(&self, prefix: &str) -> Result<u64, Self::Error> {
+        let keys = self.prefix_list_keys(prefix).await?;
+        Ok(keys.len() as u64)
+    }
+
+    /// List at most `limit` key-value records that starts with the specified prefix, in lexicographical order.
+    ///
+    /// `after` is an exclusive cursor: only the keys greater than `after` are returned.
+    /// To iterate all of the records, feed the last returned key back as `after`,
+    /// until less than `limit` records are returned.
+    ///
+    /// `after` must start with `prefix`, otherwise an `InvalidArgument` error is returned.
+    ///
+    /// This method has a default implementation by filtering the result from `list_kv()`
+    async fn prefix_list_kv_paged(
+        &self,
+        prefix: &str,
+        after: Option<&str>,
+        limit: usize,
+    ) -> Result<ListKVReply, Self::Error> {
+        if let Some(after) = after {
+            if !after.starts_with(prefix) {
+                return Err(InvalidArgument::new(
+                    AnyError::error(format!(
+                        "cursor '{}' does not start with prefix '{}'",
+                        after, prefix
+                    )),
+                    "prefix_list_kv_paged",
+                )
+                .into());
+            }
+        }
+
+        let strm = self.list_kv(prefix).await?;
+
+        let v = strm
+            .try_skip_while(|x| {
+                let skip = after.map_or(false, |after| x.key.as_str() <= after);
+                futures_util::future::ready(Ok(skip))
+            })
+            .take(limit)
+            .map_ok(|x| {
+                // Safe unwrap(): list_kv() does not return None value
+                (x.key, SeqV::from(x.value.unwrap()))
+            })
+            .try_collect::<Vec<_>>()
+            .await?;
+
+        Ok(v)
+    }
+
+    /// List key-value records whose key is in the left-close-right-open range `[start, end)`, in lexicographical order.
+    ///
+    /// It returns an `InvalidArgument` error if `start > end`.
+    ///
+    /// This method has a default implementation by filtering the result of `list_kv()`
+    /// with the common prefix of `start` and `end`.
+    async fn range_list_kv(&self, start: &str, end: &str) -> Result<ListKVReply, Self::Error> {
+        if start > end {
+            return Err(InvalidArgument::new(
+                AnyError::error(format!(
+                    "range start '{}' is greater than end '{}'",
+                    start, end
+                )),
+                "range_list_kv",
+            )
+            .into());
+        }
+
+        let prefix = kvapi::common_prefix(start, end);
+        let strm = self.list_kv(prefix).await?;
+
+        let v = strm
+            .try_skip_while(|x| futures_util::future::ready(Ok(x.key.as_str() < start)))
+            .try_take_while(|x| futures_util::future::ready(Ok(x.key.as_str() < end)))
+            .map_ok(|x| {
+                // Safe unwrap(): list_kv() does not return None value
+                (x.key, SeqV::from(x.value.unwrap()))
+            })
+            .try_collect::<Vec<_>>()
+            .await?;
+
+        Ok(v)
+    }
+
+    /// List at most `limit` key-value records that starts with the specified prefix,
+    /// in descending lexicographical order.
+    ///
+    /// It is used to find the latest records under a prefix whose keys are ordered by time.
+    ///
+    /// This method has a default implementation by scanning `list_kv()` and keeping only the last `limit` records,
+    /// thus the memory used is bounded by `limit`, but the whole prefix is still scanned.
+    /// An implementation should override it if the backend is able to scan backward.
+    async fn prefix_list_kv_rev(
+        &self,
+        prefix: &str,
+        limit: usize,
+    ) -> Result<ListKVReply, Self::Error> {
+        if limit == 0 {
+            return Ok(vec![]);
+        }
+
+        let mut strm = self.list_kv(prefix).await?;
+        let mut last = VecDeque::with_capacity(limit);
+
+        while let Some(x) = strm.try_next().await? {
+            if last.len() == limit {
+                last.pop_front();
+            }
+            // Safe unwrap(): list_kv() does not return None value
+            last.push_back((x.key, SeqV::from(x.value.unwrap())));
+        }
+
+        Ok(last.into_iter().rev().collect())
+    }
+
+    /// Iterate key-value records that starts with the specified prefix lazily, in lexicographical order.
+    ///
+    /// Unlike `prefix_list_kv()`, the result is not collected into memory at once.
+    ///
+    /// This method has a default implementation by fetching pages with `prefix_list_kv_paged()`.
+    async fn prefix_list_kv_stream(
+        &self,
+        prefix: &str,
+    ) -> Result<BoxStream<'_, Result<(String, SeqV), Self::Error>>, Self::Error> {
+        const PAGE_SIZE: usize = 1024;
+
+        let prefix = prefix.to_string();
+
+        // The state is the cursor of the next page: `None` means there is no more page.
+        let strm = futures_util::stream::try_unfold(Some(None::<String>), move |cursor| {
+            let prefix = prefix.clone();
+            async move {
+                let Some(after) = cursor else {
+                    return Ok::<_, Self::Error>(None);
+                };
+
+                let page = self
+                    .prefix_list_kv_paged(&prefix, after.as_deref(), PAGE_SIZE)
+                    .await?;
+
+                let next = if page.len() < PAGE_SIZE {
+                    None
+                } else {
+                    page.last().map(|(k, _)| Some(k.clone()))
+                };
+
+                Ok(Some((page, next)))
+            }
+        })
+        .map_ok(|page| futures_util::stream::iter(page.into_iter().map(Ok)))
+        .try_flatten();
+
+        Ok(strm.boxed())
+    }
+
     /// Run transaction: update one or more records if specified conditions are met.
     async fn transaction(&self, txn: TxnRequest) -> Result<TxnReply, Self::Error>;
+
+    /// Subscribe to the changes of the keys that starts with the specified prefix.
+    ///
+    /// The events of a key are delivered in commit order.
+    ///
+    /// An implementation must not buffer events without bound for a slow consumer:
+    /// it either applies backpressure to the source of the events,
+    /// or drops the events that do not fit in a bounded buffer and delivers a `WatchEventKind::Lagged(n)` event instead,
+    /// after which the consumer should resync with `prefix_list_kv()`.
+    ///
+    /// Not every implementation is able to push changes:
+    /// the default implementation returns an `InvalidArgument` error.
+    async fn watch(
+        &self,
+        prefix: &str,
+    ) -> Result<BoxStream<'_, Result<WatchEvent, Self::Error>>, Self::Error> {
+        Err(InvalidArgument::new(
+            AnyError::error(format!(
+                "watch '{}' is not supported by {}",
+                prefix,
+                std::any::type_name::<Self>()
+            )),
+            "watch",
+        )
+        .into())
+    }
 }
 
 #[async_trait]
@@ -139,6 +480,26 @@ impl<U: kvapi::KVApi, T: Deref<Target = U> + Send + Sync> kvapi::KVApi for T {
         self.deref().upsert_kv(act).await
     }
 
+    async fn delete_kv(&self, key: &str) -> Result<UpsertKVReply, Self::Error> {
+        self.deref().delete_kv(key).await
+    }
+
+    async fn has_kv(&self, key: &str) -> Result<bool, Self::Error> {
+        self.deref().has_kv(key).await
+    }
+
+    async fn ping(&self) -> Result<(), Self::Error> {
+        self.deref().ping().await
+    }
+
+    async fn delete_prefix_kv(&self, prefix: &str) -> Result<u64, Self::Error> {
+        self.deref().delete_prefix_kv(prefix).await
+    }
+
+    async fn mset_kv(&self, reqs: Vec<UpsertKVReq>) -> Result<Vec<UpsertKVReply>, Self::Error> {
+        self.deref().mset_kv(reqs).await
+    }
+
     async fn get_kv_stream(&self, keys: &[String]) -> Result<KVStream<Self::Error>, Self::Error> {
         self.deref().get_kv_stream(keys).await
     }
@@ -147,9 +508,54 @@ impl<U: kvapi::KVApi, T: Deref<Target = U> + Send + Sync> kvapi::KVApi for T {
         self.deref().list_kv(prefix).await
     }
 
+    async fn prefix_list_keys(&self, prefix: &str) -> Result<Vec<String>, Self::Error> {
+        self.deref().prefix_list_keys(prefix).await
+    }
+
+    async fn count_prefix_kv(&self, prefix: &str) -> Result<u64, Self::Error> {
+        self.deref().count_prefix_kv(prefix).await
+    }
+
+    async fn prefix_list_kv_paged(
+        &self,
+        prefix: &str,
+        after: Option<&str>,
+        limit: usize,
+    ) -> Result<ListKVReply, Self::Error> {
+        self.deref()
+            .prefix_list_kv_paged(prefix, after, limit)
+            .await
+    }
+
+    async fn range_list_kv(&self, start: &str, end: &str) -> Result<ListKVReply, Self::Error> {
+        self.deref().range_list_kv(start, end).await
+    }
+
+    async fn prefix_list_kv_rev(
+        &self,
+        prefix: &str,
+        limit: usize,
+    ) -> Result<ListKVReply, Self::Error> {
+        self.deref().prefix_list_kv_rev(prefix, limit).await
+    }
+
+    async fn prefix_list_kv_stream(
+        &self,
+        prefix: &str,
+    ) -> Result<BoxStream<'_, Result<(String, SeqV), Self::Error>>, Self::Error> {
+        self.deref().prefix_list_kv_stream(prefix).await
+    }
+
     async fn transaction(&self, txn: TxnRequest) -> Result<TxnReply, Self::Error> {
         self.deref().transaction(txn).await
     }
+
+    async fn watch(
+        &self,
+        prefix: &str,
+    ) -> Result<BoxStream<'_, Result<WatchEvent, Self::Error>>, Self::Error> {
+        self.deref().watch(prefix).await
+    }
 }
 
 pub trait AsKVApi {
@@ -0,0 +1,401 @@
+// Copyright 2021 Datafuse Labs
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::BTreeMap;
+use std::collections::BTreeSet;
+use std::collections::HashSet;
+use std::time::Duration;
+
+use anyerror::AnyError;
+use async_trait::async_trait;
+use databend_common_meta_stoerr::MetaBytesError;
+use databend_common_meta_stoerr::MetaStorageError;
+use databend_common_meta_types::txn_op_response;
+use databend_common_meta_types::InvalidArgument;
+use databend_common_meta_types::SeqV;
+use databend_common_meta_types::TxnCondition;
+use databend_common_meta_types::TxnOp;
+use databend_common_meta_types::TxnReply;
+use databend_common_meta_types::TxnRequest;
+use futures_util::future;
+use futures_util::stream;
+use futures_util::StreamExt;
+use futures_util::TryStreamExt;
+use serde::de::DeserializeOwned;
+
+use crate::kvapi;
+use crate::kvapi::GetKVReply;
+use crate::kvapi::ListKVReply;
+use crate::kvapi::UpsertKVReply;
+use crate::kvapi::UpsertKVReq;
+
+/// The max number of compare-and-swap attempts `increment()` makes before giving up.
+const INCREMENT_MAX_ATTEMPTS: usize = 100;
+
+/// The max number of transactions `transaction_many()` keeps in flight.
+const TRANSACTION_MANY_CONCURRENCY: usize = 64;
+
+/// Convenient methods built upon the primitive methods of kvapi::KVApi.
+///
+/// It is implemented for every kvapi::KVApi.
+#[async_trait]
+pub trait KVApiExt: kvapi::KVApi {
+    /// Update or insert a key-value record that expires after `ttl`.
+    ///
+    /// An expired record is not returned by `get_kv()`, `mget_kv()` or `prefix_list_kv()`.
+    async fn upsert_kv_with_ttl(
+        &self,
+        key: &str,
+        value: Vec<u8>,
+        ttl: Duration,
+    ) -> Result<UpsertKVReply, Self::Error> {
+        self.upsert_kv(UpsertKVReq::update(key, &value).with_ttl(ttl))
+            .await
+    }
+
+    /// Update or insert a key-value record and return whether its data is changed.
+    ///
+    /// It is `false` if the new data equals the prior one, or if `req` does not take effect, e.g., the seq does not match,
+    /// so that an idempotent writer can skip the downstream work such as invalidating a cache.
+    /// Creating or deleting a record is a change; deleting an absent record is not.
+    /// A change of only the expiration time is not a change.
+    async fn upsert_kv_changed(&self, req: UpsertKVReq) -> Result<bool, Self::Error> {
+        let reply = self.upsert_kv(req).await?;
+        Ok(reply.is_data_changed())
+    }
+
+    /// Get several key-values by keys, and return the present ones in a map keyed by key.
+    ///
+    /// Absent keys are omitted, thus the caller does not need to correlate the results to `keys` by position.
+    async fn mget_kv_map(&self, keys: &[String]) -> Result<BTreeMap<String, SeqV>, Self::Error> {
+        let values = self.mget_kv(keys).await?;
+
+        let m = keys
+            .iter()
+            .zip(values)
+            .filter_map(|(k, v)| v.map(|v| (k.clone(), v)))
+            .collect();
+
+        Ok(m)
+    }
+
+    /// Get several key-values by keys, and return one entry for every key, in the order of `keys`.
+    ///
+    /// An absent key is `None`, while a present key with an empty value is `Some` with empty data.
+    /// Duplicate keys get one entry each.
+    /// A backend that replies with a different number of values violates the `mget_kv()` contract:
+    /// it panics in debug build, otherwise the missing entries are taken as absent and the extra are dropped.
+    async fn mget_kv_ordered(&self, keys: &[String]) -> Result<Vec<Option<SeqV>>, Self::Error> {
+        let mut values = self.mget_kv(keys).await?;
+
+        debug_assert_eq!(
+            keys.len(),
+            values.len(),
+            "mget_kv() must reply one value for every key"
+        );
+        values.resize(keys.len(), None);
+
+        Ok(values)
+    }
+
+    /// Get several key-values by keys, and return the present ones sorted by key.
+    ///
+    /// Duplicate keys are removed before the single `mget_kv()` is sent, thus every key is read and returned at most once.
+    /// Absent keys are omitted.
+    async fn mget_kv_sorted_unique(
+        &self,
+        keys: &[String],
+    ) -> Result<Vec<(String, SeqV)>, Self::Error> {
+        let unique = keys.iter().cloned().collect::<BTreeSet<_>>();
+        let unique = unique.into_iter().collect::<Vec<_>>();
+
+        let values = self.mget_kv(&unique).await?;
+
+        let res = unique
+            .into_iter()
+            .zip(values)
+            .filter_map(|(k, v)| v.map(|v| (k, v)))
+            .collect();
+
+        Ok(res)
+    }
+
+    /// List key-value records that start with `prefix`, except those whose key is in `exclude`.
+    ///
+    /// The excluded records are dropped from `list_kv()` as they are received, thus they are never collected.
+    async fn prefix_list_kv_excluding(
+        &self,
+        prefix: &str,
+        exclude: &HashSet<String>,
+    ) -> Result<ListKVReply, Self::Error> {
+        let strm = self.list_kv(prefix).await?;
+
+        let v = strm
+            .try_filter(|x| future::ready(!exclude.contains(&x.key)))
+            .map_ok(|x| {
+                // Safe unwrap(): list_kv() does not return None value
+                (x.key, SeqV::from(x.value.unwrap()))
+            })
+            .try_collect::<Vec<_>>()
+            .await?;
+
+        Ok(v)
+    }
+
+    /// Get a key-value record only if it has been changed since the caller read it at `known_seq`.
+    ///
+    /// It returns `None` if the seq of `key` is still `known_seq`,
+    /// otherwise `Some` of what `get_kv()` would return, which is `Some(None)` if `key` has been deleted.
+    /// An absent key has seq 0, thus `known_seq=0` returns `None` if the key still does not exist.
+    ///
+    /// The seq is compared by the backend in a transaction,
+    /// and the value is sent back only when it does not match.
+    async fn get_kv_if_newer(
+        &self,
+        key: &str,
+        known_seq: u64,
+    ) -> Result<Option<GetKVReply>, Self::Error> {
+        let txn = TxnRequest {
+            condition: vec![TxnCondition::eq_seq(key, known_seq)],
+            if_then: vec![],
+            else_then: vec![TxnOp::get(key)],
+        };
+
+        let reply = self.transaction(txn).await?;
+
+        if reply.success {
+            return Ok(None);
+        }
+
+        let value = reply.responses.into_iter().find_map(|r| match r.response {
+            Some(txn_op_response::Response::Get(g)) => Some(g.value.map(SeqV::from)),
+            _ => None,
+        });
+
+        Ok(Some(value.flatten()))
+    }
+
+    /// Get up to `limit` versions of a key, the latest first, i.e., in descending seq order.
+    ///
+    /// **No backend retains the history of a key yet**: a record is overwritten in place by every update.
+    /// Thus it returns at most one version, the current one, and an empty vec if the key does not exist or `limit` is 0.
+    /// A caller must not assume that a previous version is absent just because it is not returned.
+    async fn get_kv_history(&self, key: &str, limit: usize) -> Result<Vec<SeqV>, Self::Error> {
+        if limit == 0 {
+            return Ok(vec![]);
+        }
+
+        let current = self.get_kv(key).await?;
+        Ok(current.into_iter().collect())
+    }
+
+    /// List the key-values with `prefix` and deserialize every value from JSON with `serde_json`, in key order.
+    ///
+    /// It is only for values written as JSON: a value in another format, such as protobuf, fails to decode.
+    ///
+    /// If a value can not be deserialized, it returns a `MetaStorageError::BytesError` with the key in its context,
+    /// and none of the values are returned.
+    async fn prefix_list_json<T>(&self, prefix: &str) -> Result<Vec<(String, T)>, Self::Error>
+    where
+        T: DeserializeOwned + Send,
+        Self::Error: From<MetaStorageError>,
+    {
+        let kvs = self.prefix_list_kv(prefix).await?;
+
+        let mut res = Vec::with_capacity(kvs.len());
+        for (key, seq_v) in kvs {
+            let v = serde_json::from_slice(&seq_v.data)
+                .map_err(|e| MetaBytesError {
+                    source: AnyError::new(&e)
+                        .add_context(|| format!("decode value of key: {}", key)),
+                })
+                .map_err(MetaStorageError::from)?;
+            res.push((key, v));
+        }
+
+        Ok(res)
+    }
+
+    /// Compare-and-swap: put `new_value` to `key` only if the current seq of `key` is `expected_seq`.
+    ///
+    /// An absent key has seq 0, thus `expected_seq=0` means to put only if the key does not exist.
+    ///
+    /// It returns `true` if the value is swapped, or `false` if the seq does not match.
+    async fn cas_kv(
+        &self,
+        key: &str,
+        expected_seq: u64,
+        new_value: Vec<u8>,
+    ) -> Result<bool, Self::Error> {
+        let txn = TxnRequest {
+            condition: vec![TxnCondition::eq_seq(key, expected_seq)],
+            if_then: vec![TxnOp::put(key, new_value)],
+            else_then: vec![],
+        };
+
+        let reply = self.transaction(txn).await?;
+
+        Ok(reply.success)
+    }
+
+    /// Atomically add `delta` to the counter stored in `key` and return the new value.
+    ///
+    /// The counter is stored as a little-endian `i64`; an absent key is treated as 0.
+    /// The addition wraps around on overflow.
+    ///
+    /// It retries the compare-and-swap at most `INCREMENT_MAX_ATTEMPTS` times when other writers update the key concurrently,
+    /// and returns a `MetaStorageError::TransactionConflict` if it still fails.
+    /// If the value is not an 8-byte `i64`, it returns a `MetaStorageError::BytesError`.
+    async fn increment(&self, key: &str, delta: i64) -> Result<i64, Self::Error>
+    where Self::Error: From<MetaStorageError> {
+        for _ in 0..INCREMENT_MAX_ATTEMPTS {
+            let (seq, curr) = match self.get_kv(key).await? {
+                None => (0, 0),
+                Some(seq_v) => (seq_v.seq, decode_counter(&seq_v.data)?),
+            };
+
+            let next = curr.wrapping_add(delta);
+            if self.cas_kv(key, seq, encode_counter(next)).await? {
+                return Ok(next);
+            }
+        }
+
+        Err(MetaStorageError::TransactionConflict.into())
+    }
+
+    /// Run the operations of an unconditional `txn` in several transactions, each of at most `max_ops` operations.
+    ///
+    /// It is meant for a large batch of operations that exceeds the max size of a transaction the backend accepts,
+    /// such as deleting all of the records of a dropped database.
+    ///
+    /// **The operations are NOT all-or-nothing**: the chunks are applied one by one in order,
+    /// and if one chunk fails, the chunks before it have been applied and the ones after it are not.
+    /// Use it only when every operation is safe to apply on its own, and it is safe to retry the whole batch.
+    ///
+    /// Thus a `txn` with `condition` or `else_then` is rejected with an `InvalidArgument` error,
+    /// because a condition can not be evaluated once for all of the chunks.
+    /// If there are at most `max_ops` operations, `txn` is run as one atomic transaction.
+    ///
+    /// It returns the reply of every chunk, in order.
+    async fn transaction_chunked(
+        &self,
+        txn: TxnRequest,
+        max_ops: usize,
+    ) -> Result<Vec<TxnReply>, Self::Error> {
+        if max_ops == 0 {
+            return Err(InvalidArgument::new(
+                AnyError::error("max_ops must be greater than 0"),
+                "transaction_chunked",
+            )
+            .into());
+        }
+
+        if !txn.condition.is_empty() || !txn.else_then.is_empty() {
+            return Err(InvalidArgument::new(
+                AnyError::error("a chunked transaction can not have condition or else_then"),
+                "transaction_chunked",
+            )
+            .into());
+        }
+
+        let mut replies = Vec::with_capacity(txn.if_then.len().div_ceil(max_ops));
+        let mut ops = txn.if_then.into_iter().peekable();
+
+        while ops.peek().is_some() {
+            let chunk = TxnRequest::unconditional(ops.by_ref().take(max_ops).collect());
+            let reply = self.transaction(chunk).await?;
+            replies.push(reply);
+        }
+
+        Ok(replies)
+    }
+
+    /// Submit several independent transactions without waiting for one to finish before sending the next,
+    /// and return their replies in the order of `txns`.
+    ///
+    /// Every transaction is atomic on its own, but the transactions are not atomic as a whole,
+    /// and there is no order in which they are applied.
+    /// A transaction whose condition does not hold gets a reply with `success == false`
+    /// and does not affect the others.
+    /// A transaction that returns an error does not stop the others:
+    /// every transaction gets its own result, and a caller has to check each of them.
+    async fn transaction_many(&self, txns: Vec<TxnRequest>) -> Vec<Result<TxnReply, Self::Error>> {
+        stream::iter(txns)
+            .map(|txn| self.transaction(txn))
+            .buffered(TRANSACTION_MANY_CONCURRENCY)
+            .collect::<Vec<_>>()
+            .await
+    }
+}
+
+impl<T: kvapi::KVApi + ?Sized> KVApiExt for T {}
+
+/// Encode a counter value as little-endian `i64`.
+pub fn encode_counter(v: i64) -> Vec<u8> {
+    v.to_le_bytes().to_vec()
+}
+
+/// Decode a counter value encoded by [`encode_counter`].
+pub fn decode_counter(data: &[u8]) -> Result<i64, MetaStorageError> {
+    let bytes = <[u8; 8]>::try_from(data).map_err(|e| MetaBytesError::new(&e))?;
+    Ok(i64::from_le_bytes(bytes))
+}
+
+#[cfg(test)]
+mod tests {
+    use databend_common_meta_types::TxnOp;
+    use databend_common_meta_types::TxnRequest;
+
+    use crate::kvapi::api_ext::decode_counter;
+    use crate::kvapi::api_ext::encode_counter;
+    use crate::kvapi::KVApi;
+    use crate::kvapi::KVApiExt;
+    use crate::kvapi::MemKVApi;
+    use crate::kvapi::SizeLimitedKVApi;
+
+    #[test]
+    fn test_counter_codec() -> anyhow::Result<()> {
+        assert_eq!(vec![1, 0, 0, 0, 0, 0, 0, 0], encode_counter(1));
+        assert_eq!(-5, decode_counter(&encode_counter(-5))?);
+        assert_eq!(i64::MAX, decode_counter(&encode_counter(i64::MAX))?);
+
+        assert!(decode_counter(b"").is_err());
+        assert!(decode_counter(b"123456789").is_err());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_transaction_many_partial_failure() -> anyhow::Result<()> {
+        let kv = SizeLimitedKVApi::new(MemKVApi::new()).with_max_value_size(10);
+
+        // The 2nd txn is rejected for its large value, the others are applied.
+        let txns = vec![
+            TxnRequest::unconditional(vec![TxnOp::put("a", b"1".to_vec())]),
+            TxnRequest::unconditional(vec![TxnOp::put("b", vec![b'x'; 11])]),
+            TxnRequest::unconditional(vec![TxnOp::put("c", b"3".to_vec())]),
+        ];
+
+        let results = kv.transaction_many(txns).await;
+        assert_eq!(3, results.len());
+        assert!(results[0].as_ref().unwrap().success);
+        assert!(results[1].is_err());
+        assert!(results[2].as_ref().unwrap().success);
+
+        assert!(kv.get_kv("a").await?.is_some());
+        assert!(kv.get_kv("b").await?.is_none());
+        assert!(kv.get_kv("c").await?.is_some());
+
+        Ok(())
+    }
+}
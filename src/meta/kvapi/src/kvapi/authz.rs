@@ -0,0 +1,325 @@
+// Copyright 2021 Datafuse Labs
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! A kvapi::KVApi decorator that checks every accessed key against an authorization predicate.
+
+use std::fmt;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use databend_common_meta_types::txn_op::Request;
+use databend_common_meta_types::PermissionDenied;
+use databend_common_meta_types::TxnReply;
+use databend_common_meta_types::TxnRequest;
+
+use crate::kvapi;
+use crate::kvapi::GetKVReply;
+use crate::kvapi::KVStream;
+use crate::kvapi::ListKVReply;
+use crate::kvapi::MGetKVReply;
+use crate::kvapi::UpsertKVReply;
+use crate::kvapi::UpsertKVReq;
+
+/// The identity on behalf of which an [`AuthzKVApi`] sends requests, such as a tenant.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Principal {
+    name: String,
+}
+
+impl Principal {
+    pub fn new(name: impl ToString) -> Self {
+        Self {
+            name: name.to_string(),
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+}
+
+impl fmt::Display for Principal {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name)
+    }
+}
+
+/// The kind of access to a key.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Access {
+    Read,
+    Write,
+}
+
+impl fmt::Display for Access {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Access::Read => write!(f, "read"),
+            Access::Write => write!(f, "write"),
+        }
+    }
+}
+
+/// A kvapi::KVApi impl that only delegates a request to the inner impl if the principal is allowed to access every key in it.
+///
+/// `authorize(principal, access, key)` decides whether `principal` is allowed to `access` `key`.
+/// A rejected request fails with a [`PermissionDenied`] error and the inner impl is not called.
+///
+/// A prefix to list is checked as a `Read` of the prefix itself,
+/// thus `authorize` must only allow reading a prefix if it allows reading every key that starts with it,
+/// e.g., by checking `key.starts_with("tenant_a/")`.
+///
+/// For a transaction, every key in the conditions and in both branches is checked before it is sent:
+/// a key in a condition or a `get` is a `Read`, the others are a `Write`.
+pub struct AuthzKVApi<T: kvapi::KVApi> {
+    inner: T,
+    principal: Principal,
+    authorize: Arc<dyn Fn(&Principal, Access, &str) -> bool + Send + Sync>,
+}
+
+impl<T> AuthzKVApi<T>
+where
+    T: kvapi::KVApi,
+    T::Error: From<PermissionDenied>,
+{
+    pub fn new(
+        inner: T,
+        principal: Principal,
+        authorize: impl Fn(&Principal, Access, &str) -> bool + Send + Sync + 'static,
+    ) -> Self {
+        Self {
+            inner,
+            principal,
+            authorize: Arc::new(authorize),
+        }
+    }
+
+    pub fn inner(&self) -> &T {
+        &self.inner
+    }
+
+    pub fn principal(&self) -> &Principal {
+        &self.principal
+    }
+
+    fn check(&self, access: Access, key: &str) -> Result<(), T::Error> {
+        if (self.authorize)(&self.principal, access, key) {
+            Ok(())
+        } else {
+            Err(PermissionDenied::new(&self.principal, access, key).into())
+        }
+    }
+
+    fn check_keys(&self, access: Access, keys: &[String]) -> Result<(), T::Error> {
+        for key in keys {
+            self.check(access, key)?;
+        }
+        Ok(())
+    }
+
+    fn check_txn(&self, txn: &TxnRequest) -> Result<(), T::Error> {
+        for cond in &txn.condition {
+            self.check(Access::Read, &cond.key)?;
+        }
+
+        for op in txn.if_then.iter().chain(txn.else_then.iter()) {
+            match &op.request {
+                Some(Request::Get(get)) => self.check(Access::Read, &get.key)?,
+                Some(Request::Put(put)) => self.check(Access::Write, &put.key)?,
+                Some(Request::Delete(del)) => self.check(Access::Write, &del.key)?,
+                Some(Request::DeleteByPrefix(del)) => self.check(Access::Write, &del.prefix)?,
+                Some(Request::ReserveSeq(reserve)) => self.check(Access::Write, &reserve.key)?,
+                None => {}
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl<T> kvapi::KVApi for AuthzKVApi<T>
+where
+    T: kvapi::KVApi,
+    T::Error: From<PermissionDenied>,
+{
+    type Error = T::Error;
+
+    async fn upsert_kv(&self, req: UpsertKVReq) -> Result<UpsertKVReply, Self::Error> {
+        self.check(Access::Write, &req.key)?;
+        self.inner.upsert_kv(req).await
+    }
+
+    async fn mset_kv(&self, reqs: Vec<UpsertKVReq>) -> Result<Vec<UpsertKVReply>, Self::Error> {
+        for req in &reqs {
+            self.check(Access::Write, &req.key)?;
+        }
+        self.inner.mset_kv(reqs).await
+    }
+
+    async fn get_kv(&self, key: &str) -> Result<GetKVReply, Self::Error> {
+        self.check(Access::Read, key)?;
+        self.inner.get_kv(key).await
+    }
+
+    async fn mget_kv(&self, keys: &[String]) -> Result<MGetKVReply, Self::Error> {
+        self.check_keys(Access::Read, keys)?;
+        self.inner.mget_kv(keys).await
+    }
+
+    async fn get_kv_stream(&self, keys: &[String]) -> Result<KVStream<Self::Error>, Self::Error> {
+        self.check_keys(Access::Read, keys)?;
+        self.inner.get_kv_stream(keys).await
+    }
+
+    async fn list_kv(&self, prefix: &str) -> Result<KVStream<Self::Error>, Self::Error> {
+        self.check(Access::Read, prefix)?;
+        self.inner.list_kv(prefix).await
+    }
+
+    async fn prefix_list_kv(&self, prefix: &str) -> Result<ListKVReply, Self::Error> {
+        self.check(Access::Read, prefix)?;
+        self.inner.prefix_list_kv(prefix).await
+    }
+
+    async fn transaction(&self, txn: TxnRequest) -> Result<TxnReply, Self::Error> {
+        self.check_txn(&txn)?;
+        self.inner.transaction(txn).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use databend_common_meta_types::MetaClientError;
+    use databend_common_meta_types::MetaError;
+    use databend_common_meta_types::TxnCondition;
+    use databend_common_meta_types::TxnOp;
+    use databend_common_meta_types::TxnRequest;
+
+    use crate::kvapi::Access;
+    use crate::kvapi::AuthzKVApi;
+    use crate::kvapi::KVApi;
+    use crate::kvapi::MemKVApi;
+    use crate::kvapi::Principal;
+    use crate::kvapi::UpsertKVReq;
+
+    fn is_permission_denied(e: &MetaError) -> bool {
+        matches!(
+            e,
+            MetaError::ClientError(MetaClientError::PermissionDenied(_))
+        )
+    }
+
+    /// A tenant is allowed to read and write only its own prefix; `admin` reads everything.
+    fn tenant_prefix(principal: &Principal, access: Access, key: &str) -> bool {
+        if principal.name() == "admin" {
+            return access == Access::Read;
+        }
+        key.starts_with(&format!("{}/", principal.name()))
+    }
+
+    #[tokio::test]
+    async fn test_authz_cross_tenant() -> anyhow::Result<()> {
+        let kv = MemKVApi::new();
+        kv.upsert_kv(UpsertKVReq::update("a/k1", b"a1")).await?;
+        kv.upsert_kv(UpsertKVReq::update("b/k1", b"b1")).await?;
+
+        let a = AuthzKVApi::new(kv.clone(), Principal::new("a"), tenant_prefix);
+
+        // Own prefix
+        {
+            assert_eq!(
+                Some(b"a1".to_vec()),
+                a.get_kv("a/k1").await?.map(|x| x.data)
+            );
+            a.upsert_kv(UpsertKVReq::update("a/k2", b"a2")).await?;
+            assert_eq!(2, a.prefix_list_kv("a/").await?.len());
+        }
+
+        // Another tenant's prefix
+        {
+            let res = a.prefix_list_kv("b/").await;
+            assert!(is_permission_denied(&res.unwrap_err()));
+
+            // An empty prefix covers every tenant.
+            let res = a.prefix_list_kv("").await;
+            assert!(is_permission_denied(&res.unwrap_err()));
+
+            let res = a.get_kv("b/k1").await;
+            assert!(is_permission_denied(&res.unwrap_err()));
+
+            let res = a.mget_kv(&["a/k1".to_string(), "b/k1".to_string()]).await;
+            assert!(is_permission_denied(&res.unwrap_err()));
+
+            let res = a.upsert_kv(UpsertKVReq::update("b/k1", b"x")).await;
+            assert!(is_permission_denied(&res.unwrap_err()));
+        }
+
+        assert_eq!(
+            Some(b"b1".to_vec()),
+            kv.get_kv("b/k1").await?.map(|x| x.data)
+        );
+
+        // Read only access
+        {
+            let admin = AuthzKVApi::new(kv.clone(), Principal::new("admin"), tenant_prefix);
+            assert_eq!(3, admin.prefix_list_kv("").await?.len());
+
+            let res = admin.delete_kv("a/k1").await;
+            assert!(is_permission_denied(&res.unwrap_err()));
+        }
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_authz_transaction() -> anyhow::Result<()> {
+        let kv = MemKVApi::new();
+        let a = AuthzKVApi::new(kv.clone(), Principal::new("a"), tenant_prefix);
+
+        let txn = TxnRequest::unconditional(vec![TxnOp::put("a/k1", b"1".to_vec())]);
+        a.transaction(txn).await?;
+        assert_eq!(
+            Some(b"1".to_vec()),
+            kv.get_kv("a/k1").await?.map(|x| x.data)
+        );
+
+        // An unauthorized key in a condition.
+        {
+            let txn = TxnRequest {
+                condition: vec![TxnCondition::eq_seq("b/k1", 0)],
+                if_then: vec![TxnOp::put("a/k2", b"2".to_vec())],
+                else_then: vec![],
+            };
+            let res = a.transaction(txn).await;
+            assert!(is_permission_denied(&res.unwrap_err()));
+        }
+
+        // An unauthorized key in the else branch.
+        {
+            let txn = TxnRequest {
+                condition: vec![TxnCondition::eq_seq("a/k1", 0)],
+                if_then: vec![TxnOp::put("a/k2", b"2".to_vec())],
+                else_then: vec![TxnOp::delete_by_prefix("b/")],
+            };
+            let res = a.transaction(txn).await;
+            assert!(is_permission_denied(&res.unwrap_err()));
+        }
+
+        // None of the rejected operations is applied.
+        assert_eq!(None, kv.get_kv("a/k2").await?);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,204 @@
+// Copyright 2021 Datafuse Labs
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Exponential backoff with optional full jitter, shared by the components that retry.
+
+use std::time::Duration;
+
+use rand::rngs::StdRng;
+use rand::Rng;
+use rand::SeedableRng;
+
+/// Produces the delays before successive retries.
+///
+/// The delay starts from `base` and is multiplied by `multiplier` after every retry, up to `max`.
+/// With full jitter, a delay is drawn uniformly from `[0, delay]` instead,
+/// to spread the retries of many clients that fail at the same time.
+///
+/// A policy is a template: a component clones it for every operation it retries, then calls `reset()`.
+/// Cloning an unseeded policy draws a new random source, so that clones do not share jitter;
+/// cloning a seeded one keeps the random state, so that the delays are reproducible.
+#[derive(Debug)]
+pub struct BackoffPolicy {
+    base: Duration,
+    multiplier: f64,
+    max: Duration,
+    jitter: bool,
+
+    /// The delay before jitter of the next retry.
+    next: Duration,
+
+    rng: StdRng,
+    seeded: bool,
+}
+
+impl Clone for BackoffPolicy {
+    fn clone(&self) -> Self {
+        let rng = if self.seeded {
+            self.rng.clone()
+        } else {
+            StdRng::from_entropy()
+        };
+
+        Self {
+            base: self.base,
+            multiplier: self.multiplier,
+            max: self.max,
+            jitter: self.jitter,
+            next: self.next,
+            rng,
+            seeded: self.seeded,
+        }
+    }
+}
+
+impl BackoffPolicy {
+    /// Create a policy without jitter.
+    ///
+    /// A `multiplier` less than 1 is treated as 1, i.e., a constant delay.
+    pub fn new(base: Duration, multiplier: f64, max: Duration) -> Self {
+        let max = max.max(base);
+        Self {
+            base,
+            multiplier: multiplier.max(1.0),
+            max,
+            jitter: false,
+            next: base,
+            rng: StdRng::from_entropy(),
+            seeded: false,
+        }
+    }
+
+    /// Enable or disable full jitter.
+    pub fn with_jitter(mut self, jitter: bool) -> Self {
+        self.jitter = jitter;
+        self
+    }
+
+    /// Make the jitter reproducible by drawing it from a random source seeded with `seed`.
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.rng = StdRng::seed_from_u64(seed);
+        self.seeded = true;
+        self
+    }
+
+    /// The delay before the first retry.
+    pub fn base(&self) -> Duration {
+        self.base
+    }
+
+    /// The upper bound of a delay.
+    pub fn max(&self) -> Duration {
+        self.max
+    }
+
+    /// Return the delay before the next retry and grow the delay for the one after.
+    pub fn next_delay(&mut self) -> Duration {
+        let delay = self.next;
+
+        let nanos = delay.as_nanos() as f64 * self.multiplier;
+        self.next = if nanos >= self.max.as_nanos() as f64 {
+            self.max
+        } else {
+            Duration::from_nanos(nanos.round() as u64)
+        };
+
+        if self.jitter {
+            let nanos = u64::try_from(delay.as_nanos()).unwrap_or(u64::MAX);
+            Duration::from_nanos(self.rng.gen_range(0..=nanos))
+        } else {
+            delay
+        }
+    }
+
+    /// Start over from the base delay, e.g., after an operation succeeds.
+    pub fn reset(&mut self) {
+        self.next = self.base;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use crate::kvapi::BackoffPolicy;
+
+    fn ms(n: u64) -> Duration {
+        Duration::from_millis(n)
+    }
+
+    #[test]
+    fn test_backoff_exponential() {
+        let mut b = BackoffPolicy::new(ms(10), 2.0, ms(50));
+
+        let got = (0..5).map(|_| b.next_delay()).collect::<Vec<_>>();
+        assert_eq!(vec![ms(10), ms(20), ms(40), ms(50), ms(50)], got);
+
+        b.reset();
+        assert_eq!(ms(10), b.next_delay());
+
+        // A fractional multiplier
+        let mut b = BackoffPolicy::new(ms(100), 1.5, ms(1000));
+        let got = (0..3).map(|_| b.next_delay()).collect::<Vec<_>>();
+        assert_eq!(vec![ms(100), ms(150), ms(225)], got);
+
+        // A multiplier less than 1 is a constant delay
+        let mut b = BackoffPolicy::new(ms(10), 0.5, ms(50));
+        assert_eq!(ms(10), b.next_delay());
+        assert_eq!(ms(10), b.next_delay());
+
+        // Does not overflow
+        let mut b = BackoffPolicy::new(ms(10), f64::MAX, Duration::MAX);
+        b.next_delay();
+        assert_eq!(Duration::MAX, b.next_delay());
+    }
+
+    #[test]
+    fn test_backoff_jitter_reproducible() {
+        let new = || {
+            BackoffPolicy::new(ms(10), 2.0, ms(1000))
+                .with_jitter(true)
+                .with_seed(7)
+        };
+
+        let mut b = new();
+        let got1 = (0..10).map(|_| b.next_delay()).collect::<Vec<_>>();
+
+        let mut b = new();
+        let got2 = (0..10).map(|_| b.next_delay()).collect::<Vec<_>>();
+
+        assert_eq!(got1, got2);
+
+        // Every delay is in [0, the delay without jitter]
+        let mut plain = BackoffPolicy::new(ms(10), 2.0, ms(1000));
+        for d in got1 {
+            assert!(d <= plain.next_delay());
+        }
+
+        // A clone of a seeded policy produces the same delays
+        let b = new();
+        let mut c1 = b.clone();
+        let mut c2 = b.clone();
+        for _ in 0..10 {
+            assert_eq!(c1.next_delay(), c2.next_delay());
+        }
+
+        // A different seed produces different delays
+        let mut b1 = new();
+        let mut b2 = new().with_seed(8);
+        let d1 = (0..10).map(|_| b1.next_delay()).collect::<Vec<_>>();
+        let d2 = (0..10).map(|_| b2.next_delay()).collect::<Vec<_>>();
+        assert_ne!(d1, d2);
+    }
+}
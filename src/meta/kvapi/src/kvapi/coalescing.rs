@@ -0,0 +1,356 @@
+// Copyright 2021 Datafuse Labs
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! A kvapi::KVApi decorator that lets concurrent reads of the same key share one request.
+
+use std::collections::BTreeSet;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::sync::Mutex;
+
+use async_trait::async_trait;
+use databend_common_meta_types::TxnReply;
+use databend_common_meta_types::TxnRequest;
+use futures_util::future::BoxFuture;
+use futures_util::future::Shared;
+use futures_util::FutureExt;
+
+use crate::kvapi;
+use crate::kvapi::GetKVReply;
+use crate::kvapi::KVStream;
+use crate::kvapi::ListKVReply;
+use crate::kvapi::MGetKVReply;
+use crate::kvapi::UpsertKVReply;
+use crate::kvapi::UpsertKVReq;
+
+type SharedRead<E> = Shared<BoxFuture<'static, Result<GetKVReply, E>>>;
+
+/// The reads that are in flight, by key.
+///
+/// Every read is tagged with a unique id,
+/// so that a finished read only removes its own entry, but not a newer one of the same key.
+struct Pending<E> {
+    next_id: u64,
+    reads: HashMap<String, (u64, SharedRead<E>)>,
+}
+
+/// A kvapi::KVApi impl that delegates to an inner impl and de-duplicates in-flight `get_kv()` and `mget_kv()`.
+///
+/// A read of a key that is already being read joins the in-flight request instead of sending a new one,
+/// and every caller receives the same result, including the error.
+/// In `mget_kv()`, the keys that are not in flight are read with one `mget_kv()` of the inner impl.
+///
+/// A write through this decorator detaches the in-flight reads of the keys it writes,
+/// so that a read started after the write returns does not join a read started before it.
+/// `transaction()` detaches all of the in-flight reads.
+/// Writes made by other clients are not tracked.
+pub struct CoalescingKVApi<T: kvapi::KVApi> {
+    inner: Arc<T>,
+    pending: Arc<Mutex<Pending<T::Error>>>,
+}
+
+impl<T> CoalescingKVApi<T>
+where
+    T: kvapi::KVApi + 'static,
+    T::Error: Clone,
+{
+    pub fn new(inner: T) -> Self {
+        Self {
+            inner: Arc::new(inner),
+            pending: Arc::new(Mutex::new(Pending {
+                next_id: 0,
+                reads: HashMap::new(),
+            })),
+        }
+    }
+
+    pub fn inner(&self) -> &T {
+        &self.inner
+    }
+
+    /// Returns the number of keys that are being read.
+    pub fn in_flight(&self) -> usize {
+        self.pending.lock().unwrap().reads.len()
+    }
+
+    /// Return a shared read for every key in `keys`, by joining the in-flight ones
+    /// and sending one request to the inner impl for the others.
+    fn shared_reads(&self, keys: &[String]) -> Vec<SharedRead<T::Error>> {
+        let mut pending = self.pending.lock().unwrap();
+
+        let missing = keys
+            .iter()
+            .filter(|k| !pending.reads.contains_key(*k))
+            .cloned()
+            .collect::<BTreeSet<_>>()
+            .into_iter()
+            .collect::<Vec<_>>();
+
+        if !missing.is_empty() {
+            let id = pending.next_id;
+            pending.next_id += 1;
+
+            let inner = self.inner.clone();
+            let pending_ref = self.pending.clone();
+            let batch_keys = missing.clone();
+
+            let batch = async move {
+                let res = if batch_keys.len() == 1 {
+                    inner.get_kv(&batch_keys[0]).await.map(|x| vec![x])
+                } else {
+                    inner.mget_kv(&batch_keys).await
+                };
+
+                let mut pending = pending_ref.lock().unwrap();
+                for key in batch_keys.iter() {
+                    if pending.reads.get(key).map(|(i, _)| *i) == Some(id) {
+                        pending.reads.remove(key);
+                    }
+                }
+
+                res
+            }
+            .boxed()
+            .shared();
+
+            for (i, key) in missing.into_iter().enumerate() {
+                let read = batch
+                    .clone()
+                    .map(move |res| res.map(|values| values[i].clone()))
+                    .boxed()
+                    .shared();
+                pending.reads.insert(key, (id, read));
+            }
+        }
+
+        keys.iter()
+            .map(|k| {
+                // Safe unwrap(): every key is either already in flight or just inserted.
+                pending.reads.get(k).unwrap().1.clone()
+            })
+            .collect()
+    }
+
+    /// Detach the in-flight reads of `keys`, or all of them if `keys` is `None`.
+    fn detach(&self, keys: Option<&[String]>) {
+        let mut pending = self.pending.lock().unwrap();
+        match keys {
+            None => pending.reads.clear(),
+            Some(keys) => {
+                for key in keys {
+                    pending.reads.remove(key);
+                }
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl<T> kvapi::KVApi for CoalescingKVApi<T>
+where
+    T: kvapi::KVApi + 'static,
+    T::Error: Clone,
+{
+    type Error = T::Error;
+
+    async fn upsert_kv(&self, req: UpsertKVReq) -> Result<UpsertKVReply, Self::Error> {
+        let key = req.key.clone();
+        let res = self.inner.upsert_kv(req).await;
+        self.detach(Some(&[key]));
+        res
+    }
+
+    async fn mset_kv(&self, reqs: Vec<UpsertKVReq>) -> Result<Vec<UpsertKVReply>, Self::Error> {
+        let keys = reqs.iter().map(|r| r.key.clone()).collect::<Vec<_>>();
+        let res = self.inner.mset_kv(reqs).await;
+        self.detach(Some(&keys));
+        res
+    }
+
+    async fn get_kv(&self, key: &str) -> Result<GetKVReply, Self::Error> {
+        let mut reads = self.shared_reads(&[key.to_string()]);
+        // Safe unwrap(): there is exactly one read for one key.
+        reads.pop().unwrap().await
+    }
+
+    async fn mget_kv(&self, keys: &[String]) -> Result<MGetKVReply, Self::Error> {
+        let reads = self.shared_reads(keys);
+        futures_util::future::try_join_all(reads).await
+    }
+
+    async fn get_kv_stream(&self, keys: &[String]) -> Result<KVStream<Self::Error>, Self::Error> {
+        self.inner.get_kv_stream(keys).await
+    }
+
+    async fn list_kv(&self, prefix: &str) -> Result<KVStream<Self::Error>, Self::Error> {
+        self.inner.list_kv(prefix).await
+    }
+
+    async fn prefix_list_kv(&self, prefix: &str) -> Result<ListKVReply, Self::Error> {
+        self.inner.prefix_list_kv(prefix).await
+    }
+
+    async fn transaction(&self, txn: TxnRequest) -> Result<TxnReply, Self::Error> {
+        let res = self.inner.transaction(txn).await;
+        self.detach(None);
+        res
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::AtomicU64;
+    use std::sync::atomic::Ordering;
+    use std::time::Duration;
+
+    use async_trait::async_trait;
+    use databend_common_meta_types::protobuf::StreamItem;
+    use databend_common_meta_types::MetaError;
+    use databend_common_meta_types::SeqV;
+    use databend_common_meta_types::TxnReply;
+    use databend_common_meta_types::TxnRequest;
+    use futures_util::stream;
+    use futures_util::StreamExt;
+
+    use crate::kvapi;
+    use crate::kvapi::CoalescingKVApi;
+    use crate::kvapi::GetKVReply;
+    use crate::kvapi::KVApi;
+    use crate::kvapi::KVStream;
+    use crate::kvapi::MGetKVReply;
+    use crate::kvapi::UpsertKVReply;
+    use crate::kvapi::UpsertKVReq;
+
+    /// A KVApi that counts the reads it receives, and replies to a read after a delay.
+    #[derive(Default)]
+    struct CountingKV {
+        get_kv: AtomicU64,
+        mget_kv: AtomicU64,
+    }
+
+    impl CountingKV {
+        fn value_of(key: &str) -> GetKVReply {
+            Some(SeqV::new(1, key.as_bytes().to_vec()))
+        }
+    }
+
+    #[async_trait]
+    impl kvapi::KVApi for CountingKV {
+        type Error = MetaError;
+
+        async fn upsert_kv(&self, _req: UpsertKVReq) -> Result<UpsertKVReply, Self::Error> {
+            Ok(UpsertKVReply::new(None, None))
+        }
+
+        async fn get_kv(&self, key: &str) -> Result<GetKVReply, Self::Error> {
+            self.get_kv.fetch_add(1, Ordering::Relaxed);
+            tokio::time::sleep(Duration::from_millis(100)).await;
+            Ok(Self::value_of(key))
+        }
+
+        async fn mget_kv(&self, keys: &[String]) -> Result<MGetKVReply, Self::Error> {
+            self.mget_kv.fetch_add(1, Ordering::Relaxed);
+            tokio::time::sleep(Duration::from_millis(100)).await;
+            Ok(keys.iter().map(|k| Self::value_of(k)).collect())
+        }
+
+        async fn get_kv_stream(
+            &self,
+            keys: &[String],
+        ) -> Result<KVStream<Self::Error>, Self::Error> {
+            let items = keys
+                .iter()
+                .map(|k| Ok(StreamItem::from((k.clone(), Self::value_of(k)))))
+                .collect::<Vec<_>>();
+            Ok(stream::iter(items).boxed())
+        }
+
+        async fn list_kv(&self, _prefix: &str) -> Result<KVStream<Self::Error>, Self::Error> {
+            Ok(stream::empty().boxed())
+        }
+
+        async fn transaction(&self, _txn: TxnRequest) -> Result<TxnReply, Self::Error> {
+            Ok(TxnReply::default())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_coalesce_get_kv() -> anyhow::Result<()> {
+        let kv = CoalescingKVApi::new(CountingKV::default());
+
+        let n = 100;
+        let reads = (0..n).map(|_| kv.get_kv("a")).collect::<Vec<_>>();
+        let got = futures_util::future::try_join_all(reads).await?;
+
+        assert_eq!(vec![CountingKV::value_of("a"); n], got);
+        assert_eq!(1, kv.inner().get_kv.load(Ordering::Relaxed));
+        assert_eq!(0, kv.in_flight());
+
+        // A read after the previous one finished sends a new request.
+        kv.get_kv("a").await?;
+        assert_eq!(2, kv.inner().get_kv.load(Ordering::Relaxed));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_coalesce_mget_kv() -> anyhow::Result<()> {
+        let kv = CoalescingKVApi::new(CountingKV::default());
+
+        let keys = ["a", "b", "c", "b"].map(|x| x.to_string());
+
+        let (a, abcb, bc) = futures_util::future::try_join3(
+            kv.get_kv("a"),
+            kv.mget_kv(&keys),
+            kv.mget_kv(&keys[1..3]),
+        )
+        .await?;
+
+        assert_eq!(CountingKV::value_of("a"), a);
+        assert_eq!(
+            keys.iter()
+                .map(|k| CountingKV::value_of(k))
+                .collect::<Vec<_>>(),
+            abcb
+        );
+        assert_eq!(abcb[1..3], bc);
+
+        // `a` is read by get_kv(), `b` and `c` are read by one mget_kv().
+        assert_eq!(1, kv.inner().get_kv.load(Ordering::Relaxed));
+        assert_eq!(1, kv.inner().mget_kv.load(Ordering::Relaxed));
+        assert_eq!(0, kv.in_flight());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_coalesce_write_detaches_reads() -> anyhow::Result<()> {
+        let kv = CoalescingKVApi::new(CountingKV::default());
+
+        let before = kv.get_kv("a");
+        let after = async {
+            // Let the first read start, then write and read again.
+            tokio::time::sleep(Duration::from_millis(10)).await;
+            kv.upsert_kv(UpsertKVReq::update("a", b"x")).await?;
+            kv.get_kv("a").await
+        };
+
+        futures_util::future::try_join(before, after).await?;
+
+        assert_eq!(2, kv.inner().get_kv.load(Ordering::Relaxed));
+        assert_eq!(0, kv.in_flight());
+
+        Ok(())
+    }
+}
@@ -0,0 +1,459 @@
+// Copyright 2021 Datafuse Labs
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! A kvapi::KVApi decorator that compresses large values before writing and decompresses them after reading.
+
+use std::fmt;
+
+use anyerror::AnyError;
+use async_trait::async_trait;
+use databend_common_meta_stoerr::MetaBytesError;
+use databend_common_meta_stoerr::MetaStorageError;
+use databend_common_meta_types::protobuf as pb;
+use databend_common_meta_types::protobuf::StreamItem;
+use databend_common_meta_types::txn_condition::Target;
+use databend_common_meta_types::txn_op::Request;
+use databend_common_meta_types::txn_op_response::Response;
+use databend_common_meta_types::Change;
+use databend_common_meta_types::InvalidArgument;
+use databend_common_meta_types::Operation;
+use databend_common_meta_types::SeqV;
+use databend_common_meta_types::TxnReply;
+use databend_common_meta_types::TxnRequest;
+use futures_util::StreamExt;
+
+use crate::kvapi;
+use crate::kvapi::GetKVReply;
+use crate::kvapi::KVStream;
+use crate::kvapi::ListKVReply;
+use crate::kvapi::MGetKVReply;
+use crate::kvapi::UpsertKVReply;
+use crate::kvapi::UpsertKVReq;
+
+/// The leading bytes of a value written by [`CompressingKVApi`], followed by one byte of codec id.
+///
+/// A value written by databend never starts with a `0x00` byte: it is either json or protobuf,
+/// in which a field tag is never 0.
+const MAGIC: &[u8] = b"\x00KVZ";
+
+const HEADER_LEN: usize = MAGIC.len() + 1;
+
+/// The codec id of a value stored as is, after the header.
+///
+/// Used only for a value that is not compressed but starts with [`MAGIC`],
+/// so that it is not mistaken for a compressed one when reading.
+const STORED: u8 = 0;
+
+/// The compression algorithm used by [`CompressingKVApi`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Codec {
+    Lz4,
+    /// Zstd with the compression level.
+    Zstd(i32),
+}
+
+impl Codec {
+    fn id(&self) -> u8 {
+        match self {
+            Codec::Lz4 => 1,
+            Codec::Zstd(_) => 2,
+        }
+    }
+
+    fn compress(&self, data: &[u8]) -> Result<Vec<u8>, MetaBytesError> {
+        let compressed = match self {
+            Codec::Lz4 => lz4::block::compress(data, None, true),
+            Codec::Zstd(level) => zstd::bulk::compress(data, *level),
+        };
+        compressed.map_err(|e| MetaBytesError::new(&e))
+    }
+}
+
+impl fmt::Display for Codec {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Codec::Lz4 => write!(f, "lz4"),
+            Codec::Zstd(level) => write!(f, "zstd({})", level),
+        }
+    }
+}
+
+/// A kvapi::KVApi impl that compresses a value larger than `threshold` bytes before delegating to the inner impl,
+/// and decompresses every value read from it.
+///
+/// A compressed value is prefixed with a header of a magic and the codec id,
+/// thus a value with a header is decompressed with the codec it was written with, regardless of the current codec,
+/// while a value without a header, such as one written before compression is enabled, is returned as is.
+/// A value is stored as is if compressing does not make it smaller.
+///
+/// Values in a transaction are compressed and decompressed too,
+/// but a transaction with a condition that compares the value of a key is rejected with an `InvalidArgument` error,
+/// because the stored bytes differ from the bytes written, and depend on the codec the value was written with.
+///
+/// Every reader of the keys written by it must read through a `CompressingKVApi` too.
+pub struct CompressingKVApi<T: kvapi::KVApi> {
+    inner: T,
+    codec: Codec,
+    threshold: usize,
+}
+
+impl<T> CompressingKVApi<T>
+where
+    T: kvapi::KVApi,
+    T::Error: From<MetaStorageError>,
+{
+    /// The default size in bytes above which a value is compressed: 4 KiB.
+    pub const DEFAULT_THRESHOLD: usize = 4 * 1024;
+
+    pub fn new(inner: T, codec: Codec) -> Self {
+        Self {
+            inner,
+            codec,
+            threshold: Self::DEFAULT_THRESHOLD,
+        }
+    }
+
+    /// Set the size in bytes above which a value is compressed.
+    pub fn with_threshold(mut self, threshold: usize) -> Self {
+        self.threshold = threshold;
+        self
+    }
+
+    pub fn inner(&self) -> &T {
+        &self.inner
+    }
+
+    pub fn codec(&self) -> Codec {
+        self.codec
+    }
+
+    pub fn threshold(&self) -> usize {
+        self.threshold
+    }
+
+    fn encode(&self, data: Vec<u8>) -> Result<Vec<u8>, T::Error> {
+        if data.len() > self.threshold {
+            let compressed = self.codec.compress(&data).map_err(MetaStorageError::from)?;
+            if compressed.len() + HEADER_LEN < data.len() {
+                return Ok(with_header(self.codec.id(), &compressed));
+            }
+        }
+
+        if data.starts_with(MAGIC) {
+            return Ok(with_header(STORED, &data));
+        }
+
+        Ok(data)
+    }
+
+    fn encode_upsert(&self, mut req: UpsertKVReq) -> Result<UpsertKVReq, T::Error> {
+        if let Operation::Update(data) = req.value {
+            req.value = Operation::Update(self.encode(data)?);
+        }
+        Ok(req)
+    }
+
+    fn encode_txn(&self, mut txn: TxnRequest) -> Result<TxnRequest, T::Error> {
+        for cond in txn.condition.iter() {
+            if let Some(Target::Value(_)) = cond.target {
+                return Err(InvalidArgument::new(
+                    AnyError::error(format!(
+                        "condition on the value of key '{}' can not be checked against a compressed value",
+                        cond.key
+                    )),
+                    "transaction",
+                )
+                .into());
+            }
+        }
+
+        for op in txn.if_then.iter_mut().chain(txn.else_then.iter_mut()) {
+            if let Some(Request::Put(put)) = &mut op.request {
+                put.value = self.encode(std::mem::take(&mut put.value))?;
+            }
+        }
+        Ok(txn)
+    }
+}
+
+#[async_trait]
+impl<T> kvapi::KVApi for CompressingKVApi<T>
+where
+    T: kvapi::KVApi,
+    T::Error: From<MetaStorageError>,
+{
+    type Error = T::Error;
+
+    async fn upsert_kv(&self, req: UpsertKVReq) -> Result<UpsertKVReply, Self::Error> {
+        let req = self.encode_upsert(req)?;
+        let reply = self.inner.upsert_kv(req).await?;
+        decode_change(reply)
+    }
+
+    async fn mset_kv(&self, reqs: Vec<UpsertKVReq>) -> Result<Vec<UpsertKVReply>, Self::Error> {
+        let reqs = reqs
+            .into_iter()
+            .map(|req| self.encode_upsert(req))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let replies = self.inner.mset_kv(reqs).await?;
+        replies.into_iter().map(decode_change).collect()
+    }
+
+    async fn get_kv(&self, key: &str) -> Result<GetKVReply, Self::Error> {
+        let reply = self.inner.get_kv(key).await?;
+        reply.map(decode_seqv).transpose()
+    }
+
+    async fn mget_kv(&self, keys: &[String]) -> Result<MGetKVReply, Self::Error> {
+        let reply = self.inner.mget_kv(keys).await?;
+        reply
+            .into_iter()
+            .map(|v| v.map(decode_seqv).transpose())
+            .collect()
+    }
+
+    async fn get_kv_stream(&self, keys: &[String]) -> Result<KVStream<Self::Error>, Self::Error> {
+        let strm = self.inner.get_kv_stream(keys).await?;
+        Ok(strm.map(|r| r.and_then(decode_stream_item)).boxed())
+    }
+
+    async fn list_kv(&self, prefix: &str) -> Result<KVStream<Self::Error>, Self::Error> {
+        let strm = self.inner.list_kv(prefix).await?;
+        Ok(strm.map(|r| r.and_then(decode_stream_item)).boxed())
+    }
+
+    async fn prefix_list_kv(&self, prefix: &str) -> Result<ListKVReply, Self::Error> {
+        let reply = self.inner.prefix_list_kv(prefix).await?;
+        reply
+            .into_iter()
+            .map(|(k, v)| Ok((k, decode_seqv(v)?)))
+            .collect()
+    }
+
+    async fn transaction(&self, txn: TxnRequest) -> Result<TxnReply, Self::Error> {
+        let txn = self.encode_txn(txn)?;
+        let mut reply = self.inner.transaction(txn).await?;
+
+        for resp in reply.responses.iter_mut() {
+            let value = match &mut resp.response {
+                Some(Response::Get(get)) => &mut get.value,
+                Some(Response::Put(put)) => &mut put.prev_value,
+                Some(Response::Delete(del)) => &mut del.prev_value,
+                _ => continue,
+            };
+
+            if let Some(v) = value {
+                v.data = decode(std::mem::take(&mut v.data))?;
+            }
+        }
+
+        Ok(reply)
+    }
+}
+
+fn with_header(codec_id: u8, data: &[u8]) -> Vec<u8> {
+    let mut buf = Vec::with_capacity(HEADER_LEN + data.len());
+    buf.extend_from_slice(MAGIC);
+    buf.push(codec_id);
+    buf.extend_from_slice(data);
+    buf
+}
+
+/// Decompress a value if it has a header, or return it as is.
+fn decode(data: Vec<u8>) -> Result<Vec<u8>, MetaStorageError> {
+    if data.len() < HEADER_LEN || !data.starts_with(MAGIC) {
+        return Ok(data);
+    }
+
+    let payload = &data[HEADER_LEN..];
+
+    let decompressed = match data[MAGIC.len()] {
+        STORED => Ok(payload.to_vec()),
+        1 => lz4::block::decompress(payload, None),
+        2 => zstd::stream::decode_all(payload),
+        id => Err(std::io::Error::new(
+            std::io::ErrorKind::InvalidData,
+            format!("unknown codec id: {}", id),
+        )),
+    };
+
+    decompressed.map_err(|e| MetaBytesError::new(&e).into())
+}
+
+fn decode_seqv<E: From<MetaStorageError>>(mut seq_v: SeqV) -> Result<SeqV, E> {
+    seq_v.data = decode(seq_v.data)?;
+    Ok(seq_v)
+}
+
+fn decode_change<E: From<MetaStorageError>>(
+    mut change: Change<Vec<u8>>,
+) -> Result<Change<Vec<u8>>, E> {
+    change.prev = change.prev.map(decode_seqv).transpose()?;
+    change.result = change.result.map(decode_seqv).transpose()?;
+    Ok(change)
+}
+
+fn decode_stream_item<E: From<MetaStorageError>>(mut item: StreamItem) -> Result<StreamItem, E> {
+    if let Some(pb::SeqV { data, .. }) = &mut item.value {
+        *data = decode(std::mem::take(data))?;
+    }
+    Ok(item)
+}
+
+#[cfg(test)]
+mod tests {
+    use databend_common_meta_types::txn_op_response::Response;
+    use databend_common_meta_types::MetaError;
+    use databend_common_meta_types::MetaNetworkError;
+    use databend_common_meta_types::TxnCondition;
+    use databend_common_meta_types::TxnOp;
+    use databend_common_meta_types::TxnRequest;
+
+    use crate::kvapi::Codec;
+    use crate::kvapi::CompressingKVApi;
+    use crate::kvapi::KVApi;
+    use crate::kvapi::MemKVApi;
+    use crate::kvapi::UpsertKVReq;
+
+    fn large_value() -> Vec<u8> {
+        b"databend-table-meta-".repeat(100)
+    }
+
+    #[tokio::test]
+    async fn test_compressing_round_trip() -> anyhow::Result<()> {
+        for codec in [Codec::Lz4, Codec::Zstd(3)] {
+            let inner = MemKVApi::new();
+            let kv = CompressingKVApi::new(inner.clone(), codec).with_threshold(100);
+
+            let large = large_value();
+            let small = b"small".to_vec();
+
+            kv.upsert_kv(UpsertKVReq::update("a/large", &large)).await?;
+            kv.upsert_kv(UpsertKVReq::update("a/small", &small)).await?;
+
+            // Only the value above the threshold is compressed.
+            let stored = inner.get_kv("a/large").await?.unwrap().data;
+            assert!(stored.len() < large.len(), "{}", codec);
+            assert_eq!(
+                Some(small.clone()),
+                inner.get_kv("a/small").await?.map(|x| x.data)
+            );
+
+            assert_eq!(
+                Some(large.clone()),
+                kv.get_kv("a/large").await?.map(|x| x.data)
+            );
+
+            let got = kv
+                .mget_kv(&["a/large".to_string(), "a/small".to_string()])
+                .await?;
+            let got = got
+                .into_iter()
+                .map(|x| x.map(|x| x.data))
+                .collect::<Vec<_>>();
+            assert_eq!(vec![Some(large.clone()), Some(small.clone())], got);
+
+            let got = kv.prefix_list_kv("a/").await?;
+            let got = got
+                .into_iter()
+                .map(|(k, v)| (k, v.data))
+                .collect::<Vec<_>>();
+            assert_eq!(
+                vec![
+                    ("a/large".to_string(), large),
+                    ("a/small".to_string(), small)
+                ],
+                got
+            );
+        }
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_compressing_legacy_value() -> anyhow::Result<()> {
+        let inner = MemKVApi::new();
+
+        // Written before compression is enabled.
+        let legacy = large_value();
+        inner.upsert_kv(UpsertKVReq::update("k", &legacy)).await?;
+
+        let kv = CompressingKVApi::new(inner.clone(), Codec::Lz4).with_threshold(100);
+        assert_eq!(Some(legacy), kv.get_kv("k").await?.map(|x| x.data));
+
+        // A value that looks like a header is not mistaken for a compressed one.
+        let tricky = b"\x00KVZ\x01not compressed".to_vec();
+        kv.upsert_kv(UpsertKVReq::update("tricky", &tricky)).await?;
+        assert_eq!(Some(tricky), kv.get_kv("tricky").await?.map(|x| x.data));
+
+        // A value written with another codec is still readable.
+        let zstd = CompressingKVApi::new(inner.clone(), Codec::Zstd(3)).with_threshold(100);
+        zstd.upsert_kv(UpsertKVReq::update("z", &large_value()))
+            .await?;
+        assert_eq!(Some(large_value()), kv.get_kv("z").await?.map(|x| x.data));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_compressing_transaction() -> anyhow::Result<()> {
+        let inner = MemKVApi::new();
+        let kv = CompressingKVApi::new(inner.clone(), Codec::Lz4).with_threshold(100);
+
+        let txn = TxnRequest::unconditional(vec![TxnOp::put("k", large_value())]);
+        kv.transaction(txn).await?;
+
+        let stored = inner.get_kv("k").await?.unwrap().data;
+        assert!(stored.len() < large_value().len());
+
+        let txn = TxnRequest::unconditional(vec![TxnOp::get("k")]);
+        let reply = kv.transaction(txn).await?;
+
+        let got = match &reply.responses[0].response {
+            Some(Response::Get(get)) => get.value.as_ref().map(|x| x.data.clone()),
+            _ => unreachable!("expect a get response"),
+        };
+        assert_eq!(Some(large_value()), got);
+
+        // A condition on the value is rejected, a condition on the seq is passed through.
+        {
+            let txn = TxnRequest {
+                condition: vec![TxnCondition::eq_value("k", large_value())],
+                if_then: vec![TxnOp::delete("k")],
+                else_then: vec![],
+            };
+            let res = kv.transaction(txn).await;
+            assert!(matches!(
+                res,
+                Err(MetaError::NetworkError(MetaNetworkError::InvalidArgument(
+                    _
+                )))
+            ));
+            assert!(inner.get_kv("k").await?.is_some(), "not applied");
+
+            let seq = inner.get_kv("k").await?.unwrap().seq;
+            let txn = TxnRequest {
+                condition: vec![TxnCondition::eq_seq("k", seq)],
+                if_then: vec![TxnOp::delete("k")],
+                else_then: vec![],
+            };
+            let reply = kv.transaction(txn).await?;
+            assert!(reply.success);
+            assert!(inner.get_kv("k").await?.is_none());
+        }
+
+        Ok(())
+    }
+}
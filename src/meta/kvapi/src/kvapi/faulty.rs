@@ -0,0 +1,356 @@
+// Copyright 2021 Datafuse Labs
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! A kvapi::KVApi decorator that injects latency and failures, for testing.
+
+use std::collections::BTreeMap;
+use std::future::Future;
+use std::sync::Arc;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use anyerror::AnyError;
+use async_trait::async_trait;
+use databend_common_meta_types::ConnectionError;
+use databend_common_meta_types::MetaNetworkError;
+use databend_common_meta_types::TxnReply;
+use databend_common_meta_types::TxnRequest;
+use rand::Rng;
+
+use crate::kvapi;
+use crate::kvapi::GetKVReply;
+use crate::kvapi::KVStream;
+use crate::kvapi::ListKVReply;
+use crate::kvapi::MGetKVReply;
+use crate::kvapi::UpsertKVReply;
+use crate::kvapi::UpsertKVReq;
+
+#[derive(Debug, Default)]
+struct FaultConfig {
+    latency: Duration,
+    failure_probability: f64,
+    method_failure_probabilities: BTreeMap<&'static str, f64>,
+}
+
+/// The faults a [`FaultyKVApi`] injects.
+///
+/// It is a shared handle: clones of it control the same faults,
+/// thus a test is able to change them while the store is in use.
+#[derive(Clone, Debug, Default)]
+pub struct Faults {
+    config: Arc<Mutex<FaultConfig>>,
+}
+
+impl Faults {
+    /// Create a handle that injects no fault.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Delay every request by `latency`.
+    pub fn set_latency(&self, latency: Duration) {
+        self.config.lock().unwrap().latency = latency;
+    }
+
+    /// Fail every request with probability `p`, unless it is overridden for the method.
+    pub fn set_failure_probability(&self, p: f64) {
+        self.config.lock().unwrap().failure_probability = p;
+    }
+
+    /// Fail the method named `method`, such as `get_kv`, with probability `p`.
+    pub fn set_method_failure_probability(&self, method: &'static str, p: f64) {
+        let mut config = self.config.lock().unwrap();
+        config.method_failure_probabilities.insert(method, p);
+    }
+
+    /// Stop injecting any fault.
+    pub fn clear(&self) {
+        *self.config.lock().unwrap() = FaultConfig::default();
+    }
+
+    pub fn latency(&self) -> Duration {
+        self.config.lock().unwrap().latency
+    }
+
+    /// Returns the probability that `method` fails.
+    pub fn failure_probability_of(&self, method: &str) -> f64 {
+        let config = self.config.lock().unwrap();
+        config
+            .method_failure_probabilities
+            .get(method)
+            .copied()
+            .unwrap_or(config.failure_probability)
+    }
+}
+
+/// A kvapi::KVApi impl that delegates to an inner impl, after delaying a request and failing it randomly.
+///
+/// An injected failure is a [`MetaNetworkError::ConnectionError`], and the inner impl is not called.
+/// For stream based methods such as `list_kv()`, only opening the stream is subject to faults.
+pub struct FaultyKVApi<T: kvapi::KVApi> {
+    inner: T,
+    faults: Faults,
+}
+
+impl<T> FaultyKVApi<T>
+where
+    T: kvapi::KVApi,
+    T::Error: From<MetaNetworkError>,
+{
+    pub fn new(inner: T, faults: Faults) -> Self {
+        Self { inner, faults }
+    }
+
+    pub fn inner(&self) -> &T {
+        &self.inner
+    }
+
+    pub fn faults(&self) -> &Faults {
+        &self.faults
+    }
+
+    async fn faulty<R>(
+        &self,
+        method: &'static str,
+        fu: impl Future<Output = Result<R, T::Error>>,
+    ) -> Result<R, T::Error> {
+        let latency = self.faults.latency();
+        if !latency.is_zero() {
+            tokio::time::sleep(latency).await;
+        }
+
+        let p = self.faults.failure_probability_of(method);
+        if p > 0.0 && rand::thread_rng().gen_bool(p.min(1.0)) {
+            let err = ConnectionError::new(
+                AnyError::error(format!("injected failure of {}", method)),
+                "FaultyKVApi",
+            );
+            return Err(MetaNetworkError::ConnectionError(err).into());
+        }
+
+        fu.await
+    }
+}
+
+#[async_trait]
+impl<T> kvapi::KVApi for FaultyKVApi<T>
+where
+    T: kvapi::KVApi,
+    T::Error: From<MetaNetworkError>,
+{
+    type Error = T::Error;
+
+    async fn upsert_kv(&self, req: UpsertKVReq) -> Result<UpsertKVReply, Self::Error> {
+        self.faulty("upsert_kv", self.inner.upsert_kv(req)).await
+    }
+
+    async fn mset_kv(&self, reqs: Vec<UpsertKVReq>) -> Result<Vec<UpsertKVReply>, Self::Error> {
+        self.faulty("mset_kv", self.inner.mset_kv(reqs)).await
+    }
+
+    async fn get_kv(&self, key: &str) -> Result<GetKVReply, Self::Error> {
+        self.faulty("get_kv", self.inner.get_kv(key)).await
+    }
+
+    async fn mget_kv(&self, keys: &[String]) -> Result<MGetKVReply, Self::Error> {
+        self.faulty("mget_kv", self.inner.mget_kv(keys)).await
+    }
+
+    async fn get_kv_stream(&self, keys: &[String]) -> Result<KVStream<Self::Error>, Self::Error> {
+        self.faulty("get_kv_stream", self.inner.get_kv_stream(keys))
+            .await
+    }
+
+    async fn list_kv(&self, prefix: &str) -> Result<KVStream<Self::Error>, Self::Error> {
+        self.faulty("list_kv", self.inner.list_kv(prefix)).await
+    }
+
+    async fn prefix_list_kv(&self, prefix: &str) -> Result<ListKVReply, Self::Error> {
+        self.faulty("prefix_list_kv", self.inner.prefix_list_kv(prefix))
+            .await
+    }
+
+    async fn transaction(&self, txn: TxnRequest) -> Result<TxnReply, Self::Error> {
+        self.faulty("transaction", self.inner.transaction(txn))
+            .await
+    }
+}
+
+/// Wraps a base kvapi::ApiBuilder and wraps every store it builds in a [`FaultyKVApi`].
+///
+/// All of the built stores share the [`Faults`] of this builder.
+#[derive(Clone)]
+pub struct FaultyApiBuilder<B> {
+    base: B,
+    faults: Faults,
+}
+
+impl<B> FaultyApiBuilder<B> {
+    pub fn new(base: B, faults: Faults) -> Self {
+        Self { base, faults }
+    }
+
+    pub fn faults(&self) -> &Faults {
+        &self.faults
+    }
+}
+
+#[async_trait]
+impl<T, B> kvapi::ApiBuilder<FaultyKVApi<T>> for FaultyApiBuilder<B>
+where
+    T: kvapi::KVApi,
+    T::Error: From<MetaNetworkError>,
+    B: kvapi::ApiBuilder<T> + Send + Sync,
+{
+    async fn build(&self) -> FaultyKVApi<T> {
+        let kv = self.base.build().await;
+        FaultyKVApi::new(kv, self.faults.clone())
+    }
+
+    async fn build_cluster(&self) -> Vec<FaultyKVApi<T>> {
+        let cluster = self.base.build_cluster().await;
+        cluster
+            .into_iter()
+            .map(|kv| FaultyKVApi::new(kv, self.faults.clone()))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use databend_common_meta_types::MetaError;
+    use databend_common_meta_types::MetaNetworkError;
+
+    use crate::kvapi::ApiBuilder;
+    use crate::kvapi::Faults;
+    use crate::kvapi::FaultyApiBuilder;
+    use crate::kvapi::FaultyKVApi;
+    use crate::kvapi::KVApi;
+    use crate::kvapi::MemKVApi;
+    use crate::kvapi::MemKVApiBuilder;
+    use crate::kvapi::RetryKVApi;
+    use crate::kvapi::TimeoutKVApi;
+    use crate::kvapi::UpsertKVReq;
+
+    fn is_connection_error(e: &MetaError) -> bool {
+        matches!(
+            e,
+            MetaError::NetworkError(MetaNetworkError::ConnectionError(_))
+        )
+    }
+
+    #[tokio::test]
+    async fn test_faulty_toggle_failures() -> anyhow::Result<()> {
+        let builder = FaultyApiBuilder::new(MemKVApiBuilder {}, Faults::new());
+        let kv: FaultyKVApi<MemKVApi> = builder.build().await;
+
+        kv.upsert_kv(UpsertKVReq::update("a", b"1")).await?;
+
+        builder.faults().set_failure_probability(1.0);
+        {
+            let res = kv.get_kv("a").await;
+            assert!(is_connection_error(&res.unwrap_err()));
+
+            let res = kv.upsert_kv(UpsertKVReq::update("a", b"2")).await;
+            assert!(res.is_err());
+        }
+
+        // Override for one method
+        builder
+            .faults()
+            .set_method_failure_probability("get_kv", 0.0);
+        {
+            assert_eq!(Some(b"1".to_vec()), kv.get_kv("a").await?.map(|x| x.data));
+            assert!(kv.prefix_list_kv("").await.is_err());
+        }
+
+        builder.faults().clear();
+        {
+            kv.upsert_kv(UpsertKVReq::update("a", b"2")).await?;
+            assert_eq!(Some(b"2".to_vec()), kv.get_kv("a").await?.map(|x| x.data));
+        }
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_faulty_build_cluster_ready() -> anyhow::Result<()> {
+        let builder = FaultyApiBuilder::new(MemKVApiBuilder {}, Faults::new());
+
+        // Nodes do not serve until the faults are cleared.
+        builder.faults().set_failure_probability(1.0);
+        {
+            let faults = builder.faults().clone();
+            tokio::spawn(async move {
+                tokio::time::sleep(Duration::from_millis(200)).await;
+                faults.clear();
+            });
+        }
+
+        let cluster: Vec<FaultyKVApi<MemKVApi>> = builder.build_cluster_ready().await?;
+        assert_eq!(3, cluster.len());
+
+        for kv in cluster.iter() {
+            kv.ping().await?;
+        }
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_faulty_with_retry_and_timeout() -> anyhow::Result<()> {
+        let faults = Faults::new();
+
+        // A failure that is retried until the attempts are exhausted.
+        {
+            faults.set_failure_probability(1.0);
+
+            let kv = RetryKVApi::new(
+                FaultyKVApi::new(MemKVApi::new(), faults.clone()),
+                is_connection_error,
+            )
+            .with_backoff(Duration::from_millis(1), Duration::from_millis(1));
+
+            let res = kv.get_kv("a").await;
+            assert!(is_connection_error(&res.unwrap_err()));
+
+            faults.clear();
+            assert_eq!(None, kv.get_kv("a").await?);
+        }
+
+        // Latency exceeds the timeout.
+        {
+            faults.set_latency(Duration::from_secs(10));
+
+            let kv = TimeoutKVApi::new(
+                FaultyKVApi::new(MemKVApi::new(), faults.clone()),
+                Duration::from_millis(50),
+            );
+
+            let err = kv.get_kv("a").await.unwrap_err();
+            assert!(
+                matches!(
+                    err,
+                    MetaError::NetworkError(MetaNetworkError::RequestTimeout(_))
+                ),
+                "expect RequestTimeout, got: {:?}",
+                err
+            );
+        }
+
+        Ok(())
+    }
+}
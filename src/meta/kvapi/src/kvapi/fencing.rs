@@ -0,0 +1,273 @@
+// Copyright 2021 Datafuse Labs
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! A kvapi::KVApi decorator that fences the writes issued with a stale epoch.
+
+use anyerror::AnyError;
+use async_trait::async_trait;
+use databend_common_meta_types::txn_op_response;
+use databend_common_meta_types::FencedOut;
+use databend_common_meta_types::InvalidArgument;
+use databend_common_meta_types::Operation;
+use databend_common_meta_types::TxnCondition;
+use databend_common_meta_types::TxnOp;
+use databend_common_meta_types::TxnReply;
+use databend_common_meta_types::TxnRequest;
+use futures_util::stream::BoxStream;
+
+use crate::kvapi;
+use crate::kvapi::mset;
+use crate::kvapi::GetKVReply;
+use crate::kvapi::KVStream;
+use crate::kvapi::ListKVReply;
+use crate::kvapi::MGetKVReply;
+use crate::kvapi::UpsertKVReply;
+use crate::kvapi::UpsertKVReq;
+use crate::kvapi::WatchEvent;
+
+/// Encode an epoch as big-endian `u64`, the value of a fence record.
+pub fn encode_epoch(epoch: u64) -> Vec<u8> {
+    epoch.to_be_bytes().to_vec()
+}
+
+/// Decode an epoch encoded by [`encode_epoch`], `None` if it is not an 8-byte `u64`.
+pub fn decode_epoch(data: &[u8]) -> Option<u64> {
+    let bytes = <[u8; 8]>::try_from(data).ok()?;
+    Some(u64::from_be_bytes(bytes))
+}
+
+/// A kvapi::KVApi impl that delegates to an inner impl, and applies every write only if
+/// the fence record `fence_key` is at `epoch`.
+///
+/// A holder of a role, such as a leader or a lock holder, writes through a `FencedKVApi` with the epoch it is issued.
+/// When the role is passed to another holder, the fence is advanced to a greater epoch,
+/// thus a write of the stale holder, e.g., one that comes back after a partition, fails with a [`FencedOut`] error,
+/// instead of overriding what the new holder has written.
+/// See `KvLock` and `LockGuard::fenced()`.
+///
+/// The fence is checked by the backend atomically with the write:
+/// every write is sent as a `transaction()` with a condition on the value of the fence.
+/// Reads are not fenced.
+pub struct FencedKVApi<T: kvapi::KVApi> {
+    inner: T,
+    fence_key: String,
+    epoch: u64,
+}
+
+impl<T> FencedKVApi<T>
+where
+    T: kvapi::KVApi,
+    T::Error: From<FencedOut>,
+{
+    pub fn new(inner: T, fence_key: impl ToString, epoch: u64) -> Self {
+        Self {
+            inner,
+            fence_key: fence_key.to_string(),
+            epoch,
+        }
+    }
+
+    pub fn inner(&self) -> &T {
+        &self.inner
+    }
+
+    pub fn fence_key(&self) -> &str {
+        &self.fence_key
+    }
+
+    pub fn epoch(&self) -> u64 {
+        self.epoch
+    }
+
+    /// Add the fence condition to `txn`, and read the fence in `else_then` to tell why it is not applied.
+    fn fence_txn(&self, mut txn: TxnRequest) -> TxnRequest {
+        txn.condition.push(TxnCondition::eq_value(
+            &self.fence_key,
+            encode_epoch(self.epoch),
+        ));
+        txn.else_then.insert(0, TxnOp::get(&self.fence_key));
+        txn
+    }
+
+    /// Returns a [`FencedOut`] error if the fence is not at `epoch`,
+    /// otherwise remove the fence read from the reply of a transaction that is not applied.
+    fn unfence_reply(&self, mut reply: TxnReply) -> Result<TxnReply, T::Error> {
+        if reply.success || reply.responses.is_empty() {
+            return Ok(reply);
+        }
+
+        let fence = reply.responses.remove(0);
+        let current = match fence.response {
+            Some(txn_op_response::Response::Get(get)) => {
+                get.value.and_then(|v| decode_epoch(&v.data))
+            }
+            _ => None,
+        };
+
+        if current != Some(self.epoch) {
+            return Err(FencedOut::new(&self.fence_key, self.epoch, current).into());
+        }
+
+        Ok(reply)
+    }
+}
+
+#[async_trait]
+impl<T> kvapi::KVApi for FencedKVApi<T>
+where
+    T: kvapi::KVApi,
+    T::Error: From<FencedOut>,
+{
+    type Error = T::Error;
+
+    async fn upsert_kv(&self, req: UpsertKVReq) -> Result<UpsertKVReply, Self::Error> {
+        if let Operation::AsIs = req.value {
+            return Err(InvalidArgument::new(
+                AnyError::error(format!(
+                    "Operation::AsIs for key '{}' can not be fenced",
+                    req.key
+                )),
+                "upsert_kv",
+            )
+            .into());
+        }
+
+        let txn = mset::mset_txn(&[req])?;
+        let reply = self.transaction(txn).await?;
+
+        // Safe unwrap(): one reply for one request.
+        Ok(mset::mset_replies(reply).pop().unwrap())
+    }
+
+    async fn get_kv(&self, key: &str) -> Result<GetKVReply, Self::Error> {
+        self.inner.get_kv(key).await
+    }
+
+    async fn mget_kv(&self, keys: &[String]) -> Result<MGetKVReply, Self::Error> {
+        self.inner.mget_kv(keys).await
+    }
+
+    async fn get_kv_stream(&self, keys: &[String]) -> Result<KVStream<Self::Error>, Self::Error> {
+        self.inner.get_kv_stream(keys).await
+    }
+
+    async fn list_kv(&self, prefix: &str) -> Result<KVStream<Self::Error>, Self::Error> {
+        self.inner.list_kv(prefix).await
+    }
+
+    async fn prefix_list_kv(&self, prefix: &str) -> Result<ListKVReply, Self::Error> {
+        self.inner.prefix_list_kv(prefix).await
+    }
+
+    async fn transaction(&self, txn: TxnRequest) -> Result<TxnReply, Self::Error> {
+        let reply = self.inner.transaction(self.fence_txn(txn)).await?;
+        self.unfence_reply(reply)
+    }
+
+    async fn watch(
+        &self,
+        prefix: &str,
+    ) -> Result<BoxStream<'_, Result<WatchEvent, Self::Error>>, Self::Error> {
+        self.inner.watch(prefix).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use databend_common_meta_types::MatchSeq;
+    use databend_common_meta_types::MetaClientError;
+    use databend_common_meta_types::MetaError;
+    use databend_common_meta_types::TxnCondition;
+    use databend_common_meta_types::TxnOp;
+    use databend_common_meta_types::TxnRequest;
+    use databend_common_meta_types::With;
+
+    use crate::kvapi::encode_epoch;
+    use crate::kvapi::FencedKVApi;
+    use crate::kvapi::KVApi;
+    use crate::kvapi::MemKVApi;
+    use crate::kvapi::UpsertKVReq;
+
+    fn fenced_out_at(e: &MetaError) -> Option<Option<u64>> {
+        match e {
+            MetaError::ClientError(MetaClientError::FencedOut(f)) => Some(f.current()),
+            _ => None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_fenced_upsert() -> anyhow::Result<()> {
+        let kv = MemKVApi::new();
+
+        // No fence yet.
+        let res = FencedKVApi::new(&kv, "fence", 1)
+            .upsert_kv(UpsertKVReq::update("a", b"1"))
+            .await;
+        assert_eq!(Some(None), fenced_out_at(&res.unwrap_err()));
+
+        kv.upsert_kv(UpsertKVReq::update("fence", &encode_epoch(1)))
+            .await?;
+
+        let fenced = FencedKVApi::new(&kv, "fence", 1);
+        let reply = fenced.upsert_kv(UpsertKVReq::update("a", b"1")).await?;
+        assert!(reply.is_changed());
+        assert_eq!(Some(b"1".to_vec()), kv.get_kv("a").await?.map(|x| x.data));
+
+        // A seq mismatch is not a fencing failure.
+        let reply = fenced
+            .upsert_kv(UpsertKVReq::update("a", b"2").with(MatchSeq::Exact(0)))
+            .await?;
+        assert!(!reply.is_changed());
+        assert_eq!(Some(b"1".to_vec()), reply.result.map(|x| x.data));
+
+        let reply = fenced.delete_kv("a").await?;
+        assert!(reply.is_changed());
+        assert_eq!(None, kv.get_kv("a").await?);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_fenced_transaction() -> anyhow::Result<()> {
+        let kv = MemKVApi::new();
+        kv.upsert_kv(UpsertKVReq::update("fence", &encode_epoch(2)))
+            .await?;
+
+        let txn = || TxnRequest {
+            condition: vec![TxnCondition::eq_seq("a", 0)],
+            if_then: vec![TxnOp::put("a", b"x".to_vec())],
+            else_then: vec![TxnOp::get("a")],
+        };
+
+        let stale = FencedKVApi::new(&kv, "fence", 1);
+        let res = stale.transaction(txn()).await;
+        assert_eq!(Some(Some(2)), fenced_out_at(&res.unwrap_err()));
+        assert_eq!(None, kv.get_kv("a").await?);
+
+        let current = FencedKVApi::new(&kv, "fence", 2);
+        let reply = current.transaction(txn()).await?;
+        assert!(reply.success);
+
+        // The fence read is not seen by the caller.
+        let reply = current.transaction(txn()).await?;
+        assert!(!reply.success);
+        assert_eq!(1, reply.responses.len());
+        assert_eq!(
+            Some(b"x".to_vec()),
+            reply.get_response("a").map(|x| x.data.clone())
+        );
+
+        Ok(())
+    }
+}
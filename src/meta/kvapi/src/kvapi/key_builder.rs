@@ -52,11 +52,21 @@ impl KeyBuilder {
     pub fn done(self) -> String {
         String::from_utf8(self.buf).unwrap()
     }
+
+    /// Build a prefix to list the keys under it, i.e., the key with a trailing `/`.
+    ///
+    /// Without the trailing `/`, listing by `_foo/1` would also return the keys under `_foo/10`.
+    pub fn done_prefix(mut self) -> String {
+        // `/`
+        self.buf.push(0x2f);
+        self.done()
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use crate::kvapi::key_builder::KeyBuilder;
+    use crate::kvapi::KeyParser;
 
     #[test]
     fn test_key_builder() -> anyhow::Result<()> {
@@ -69,4 +79,73 @@ mod tests {
         assert_eq!("_foo/a%20b/5/a b", s);
         Ok(())
     }
+
+    #[test]
+    fn test_key_builder_done_prefix() -> anyhow::Result<()> {
+        let p = KeyBuilder::new_prefixed("_foo").push_u64(1).done_prefix();
+        assert_eq!("_foo/1/", p);
+
+        let k1 = KeyBuilder::new_prefixed("_foo")
+            .push_u64(1)
+            .push_str("a")
+            .done();
+        let k10 = KeyBuilder::new_prefixed("_foo")
+            .push_u64(10)
+            .push_str("a")
+            .done();
+        assert!(k1.starts_with(&p));
+        assert!(!k10.starts_with(&p));
+
+        // The prefix of a name does not match a longer name.
+        let p = KeyBuilder::new_prefixed("_foo")
+            .push_str("db1")
+            .done_prefix();
+        let k = KeyBuilder::new_prefixed("_foo")
+            .push_str("db10")
+            .push_str("t")
+            .done();
+        assert!(!k.starts_with(&p));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_key_builder_parser_round_trip() -> anyhow::Result<()> {
+        for name in [
+            "",
+            "a/b",
+            "/",
+            "a%2fb",
+            "%",
+            "a b",
+            "db1/t1",
+            "数据库",
+            "\\t\\n",
+        ] {
+            let k = KeyBuilder::new_prefixed("_foo")
+                .push_str(name)
+                .push_u64(3)
+                .push_str(name)
+                .done();
+
+            // The delimiter in a name is always escaped.
+            assert_eq!(4, k.split('/').count(), "key: {}", k);
+
+            let mut p = KeyParser::new_prefixed(&k, "_foo")?;
+            assert_eq!(name, p.next_str()?);
+            assert_eq!(3, p.next_u64()?);
+            assert_eq!(name, p.next_str()?);
+            p.done()?;
+        }
+
+        // Two names with a delimiter never build the same key as a deeper one.
+        let a = KeyBuilder::new_prefixed("_foo").push_str("a/b").done();
+        let b = KeyBuilder::new_prefixed("_foo")
+            .push_str("a")
+            .push_str("b")
+            .done();
+        assert_ne!(a, b);
+
+        Ok(())
+    }
 }
@@ -0,0 +1,424 @@
+// Copyright 2021 Datafuse Labs
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! A distributed lock with a lease, built on kvapi::KVApi.
+
+use std::sync::atomic::AtomicU64;
+use std::sync::atomic::Ordering;
+use std::sync::Arc;
+use std::time::Duration;
+
+use databend_common_meta_types::FencedOut;
+use databend_common_meta_types::MatchSeq;
+use databend_common_meta_types::Operation;
+use databend_common_meta_types::With;
+use log::info;
+use log::warn;
+use tokio::task::JoinHandle;
+
+use crate::kvapi;
+use crate::kvapi::decode_epoch;
+use crate::kvapi::encode_epoch;
+use crate::kvapi::BackoffPolicy;
+use crate::kvapi::FencedKVApi;
+use crate::kvapi::KVApiExt;
+use crate::kvapi::UpsertKVReq;
+
+/// A lock on a key that is held by at most one holder at a time.
+///
+/// The lock is a record put with `seq == 0` as the condition and a TTL as the lease:
+/// a holder that crashes without releasing the lock blocks the others for at most one TTL.
+/// A holder is identified by the `seq` of the record it put,
+/// thus renewing and releasing are both conditioned on this `seq`,
+/// and never touch a lock acquired by another holder once the lease has expired.
+///
+/// The value of the record is the `holder` string, to tell who holds a lock.
+///
+/// The expiration time of a record is in seconds, thus `ttl` should be at least a few seconds.
+///
+/// A lease does not stop a holder that is paused for longer than `ttl` from writing when it resumes.
+/// To reject such writes, every acquisition is issued an epoch, the `seq` of the lock record,
+/// and advances the fence record `<key>/fence` to it.
+/// The writes made through [`LockGuard::fenced`] are applied only while the fence is at the epoch of the guard.
+pub struct KvLock<KV: kvapi::KVApi + 'static> {
+    kv: Arc<KV>,
+    holder: String,
+    /// The delay between the attempts of [`Self::lock()`].
+    backoff: BackoffPolicy,
+}
+
+impl<KV: kvapi::KVApi + 'static> KvLock<KV> {
+    pub fn new(kv: Arc<KV>, holder: impl ToString) -> Self {
+        Self {
+            kv,
+            holder: holder.to_string(),
+            backoff: BackoffPolicy::new(Duration::from_millis(20), 2.0, Duration::from_secs(1))
+                .with_jitter(true),
+        }
+    }
+
+    /// Set the policy that produces the delay between the attempts to acquire a lock held by another holder.
+    pub fn with_backoff_policy(mut self, policy: BackoffPolicy) -> Self {
+        self.backoff = policy;
+        self
+    }
+
+    /// Try to acquire the lock on `key`, with a lease of `ttl`.
+    ///
+    /// It returns `None` at once if the lock is held by another holder.
+    /// The lease of a returned guard is not renewed; the lock is lost after `ttl`.
+    pub async fn try_lock(
+        &self,
+        key: &str,
+        ttl: Duration,
+    ) -> Result<Option<LockGuard<KV>>, KV::Error> {
+        let req = UpsertKVReq::insert(key, self.holder.as_bytes()).with_ttl(ttl);
+        let reply = self.kv.upsert_kv(req).await?;
+
+        if !reply.is_changed() {
+            return Ok(None);
+        }
+
+        // Safe unwrap(): the record is just inserted.
+        let seq = reply.result.unwrap().seq;
+
+        info!(
+            "KvLock: {} acquired lock {} at seq {}",
+            self.holder, key, seq
+        );
+
+        let guard = LockGuard {
+            kv: self.kv.clone(),
+            key: key.to_string(),
+            fence_key: format!("{}/fence", key),
+            epoch: seq,
+            seq: Arc::new(AtomicU64::new(seq)),
+            renewal: None,
+        };
+
+        // If it fails, the lock is released when the guard is dropped.
+        self.advance_fence(&guard.fence_key, guard.epoch).await?;
+
+        Ok(Some(guard))
+    }
+
+    /// Advance the fence to `epoch`, unless it is already at a greater one.
+    ///
+    /// The fence is never moved backward: a holder that is paused after acquiring a lock,
+    /// until the lock expires and is acquired by another holder, does not fence out the new holder.
+    async fn advance_fence(&self, fence_key: &str, epoch: u64) -> Result<(), KV::Error> {
+        loop {
+            let (seq, curr) = match self.kv.get_kv(fence_key).await? {
+                Some(seq_v) => (seq_v.seq, decode_epoch(&seq_v.data)),
+                None => (0, None),
+            };
+
+            if curr >= Some(epoch) {
+                warn!(
+                    "KvLock: {} is fenced out by {} at epoch {:?}, the issued epoch is {}",
+                    self.holder, fence_key, curr, epoch
+                );
+                return Ok(());
+            }
+
+            if self.kv.cas_kv(fence_key, seq, encode_epoch(epoch)).await? {
+                return Ok(());
+            }
+        }
+    }
+
+    /// Acquire the lock on `key`, waiting until it is released by the current holder or expires.
+    ///
+    /// The lease of `ttl` is renewed in background every `ttl / 3`, until the guard is dropped.
+    pub async fn lock(&self, key: &str, ttl: Duration) -> Result<LockGuard<KV>, KV::Error> {
+        let mut backoff = self.backoff.clone();
+        backoff.reset();
+
+        loop {
+            if let Some(mut guard) = self.try_lock(key, ttl).await? {
+                guard.renewal = Some(self.spawn_renewal(&guard, ttl));
+                return Ok(guard);
+            }
+
+            tokio::time::sleep(backoff.next_delay()).await;
+        }
+    }
+
+    fn spawn_renewal(&self, guard: &LockGuard<KV>, ttl: Duration) -> JoinHandle<()> {
+        let kv = self.kv.clone();
+        let holder = self.holder.clone();
+        let key = guard.key.clone();
+        let seq = guard.seq.clone();
+
+        tokio::spawn(async move {
+            loop {
+                tokio::time::sleep(ttl / 3).await;
+
+                let curr = seq.load(Ordering::Acquire);
+                let req = UpsertKVReq::update(&key, holder.as_bytes())
+                    .with(MatchSeq::Exact(curr))
+                    .with_ttl(ttl);
+
+                match kv.upsert_kv(req).await {
+                    Ok(reply) => {
+                        if !reply.is_changed() {
+                            warn!("KvLock: {} lost lock {} at seq {}", holder, key, curr);
+                            return;
+                        }
+                        // Safe unwrap(): the record is just updated.
+                        seq.store(reply.result.unwrap().seq, Ordering::Release);
+                    }
+                    Err(e) => {
+                        // Retry at the next tick, the lease may not have expired yet.
+                        warn!("KvLock: {} failed to renew lock {}: {}", holder, key, e);
+                    }
+                }
+            }
+        })
+    }
+}
+
+/// Holds a lock acquired by [`KvLock`].
+///
+/// The lock is released on drop in background, on a best-effort basis:
+/// if the release fails, the lock is released when the lease expires.
+/// Use [`LockGuard::unlock`] to release it and wait for the result.
+pub struct LockGuard<KV: kvapi::KVApi + 'static> {
+    kv: Arc<KV>,
+    key: String,
+    fence_key: String,
+    /// The epoch issued to this acquisition, it does not change on renewal.
+    epoch: u64,
+    /// The seq of the record, updated by every renewal.
+    seq: Arc<AtomicU64>,
+    renewal: Option<JoinHandle<()>>,
+}
+
+impl<KV: kvapi::KVApi + 'static> LockGuard<KV> {
+    pub fn key(&self) -> &str {
+        &self.key
+    }
+
+    pub fn fence_key(&self) -> &str {
+        &self.fence_key
+    }
+
+    /// Returns the epoch issued to this acquisition of the lock.
+    ///
+    /// An acquisition of the same lock later is issued a greater epoch.
+    pub fn epoch(&self) -> u64 {
+        self.epoch
+    }
+
+    /// Returns a kvapi::KVApi whose writes are rejected with [`FencedOut`],
+    /// once the lock is acquired by another holder.
+    ///
+    /// Unlike the lease, the fence is checked by the backend at the time a write is applied,
+    /// thus the writes of a holder paused for longer than the lease are rejected too.
+    pub fn fenced(&self) -> FencedKVApi<Arc<KV>>
+    where KV::Error: From<FencedOut> {
+        FencedKVApi::new(self.kv.clone(), &self.fence_key, self.epoch)
+    }
+
+    /// Returns the seq of the record that represents the held lock.
+    pub fn seq(&self) -> u64 {
+        self.seq.load(Ordering::Acquire)
+    }
+
+    /// Release the lock, unless it has already expired and been acquired by another holder.
+    pub async fn unlock(mut self) -> Result<(), KV::Error> {
+        self.stop_renewal();
+
+        let req = Self::release_req(&self.key, self.seq());
+        let res = self.kv.upsert_kv(req).await;
+
+        // Released, nothing to do on drop.
+        self.key.clear();
+        res.map(|_| ())
+    }
+
+    fn stop_renewal(&mut self) {
+        if let Some(renewal) = self.renewal.take() {
+            renewal.abort();
+        }
+    }
+
+    fn release_req(key: &str, seq: u64) -> UpsertKVReq {
+        UpsertKVReq::new(key, MatchSeq::Exact(seq), Operation::Delete, None)
+    }
+}
+
+impl<KV: kvapi::KVApi + 'static> Drop for LockGuard<KV> {
+    fn drop(&mut self) {
+        self.stop_renewal();
+
+        if self.key.is_empty() {
+            return;
+        }
+
+        let Ok(rt) = tokio::runtime::Handle::try_current() else {
+            warn!("KvLock: no runtime to release lock {}", self.key);
+            return;
+        };
+
+        let kv = self.kv.clone();
+        let req = Self::release_req(&self.key, self.seq());
+        rt.spawn(async move {
+            if let Err(e) = kv.upsert_kv(req).await {
+                warn!("KvLock: failed to release lock: {}", e);
+            }
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    use databend_common_meta_types::MetaClientError;
+    use databend_common_meta_types::MetaError;
+
+    use crate::kvapi::KVApi;
+    use crate::kvapi::KvLock;
+    use crate::kvapi::MemKVApi;
+    use crate::kvapi::UpsertKVReq;
+
+    #[tokio::test]
+    async fn test_kv_lock_contention() -> anyhow::Result<()> {
+        let kv = Arc::new(MemKVApi::new());
+
+        let locks = (0..20)
+            .map(|i| KvLock::new(kv.clone(), format!("node-{}", i)))
+            .collect::<Vec<_>>();
+
+        let ttl = Duration::from_secs(60);
+        let tries = locks.iter().map(|l| l.try_lock("lock/compaction", ttl));
+        let got = futures_util::future::try_join_all(tries).await?;
+
+        let winners = got.into_iter().flatten().collect::<Vec<_>>();
+        assert_eq!(1, winners.len());
+
+        // Locked, until the winner unlocks.
+        assert!(locks[0].try_lock("lock/compaction", ttl).await?.is_none());
+
+        for w in winners {
+            w.unlock().await?;
+        }
+        assert!(locks[0].try_lock("lock/compaction", ttl).await?.is_some());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_kv_lock_release_on_drop() -> anyhow::Result<()> {
+        let kv = Arc::new(MemKVApi::new());
+        let a = KvLock::new(kv.clone(), "a");
+        let b = KvLock::new(kv.clone(), "b");
+
+        let ttl = Duration::from_secs(60);
+
+        {
+            let _guard = a.try_lock("k", ttl).await?.unwrap();
+            assert!(b.try_lock("k", ttl).await?.is_none());
+        }
+
+        // Released in background.
+        let guard = tokio::time::timeout(Duration::from_secs(5), b.lock("k", ttl)).await??;
+        assert_eq!("k", guard.key());
+        assert_eq!(Some(b"b".to_vec()), kv.get_kv("k").await?.map(|x| x.data));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_kv_lock_lease() -> anyhow::Result<()> {
+        let kv = Arc::new(MemKVApi::new());
+        let a = KvLock::new(kv.clone(), "a");
+        let b = KvLock::new(kv.clone(), "b");
+
+        // A crashed holder does not release the lock, it expires after ttl.
+        {
+            let ttl = Duration::from_secs(2);
+
+            let guard = a.try_lock("crashed", ttl).await?.unwrap();
+            std::mem::forget(guard);
+
+            assert!(b.try_lock("crashed", ttl).await?.is_none());
+            tokio::time::sleep(ttl + Duration::from_millis(500)).await;
+            assert!(b.try_lock("crashed", ttl).await?.is_some());
+        }
+
+        // The lease of lock() is renewed.
+        {
+            let ttl = Duration::from_secs(3);
+
+            let guard = a.lock("renewed", ttl).await?;
+            tokio::time::sleep(ttl + Duration::from_secs(1)).await;
+
+            assert!(b.try_lock("renewed", ttl).await?.is_none());
+            assert!(guard.seq() > 0);
+
+            guard.unlock().await?;
+            assert!(b.try_lock("renewed", ttl).await?.is_some());
+        }
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_kv_lock_fencing() -> anyhow::Result<()> {
+        let kv = Arc::new(MemKVApi::new());
+        let a = KvLock::new(kv.clone(), "a");
+        let b = KvLock::new(kv.clone(), "b");
+
+        let ttl = Duration::from_secs(2);
+
+        let guard_a = a.try_lock("leader", ttl).await?.unwrap();
+        guard_a
+            .fenced()
+            .upsert_kv(UpsertKVReq::update("meta", b"a"))
+            .await?;
+
+        // `a` is paused longer than the lease, and `b` takes over.
+        tokio::time::sleep(ttl + Duration::from_millis(500)).await;
+        let guard_b = b.try_lock("leader", ttl).await?.unwrap();
+        assert!(guard_b.epoch() > guard_a.epoch());
+
+        guard_b
+            .fenced()
+            .upsert_kv(UpsertKVReq::update("meta", b"b"))
+            .await?;
+
+        // `a` resumes, its write is rejected.
+        let res = guard_a
+            .fenced()
+            .upsert_kv(UpsertKVReq::update("meta", b"a"))
+            .await;
+        match res.unwrap_err() {
+            MetaError::ClientError(MetaClientError::FencedOut(f)) => {
+                assert_eq!(guard_a.epoch(), f.epoch());
+                assert_eq!(Some(guard_b.epoch()), f.current());
+            }
+            e => panic!("expect FencedOut, got: {}", e),
+        }
+
+        assert_eq!(
+            Some(b"b".to_vec()),
+            kv.get_kv("meta").await?.map(|x| x.data)
+        );
+
+        Ok(())
+    }
+}
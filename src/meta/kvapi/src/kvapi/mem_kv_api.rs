@@ -0,0 +1,308 @@
+// Copyright 2021 Datafuse Labs
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! An in-memory kvapi::KVApi impl for testing.
+
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use std::sync::Mutex;
+
+use async_trait::async_trait;
+use databend_common_meta_types::protobuf as pb;
+use databend_common_meta_types::txn_condition;
+use databend_common_meta_types::txn_op;
+use databend_common_meta_types::Change;
+use databend_common_meta_types::CmdContext;
+use databend_common_meta_types::ConditionResult;
+use databend_common_meta_types::Interval;
+use databend_common_meta_types::MatchSeq;
+use databend_common_meta_types::MatchSeqExt;
+use databend_common_meta_types::MetaError;
+use databend_common_meta_types::MetaSpec;
+use databend_common_meta_types::Operation;
+use databend_common_meta_types::SeqV;
+use databend_common_meta_types::SeqValue;
+use databend_common_meta_types::TxnCondition;
+use databend_common_meta_types::TxnOp;
+use databend_common_meta_types::TxnOpResponse;
+use databend_common_meta_types::TxnReply;
+use databend_common_meta_types::TxnRequest;
+use databend_common_meta_types::UpsertKV;
+use databend_common_meta_types::With;
+use futures_util::stream;
+use futures_util::StreamExt;
+
+use crate::kvapi;
+use crate::kvapi::KVStream;
+use crate::kvapi::UpsertKVReply;
+use crate::kvapi::UpsertKVReq;
+
+/// A kvapi::KVApi impl that stores everything in a `BTreeMap`, without network or storage.
+///
+/// It follows the semantics of the meta-service state machine:
+/// - Every effective update bumps a global seq; a delete does not.
+/// - An expired record is treated as absent.
+/// - Every state machine feature is enabled, e.g., `ReserveSeq` is applied.
+///
+/// Cloned instances share the same data.
+#[derive(Clone, Debug, Default)]
+pub struct MemKVApi {
+    inner: Arc<Mutex<MemKV>>,
+}
+
+impl MemKVApi {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[derive(Debug, Default)]
+struct MemKV {
+    /// The last used seq.
+    curr_seq: u64,
+    kvs: BTreeMap<String, SeqV>,
+}
+
+impl MemKV {
+    /// Get a non-expired record.
+    fn get(&self, key: &str, now_ms: u64) -> Option<SeqV> {
+        let seq_v = self.kvs.get(key)?;
+        if seq_v.is_expired(now_ms) {
+            return None;
+        }
+        Some(seq_v.clone())
+    }
+
+    fn list(&self, prefix: &str, now_ms: u64) -> Vec<(String, SeqV)> {
+        self.kvs
+            .range(prefix.to_string()..)
+            .take_while(|(k, _)| k.starts_with(prefix))
+            .filter(|(_, v)| !v.is_expired(now_ms))
+            .map(|(k, v)| (k.clone(), v.clone()))
+            .collect()
+    }
+
+    /// Apply an upsert and returns the record before and after it.
+    fn upsert(&mut self, upsert_kv: &UpsertKV, now_ms: u64) -> (Option<SeqV>, Option<SeqV>) {
+        let prev = self.get(&upsert_kv.key, now_ms);
+
+        if upsert_kv.seq.match_seq(&prev).is_err() {
+            return (prev.clone(), prev);
+        }
+
+        let kv_meta = upsert_kv
+            .value_meta
+            .as_ref()
+            .map(|m| m.to_kv_meta(&CmdContext::from_millis(now_ms)));
+
+        let data = match &upsert_kv.value {
+            Operation::Update(v) => v.clone(),
+            Operation::AsIs => match &prev {
+                Some(p) => p.data.clone(),
+                None => return (None, None),
+            },
+            Operation::Delete => {
+                self.kvs.remove(&upsert_kv.key);
+                return (prev, None);
+            }
+        };
+
+        self.curr_seq += 1;
+        let result = SeqV::with_meta(self.curr_seq, kv_meta, data);
+
+        if result.is_expired(now_ms) {
+            // An already expired record is removed at once, but the seq is still consumed.
+            self.kvs.remove(&upsert_kv.key);
+            return (prev, None);
+        }
+
+        self.kvs.insert(upsert_kv.key.clone(), result.clone());
+        (prev, Some(result))
+    }
+
+    fn transaction(&mut self, txn: &TxnRequest, now_ms: u64) -> TxnReply {
+        let success = txn.condition.iter().all(|c| self.eval_condition(c, now_ms));
+
+        let ops = if success {
+            &txn.if_then
+        } else {
+            &txn.else_then
+        };
+
+        let responses = ops
+            .iter()
+            .filter_map(|op| self.execute_op(op, now_ms))
+            .collect();
+
+        TxnReply {
+            success,
+            error: "".to_string(),
+            responses,
+        }
+    }
+
+    fn eval_condition(&self, cond: &TxnCondition, now_ms: u64) -> bool {
+        let seq_v = self.get(&cond.key, now_ms);
+
+        let Ok(op) = ConditionResult::try_from(cond.expected) else {
+            return false;
+        };
+
+        match &cond.target {
+            Some(txn_condition::Target::Seq(right)) => compare(op, &seq_v.seq(), right),
+            Some(txn_condition::Target::Value(right)) => match seq_v.value() {
+                Some(left) => compare(op, left, right),
+                None => false,
+            },
+            None => false,
+        }
+    }
+
+    fn execute_op(&mut self, op: &TxnOp, now_ms: u64) -> Option<TxnOpResponse> {
+        let resp = match op.request.as_ref()? {
+            txn_op::Request::Get(get) => TxnOpResponse::get(&get.key, self.get(&get.key, now_ms)),
+            txn_op::Request::Put(put) => {
+                let upsert = UpsertKV::update(&put.key, &put.value).with(MetaSpec::new(
+                    put.expire_at,
+                    put.ttl_ms.map(Interval::from_millis),
+                ));
+                let (prev, _result) = self.upsert(&upsert, now_ms);
+
+                let prev_value = prev.filter(|_| put.prev_value).map(pb::SeqV::from);
+                TxnOpResponse::put(&put.key, prev_value)
+            }
+            txn_op::Request::Delete(delete) => {
+                let upsert = UpsertKV::delete(&delete.key);
+
+                // If `delete.match_seq` is `Some`, only delete the entry with the exact `seq`.
+                let upsert = if let Some(seq) = delete.match_seq {
+                    upsert.with(MatchSeq::Exact(seq))
+                } else {
+                    upsert
+                };
+                let (prev, result) = self.upsert(&upsert, now_ms);
+                let success = prev.is_some() && result.is_none();
+
+                let prev_value = prev.filter(|_| delete.prev_value).map(pb::SeqV::from);
+                TxnOpResponse::delete(&delete.key, success, prev_value)
+            }
+            txn_op::Request::DeleteByPrefix(delete_by_prefix) => {
+                let keys = self.list(&delete_by_prefix.prefix, now_ms);
+                for (key, _) in keys.iter() {
+                    self.kvs.remove(key);
+                }
+
+                TxnOpResponse {
+                    response: Some(pb::txn_op_response::Response::DeleteByPrefix(
+                        pb::TxnDeleteByPrefixResponse {
+                            prefix: delete_by_prefix.prefix.clone(),
+                            count: keys.len() as u32,
+                        },
+                    )),
+                }
+            }
+            txn_op::Request::ReserveSeq(reserve_seq) => {
+                let count = reserve_seq.count.max(1);
+                self.curr_seq += count - 1;
+                let (_prev, result) = self.upsert(&UpsertKV::update(&reserve_seq.key, b""), now_ms);
+
+                let end = result.seq() + 1;
+                TxnOpResponse::reserve_seq(&reserve_seq.key, end - count, end)
+            }
+        };
+
+        Some(resp)
+    }
+}
+
+fn compare<T: PartialOrd + ?Sized>(op: ConditionResult, left: &T, right: &T) -> bool {
+    match op {
+        ConditionResult::Eq => left == right,
+        ConditionResult::Gt => left > right,
+        ConditionResult::Lt => left < right,
+        ConditionResult::Ne => left != right,
+        ConditionResult::Ge => left >= right,
+        ConditionResult::Le => left <= right,
+    }
+}
+
+#[async_trait]
+impl kvapi::KVApi for MemKVApi {
+    type Error = MetaError;
+
+    async fn upsert_kv(&self, req: UpsertKVReq) -> Result<UpsertKVReply, Self::Error> {
+        let mut kv = self.inner.lock().unwrap();
+        let (prev, result) = kv.upsert(&req, SeqV::<()>::now_ms());
+        Ok(Change::new(prev, result))
+    }
+
+    async fn get_kv_stream(&self, keys: &[String]) -> Result<KVStream<Self::Error>, Self::Error> {
+        let now_ms = SeqV::<()>::now_ms();
+        let kv = self.inner.lock().unwrap();
+
+        let items = keys
+            .iter()
+            .map(|k| Ok(pb::StreamItem::from((k.clone(), kv.get(k, now_ms)))))
+            .collect::<Vec<_>>();
+
+        Ok(stream::iter(items).boxed())
+    }
+
+    async fn list_kv(&self, prefix: &str) -> Result<KVStream<Self::Error>, Self::Error> {
+        let now_ms = SeqV::<()>::now_ms();
+        let kv = self.inner.lock().unwrap();
+
+        let items = kv
+            .list(prefix, now_ms)
+            .into_iter()
+            .map(|kv| Ok(pb::StreamItem::from(kv)))
+            .collect::<Vec<_>>();
+
+        Ok(stream::iter(items).boxed())
+    }
+
+    async fn transaction(&self, txn: TxnRequest) -> Result<TxnReply, Self::Error> {
+        let mut kv = self.inner.lock().unwrap();
+        Ok(kv.transaction(&txn, SeqV::<()>::now_ms()))
+    }
+}
+
+/// Build [`MemKVApi`] for the generic kvapi test suite.
+///
+/// The nodes of a cluster share the same data.
+#[derive(Clone, Debug, Default)]
+pub struct MemKVApiBuilder {}
+
+#[async_trait]
+impl kvapi::ApiBuilder<MemKVApi> for MemKVApiBuilder {
+    async fn build(&self) -> MemKVApi {
+        MemKVApi::new()
+    }
+
+    async fn build_cluster(&self) -> Vec<MemKVApi> {
+        let kv = MemKVApi::new();
+        vec![kv.clone(), kv.clone(), kv]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::kvapi;
+    use crate::kvapi::MemKVApiBuilder;
+
+    #[tokio::test]
+    async fn test_mem_kv_api() -> anyhow::Result<()> {
+        kvapi::TestSuite {}.test_all(MemKVApiBuilder {}).await
+    }
+}
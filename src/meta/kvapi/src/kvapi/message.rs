@@ -12,6 +12,7 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use databend_common_meta_types::protobuf as pb;
 use databend_common_meta_types::Change;
 use databend_common_meta_types::SeqV;
 use databend_common_meta_types::UpsertKV;
@@ -31,6 +32,20 @@ impl GetKVReq {
     }
 }
 
+/// Check if a key exists, without sending back the value.
+#[derive(serde::Serialize, serde::Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct HasKVReq {
+    pub key: String,
+}
+
+impl HasKVReq {
+    pub fn new(key: impl ToString) -> Self {
+        Self {
+            key: key.to_string(),
+        }
+    }
+}
+
 #[derive(serde::Serialize, serde::Deserialize, Clone, Debug, PartialEq, Eq)]
 pub struct MGetKVReq {
     pub keys: Vec<String>,
@@ -57,7 +72,87 @@ impl ListKVReq {
     }
 }
 
+/// List the keys by prefix, without sending back the values.
+#[derive(serde::Serialize, serde::Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct ListKeysReq {
+    pub prefix: String,
+}
+
+impl ListKeysReq {
+    pub fn new(prefix: impl ToString) -> Self {
+        Self {
+            prefix: prefix.to_string(),
+        }
+    }
+}
+
 pub type UpsertKVReply = Change<Vec<u8>>;
 pub type GetKVReply = Option<SeqV<Vec<u8>>>;
 pub type MGetKVReply = Vec<Option<SeqV<Vec<u8>>>>;
 pub type ListKVReply = Vec<(String, SeqV<Vec<u8>>)>;
+
+/// The kind of change a [`WatchEvent`] describes.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum WatchEventKind {
+    Create,
+    Update,
+    Delete,
+    /// The consumer did not keep up, and this many events before this one are dropped.
+    ///
+    /// The event has an empty key and no value.
+    /// The consumer should resync with a fresh `prefix_list_kv()`.
+    Lagged(u64),
+}
+
+/// A change to a key that is delivered by `KVApi::watch()`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct WatchEvent {
+    pub key: String,
+    pub kind: WatchEventKind,
+    /// The value after the change, `None` if the key is deleted.
+    pub value: Option<SeqV<Vec<u8>>>,
+}
+
+impl WatchEvent {
+    /// Build an event from the value before and after a change.
+    pub fn new(
+        key: impl ToString,
+        prev: Option<SeqV<Vec<u8>>>,
+        current: Option<SeqV<Vec<u8>>>,
+    ) -> Self {
+        let kind = match (&prev, &current) {
+            (_, None) => WatchEventKind::Delete,
+            (None, Some(_)) => WatchEventKind::Create,
+            (Some(_), Some(_)) => WatchEventKind::Update,
+        };
+
+        Self {
+            key: key.to_string(),
+            kind,
+            value: current,
+        }
+    }
+
+    /// Build an event telling that `n` events are dropped.
+    pub fn lagged(n: u64) -> Self {
+        Self {
+            key: "".to_string(),
+            kind: WatchEventKind::Lagged(n),
+            value: None,
+        }
+    }
+
+    /// Build an event from a watch response, `None` if it carries neither a change nor a lag.
+    pub fn from_response(resp: pb::WatchResponse) -> Option<Self> {
+        if resp.lagged > 0 {
+            return Some(Self::lagged(resp.lagged));
+        }
+        resp.event.map(Self::from)
+    }
+}
+
+impl From<pb::Event> for WatchEvent {
+    fn from(ev: pb::Event) -> Self {
+        WatchEvent::new(ev.key, ev.prev.map(SeqV::from), ev.current.map(SeqV::from))
+    }
+}
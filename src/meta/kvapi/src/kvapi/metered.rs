@@ -0,0 +1,231 @@
+// Copyright 2021 Datafuse Labs
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! A kvapi::KVApi decorator that records the latency of every request.
+
+use std::future::Future;
+use std::sync::LazyLock;
+use std::time::Instant;
+
+use async_trait::async_trait;
+use databend_common_meta_types::TxnReply;
+use databend_common_meta_types::TxnRequest;
+use databend_common_metrics::register_histogram_family_in_milliseconds;
+use databend_common_metrics::Family;
+use databend_common_metrics::Histogram;
+use databend_common_metrics::VecLabels;
+
+use crate::kvapi;
+use crate::kvapi::GetKVReply;
+use crate::kvapi::KVStream;
+use crate::kvapi::ListKVReply;
+use crate::kvapi::MGetKVReply;
+use crate::kvapi::UpsertKVReply;
+use crate::kvapi::UpsertKVReq;
+
+/// Name of the histogram of request duration in milliseconds.
+pub const METRIC_KV_API_REQUEST_DURATION_MS: &str = "meta_kv_api_request_duration_ms";
+
+/// Label of the name of the called method, such as `get_kv`.
+pub const LABEL_METHOD: &str = "method";
+
+/// Label of the outcome of a request: [`OUTCOME_SUCCESS`] or [`OUTCOME_ERROR`].
+pub const LABEL_OUTCOME: &str = "outcome";
+
+pub const OUTCOME_SUCCESS: &str = "success";
+pub const OUTCOME_ERROR: &str = "error";
+
+static KV_API_REQUEST_DURATION_MS: LazyLock<Family<VecLabels, Histogram>> =
+    LazyLock::new(|| register_histogram_family_in_milliseconds(METRIC_KV_API_REQUEST_DURATION_MS));
+
+fn record_request_duration_ms(method: &'static str, success: bool, duration_ms: f64) {
+    let outcome = if success {
+        OUTCOME_SUCCESS
+    } else {
+        OUTCOME_ERROR
+    };
+
+    let labels = vec![
+        (LABEL_METHOD, method.to_string()),
+        (LABEL_OUTCOME, outcome.to_string()),
+    ];
+    KV_API_REQUEST_DURATION_MS
+        .get_or_create(&labels)
+        .observe(duration_ms);
+}
+
+/// A kvapi::KVApi impl that delegates to an inner impl and records the duration of every method,
+/// labeled by method name and outcome.
+///
+/// For stream based methods such as `list_kv()`, only the time to open the stream is recorded.
+pub struct MeteredKVApi<T: kvapi::KVApi> {
+    inner: T,
+}
+
+impl<T: kvapi::KVApi> MeteredKVApi<T> {
+    pub fn new(inner: T) -> Self {
+        Self { inner }
+    }
+
+    pub fn inner(&self) -> &T {
+        &self.inner
+    }
+
+    async fn metered<R>(
+        method: &'static str,
+        fu: impl Future<Output = Result<R, T::Error>>,
+    ) -> Result<R, T::Error> {
+        let start = Instant::now();
+        let res = fu.await;
+        record_request_duration_ms(method, res.is_ok(), start.elapsed().as_millis() as f64);
+        res
+    }
+}
+
+#[async_trait]
+impl<T: kvapi::KVApi> kvapi::KVApi for MeteredKVApi<T> {
+    type Error = T::Error;
+
+    async fn upsert_kv(&self, req: UpsertKVReq) -> Result<UpsertKVReply, Self::Error> {
+        Self::metered("upsert_kv", self.inner.upsert_kv(req)).await
+    }
+
+    async fn mset_kv(&self, reqs: Vec<UpsertKVReq>) -> Result<Vec<UpsertKVReply>, Self::Error> {
+        Self::metered("mset_kv", self.inner.mset_kv(reqs)).await
+    }
+
+    async fn get_kv(&self, key: &str) -> Result<GetKVReply, Self::Error> {
+        Self::metered("get_kv", self.inner.get_kv(key)).await
+    }
+
+    async fn mget_kv(&self, keys: &[String]) -> Result<MGetKVReply, Self::Error> {
+        Self::metered("mget_kv", self.inner.mget_kv(keys)).await
+    }
+
+    async fn get_kv_stream(&self, keys: &[String]) -> Result<KVStream<Self::Error>, Self::Error> {
+        Self::metered("get_kv_stream", self.inner.get_kv_stream(keys)).await
+    }
+
+    async fn list_kv(&self, prefix: &str) -> Result<KVStream<Self::Error>, Self::Error> {
+        Self::metered("list_kv", self.inner.list_kv(prefix)).await
+    }
+
+    async fn prefix_list_kv(&self, prefix: &str) -> Result<ListKVReply, Self::Error> {
+        Self::metered("prefix_list_kv", self.inner.prefix_list_kv(prefix)).await
+    }
+
+    async fn transaction(&self, txn: TxnRequest) -> Result<TxnReply, Self::Error> {
+        Self::metered("transaction", self.inner.transaction(txn)).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use databend_common_metrics::dump_metric_samples;
+    use databend_common_metrics::load_global_prometheus_registry;
+    use databend_common_metrics::HistogramCount;
+    use databend_common_metrics::MetricValue;
+
+    use super::LABEL_METHOD;
+    use super::LABEL_OUTCOME;
+    use super::METRIC_KV_API_REQUEST_DURATION_MS;
+    use super::OUTCOME_ERROR;
+    use super::OUTCOME_SUCCESS;
+    use crate::kvapi::Faults;
+    use crate::kvapi::FaultyKVApi;
+    use crate::kvapi::KVApi;
+    use crate::kvapi::MemKVApi;
+    use crate::kvapi::MeteredKVApi;
+    use crate::kvapi::UpsertKVReq;
+
+    /// The histogram buckets recorded for `method` and `outcome`, empty if nothing is recorded.
+    fn recorded(method: &str, outcome: &str) -> Vec<HistogramCount> {
+        let registry = load_global_prometheus_registry();
+        let samples = dump_metric_samples(registry.inner()).unwrap();
+
+        samples
+            .into_iter()
+            .filter(|s| s.name == METRIC_KV_API_REQUEST_DURATION_MS)
+            .filter(|s| s.labels.get(LABEL_METHOD).map(|x| x.as_str()) == Some(method))
+            .filter(|s| s.labels.get(LABEL_OUTCOME).map(|x| x.as_str()) == Some(outcome))
+            .find_map(|s| match s.value {
+                MetricValue::Histogram(buckets) => Some(buckets),
+                _ => None,
+            })
+            .unwrap_or_default()
+    }
+
+    /// The number of recorded requests: the count of the last bucket, `+Inf`.
+    fn count(method: &str, outcome: &str) -> f64 {
+        recorded(method, outcome)
+            .last()
+            .map(|b| b.count)
+            .unwrap_or_default()
+    }
+
+    /// The number of recorded requests that took less than `ms` milliseconds.
+    fn count_less_than(method: &str, outcome: &str, ms: f64) -> f64 {
+        recorded(method, outcome)
+            .iter()
+            .find(|b| b.less_than == ms)
+            .map(|b| b.count)
+            .unwrap_or_default()
+    }
+
+    #[tokio::test]
+    async fn test_metered_outcome() -> anyhow::Result<()> {
+        let faults = Faults::new();
+        let kv = MeteredKVApi::new(FaultyKVApi::new(MemKVApi::new(), faults.clone()));
+
+        let upsert_ok = count("upsert_kv", OUTCOME_SUCCESS);
+        let upsert_err = count("upsert_kv", OUTCOME_ERROR);
+        let get_ok = count("get_kv", OUTCOME_SUCCESS);
+        let get_err = count("get_kv", OUTCOME_ERROR);
+
+        kv.upsert_kv(UpsertKVReq::update("a", b"1")).await?;
+        kv.get_kv("a").await?;
+        kv.get_kv("b").await?;
+
+        faults.set_method_failure_probability("get_kv", 1.0);
+        assert!(kv.get_kv("a").await.is_err());
+
+        assert_eq!(upsert_ok + 1.0, count("upsert_kv", OUTCOME_SUCCESS));
+        assert_eq!(upsert_err, count("upsert_kv", OUTCOME_ERROR));
+        assert_eq!(get_ok + 2.0, count("get_kv", OUTCOME_SUCCESS));
+        assert_eq!(get_err + 1.0, count("get_kv", OUTCOME_ERROR));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_metered_duration() -> anyhow::Result<()> {
+        let faults = Faults::new();
+        let kv = MeteredKVApi::new(FaultyKVApi::new(MemKVApi::new(), faults.clone()));
+
+        let total = count("mget_kv", OUTCOME_ERROR);
+        let fast = count_less_than("mget_kv", OUTCOME_ERROR, 50.0);
+
+        // A failed request is recorded with the time it waited.
+        faults.set_latency(Duration::from_millis(60));
+        faults.set_method_failure_probability("mget_kv", 1.0);
+        assert!(kv.mget_kv(&["a".to_string()]).await.is_err());
+
+        assert_eq!(total + 1.0, count("mget_kv", OUTCOME_ERROR));
+        assert_eq!(fast, count_less_than("mget_kv", OUTCOME_ERROR, 50.0));
+
+        Ok(())
+    }
+}
@@ -13,29 +13,97 @@
 // limitations under the License.
 
 mod api;
+mod api_ext;
+mod authz;
+mod backoff;
+mod coalescing;
+mod compressing;
+mod faulty;
+mod fencing;
 mod helper;
 mod key;
 mod key_builder;
 mod key_parser;
+mod kv_lock;
+mod mem_kv_api;
 mod message;
+mod metered;
+mod mset;
 mod prefix;
+mod recording;
+mod retry;
+mod seeded_builder;
+mod sharded;
+mod size_limited;
 mod test_suite;
+mod timeout;
+mod traced;
+mod txn_builder;
 
 pub use api::ApiBuilder;
 pub use api::AsKVApi;
 pub use api::KVApi;
 pub use api::KVStream;
+pub use api_ext::decode_counter;
+pub use api_ext::encode_counter;
+pub use api_ext::KVApiExt;
+pub use authz::Access;
+pub use authz::AuthzKVApi;
+pub use authz::Principal;
+pub use backoff::BackoffPolicy;
+pub use coalescing::CoalescingKVApi;
+pub use compressing::Codec;
+pub use compressing::CompressingKVApi;
+pub use faulty::Faults;
+pub use faulty::FaultyApiBuilder;
+pub use faulty::FaultyKVApi;
+pub use fencing::decode_epoch;
+pub use fencing::encode_epoch;
+pub use fencing::FencedKVApi;
 pub use key::Key;
 pub use key::KeyError;
 pub use key_builder::KeyBuilder;
 pub use key_parser::KeyParser;
+pub use kv_lock::KvLock;
+pub use kv_lock::LockGuard;
+pub use mem_kv_api::MemKVApi;
+pub use mem_kv_api::MemKVApiBuilder;
 pub use message::GetKVReply;
 pub use message::GetKVReq;
+pub use message::HasKVReq;
 pub use message::ListKVReply;
 pub use message::ListKVReq;
+pub use message::ListKeysReq;
 pub use message::MGetKVReply;
 pub use message::MGetKVReq;
 pub use message::UpsertKVReply;
 pub use message::UpsertKVReq;
+pub use message::WatchEvent;
+pub use message::WatchEventKind;
+pub use metered::MeteredKVApi;
+pub use metered::LABEL_METHOD;
+pub use metered::LABEL_OUTCOME;
+pub use metered::METRIC_KV_API_REQUEST_DURATION_MS;
+pub use metered::OUTCOME_ERROR;
+pub use metered::OUTCOME_SUCCESS;
+pub use prefix::bytes_next;
+pub use prefix::common_prefix;
+pub use prefix::prefix_to_open_range;
 pub use prefix::prefix_to_range;
+pub use prefix::prefix_to_range_bytes;
+pub use prefix::prefix_upper_bound;
+pub use recording::KVApiCall;
+pub use recording::Recording;
+pub use recording::RecordingKVApi;
+pub use recording::ReplayKVApi;
+pub use recording::StreamItems;
+pub use retry::RetryKVApi;
+pub use retry::RetryPredicate;
+pub use seeded_builder::SeededApiBuilder;
+pub use sharded::ShardedKVApi;
+pub use size_limited::SizeLimitedKVApi;
+pub use size_limited::METRIC_KV_API_TXN_SIZE_BYTES;
 pub use test_suite::TestSuite;
+pub use timeout::TimeoutKVApi;
+pub use traced::TracedKVApi;
+pub use txn_builder::TxnBuilder;
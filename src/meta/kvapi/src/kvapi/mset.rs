@@ -0,0 +1,216 @@
+// Copyright 2021 Datafuse Labs
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Fold a batch of upserts into one transaction, for the default impl of `KVApi::mset_kv()`.
+
+use anyerror::AnyError;
+use databend_common_meta_types::protobuf as pb;
+use databend_common_meta_types::txn_condition;
+use databend_common_meta_types::txn_op;
+use databend_common_meta_types::txn_op_response;
+use databend_common_meta_types::Change;
+use databend_common_meta_types::ConditionResult;
+use databend_common_meta_types::InvalidArgument;
+use databend_common_meta_types::MatchSeq;
+use databend_common_meta_types::Operation;
+use databend_common_meta_types::SeqV;
+use databend_common_meta_types::TxnCondition;
+use databend_common_meta_types::TxnOp;
+use databend_common_meta_types::TxnOpResponse;
+use databend_common_meta_types::TxnReply;
+use databend_common_meta_types::TxnRequest;
+
+use crate::kvapi::UpsertKVReply;
+use crate::kvapi::UpsertKVReq;
+
+/// Build a transaction that applies all of the `reqs` if every `seq` of them matches.
+///
+/// In the `if_then` branch, every upsert is followed by a `get` to read the result of it.
+/// In the `else_then` branch, it reads every key so that the replies still reflect the current state.
+pub(crate) fn mset_txn(reqs: &[UpsertKVReq]) -> Result<TxnRequest, InvalidArgument> {
+    let mut condition = vec![];
+    let mut if_then = vec![];
+    let mut else_then = vec![];
+
+    for req in reqs {
+        let expected = match req.seq {
+            MatchSeq::Any | MatchSeq::GE(0) => None,
+            MatchSeq::Exact(seq) => Some((ConditionResult::Eq, seq)),
+            MatchSeq::GE(seq) => Some((ConditionResult::Ge, seq)),
+        };
+
+        if let Some((op, seq)) = expected {
+            condition.push(TxnCondition {
+                key: req.key.clone(),
+                expected: op as i32,
+                target: Some(txn_condition::Target::Seq(seq)),
+            });
+        }
+
+        let op = match &req.value {
+            Operation::Update(v) => {
+                let meta = req.value_meta.as_ref();
+                TxnOp {
+                    request: Some(txn_op::Request::Put(pb::TxnPutRequest {
+                        key: req.key.clone(),
+                        value: v.clone(),
+                        prev_value: true,
+                        expire_at: meta.and_then(|m| m.get_expire_at()),
+                        ttl_ms: meta.and_then(|m| m.get_ttl()).map(|t| t.millis()),
+                    })),
+                }
+            }
+            Operation::Delete => TxnOp::delete(&req.key),
+            Operation::AsIs => {
+                return Err(InvalidArgument::new(
+                    AnyError::error(format!(
+                        "Operation::AsIs for key '{}' can not be batched",
+                        req.key
+                    )),
+                    "mset_kv",
+                ));
+            }
+        };
+
+        if_then.push(op);
+        if_then.push(TxnOp::get(&req.key));
+        else_then.push(TxnOp::get(&req.key));
+    }
+
+    Ok(TxnRequest {
+        condition,
+        if_then,
+        else_then,
+    })
+}
+
+/// Convert the reply of the transaction built by [`mset_txn`] to one [`UpsertKVReply`] per request.
+///
+/// If the transaction is not applied, every reply has `prev == result`.
+pub(crate) fn mset_replies(reply: TxnReply) -> Vec<UpsertKVReply> {
+    if reply.success {
+        reply
+            .responses
+            .chunks(2)
+            .map(|pair| {
+                let prev = pair.first().and_then(prev_value);
+                let result = pair.get(1).and_then(get_value);
+                Change::new(prev, result)
+            })
+            .collect()
+    } else {
+        reply
+            .responses
+            .iter()
+            .map(|resp| {
+                let curr = get_value(resp);
+                Change::new(curr.clone(), curr)
+            })
+            .collect()
+    }
+}
+
+fn prev_value(resp: &TxnOpResponse) -> Option<SeqV> {
+    let prev = match resp.response.as_ref()? {
+        txn_op_response::Response::Put(put) => put.prev_value.clone(),
+        txn_op_response::Response::Delete(delete) => delete.prev_value.clone(),
+        _ => None,
+    };
+    prev.map(SeqV::from)
+}
+
+fn get_value(resp: &TxnOpResponse) -> Option<SeqV> {
+    match resp.response.as_ref()? {
+        txn_op_response::Response::Get(get) => get.value.clone().map(SeqV::from),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use databend_common_meta_types::protobuf as pb;
+    use databend_common_meta_types::MatchSeq;
+    use databend_common_meta_types::Operation;
+    use databend_common_meta_types::SeqV;
+    use databend_common_meta_types::TxnOpResponse;
+    use databend_common_meta_types::TxnReply;
+    use databend_common_meta_types::With;
+
+    use crate::kvapi::mset::mset_replies;
+    use crate::kvapi::mset::mset_txn;
+    use crate::kvapi::UpsertKVReply;
+    use crate::kvapi::UpsertKVReq;
+
+    #[test]
+    fn test_mset_txn() -> anyhow::Result<()> {
+        let txn = mset_txn(&[
+            UpsertKVReq::update("a", b"1"),
+            UpsertKVReq::update("b", b"2").with(MatchSeq::Exact(3)),
+            UpsertKVReq::delete("c"),
+        ])?;
+
+        assert_eq!(1, txn.condition.len());
+        assert_eq!(6, txn.if_then.len());
+        assert_eq!(3, txn.else_then.len());
+
+        let res = mset_txn(&[UpsertKVReq::new(
+            "a",
+            MatchSeq::GE(0),
+            Operation::AsIs,
+            None,
+        )]);
+        assert!(res.is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_mset_replies() {
+        let sv = |seq, v: &str| SeqV::new(seq, v.as_bytes().to_vec());
+
+        let reply = TxnReply {
+            success: true,
+            error: "".to_string(),
+            responses: vec![
+                TxnOpResponse::put("a", Some(pb::SeqV::from(sv(1, "x")))),
+                TxnOpResponse::get("a", Some(sv(3, "y"))),
+                TxnOpResponse::delete("b", true, Some(pb::SeqV::from(sv(2, "z")))),
+                TxnOpResponse::get("b", None),
+            ],
+        };
+        assert_eq!(
+            vec![
+                UpsertKVReply::new(Some(sv(1, "x")), Some(sv(3, "y"))),
+                UpsertKVReply::new(Some(sv(2, "z")), None),
+            ],
+            mset_replies(reply)
+        );
+
+        let reply = TxnReply {
+            success: false,
+            error: "".to_string(),
+            responses: vec![
+                TxnOpResponse::get("a", Some(sv(1, "x"))),
+                TxnOpResponse::get("b", None),
+            ],
+        };
+        assert_eq!(
+            vec![
+                UpsertKVReply::new(Some(sv(1, "x")), Some(sv(1, "x"))),
+                UpsertKVReply::new(None, None),
+            ],
+            mset_replies(reply)
+        );
+    }
+}
@@ -18,42 +18,114 @@ use crate::kvapi::KeyError;
 
 /// Convert a `prefix` to a left-close-right-open range (start, end) that includes exactly all possible string that starts with `prefix`.
 ///
-/// It only supports ASCII characters.
+/// It supports any unicode chars.
+/// An empty `end` means the range is unbounded on the right, like [`prefix_to_range_bytes`],
+/// which is the case if `prefix` is empty or consists of only `char::MAX`.
 pub fn prefix_to_range(prefix: &str) -> Result<(String, String), KeyError> {
-    Ok((prefix.to_string(), ascii_next(prefix)?))
+    Ok((prefix.to_string(), str_next(prefix).unwrap_or_default()))
 }
 
-// TODO: the algo is not likely correct.
-/// Return a ascii string that bigger than all the string starts with the input `s`.
+/// Convert a `prefix` to a range `(start, end)` like [`prefix_to_range`],
+/// except that `end` is `None` if the range is unbounded on the right.
 ///
-/// It only supports ASCII char.
+/// A backend that supports open-ended scans should prefer it to a synthesized sentinel end.
+pub fn prefix_to_open_range(prefix: &str) -> (String, Option<String>) {
+    (prefix.to_string(), prefix_upper_bound(prefix))
+}
+
+/// Return the smallest string that is bigger than all the strings starting with `prefix`,
+/// or `None` if there is no such string.
 ///
-/// "a" -> "b"
-/// "1" -> "2"
-/// [96,97,127] -> [96,98,127]
-/// [127] -> [127, 127]
-/// [127, 127, 127, 127] -> [127, 127, 127, 127, 127]
-fn ascii_next(s: &str) -> Result<String, KeyError> {
-    for c in s.chars() {
-        if !c.is_ascii() {
-            return Err(KeyError::AsciiError {
-                non_ascii: s.to_string(),
-            });
-        }
-    }
+/// There is no finite upper bound if `prefix` is empty or consists of only `char::MAX`:
+/// every key, or every key starting with `char::MAX`, is included, i.e., the upper bound is open.
+///
+/// "a" -> Some("b")
+/// ['a', char::MAX] -> Some("b")
+/// "" -> None
+/// [char::MAX, char::MAX] -> None
+pub fn prefix_upper_bound(prefix: &str) -> Option<String> {
+    str_next(prefix)
+}
 
-    let mut l = s.len();
-    while l > 0 {
-        l -= 1;
-        if let Some(c) = s.chars().nth(l) {
-            if c == 127 as char {
-                continue;
-            }
-            return Ok(replace_nth_char(s, l, (c as u8 + 1) as char));
-        }
+/// Convert a binary `prefix` to a left-close-right-open range (start, end) that includes exactly all keys that start with `prefix`.
+///
+/// It is the byte-oriented version of [`prefix_to_range`], for keys that are not valid UTF-8.
+/// An empty `end` means the range is unbounded on the right,
+/// which is the case if `prefix` is empty or consists of only `0xFF`.
+pub fn prefix_to_range_bytes(prefix: &[u8]) -> (Vec<u8>, Vec<u8>) {
+    (prefix.to_vec(), bytes_next(prefix))
+}
+
+/// Return the smallest byte string that is bigger than all the byte strings starting with `prefix`.
+///
+/// It strips all the trailing `0xFF` and increments the last byte left.
+/// If there is no byte left, i.e., every byte is `0xFF` or `prefix` is empty,
+/// there is no such upper bound and an empty vec is returned.
+///
+/// [1, 2] -> [1, 3]
+/// [1, 2, 0xFF] -> [1, 3]
+/// [0xFF, 0xFF] -> []
+/// [] -> []
+pub fn bytes_next(prefix: &[u8]) -> Vec<u8> {
+    let Some(last) = prefix.iter().rposition(|b| *b != 0xFF) else {
+        return vec![];
+    };
+
+    let mut next = prefix[..=last].to_vec();
+    next[last] += 1;
+    next
+}
+
+/// Return the longest common prefix of `a` and `b`, on char boundary.
+///
+/// common_prefix("abc", "abd") -> "ab"
+/// common_prefix("我的", "我们") -> "我"
+pub fn common_prefix<'a>(a: &'a str, b: &str) -> &'a str {
+    let len = a
+        .char_indices()
+        .zip(b.chars())
+        .find(|((_, ca), cb)| ca != cb)
+        .map(|((i, _), _)| i)
+        .unwrap_or_else(|| a.len().min(b.len()));
+
+    &a[..len]
+}
+
+/// Return the smallest string that is bigger than all the strings starting with `s`,
+/// or `None` if `s` is empty or consists of only `char::MAX`.
+///
+/// It strips all the trailing `char::MAX` and increments the last byte left, like [`bytes_next`].
+/// If the incremented byte is not valid UTF-8, i.e., the last char is the last one of its encoding length,
+/// the increment carries to the whole char, which is replaced with the next char.
+///
+/// "a" -> Some("b")
+/// "1" -> Some("2")
+/// "a我" -> Some("a戒")
+/// "\u{7f}" -> Some("\u{80}")
+/// ['a', 'b', char::MAX] -> Some("ac")
+/// [char::MAX] -> None
+fn str_next(s: &str) -> Option<String> {
+    let trimmed = s.trim_end_matches(char::MAX);
+    let last = trimmed.chars().last()?;
+
+    if let Ok(next) = String::from_utf8(bytes_next(trimmed.as_bytes())) {
+        return Some(next);
     }
 
-    Ok(format!("{}{}", s, 127 as char))
+    let mut next = trimmed[..trimmed.len() - last.len_utf8()].to_string();
+    next.push(char_next(last));
+    Some(next)
+}
+
+/// Return the next unicode scalar value of `c`, skipping the surrogate range.
+///
+/// `c` must not be `char::MAX`.
+fn char_next(c: char) -> char {
+    debug_assert!(c != char::MAX);
+
+    // The only invalid values below char::MAX are surrogates: 0xD800..=0xDFFF.
+    // Safe unwrap(): 0xE000 is a valid char.
+    char::from_u32(c as u32 + 1).unwrap_or_else(|| char::from_u32(0xE000).unwrap())
 }
 
 /// Replace idx-th char as new char
@@ -70,43 +142,53 @@ pub fn replace_nth_char(s: &str, idx: usize, new_char: char) -> String {
 
 #[cfg(test)]
 mod tests {
-    use crate::kvapi::prefix::ascii_next;
+    use crate::kvapi::prefix::bytes_next;
+    use crate::kvapi::prefix::char_next;
+    use crate::kvapi::prefix::common_prefix;
     use crate::kvapi::prefix::replace_nth_char;
+    use crate::kvapi::prefix::str_next;
+    use crate::kvapi::prefix_to_open_range;
     use crate::kvapi::prefix_to_range;
+    use crate::kvapi::prefix_to_range_bytes;
+    use crate::kvapi::prefix_upper_bound;
 
     #[test]
-    fn test_ascii_next() -> anyhow::Result<()> {
-        assert_eq!("b".to_string(), ascii_next("a")?);
-        assert_eq!("2".to_string(), ascii_next("1")?);
+    fn test_str_next() {
+        assert_eq!(Some("b".to_string()), str_next("a"));
+        assert_eq!(Some("2".to_string()), str_next("1"));
         assert_eq!(
-            "__fd_table_by_ie".to_string(),
-            ascii_next("__fd_table_by_id")?
+            Some("__fd_table_by_ie".to_string()),
+            str_next("__fd_table_by_id")
         );
+        assert_eq!(Some("a戒".to_string()), str_next("a我"));
+        assert_eq!(Some("db/😁".to_string()), str_next("db/😀"));
+
+        // The incremented last byte is not valid UTF-8: carry to the next char
+        assert_eq!(Some("\u{80}".to_string()), str_next("\u{7f}"));
+        assert_eq!(Some("a\u{800}".to_string()), str_next("a\u{7ff}"));
+        assert_eq!(Some("\u{E000}".to_string()), str_next("\u{D7FF}"));
+        assert_eq!(Some("\u{10000}".to_string()), str_next("\u{FFFF}"));
+
         {
-            let str = 127 as char;
-            let s = str.to_string();
-            let ret = ascii_next(&s)?;
-            for byte in ret.as_bytes() {
-                assert_eq!(*byte, 127_u8);
-            }
-        }
-        {
-            let s = format!("ab{}", 127 as char);
-            let ret = ascii_next(&s)?;
-            assert_eq!(ret, format!("ac{}", 127 as char));
+            let s = format!("ab{}{}", char::MAX, char::MAX);
+            assert_eq!(Some("ac".to_string()), str_next(&s));
         }
         {
-            let s = "我".to_string();
-            let ret = ascii_next(&s);
-            match ret {
-                Err(e) => {
-                    assert_eq!("Non-ascii char are not supported: '我'", e.to_string(),);
-                }
-                Ok(_) => panic!("MUST return error "),
-            }
+            let s = format!("表{}", char::MAX);
+            assert_eq!(Some("衩".to_string()), str_next(&s));
         }
 
-        Ok(())
+        // No upper bound
+        assert_eq!(None, str_next(""));
+        assert_eq!(None, str_next(&char::MAX.to_string()));
+        assert_eq!(None, str_next(&format!("{}{}", char::MAX, char::MAX)));
+    }
+
+    #[test]
+    fn test_char_next() {
+        assert_eq!('b', char_next('a'));
+        assert_eq!('\u{E000}', char_next('\u{D7FF}'));
+        assert_eq!(char::MAX, char_next('\u{10FFFE}'));
     }
 
     #[test]
@@ -114,28 +196,139 @@ mod tests {
         assert_eq!(("aa".to_string(), "ab".to_string()), prefix_to_range("aa")?);
         assert_eq!(("a1".to_string(), "a2".to_string()), prefix_to_range("a1")?);
         {
-            let str = 127 as char;
-            let s = str.to_string();
-            let (_, end) = prefix_to_range(&s)?;
-            for byte in end.as_bytes() {
-                assert_eq!(*byte, 127_u8);
-            }
+            let s = format!("a{}", char::MAX);
+            assert_eq!((s.clone(), "b".to_string()), prefix_to_range(&s)?);
+        }
+
+        // No finite upper bound: an empty end
+        assert_eq!(("".to_string(), "".to_string()), prefix_to_range("")?);
+        {
+            let s = char::MAX.to_string();
+            assert_eq!((s.clone(), "".to_string()), prefix_to_range(&s)?);
         }
+
+        fn in_range(range: &(String, String), key: &str) -> bool {
+            range.0.as_str() <= key && key < range.1.as_str()
+        }
+
+        // CJK prefix
+        {
+            let range = prefix_to_range("db/我")?;
+            assert!(in_range(&range, "db/我"));
+            assert!(in_range(&range, "db/我的表"));
+            assert!(in_range(&range, &format!("db/我{}", char::MAX)));
+            assert!(!in_range(&range, "db/戒"));
+            assert!(!in_range(&range, "db/戈"));
+            assert!(!in_range(&range, "db/"));
+        }
+
+        // Emoji prefix
         {
-            let ret = prefix_to_range("我");
-            match ret {
-                Err(e) => {
-                    assert_eq!("Non-ascii char are not supported: '我'", e.to_string(),);
-                }
-                Ok(_) => panic!("MUST return error "),
-            }
+            let range = prefix_to_range("t/😀")?;
+            assert!(in_range(&range, "t/😀"));
+            assert!(in_range(&range, "t/😀😀"));
+            assert!(in_range(&range, "t/😀a"));
+            assert!(!in_range(&range, "t/😁"));
+            assert!(!in_range(&range, "t/🙂"));
+            assert!(!in_range(&range, "t/a"));
         }
         Ok(())
     }
 
+    #[test]
+    fn test_prefix_upper_bound() {
+        // An ordinary prefix
+        assert_eq!(Some("ab".to_string()), prefix_upper_bound("aa"));
+        assert_eq!(
+            Some("__fd_table_by_ie".to_string()),
+            prefix_upper_bound("__fd_table_by_id")
+        );
+        assert_eq!(
+            Some("b".to_string()),
+            prefix_upper_bound(&format!("a{}", char::MAX))
+        );
+
+        // `\x7f` is not the max char: there is a finite upper bound
+        assert_eq!(
+            Some("\x7f\u{80}".to_string()),
+            prefix_upper_bound("\x7f\x7f")
+        );
+
+        // Empty prefix: open upper bound
+        assert_eq!(None, prefix_upper_bound(""));
+
+        // All `char::MAX`: open upper bound
+        assert_eq!(None, prefix_upper_bound(&char::MAX.to_string()));
+        assert_eq!(
+            None,
+            prefix_upper_bound(&format!("{}{}", char::MAX, char::MAX))
+        );
+
+        assert_eq!(
+            ("aa".to_string(), Some("ab".to_string())),
+            prefix_to_open_range("aa")
+        );
+        assert_eq!(("".to_string(), None), prefix_to_open_range(""));
+    }
+
+    #[test]
+    fn test_bytes_next() {
+        assert_eq!(vec![1, 3], bytes_next(&[1, 2]));
+        assert_eq!(vec![1, 3], bytes_next(&[1, 2, 0xFF, 0xFF]));
+        assert_eq!(vec![0x80], bytes_next(&[0x7F]));
+        assert_eq!(vec![0xFF], bytes_next(&[0xFE]));
+        assert_eq!(vec![0, 1], bytes_next(&[0, 0]));
+
+        // No upper bound
+        assert_eq!(Vec::<u8>::new(), bytes_next(&[0xFF]));
+        assert_eq!(Vec::<u8>::new(), bytes_next(&[0xFF, 0xFF, 0xFF]));
+        assert_eq!(Vec::<u8>::new(), bytes_next(&[]));
+    }
+
+    #[test]
+    fn test_prefix_to_range_bytes() {
+        fn in_range(range: &(Vec<u8>, Vec<u8>), key: &[u8]) -> bool {
+            range.0.as_slice() <= key && (range.1.is_empty() || key < range.1.as_slice())
+        }
+
+        // Not valid UTF-8
+        let range = prefix_to_range_bytes(&[b'k', 0xC3, 0xFF]);
+        assert_eq!((vec![b'k', 0xC3, 0xFF], vec![b'k', 0xC4]), range);
+        assert!(in_range(&range, &[b'k', 0xC3, 0xFF]));
+        assert!(in_range(&range, &[b'k', 0xC3, 0xFF, 0xFF, 0]));
+        assert!(!in_range(&range, &[b'k', 0xC3, 0xFE]));
+        assert!(!in_range(&range, &[b'k', 0xC4]));
+        assert!(!in_range(&range, &[b'k']));
+
+        // All 0xFF: unbounded on the right
+        let range = prefix_to_range_bytes(&[0xFF, 0xFF]);
+        assert_eq!((vec![0xFF, 0xFF], vec![]), range);
+        assert!(in_range(&range, &[0xFF, 0xFF, 0xFF]));
+        assert!(!in_range(&range, &[0xFF, 0xFE]));
+
+        // Empty prefix matches every key
+        let range = prefix_to_range_bytes(&[]);
+        assert_eq!((vec![], vec![]), range);
+        assert!(in_range(&range, &[]));
+        assert!(in_range(&range, &[0xFF]));
+    }
+
+    #[test]
+    fn test_common_prefix() {
+        assert_eq!("ab", common_prefix("abc", "abd"));
+        assert_eq!("ab", common_prefix("ab", "abd"));
+        assert_eq!("ab", common_prefix("abd", "ab"));
+        assert_eq!("", common_prefix("abc", "bcd"));
+        assert_eq!("", common_prefix("", "abc"));
+        assert_eq!("abc", common_prefix("abc", "abc"));
+        assert_eq!("我", common_prefix("我的", "我们"));
+        assert_eq!("a", common_prefix("a😀", "a😁"));
+    }
+
     #[test]
     fn test_replace_nth_char() {
         assert_eq!("a23".to_string(), replace_nth_char("a13", 1, '2'));
         assert_eq!("a13".to_string(), replace_nth_char("a13", 10, '2'));
+        assert_eq!("我2表".to_string(), replace_nth_char("我的表", 1, '2'));
     }
 }
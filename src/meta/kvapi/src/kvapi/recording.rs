@@ -0,0 +1,454 @@
+// Copyright 2021 Datafuse Labs
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! A kvapi::KVApi decorator that records every call, and a kvapi::KVApi impl that replays the recorded calls.
+
+use std::collections::VecDeque;
+use std::sync::Arc;
+use std::sync::Mutex;
+
+use anyerror::AnyError;
+use async_trait::async_trait;
+use databend_common_meta_types::protobuf as pb;
+use databend_common_meta_types::protobuf::StreamItem;
+use databend_common_meta_types::InvalidArgument;
+use databend_common_meta_types::MetaError;
+use databend_common_meta_types::TxnReply;
+use databend_common_meta_types::TxnRequest;
+use futures_util::StreamExt;
+use futures_util::TryStreamExt;
+use serde::Deserialize;
+use serde::Serialize;
+
+use crate::kvapi;
+use crate::kvapi::GetKVReply;
+use crate::kvapi::KVStream;
+use crate::kvapi::ListKVReply;
+use crate::kvapi::MGetKVReply;
+use crate::kvapi::UpsertKVReply;
+use crate::kvapi::UpsertKVReq;
+
+/// The items of a recorded stream, such as the one returned by `list_kv()`.
+pub type StreamItems = Vec<(String, Option<pb::SeqV>)>;
+
+/// A call to a kvapi::KVApi method with its arguments and what it returned.
+///
+/// The items of a stream are collected, and an error that occurs while reading it is recorded as the result.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub enum KVApiCall {
+    UpsertKV {
+        req: UpsertKVReq,
+        reply: Result<UpsertKVReply, MetaError>,
+    },
+    MSetKV {
+        reqs: Vec<UpsertKVReq>,
+        reply: Result<Vec<UpsertKVReply>, MetaError>,
+    },
+    GetKV {
+        key: String,
+        reply: Result<GetKVReply, MetaError>,
+    },
+    MGetKV {
+        keys: Vec<String>,
+        reply: Result<MGetKVReply, MetaError>,
+    },
+    GetKVStream {
+        keys: Vec<String>,
+        reply: Result<StreamItems, MetaError>,
+    },
+    ListKV {
+        prefix: String,
+        reply: Result<StreamItems, MetaError>,
+    },
+    PrefixListKV {
+        prefix: String,
+        reply: Result<ListKVReply, MetaError>,
+    },
+    Transaction {
+        txn: TxnRequest,
+        reply: Result<TxnReply, MetaError>,
+    },
+}
+
+/// The calls a [`RecordingKVApi`] records, in call order.
+///
+/// It is a shared handle: clones of it share the same calls,
+/// thus a test is able to read them while the store is in use.
+/// The calls are serde-serializable, to be saved to a file and replayed with [`ReplayKVApi`] later.
+#[derive(Clone, Debug, Default)]
+pub struct Recording {
+    calls: Arc<Mutex<Vec<KVApiCall>>>,
+}
+
+impl Recording {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns a copy of the calls recorded so far.
+    pub fn calls(&self) -> Vec<KVApiCall> {
+        self.calls.lock().unwrap().clone()
+    }
+
+    fn push(&self, call: KVApiCall) {
+        self.calls.lock().unwrap().push(call);
+    }
+}
+
+/// A kvapi::KVApi impl that delegates to an inner impl and records every call to a [`Recording`].
+///
+/// A stream returned by the inner impl is read to the end before it is returned,
+/// so that all of its items are recorded.
+pub struct RecordingKVApi<T> {
+    inner: T,
+    recording: Recording,
+}
+
+impl<T> RecordingKVApi<T>
+where T: kvapi::KVApi<Error = MetaError>
+{
+    pub fn new(inner: T, recording: Recording) -> Self {
+        Self { inner, recording }
+    }
+
+    pub fn inner(&self) -> &T {
+        &self.inner
+    }
+
+    pub fn recording(&self) -> &Recording {
+        &self.recording
+    }
+
+    async fn collect(
+        strm: Result<KVStream<MetaError>, MetaError>,
+    ) -> Result<StreamItems, MetaError> {
+        let items = strm?
+            .map_ok(|item| (item.key, item.value))
+            .try_collect::<Vec<_>>()
+            .await?;
+        Ok(items)
+    }
+}
+
+/// Build a stream of recorded items.
+fn into_stream(items: Result<StreamItems, MetaError>) -> Result<KVStream<MetaError>, MetaError> {
+    let items = items?;
+    let strm = futures_util::stream::iter(items.into_iter().map(|kv| Ok(StreamItem::from(kv))));
+    Ok(strm.boxed())
+}
+
+#[async_trait]
+impl<T> kvapi::KVApi for RecordingKVApi<T>
+where T: kvapi::KVApi<Error = MetaError>
+{
+    type Error = MetaError;
+
+    async fn upsert_kv(&self, req: UpsertKVReq) -> Result<UpsertKVReply, Self::Error> {
+        let reply = self.inner.upsert_kv(req.clone()).await;
+        self.recording.push(KVApiCall::UpsertKV {
+            req,
+            reply: reply.clone(),
+        });
+        reply
+    }
+
+    async fn mset_kv(&self, reqs: Vec<UpsertKVReq>) -> Result<Vec<UpsertKVReply>, Self::Error> {
+        let reply = self.inner.mset_kv(reqs.clone()).await;
+        self.recording.push(KVApiCall::MSetKV {
+            reqs,
+            reply: reply.clone(),
+        });
+        reply
+    }
+
+    async fn get_kv(&self, key: &str) -> Result<GetKVReply, Self::Error> {
+        let reply = self.inner.get_kv(key).await;
+        self.recording.push(KVApiCall::GetKV {
+            key: key.to_string(),
+            reply: reply.clone(),
+        });
+        reply
+    }
+
+    async fn mget_kv(&self, keys: &[String]) -> Result<MGetKVReply, Self::Error> {
+        let reply = self.inner.mget_kv(keys).await;
+        self.recording.push(KVApiCall::MGetKV {
+            keys: keys.to_vec(),
+            reply: reply.clone(),
+        });
+        reply
+    }
+
+    async fn get_kv_stream(&self, keys: &[String]) -> Result<KVStream<Self::Error>, Self::Error> {
+        let items = Self::collect(self.inner.get_kv_stream(keys).await).await;
+        self.recording.push(KVApiCall::GetKVStream {
+            keys: keys.to_vec(),
+            reply: items.clone(),
+        });
+        into_stream(items)
+    }
+
+    async fn list_kv(&self, prefix: &str) -> Result<KVStream<Self::Error>, Self::Error> {
+        let items = Self::collect(self.inner.list_kv(prefix).await).await;
+        self.recording.push(KVApiCall::ListKV {
+            prefix: prefix.to_string(),
+            reply: items.clone(),
+        });
+        into_stream(items)
+    }
+
+    async fn prefix_list_kv(&self, prefix: &str) -> Result<ListKVReply, Self::Error> {
+        let reply = self.inner.prefix_list_kv(prefix).await;
+        self.recording.push(KVApiCall::PrefixListKV {
+            prefix: prefix.to_string(),
+            reply: reply.clone(),
+        });
+        reply
+    }
+
+    async fn transaction(&self, txn: TxnRequest) -> Result<TxnReply, Self::Error> {
+        let reply = self.inner.transaction(txn.clone()).await;
+        self.recording.push(KVApiCall::Transaction {
+            txn,
+            reply: reply.clone(),
+        });
+        reply
+    }
+}
+
+/// A kvapi::KVApi impl that serves the calls recorded by a [`RecordingKVApi`], without any store.
+///
+/// The calls must be made in the recorded order with the recorded arguments,
+/// and every call returns exactly what it returned when it was recorded.
+/// A call that does not match the next recorded one, or is made after all of them are replayed,
+/// fails with an [`InvalidArgument`] error, and the recorded call is not consumed.
+pub struct ReplayKVApi {
+    calls: Mutex<VecDeque<KVApiCall>>,
+}
+
+impl ReplayKVApi {
+    pub fn new(calls: Vec<KVApiCall>) -> Self {
+        Self {
+            calls: Mutex::new(calls.into()),
+        }
+    }
+
+    /// Returns the number of recorded calls that are not replayed yet.
+    pub fn remaining(&self) -> usize {
+        self.calls.lock().unwrap().len()
+    }
+
+    /// Pop the next recorded call and return its reply if `matches()` accepts it.
+    fn replay<R>(
+        &self,
+        method: &str,
+        matches: impl FnOnce(KVApiCall) -> Result<Result<R, MetaError>, KVApiCall>,
+    ) -> Result<R, MetaError> {
+        let mut calls = self.calls.lock().unwrap();
+
+        let Some(call) = calls.pop_front() else {
+            return Err(InvalidArgument::new(
+                AnyError::error("all of the recorded calls are replayed"),
+                format!("replay {}", method),
+            )
+            .into());
+        };
+
+        match matches(call) {
+            Ok(reply) => reply,
+            Err(call) => {
+                let err = InvalidArgument::new(
+                    AnyError::error(format!("the next recorded call is: {:?}", call)),
+                    format!("replay {}: the call does not match the recording", method),
+                );
+                calls.push_front(call);
+                Err(err.into())
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl kvapi::KVApi for ReplayKVApi {
+    type Error = MetaError;
+
+    async fn upsert_kv(&self, req: UpsertKVReq) -> Result<UpsertKVReply, Self::Error> {
+        self.replay("upsert_kv", |call| match call {
+            KVApiCall::UpsertKV { req: r, reply } if r == req => Ok(reply),
+            _ => Err(call),
+        })
+    }
+
+    async fn mset_kv(&self, reqs: Vec<UpsertKVReq>) -> Result<Vec<UpsertKVReply>, Self::Error> {
+        self.replay("mset_kv", |call| match call {
+            KVApiCall::MSetKV { reqs: r, reply } if r == reqs => Ok(reply),
+            _ => Err(call),
+        })
+    }
+
+    async fn get_kv(&self, key: &str) -> Result<GetKVReply, Self::Error> {
+        self.replay("get_kv", |call| match call {
+            KVApiCall::GetKV { key: k, reply } if k == key => Ok(reply),
+            _ => Err(call),
+        })
+    }
+
+    async fn mget_kv(&self, keys: &[String]) -> Result<MGetKVReply, Self::Error> {
+        self.replay("mget_kv", |call| match call {
+            KVApiCall::MGetKV { keys: k, reply } if k == keys => Ok(reply),
+            _ => Err(call),
+        })
+    }
+
+    async fn get_kv_stream(&self, keys: &[String]) -> Result<KVStream<Self::Error>, Self::Error> {
+        let items = self.replay("get_kv_stream", |call| match call {
+            KVApiCall::GetKVStream { keys: k, reply } if k == keys => Ok(reply),
+            _ => Err(call),
+        });
+        into_stream(items)
+    }
+
+    async fn list_kv(&self, prefix: &str) -> Result<KVStream<Self::Error>, Self::Error> {
+        let items = self.replay("list_kv", |call| match call {
+            KVApiCall::ListKV { prefix: p, reply } if p == prefix => Ok(reply),
+            _ => Err(call),
+        });
+        into_stream(items)
+    }
+
+    async fn prefix_list_kv(&self, prefix: &str) -> Result<ListKVReply, Self::Error> {
+        self.replay("prefix_list_kv", |call| match call {
+            KVApiCall::PrefixListKV { prefix: p, reply } if p == prefix => Ok(reply),
+            _ => Err(call),
+        })
+    }
+
+    async fn transaction(&self, txn: TxnRequest) -> Result<TxnReply, Self::Error> {
+        self.replay("transaction", |call| match call {
+            KVApiCall::Transaction { txn: t, reply } if t == txn => Ok(reply),
+            _ => Err(call),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use databend_common_meta_types::MetaError;
+    use databend_common_meta_types::MetaNetworkError;
+    use databend_common_meta_types::TxnOp;
+    use databend_common_meta_types::TxnRequest;
+    use futures_util::TryStreamExt;
+
+    use crate::kvapi;
+    use crate::kvapi::KVApi;
+    use crate::kvapi::KVApiCall;
+    use crate::kvapi::MemKVApi;
+    use crate::kvapi::Recording;
+    use crate::kvapi::RecordingKVApi;
+    use crate::kvapi::ReplayKVApi;
+    use crate::kvapi::UpsertKVReq;
+
+    /// Run a small workload and returns the debug output of every result.
+    async fn workload(kv: &impl kvapi::KVApi<Error = MetaError>) -> Vec<String> {
+        let mut outputs = vec![];
+
+        outputs.push(format!(
+            "{:?}",
+            kv.upsert_kv(UpsertKVReq::update("a", b"1")).await
+        ));
+        outputs.push(format!(
+            "{:?}",
+            kv.mset_kv(vec![
+                UpsertKVReq::update("b", b"2"),
+                UpsertKVReq::update("c", b"3")
+            ])
+            .await
+        ));
+        outputs.push(format!("{:?}", kv.get_kv("a").await));
+        outputs.push(format!(
+            "{:?}",
+            kv.mget_kv(&["a".to_string(), "x".to_string()]).await
+        ));
+        outputs.push(format!(
+            "{:?}",
+            kv.list_kv("").await.unwrap().try_collect::<Vec<_>>().await
+        ));
+        outputs.push(format!("{:?}", kv.prefix_list_kv("b").await));
+        outputs.push(format!(
+            "{:?}",
+            kv.transaction(TxnRequest::unconditional(vec![
+                TxnOp::delete("a"),
+                TxnOp::get("b"),
+            ]))
+            .await
+        ));
+        outputs.push(format!("{:?}", kv.get_kv("a").await));
+
+        outputs
+    }
+
+    #[tokio::test]
+    async fn test_record_and_replay() -> anyhow::Result<()> {
+        let recording = Recording::new();
+        let kv = RecordingKVApi::new(MemKVApi::new(), recording.clone());
+
+        let recorded_outputs = workload(&kv).await;
+        assert_eq!(8, recording.calls().len());
+
+        // Round trip through a file format.
+        let saved = serde_json::to_string(&recording.calls())?;
+        let calls: Vec<KVApiCall> = serde_json::from_str(&saved)?;
+        assert_eq!(recording.calls(), calls);
+
+        let replay = ReplayKVApi::new(calls);
+        let replayed_outputs = workload(&replay).await;
+
+        assert_eq!(recorded_outputs, replayed_outputs);
+        assert_eq!(0, replay.remaining());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_replay_recorded_error() -> anyhow::Result<()> {
+        let err = MetaError::NetworkError(MetaNetworkError::GetNodeAddrError("foo".to_string()));
+        let replay = ReplayKVApi::new(vec![KVApiCall::GetKV {
+            key: "a".to_string(),
+            reply: Err(err.clone()),
+        }]);
+
+        // Mismatching call does not consume the recorded one.
+        let res = replay.get_kv("b").await;
+        assert!(matches!(
+            res,
+            Err(MetaError::NetworkError(MetaNetworkError::InvalidArgument(
+                _
+            )))
+        ));
+        assert_eq!(1, replay.remaining());
+
+        assert_eq!(Err(err), replay.get_kv("a").await);
+
+        // No more recorded call.
+        let res = replay.get_kv("a").await;
+        assert!(matches!(
+            res,
+            Err(MetaError::NetworkError(MetaNetworkError::InvalidArgument(
+                _
+            )))
+        ));
+
+        Ok(())
+    }
+}
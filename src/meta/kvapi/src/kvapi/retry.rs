@@ -0,0 +1,387 @@
+// Copyright 2021 Datafuse Labs
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! A kvapi::KVApi decorator that retries transient errors.
+
+use std::future::Future;
+use std::sync::Arc;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use databend_common_meta_types::TxnReply;
+use databend_common_meta_types::TxnRequest;
+use log::warn;
+
+use crate::kvapi;
+use crate::kvapi::BackoffPolicy;
+use crate::kvapi::GetKVReply;
+use crate::kvapi::KVStream;
+use crate::kvapi::ListKVReply;
+use crate::kvapi::MGetKVReply;
+use crate::kvapi::UpsertKVReply;
+use crate::kvapi::UpsertKVReq;
+
+/// Decide if an error is transient and the request can be retried.
+pub type RetryPredicate<E> = Arc<dyn Fn(&E) -> bool + Send + Sync>;
+
+/// A kvapi::KVApi impl that delegates to an inner impl and retries errors that are classified as retryable,
+/// with the backoff produced by a [`BackoffPolicy`].
+///
+/// An `upsert_kv()` may have been applied when an error is returned,
+/// thus every attempt of it carries the same idempotency token, a random one if the caller does not set it,
+/// so that a backend that records the tokens applies it at most once.
+///
+/// A `transaction()` may have been partially applied when an error is returned,
+/// thus it is retried only when it is explicitly marked as idempotent with [`RetryKVApi::with_idempotent_txn`].
+pub struct RetryKVApi<T: kvapi::KVApi> {
+    inner: T,
+    is_retryable: RetryPredicate<T::Error>,
+    max_attempts: u32,
+    backoff: BackoffPolicy,
+    idempotent_txn: bool,
+}
+
+impl<T: kvapi::KVApi> RetryKVApi<T> {
+    pub const DEFAULT_MAX_ATTEMPTS: u32 = 3;
+    pub const DEFAULT_BASE_BACKOFF: Duration = Duration::from_millis(10);
+    pub const DEFAULT_MAX_BACKOFF: Duration = Duration::from_secs(1);
+
+    pub fn new(inner: T, is_retryable: impl Fn(&T::Error) -> bool + Send + Sync + 'static) -> Self {
+        Self {
+            inner,
+            is_retryable: Arc::new(is_retryable),
+            max_attempts: Self::DEFAULT_MAX_ATTEMPTS,
+            backoff: BackoffPolicy::new(Self::DEFAULT_BASE_BACKOFF, 2.0, Self::DEFAULT_MAX_BACKOFF),
+            idempotent_txn: false,
+        }
+    }
+
+    /// Set the max number of attempts, including the first one.
+    pub fn with_max_attempts(mut self, max_attempts: u32) -> Self {
+        self.max_attempts = max_attempts.max(1);
+        self
+    }
+
+    /// Set the backoff before the first retry, and the upper bound of it.
+    ///
+    /// The backoff doubles after every retry until it reaches `max`.
+    pub fn with_backoff(mut self, base: Duration, max: Duration) -> Self {
+        self.backoff = BackoffPolicy::new(base, 2.0, max);
+        self
+    }
+
+    /// Set the policy that produces the backoff before every retry, e.g., to enable jitter.
+    ///
+    /// Every retried call starts over from the base delay of the policy.
+    pub fn with_backoff_policy(mut self, policy: BackoffPolicy) -> Self {
+        self.backoff = policy;
+        self
+    }
+
+    /// Mark `transaction()` as idempotent so that it can be retried.
+    pub fn with_idempotent_txn(mut self, idempotent: bool) -> Self {
+        self.idempotent_txn = idempotent;
+        self
+    }
+
+    pub fn inner(&self) -> &T {
+        &self.inner
+    }
+
+    /// Return a backoff state for a call, starting from the base delay.
+    fn new_backoff(&self) -> BackoffPolicy {
+        let mut backoff = self.backoff.clone();
+        backoff.reset();
+        backoff
+    }
+
+    async fn retry<R, F, Fut>(&self, ctx: &str, f: F) -> Result<R, T::Error>
+    where
+        F: Fn() -> Fut,
+        Fut: Future<Output = Result<R, T::Error>>,
+    {
+        let mut backoff_policy = self.new_backoff();
+        let mut attempt = 1;
+        loop {
+            let err = match f().await {
+                Ok(r) => return Ok(r),
+                Err(e) => e,
+            };
+
+            if attempt >= self.max_attempts || !(self.is_retryable)(&err) {
+                return Err(err);
+            }
+
+            let backoff = backoff_policy.next_delay();
+            warn!(
+                "RetryKVApi: {} failed at {}th attempt: {}; retry after {:?}",
+                ctx, attempt, err, backoff
+            );
+
+            tokio::time::sleep(backoff).await;
+            attempt += 1;
+        }
+    }
+}
+
+#[async_trait]
+impl<T: kvapi::KVApi> kvapi::KVApi for RetryKVApi<T> {
+    type Error = T::Error;
+
+    async fn upsert_kv(&self, req: UpsertKVReq) -> Result<UpsertKVReply, Self::Error> {
+        let req = if req.idempotency_token.is_some() {
+            req
+        } else {
+            let token = format!(
+                "{:016x}{:016x}",
+                rand::random::<u64>(),
+                rand::random::<u64>()
+            );
+            req.with_idempotency_token(token)
+        };
+
+        self.retry("upsert_kv", || self.inner.upsert_kv(req.clone()))
+            .await
+    }
+
+    async fn get_kv(&self, key: &str) -> Result<GetKVReply, Self::Error> {
+        self.retry("get_kv", || self.inner.get_kv(key)).await
+    }
+
+    async fn mget_kv(&self, keys: &[String]) -> Result<MGetKVReply, Self::Error> {
+        self.retry("mget_kv", || self.inner.mget_kv(keys)).await
+    }
+
+    async fn get_kv_stream(&self, keys: &[String]) -> Result<KVStream<Self::Error>, Self::Error> {
+        self.retry("get_kv_stream", || self.inner.get_kv_stream(keys))
+            .await
+    }
+
+    async fn list_kv(&self, prefix: &str) -> Result<KVStream<Self::Error>, Self::Error> {
+        self.retry("list_kv", || self.inner.list_kv(prefix)).await
+    }
+
+    async fn prefix_list_kv(&self, prefix: &str) -> Result<ListKVReply, Self::Error> {
+        self.retry("prefix_list_kv", || self.inner.prefix_list_kv(prefix))
+            .await
+    }
+
+    async fn transaction(&self, txn: TxnRequest) -> Result<TxnReply, Self::Error> {
+        if !self.idempotent_txn {
+            return self.inner.transaction(txn).await;
+        }
+
+        self.retry("transaction", || self.inner.transaction(txn.clone()))
+            .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::AtomicU32;
+    use std::sync::atomic::Ordering;
+    use std::sync::Mutex;
+    use std::time::Duration;
+
+    use async_trait::async_trait;
+    use databend_common_meta_types::MetaError;
+    use databend_common_meta_types::MetaNetworkError;
+    use databend_common_meta_types::TxnReply;
+    use databend_common_meta_types::TxnRequest;
+    use futures_util::stream;
+    use futures_util::StreamExt;
+
+    use crate::kvapi;
+    use crate::kvapi::BackoffPolicy;
+    use crate::kvapi::KVApi;
+    use crate::kvapi::KVStream;
+    use crate::kvapi::RetryKVApi;
+    use crate::kvapi::UpsertKVReply;
+    use crate::kvapi::UpsertKVReq;
+
+    /// A KVApi that fails the first `n_failures` calls then succeeds.
+    struct FlakyKV {
+        n_failures: u32,
+        calls: AtomicU32,
+        /// The idempotency token of every upsert received.
+        tokens: Mutex<Vec<Option<String>>>,
+    }
+
+    impl FlakyKV {
+        fn new(n_failures: u32) -> Self {
+            Self {
+                n_failures,
+                calls: AtomicU32::new(0),
+                tokens: Mutex::new(vec![]),
+            }
+        }
+
+        fn tokens(&self) -> Vec<Option<String>> {
+            self.tokens.lock().unwrap().clone()
+        }
+
+        fn calls(&self) -> u32 {
+            self.calls.load(Ordering::Relaxed)
+        }
+
+        fn check(&self) -> Result<(), MetaError> {
+            let i = self.calls.fetch_add(1, Ordering::Relaxed);
+            if i < self.n_failures {
+                return Err(MetaNetworkError::GetNodeAddrError(format!("failure-{}", i)).into());
+            }
+            Ok(())
+        }
+    }
+
+    #[async_trait]
+    impl kvapi::KVApi for FlakyKV {
+        type Error = MetaError;
+
+        async fn upsert_kv(&self, req: UpsertKVReq) -> Result<UpsertKVReply, Self::Error> {
+            self.tokens.lock().unwrap().push(req.idempotency_token);
+            self.check()?;
+            Ok(UpsertKVReply::new(None, None))
+        }
+
+        async fn get_kv_stream(
+            &self,
+            _keys: &[String],
+        ) -> Result<KVStream<Self::Error>, Self::Error> {
+            self.check()?;
+            Ok(stream::empty().boxed())
+        }
+
+        async fn list_kv(&self, _prefix: &str) -> Result<KVStream<Self::Error>, Self::Error> {
+            self.check()?;
+            Ok(stream::empty().boxed())
+        }
+
+        async fn transaction(&self, _txn: TxnRequest) -> Result<TxnReply, Self::Error> {
+            self.check()?;
+            Ok(TxnReply::default())
+        }
+    }
+
+    fn retry_kv(n_failures: u32, max_attempts: u32) -> RetryKVApi<FlakyKV> {
+        RetryKVApi::new(FlakyKV::new(n_failures), |_e: &MetaError| true)
+            .with_max_attempts(max_attempts)
+            .with_backoff(Duration::from_millis(1), Duration::from_millis(5))
+    }
+
+    #[tokio::test]
+    async fn test_retry_until_success() -> anyhow::Result<()> {
+        let kv = retry_kv(2, 3);
+
+        kv.upsert_kv(UpsertKVReq::update("a", b"b")).await?;
+        assert_eq!(3, kv.inner().calls());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_retry_upsert_reuses_idempotency_token() -> anyhow::Result<()> {
+        let kv = retry_kv(2, 3);
+
+        kv.upsert_kv(UpsertKVReq::update("a", b"b")).await?;
+
+        let tokens = kv.inner().tokens();
+        assert_eq!(3, tokens.len());
+        assert!(tokens[0].is_some());
+        assert!(tokens.iter().all(|t| t == &tokens[0]));
+
+        // Another logical operation gets another token.
+        kv.upsert_kv(UpsertKVReq::update("a", b"b")).await?;
+        assert_ne!(tokens[0], kv.inner().tokens()[3]);
+
+        // A token set by the caller is kept.
+        kv.upsert_kv(UpsertKVReq::update("a", b"b").with_idempotency_token("t1"))
+            .await?;
+        assert_eq!(Some("t1".to_string()), kv.inner().tokens()[4]);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_retry_exceeds_max_attempts() -> anyhow::Result<()> {
+        let kv = retry_kv(3, 3);
+
+        let res = kv.upsert_kv(UpsertKVReq::update("a", b"b")).await;
+        assert!(res.is_err());
+        assert_eq!(3, kv.inner().calls());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_retry_non_retryable() -> anyhow::Result<()> {
+        let kv = RetryKVApi::new(FlakyKV::new(1), |_e: &MetaError| false);
+
+        let res = kv.upsert_kv(UpsertKVReq::update("a", b"b")).await;
+        assert!(res.is_err());
+        assert_eq!(1, kv.inner().calls());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_retry_transaction() -> anyhow::Result<()> {
+        // Not idempotent: never retried
+        let kv = retry_kv(1, 3);
+
+        let res = kv.transaction(TxnRequest::default()).await;
+        assert!(res.is_err());
+        assert_eq!(1, kv.inner().calls());
+
+        // Idempotent: retried
+        let kv = retry_kv(1, 3).with_idempotent_txn(true);
+
+        kv.transaction(TxnRequest::default()).await?;
+        assert_eq!(2, kv.inner().calls());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_ith_backoff() {
+        let kv = RetryKVApi::new(FlakyKV::new(0), |_e: &MetaError| true)
+            .with_backoff(Duration::from_millis(10), Duration::from_millis(50));
+
+        let mut b = kv.new_backoff();
+        assert_eq!(Duration::from_millis(10), b.next_delay());
+        assert_eq!(Duration::from_millis(20), b.next_delay());
+        assert_eq!(Duration::from_millis(40), b.next_delay());
+        assert_eq!(Duration::from_millis(50), b.next_delay());
+        assert_eq!(Duration::from_millis(50), b.next_delay());
+
+        // Every call starts over from the base delay.
+        let mut b = kv.new_backoff();
+        assert_eq!(Duration::from_millis(10), b.next_delay());
+    }
+
+    #[test]
+    fn test_backoff_policy_with_jitter() {
+        let policy = BackoffPolicy::new(Duration::from_millis(10), 2.0, Duration::from_millis(50))
+            .with_jitter(true)
+            .with_seed(1);
+
+        let kv = RetryKVApi::new(FlakyKV::new(0), |_e: &MetaError| true)
+            .with_backoff_policy(policy.clone());
+
+        let mut expected = policy;
+        let mut b = kv.new_backoff();
+        for _ in 0..5 {
+            assert_eq!(expected.next_delay(), b.next_delay());
+        }
+    }
+}
@@ -0,0 +1,165 @@
+// Copyright 2021 Datafuse Labs
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! A kvapi::ApiBuilder that builds a store with initial data.
+
+use std::time::Duration;
+use std::time::Instant;
+
+use async_trait::async_trait;
+use log::info;
+
+use crate::kvapi;
+use crate::kvapi::UpsertKVReq;
+
+/// Wraps a base kvapi::ApiBuilder and writes `seeds` into every store it builds before returning it.
+///
+/// For a cluster, the seeds are written to the first node,
+/// then it waits until they are visible on every node.
+/// Because `ApiBuilder` is infallible, it panics if seeding fails or times out.
+#[derive(Clone)]
+pub struct SeededApiBuilder<B> {
+    base: B,
+    seeds: Vec<(String, Vec<u8>)>,
+    replication_timeout: Duration,
+}
+
+impl<B> SeededApiBuilder<B> {
+    pub const DEFAULT_REPLICATION_TIMEOUT: Duration = Duration::from_secs(10);
+
+    pub fn new(base: B, seeds: Vec<(String, Vec<u8>)>) -> Self {
+        Self {
+            base,
+            seeds,
+            replication_timeout: Self::DEFAULT_REPLICATION_TIMEOUT,
+        }
+    }
+
+    /// Set the max time to wait for the seeds to be replicated to every node of a cluster.
+    pub fn with_replication_timeout(mut self, timeout: Duration) -> Self {
+        self.replication_timeout = timeout;
+        self
+    }
+
+    pub fn seeds(&self) -> &[(String, Vec<u8>)] {
+        &self.seeds
+    }
+
+    async fn seed<T: kvapi::KVApi>(&self, kv: &T) {
+        for (key, value) in self.seeds.iter() {
+            kv.upsert_kv(UpsertKVReq::update(key, value))
+                .await
+                .unwrap_or_else(|e| panic!("failed to seed key {}: {}", key, e));
+        }
+    }
+
+    /// Block until every seed is readable from `kv`.
+    async fn wait_for_seeds<T: kvapi::KVApi>(&self, kv: &T) {
+        let start = Instant::now();
+
+        for (key, value) in self.seeds.iter() {
+            loop {
+                let got = kv
+                    .get_kv(key)
+                    .await
+                    .unwrap_or_else(|e| panic!("failed to read seeded key {}: {}", key, e));
+
+                if got.map(|seq_v| seq_v.data).as_ref() == Some(value) {
+                    break;
+                }
+
+                if start.elapsed() > self.replication_timeout {
+                    panic!(
+                        "seeded key {} is not replicated in {:?}",
+                        key, self.replication_timeout
+                    );
+                }
+
+                tokio::time::sleep(Duration::from_millis(50)).await;
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl<T, B> kvapi::ApiBuilder<T> for SeededApiBuilder<B>
+where
+    T: kvapi::KVApi,
+    B: kvapi::ApiBuilder<T> + Send + Sync,
+{
+    async fn build(&self) -> T {
+        let kv = self.base.build().await;
+        self.seed(&kv).await;
+        kv
+    }
+
+    async fn build_cluster(&self) -> Vec<T> {
+        let cluster = self.base.build_cluster().await;
+
+        if let Some(first) = cluster.first() {
+            self.seed(first).await;
+        }
+
+        for (i, kv) in cluster.iter().enumerate() {
+            self.wait_for_seeds(kv).await;
+            info!("seeds are replicated to {}-th node", i);
+        }
+
+        cluster
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::kvapi::ApiBuilder;
+    use crate::kvapi::KVApi;
+    use crate::kvapi::MemKVApi;
+    use crate::kvapi::MemKVApiBuilder;
+    use crate::kvapi::SeededApiBuilder;
+
+    fn seeds() -> Vec<(String, Vec<u8>)> {
+        vec![
+            ("a".to_string(), b"1".to_vec()),
+            ("b".to_string(), b"2".to_vec()),
+        ]
+    }
+
+    #[tokio::test]
+    async fn test_seeded_build() -> anyhow::Result<()> {
+        let builder = SeededApiBuilder::new(MemKVApiBuilder {}, seeds());
+
+        let kv: MemKVApi = builder.build().await;
+        let got = kv.prefix_list_kv("").await?;
+        assert_eq!(
+            seeds(),
+            got.into_iter()
+                .map(|(k, seq_v)| (k, seq_v.data))
+                .collect::<Vec<_>>()
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_seeded_build_cluster() -> anyhow::Result<()> {
+        let builder = SeededApiBuilder::new(MemKVApiBuilder {}, seeds());
+
+        let cluster: Vec<MemKVApi> = builder.build_cluster().await;
+        for kv in cluster.iter() {
+            assert_eq!(Some(b"2".to_vec()), kv.get_kv("b").await?.map(|x| x.data));
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,450 @@
+// Copyright 2021 Datafuse Labs
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! A kvapi::KVApi impl that partitions the keys across several inner impls by the hash of a key.
+
+use std::collections::BTreeMap;
+use std::collections::BTreeSet;
+use std::sync::Arc;
+
+use anyerror::AnyError;
+use async_trait::async_trait;
+use databend_common_meta_types::errors;
+use databend_common_meta_types::protobuf::StreamItem;
+use databend_common_meta_types::txn_op::Request;
+use databend_common_meta_types::InvalidArgument;
+use databend_common_meta_types::TxnReply;
+use databend_common_meta_types::TxnRequest;
+use futures_util::future::try_join_all;
+use futures_util::StreamExt;
+use futures_util::TryStreamExt;
+
+use crate::kvapi;
+use crate::kvapi::GetKVReply;
+use crate::kvapi::KVStream;
+use crate::kvapi::ListKVReply;
+use crate::kvapi::MGetKVReply;
+use crate::kvapi::UpsertKVReply;
+use crate::kvapi::UpsertKVReq;
+
+/// A kvapi::KVApi impl that stores every key in the shard `hash(key) % shards.len()`.
+///
+/// A single key request is sent to the shard that owns the key.
+/// A multi-key read is split by shard, sent to the shards concurrently,
+/// and the replies are put back in the request order.
+/// Listing a prefix is sent to every shard, because the keys with a prefix are spread over all of them,
+/// and the results are merged in key order.
+///
+/// The shards do not coordinate with each other:
+/// - A transaction is applied atomically only if all of its keys belong to one shard,
+///   a transaction that spans shards is rejected with an `InvalidArgument` error before it is sent.
+///   A `DeleteByPrefix` operation spans every shard, unless there is only one.
+/// - `delete_prefix_kv()` deletes from every shard one by one, it is not atomic.
+/// - A listing is not a consistent snapshot among shards.
+///
+/// The hash must be stable: changing it, or the number of shards, makes existing keys unreachable.
+pub struct ShardedKVApi<T: kvapi::KVApi> {
+    shards: Vec<T>,
+    hash: Arc<dyn Fn(&str) -> u64 + Send + Sync>,
+}
+
+impl<T: kvapi::KVApi> ShardedKVApi<T> {
+    /// Create a sharded impl with `shards`.
+    ///
+    /// It returns an `InvalidArgument` error if `shards` is empty.
+    pub fn new(
+        shards: Vec<T>,
+        hash: impl Fn(&str) -> u64 + Send + Sync + 'static,
+    ) -> Result<Self, InvalidArgument> {
+        if shards.is_empty() {
+            return Err(InvalidArgument::new(
+                AnyError::error("ShardedKVApi requires at least one shard"),
+                "shards",
+            ));
+        }
+
+        Ok(Self {
+            shards,
+            hash: Arc::new(hash),
+        })
+    }
+
+    pub fn shards(&self) -> &[T] {
+        &self.shards
+    }
+
+    /// Returns the index of the shard that owns `key`.
+    pub fn shard_of(&self, key: &str) -> usize {
+        ((self.hash)(key) % self.shards.len() as u64) as usize
+    }
+
+    fn shard(&self, key: &str) -> &T {
+        &self.shards[self.shard_of(key)]
+    }
+
+    /// Split `keys` by shard: a shard index maps to the positions and the keys it owns.
+    fn split_keys(&self, keys: &[String]) -> BTreeMap<usize, (Vec<usize>, Vec<String>)> {
+        let mut groups: BTreeMap<usize, (Vec<usize>, Vec<String>)> = BTreeMap::new();
+
+        for (i, key) in keys.iter().enumerate() {
+            let (positions, shard_keys) = groups.entry(self.shard_of(key)).or_default();
+            positions.push(i);
+            shard_keys.push(key.clone());
+        }
+
+        groups
+    }
+
+    /// Put the replies of every shard back to the positions of the keys in the request.
+    fn gather<V>(
+        n: usize,
+        groups: &BTreeMap<usize, (Vec<usize>, Vec<String>)>,
+        replies: Vec<Vec<V>>,
+    ) -> Result<Vec<V>, errors::IncompleteStream> {
+        let mut slots = (0..n).map(|_| None).collect::<Vec<_>>();
+
+        for ((positions, _), reply) in groups.values().zip(replies) {
+            if reply.len() != positions.len() {
+                return Err(errors::IncompleteStream::new(
+                    positions.len() as u64,
+                    reply.len() as u64,
+                ));
+            }
+
+            for (i, v) in positions.iter().zip(reply) {
+                slots[*i] = Some(v);
+            }
+        }
+
+        // Safe unwrap(): every position is filled by exactly one shard.
+        Ok(slots.into_iter().map(|x| x.unwrap()).collect())
+    }
+
+    /// Returns the only shard every key in `txn` belongs to.
+    ///
+    /// A transaction without any key is sent to the first shard.
+    fn txn_shard(&self, txn: &TxnRequest) -> Result<usize, InvalidArgument> {
+        let mut keys = vec![];
+        let mut shards = BTreeSet::new();
+
+        for cond in &txn.condition {
+            keys.push(cond.key.as_str());
+            shards.insert(self.shard_of(&cond.key));
+        }
+
+        for op in txn.if_then.iter().chain(txn.else_then.iter()) {
+            let key = match &op.request {
+                Some(Request::Get(get)) => &get.key,
+                Some(Request::Put(put)) => &put.key,
+                Some(Request::Delete(del)) => &del.key,
+                Some(Request::ReserveSeq(reserve)) => &reserve.key,
+                Some(Request::DeleteByPrefix(del)) => {
+                    keys.push(del.prefix.as_str());
+                    shards.extend(0..self.shards.len());
+                    continue;
+                }
+                None => continue,
+            };
+            keys.push(key.as_str());
+            shards.insert(self.shard_of(key));
+        }
+
+        if shards.len() > 1 {
+            return Err(InvalidArgument::new(
+                AnyError::error(format!(
+                    "keys {:?} span shards {:?}, a transaction across shards is not atomic",
+                    keys, shards
+                )),
+                "transaction",
+            ));
+        }
+
+        Ok(shards.into_iter().next().unwrap_or_default())
+    }
+
+    /// Collect the records with `prefix` from every shard, in key order.
+    async fn list_all(&self, prefix: &str) -> Result<Vec<StreamItem>, T::Error> {
+        let lists = try_join_all(self.shards.iter().map(|s| async move {
+            let strm = s.list_kv(prefix).await?;
+            strm.try_collect::<Vec<_>>().await
+        }))
+        .await?;
+
+        let mut items = lists.into_iter().flatten().collect::<Vec<_>>();
+        items.sort_by(|a, b| a.key.cmp(&b.key));
+
+        Ok(items)
+    }
+}
+
+#[async_trait]
+impl<T: kvapi::KVApi> kvapi::KVApi for ShardedKVApi<T> {
+    type Error = T::Error;
+
+    async fn upsert_kv(&self, req: UpsertKVReq) -> Result<UpsertKVReply, Self::Error> {
+        self.shard(&req.key).upsert_kv(req).await
+    }
+
+    async fn delete_kv(&self, key: &str) -> Result<UpsertKVReply, Self::Error> {
+        self.shard(key).delete_kv(key).await
+    }
+
+    async fn delete_prefix_kv(&self, prefix: &str) -> Result<u64, Self::Error> {
+        let mut count = 0;
+        for s in &self.shards {
+            count += s.delete_prefix_kv(prefix).await?;
+        }
+        Ok(count)
+    }
+
+    async fn get_kv(&self, key: &str) -> Result<GetKVReply, Self::Error> {
+        self.shard(key).get_kv(key).await
+    }
+
+    async fn has_kv(&self, key: &str) -> Result<bool, Self::Error> {
+        self.shard(key).has_kv(key).await
+    }
+
+    async fn ping(&self) -> Result<(), Self::Error> {
+        try_join_all(self.shards.iter().map(|s| s.ping())).await?;
+        Ok(())
+    }
+
+    async fn mget_kv(&self, keys: &[String]) -> Result<MGetKVReply, Self::Error> {
+        let groups = self.split_keys(keys);
+
+        let replies = try_join_all(
+            groups
+                .iter()
+                .map(|(shard, (_, shard_keys))| self.shards[*shard].mget_kv(shard_keys)),
+        )
+        .await?;
+
+        let values =
+            Self::gather(keys.len(), &groups, replies).map_err(|e| e.context(" while mget_kv"))?;
+        Ok(values)
+    }
+
+    async fn get_kv_stream(&self, keys: &[String]) -> Result<KVStream<Self::Error>, Self::Error> {
+        let groups = self.split_keys(keys);
+
+        let replies = try_join_all(groups.iter().map(|(shard, (_, shard_keys))| async move {
+            let strm = self.shards[*shard].get_kv_stream(shard_keys).await?;
+            strm.try_collect::<Vec<_>>().await
+        }))
+        .await?;
+
+        let items = Self::gather(keys.len(), &groups, replies)
+            .map_err(|e| e.context(" while get_kv_stream"))?;
+        Ok(futures_util::stream::iter(items.into_iter().map(Ok)).boxed())
+    }
+
+    async fn list_kv(&self, prefix: &str) -> Result<KVStream<Self::Error>, Self::Error> {
+        let items = self.list_all(prefix).await?;
+        Ok(futures_util::stream::iter(items.into_iter().map(Ok)).boxed())
+    }
+
+    async fn prefix_list_kv(&self, prefix: &str) -> Result<ListKVReply, Self::Error> {
+        let lists = try_join_all(self.shards.iter().map(|s| s.prefix_list_kv(prefix))).await?;
+
+        let mut records = lists.into_iter().flatten().collect::<Vec<_>>();
+        records.sort_by(|a, b| a.0.cmp(&b.0));
+
+        Ok(records)
+    }
+
+    async fn transaction(&self, txn: TxnRequest) -> Result<TxnReply, Self::Error> {
+        let shard = self.txn_shard(&txn)?;
+        self.shards[shard].transaction(txn).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use databend_common_meta_types::MetaError;
+    use databend_common_meta_types::MetaNetworkError;
+    use databend_common_meta_types::TxnCondition;
+    use databend_common_meta_types::TxnOp;
+    use databend_common_meta_types::TxnRequest;
+
+    use crate::kvapi::KVApi;
+    use crate::kvapi::MemKVApi;
+    use crate::kvapi::ShardedKVApi;
+    use crate::kvapi::UpsertKVReq;
+
+    /// Keys are assigned to a shard by their last byte, so that a test knows where a key goes:
+    /// with 2 shards, `a0` goes to shard 0 and `a1` goes to shard 1.
+    fn last_byte(key: &str) -> u64 {
+        key.as_bytes().last().copied().unwrap_or_default() as u64
+    }
+
+    fn new_sharded() -> (Vec<MemKVApi>, ShardedKVApi<MemKVApi>) {
+        let shards = vec![MemKVApi::new(), MemKVApi::new()];
+        let sharded = ShardedKVApi::new(shards.clone(), last_byte).unwrap();
+        (shards, sharded)
+    }
+
+    fn is_invalid_argument(e: &MetaError) -> bool {
+        matches!(
+            e,
+            MetaError::NetworkError(MetaNetworkError::InvalidArgument(_))
+        )
+    }
+
+    #[test]
+    fn test_sharded_new_without_shards() {
+        let res = ShardedKVApi::<MemKVApi>::new(vec![], last_byte);
+        assert!(res.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_sharded_routing() -> anyhow::Result<()> {
+        let (shards, kv) = new_sharded();
+
+        for key in ["a0", "a1", "b0", "b1", "c1"] {
+            kv.upsert_kv(UpsertKVReq::update(key, key.as_bytes()))
+                .await?;
+        }
+
+        // Every key is stored only in its own shard.
+        {
+            assert_eq!(vec!["a0", "b0"], shards[0].prefix_list_keys("").await?);
+            assert_eq!(
+                vec!["a1", "b1", "c1"],
+                shards[1].prefix_list_keys("").await?
+            );
+        }
+
+        // Single key
+        {
+            assert_eq!(0, kv.shard_of("a0"));
+            assert_eq!(1, kv.shard_of("a1"));
+
+            assert_eq!(Some(b"b1".to_vec()), kv.get_kv("b1").await?.map(|x| x.data));
+            assert!(kv.has_kv("c1").await?);
+
+            kv.delete_kv("c1").await?;
+            assert_eq!(None, shards[1].get_kv("c1").await?);
+        }
+
+        // Multiple keys are returned in the request order.
+        {
+            let keys = ["b1", "a0", "x0", "a1"].map(String::from);
+            let got = kv.mget_kv(&keys).await?;
+            let got = got
+                .into_iter()
+                .map(|x| x.map(|v| v.data))
+                .collect::<Vec<_>>();
+            assert_eq!(
+                vec![
+                    Some(b"b1".to_vec()),
+                    Some(b"a0".to_vec()),
+                    None,
+                    Some(b"a1".to_vec())
+                ],
+                got
+            );
+        }
+
+        // A prefix is merged from every shard in key order.
+        {
+            let got = kv.prefix_list_kv("").await?;
+            let got = got.into_iter().map(|(k, _)| k).collect::<Vec<_>>();
+            assert_eq!(vec!["a0", "a1", "b0", "b1"], got);
+
+            let got = kv.prefix_list_keys("a").await?;
+            assert_eq!(vec!["a0", "a1"], got);
+        }
+
+        // Deleting a prefix deletes from every shard.
+        {
+            assert_eq!(2, kv.delete_prefix_kv("a").await?);
+            assert_eq!(vec!["b0", "b1"], kv.prefix_list_keys("").await?);
+        }
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_sharded_transaction() -> anyhow::Result<()> {
+        let (shards, kv) = new_sharded();
+
+        // All keys in one shard.
+        {
+            let txn = TxnRequest {
+                condition: vec![TxnCondition::eq_seq("a1", 0)],
+                if_then: vec![
+                    TxnOp::put("a1", b"a1".to_vec()),
+                    TxnOp::put("b1", b"b1".to_vec()),
+                ],
+                else_then: vec![],
+            };
+            let reply = kv.transaction(txn).await?;
+            assert!(reply.success);
+
+            assert_eq!(2, shards[1].prefix_list_kv("").await?.len());
+            assert!(shards[0].prefix_list_kv("").await?.is_empty());
+        }
+
+        // Keys across shards are rejected, whichever part of the txn they are in.
+        {
+            let txns = vec![
+                TxnRequest::unconditional(vec![
+                    TxnOp::put("a0", b"a0".to_vec()),
+                    TxnOp::put("a1", b"x".to_vec()),
+                ]),
+                TxnRequest {
+                    condition: vec![TxnCondition::eq_seq("a0", 0)],
+                    if_then: vec![TxnOp::put("a1", b"x".to_vec())],
+                    else_then: vec![],
+                },
+                TxnRequest {
+                    condition: vec![TxnCondition::eq_seq("a1", 0)],
+                    if_then: vec![],
+                    else_then: vec![TxnOp::delete("a0")],
+                },
+                TxnRequest::unconditional(vec![TxnOp::delete_by_prefix("a")]),
+            ];
+
+            for txn in txns {
+                let res = kv.transaction(txn).await;
+                assert!(is_invalid_argument(&res.unwrap_err()));
+            }
+
+            // None of the rejected transactions is applied.
+            assert_eq!(None, kv.get_kv("a0").await?);
+            assert_eq!(Some(b"a1".to_vec()), kv.get_kv("a1").await?.map(|x| x.data));
+        }
+
+        // mset_kv() is a transaction too.
+        {
+            let res = kv
+                .mset_kv(vec![
+                    UpsertKVReq::update("c0", b"c0"),
+                    UpsertKVReq::update("c1", b"c1"),
+                ])
+                .await;
+            assert!(is_invalid_argument(&res.unwrap_err()));
+
+            kv.mset_kv(vec![
+                UpsertKVReq::update("c0", b"c0"),
+                UpsertKVReq::update("d0", b"d0"),
+            ])
+            .await?;
+            assert_eq!(2, shards[0].prefix_list_kv("").await?.len());
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,338 @@
+// Copyright 2021 Datafuse Labs
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! A kvapi::KVApi decorator that rejects writing a value or a transaction larger than a limit.
+
+use std::sync::LazyLock;
+
+use async_trait::async_trait;
+use databend_common_meta_types::txn_op::Request;
+use databend_common_meta_types::Operation;
+use databend_common_meta_types::TxnReply;
+use databend_common_meta_types::TxnRequest;
+use databend_common_meta_types::TxnTooLarge;
+use databend_common_meta_types::ValueTooLarge;
+use databend_common_metrics::register_histogram_in_bytes;
+use databend_common_metrics::Histogram;
+
+use crate::kvapi;
+use crate::kvapi::GetKVReply;
+use crate::kvapi::KVStream;
+use crate::kvapi::ListKVReply;
+use crate::kvapi::MGetKVReply;
+use crate::kvapi::UpsertKVReply;
+use crate::kvapi::UpsertKVReq;
+
+/// Name of the histogram of the encoded size in bytes of the transactions, including the rejected ones.
+pub const METRIC_KV_API_TXN_SIZE_BYTES: &str = "meta_kv_api_txn_size_bytes";
+
+static KV_API_TXN_SIZE_BYTES: LazyLock<Histogram> =
+    LazyLock::new(|| register_histogram_in_bytes(METRIC_KV_API_TXN_SIZE_BYTES));
+
+/// A kvapi::KVApi impl that delegates to an inner impl, after checking the size of every value to write.
+///
+/// A write of a value larger than `max_value_size` bytes fails with a [`ValueTooLarge`] error
+/// and the inner impl is not called.
+/// For a transaction, the `put` in both branches are checked before it is sent.
+///
+/// A transaction whose encoded size is larger than `max_txn_size` bytes,
+/// or that has more than `max_txn_ops` operations, fails with a [`TxnTooLarge`] error.
+/// The encoded size of every transaction is recorded in the [`METRIC_KV_API_TXN_SIZE_BYTES`] histogram.
+pub struct SizeLimitedKVApi<T: kvapi::KVApi> {
+    inner: T,
+    max_value_size: usize,
+    max_txn_size: usize,
+    max_txn_ops: usize,
+}
+
+impl<T> SizeLimitedKVApi<T>
+where
+    T: kvapi::KVApi,
+    T::Error: From<ValueTooLarge> + From<TxnTooLarge>,
+{
+    /// The default limit of a value: 1 MiB.
+    pub const DEFAULT_MAX_VALUE_SIZE: usize = 1024 * 1024;
+
+    /// The default limit of an encoded transaction: 16 MiB, the max gRPC message the meta-service accepts.
+    pub const DEFAULT_MAX_TXN_SIZE: usize = 16 * 1024 * 1024;
+
+    /// The default limit of the number of operations in a transaction.
+    pub const DEFAULT_MAX_TXN_OPS: usize = 10_000;
+
+    pub fn new(inner: T) -> Self {
+        Self {
+            inner,
+            max_value_size: Self::DEFAULT_MAX_VALUE_SIZE,
+            max_txn_size: Self::DEFAULT_MAX_TXN_SIZE,
+            max_txn_ops: Self::DEFAULT_MAX_TXN_OPS,
+        }
+    }
+
+    /// Set the max size in bytes of a value to write.
+    pub fn with_max_value_size(mut self, max_value_size: usize) -> Self {
+        self.max_value_size = max_value_size;
+        self
+    }
+
+    /// Set the max encoded size in bytes of a transaction.
+    pub fn with_max_txn_size(mut self, max_txn_size: usize) -> Self {
+        self.max_txn_size = max_txn_size;
+        self
+    }
+
+    /// Set the max number of operations in a transaction.
+    pub fn with_max_txn_ops(mut self, max_txn_ops: usize) -> Self {
+        self.max_txn_ops = max_txn_ops;
+        self
+    }
+
+    pub fn inner(&self) -> &T {
+        &self.inner
+    }
+
+    pub fn max_value_size(&self) -> usize {
+        self.max_value_size
+    }
+
+    pub fn max_txn_size(&self) -> usize {
+        self.max_txn_size
+    }
+
+    pub fn max_txn_ops(&self) -> usize {
+        self.max_txn_ops
+    }
+
+    fn check(&self, key: &str, value: &[u8]) -> Result<(), T::Error> {
+        if value.len() > self.max_value_size {
+            return Err(ValueTooLarge::new(key, value.len(), self.max_value_size).into());
+        }
+        Ok(())
+    }
+
+    fn check_upsert(&self, req: &UpsertKVReq) -> Result<(), T::Error> {
+        if let Operation::Update(value) = &req.value {
+            self.check(&req.key, value)?;
+        }
+        Ok(())
+    }
+
+    fn check_txn(&self, txn: &TxnRequest) -> Result<(), T::Error> {
+        let size = txn.encoded_size();
+        let ops = txn.op_count();
+        KV_API_TXN_SIZE_BYTES.observe(size as f64);
+
+        if size > self.max_txn_size || ops > self.max_txn_ops {
+            return Err(TxnTooLarge::new(size, ops, self.max_txn_size, self.max_txn_ops).into());
+        }
+
+        for op in txn.if_then.iter().chain(txn.else_then.iter()) {
+            if let Some(Request::Put(put)) = &op.request {
+                self.check(&put.key, &put.value)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl<T> kvapi::KVApi for SizeLimitedKVApi<T>
+where
+    T: kvapi::KVApi,
+    T::Error: From<ValueTooLarge> + From<TxnTooLarge>,
+{
+    type Error = T::Error;
+
+    async fn upsert_kv(&self, req: UpsertKVReq) -> Result<UpsertKVReply, Self::Error> {
+        self.check_upsert(&req)?;
+        self.inner.upsert_kv(req).await
+    }
+
+    async fn mset_kv(&self, reqs: Vec<UpsertKVReq>) -> Result<Vec<UpsertKVReply>, Self::Error> {
+        for req in &reqs {
+            self.check_upsert(req)?;
+        }
+        self.inner.mset_kv(reqs).await
+    }
+
+    async fn get_kv(&self, key: &str) -> Result<GetKVReply, Self::Error> {
+        self.inner.get_kv(key).await
+    }
+
+    async fn mget_kv(&self, keys: &[String]) -> Result<MGetKVReply, Self::Error> {
+        self.inner.mget_kv(keys).await
+    }
+
+    async fn get_kv_stream(&self, keys: &[String]) -> Result<KVStream<Self::Error>, Self::Error> {
+        self.inner.get_kv_stream(keys).await
+    }
+
+    async fn list_kv(&self, prefix: &str) -> Result<KVStream<Self::Error>, Self::Error> {
+        self.inner.list_kv(prefix).await
+    }
+
+    async fn prefix_list_kv(&self, prefix: &str) -> Result<ListKVReply, Self::Error> {
+        self.inner.prefix_list_kv(prefix).await
+    }
+
+    async fn transaction(&self, txn: TxnRequest) -> Result<TxnReply, Self::Error> {
+        self.check_txn(&txn)?;
+        self.inner.transaction(txn).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use databend_common_meta_types::MetaClientError;
+    use databend_common_meta_types::MetaError;
+    use databend_common_meta_types::TxnCondition;
+    use databend_common_meta_types::TxnOp;
+    use databend_common_meta_types::TxnRequest;
+    use databend_common_meta_types::TxnTooLarge;
+
+    use crate::kvapi::KVApi;
+    use crate::kvapi::MemKVApi;
+    use crate::kvapi::SizeLimitedKVApi;
+    use crate::kvapi::UpsertKVReq;
+
+    fn is_value_too_large(e: &MetaError) -> bool {
+        matches!(e, MetaError::ClientError(MetaClientError::ValueTooLarge(_)))
+    }
+
+    fn txn_too_large(e: &MetaError) -> Option<&TxnTooLarge> {
+        match e {
+            MetaError::ClientError(MetaClientError::TxnTooLarge(t)) => Some(t),
+            _ => None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_size_limited_upsert() -> anyhow::Result<()> {
+        let kv = SizeLimitedKVApi::new(MemKVApi::new()).with_max_value_size(10);
+
+        let just_under = vec![b'x'; 10];
+        let just_over = vec![b'x'; 11];
+
+        kv.upsert_kv(UpsertKVReq::update("a", &just_under)).await?;
+        assert_eq!(
+            Some(just_under.clone()),
+            kv.get_kv("a").await?.map(|x| x.data)
+        );
+
+        let res = kv.upsert_kv(UpsertKVReq::update("a", &just_over)).await;
+        assert!(is_value_too_large(&res.unwrap_err()));
+
+        let res = kv
+            .mset_kv(vec![
+                UpsertKVReq::update("b", &just_under),
+                UpsertKVReq::update("c", &just_over),
+            ])
+            .await;
+        assert!(is_value_too_large(&res.unwrap_err()));
+
+        // Nothing is written by a rejected request.
+        assert_eq!(Some(just_under), kv.get_kv("a").await?.map(|x| x.data));
+        assert_eq!(None, kv.get_kv("b").await?);
+
+        // Delete is not limited.
+        kv.upsert_kv(UpsertKVReq::delete("a")).await?;
+        assert_eq!(None, kv.get_kv("a").await?);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_size_limited_transaction() -> anyhow::Result<()> {
+        let kv = SizeLimitedKVApi::new(MemKVApi::new()).with_max_value_size(10);
+
+        let txn = TxnRequest::unconditional(vec![TxnOp::put("a", vec![b'x'; 10])]);
+        kv.transaction(txn).await?;
+
+        let txn = TxnRequest {
+            condition: vec![],
+            if_then: vec![TxnOp::put("b", b"1".to_vec())],
+            else_then: vec![TxnOp::put("c", vec![b'x'; 11])],
+        };
+        let res = kv.transaction(txn).await;
+        assert!(is_value_too_large(&res.unwrap_err()));
+        assert_eq!(None, kv.get_kv("b").await?);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_size_limited_txn_size() -> anyhow::Result<()> {
+        let txn = |value_size: usize| {
+            TxnRequest::unconditional(vec![
+                TxnOp::put("a", vec![b'x'; value_size]),
+                TxnOp::put("b", b"1".to_vec()),
+            ])
+        };
+
+        let limit = txn(100).encoded_size();
+        let kv = SizeLimitedKVApi::new(MemKVApi::new()).with_max_txn_size(limit);
+
+        kv.transaction(txn(100)).await?;
+        assert_eq!(Some(100), kv.get_kv("a").await?.map(|x| x.data.len()));
+
+        let over = txn(101);
+        let size = over.encoded_size();
+        assert_eq!(limit + 1, size);
+
+        let res = kv.transaction(over).await;
+        let err = res.unwrap_err();
+        let too_large = txn_too_large(&err).unwrap();
+        assert_eq!(size, too_large.size());
+        assert_eq!(2, too_large.ops());
+        assert_eq!(limit, too_large.max_size());
+        assert!(err.to_string().contains(&format!("{} bytes", size)));
+
+        // Nothing is written by a rejected transaction.
+        assert_eq!(Some(100), kv.get_kv("a").await?.map(|x| x.data.len()));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_size_limited_txn_ops() -> anyhow::Result<()> {
+        let kv = SizeLimitedKVApi::new(MemKVApi::new()).with_max_txn_ops(3);
+
+        let txn = TxnRequest {
+            condition: vec![TxnCondition::eq_seq("a", 0)],
+            if_then: vec![
+                TxnOp::put("a", b"1".to_vec()),
+                TxnOp::put("b", b"1".to_vec()),
+            ],
+            else_then: vec![TxnOp::get("a")],
+        };
+        assert_eq!(3, txn.op_count(), "conditions are not counted");
+        kv.transaction(txn.clone()).await?;
+
+        let mut over = txn;
+        over.else_then.push(TxnOp::get("b"));
+
+        let res = kv.transaction(over).await;
+        let err = res.unwrap_err();
+        assert_eq!(4, txn_too_large(&err).map(|x| x.ops()).unwrap());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_size_limited_default() {
+        let kv = SizeLimitedKVApi::new(MemKVApi::new());
+        assert_eq!(1024 * 1024, kv.max_value_size());
+        assert_eq!(16 * 1024 * 1024, kv.max_txn_size());
+        assert_eq!(10_000, kv.max_txn_ops());
+    }
+}
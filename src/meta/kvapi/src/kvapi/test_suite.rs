@@ -12,8 +12,10 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::collections::HashSet;
 use std::time::Duration;
 
+use databend_common_meta_stoerr::MetaStorageError;
 use databend_common_meta_types::protobuf as pb;
 use databend_common_meta_types::txn_condition;
 use databend_common_meta_types::txn_op;
@@ -37,12 +39,15 @@ use databend_common_meta_types::TxnPutResponse;
 use databend_common_meta_types::TxnReply;
 use databend_common_meta_types::TxnRequest;
 use databend_common_meta_types::With;
+use futures_util::TryStreamExt;
 use log::debug;
 use log::info;
 use minitrace::full_name;
 use minitrace::func_name;
 
 use crate::kvapi;
+use crate::kvapi::decode_counter;
+use crate::kvapi::KVApiExt;
 use crate::kvapi::UpsertKVReq;
 
 pub struct TestSuite {}
@@ -56,11 +61,18 @@ impl kvapi::TestSuite {
     {
         self.kv_write_read(&builder.build().await).await?;
         self.kv_delete(&builder.build().await).await?;
+        self.kv_delete_kv(&builder.build().await).await?;
+        self.kv_has_kv(&builder.build().await).await?;
         self.kv_update(&builder.build().await).await?;
         self.kv_timeout(&builder.build().await).await?;
         self.kv_upsert_with_ttl(&builder.build().await).await?;
+        self.kv_ext_upsert_with_ttl(&builder.build().await).await?;
         self.kv_meta(&builder.build().await).await?;
         self.kv_list(&builder.build().await).await?;
+        self.kv_list_keys(&builder.build().await).await?;
+        self.kv_list_paged(&builder.build().await).await?;
+        self.kv_list_stream(&builder.build().await).await?;
+        self.kv_range_list(&builder.build().await).await?;
         self.kv_mget(&builder.build().await).await?;
         self.kv_txn_absent_seq_0(&builder.build().await).await?;
         self.kv_transaction(&builder.build().await).await?;
@@ -71,13 +83,32 @@ impl kvapi::TestSuite {
             .await?;
         self.kv_transaction_delete_match_seq_some_match(&builder.build().await)
             .await?;
+        self.kv_transaction_reply_by_key(&builder.build().await)
+            .await?;
         self.kv_delete_by_prefix_transaction(&builder.build().await)
             .await?;
+        self.kv_cas(&builder.build().await).await?;
+        self.kv_mset(&builder.build().await).await?;
+        self.kv_delete_prefix_kv(&builder.build().await).await?;
+        self.kv_ping(&builder.build().await).await?;
+        self.kv_count_prefix(&builder.build().await).await?;
+        self.kv_ext_mget_map(&builder.build().await).await?;
+        self.kv_ext_mget_ordered(&builder.build().await).await?;
+        self.kv_ext_mget_sorted_unique(&builder.build().await)
+            .await?;
+        self.kv_ext_prefix_list_excluding(&builder.build().await)
+            .await?;
+        self.kv_list_rev(&builder.build().await).await?;
+        self.kv_transaction_chunked(&builder.build().await).await?;
+        self.kv_transaction_many(&builder.build().await).await?;
+        self.kv_ext_get_if_newer(&builder.build().await).await?;
+        self.kv_ext_get_history(&builder.build().await).await?;
+        self.kv_ext_upsert_changed(&builder.build().await).await?;
 
         // Run cross node test on every 2 adjacent nodes
         let mut i = 0;
         loop {
-            let cluster = builder.build_cluster().await;
+            let cluster = builder.build_cluster_ready().await?;
             self.kv_write_read_across_nodes(&cluster[i], &cluster[i + 1])
                 .await?;
 
@@ -195,6 +226,50 @@ impl kvapi::TestSuite {
         Ok(())
     }
 
+    #[minitrace::trace]
+    pub async fn kv_delete_kv<KV: kvapi::KVApi>(&self, kv: &KV) -> anyhow::Result<()> {
+        info!("--- kvapi::KVApiTestSuite::kv_delete_kv() start");
+        let test_key = "test_key_for_delete_kv";
+
+        kv.upsert_kv(UpsertKVReq::update(test_key, b"v1")).await?;
+
+        info!("--- delete existing key returns the prev value");
+        {
+            let res = kv.delete_kv(test_key).await?;
+            assert_eq!(
+                (Some(SeqV::with_meta(1, None, b"v1".to_vec())), None),
+                (res.prev, res.result)
+            );
+
+            let r = kv.get_kv(test_key).await?;
+            assert!(r.is_none());
+        }
+
+        info!("--- delete absent key returns None prev");
+        {
+            let res = kv.delete_kv(test_key).await?;
+            assert_eq!((None, None), (res.prev, res.result));
+        }
+
+        Ok(())
+    }
+
+    #[minitrace::trace]
+    pub async fn kv_has_kv<KV: kvapi::KVApi>(&self, kv: &KV) -> anyhow::Result<()> {
+        info!("--- kvapi::KVApiTestSuite::kv_has_kv() start");
+        let test_key = "test_key_for_has_kv";
+
+        assert!(!kv.has_kv(test_key).await?);
+
+        kv.upsert_kv(UpsertKVReq::update(test_key, b"v1")).await?;
+        assert!(kv.has_kv(test_key).await?);
+
+        kv.upsert_kv(UpsertKVReq::delete(test_key)).await?;
+        assert!(!kv.has_kv(test_key).await?);
+
+        Ok(())
+    }
+
     #[minitrace::trace]
     pub async fn kv_update<KV: kvapi::KVApi>(&self, kv: &KV) -> anyhow::Result<()> {
         info!("--- kvapi::KVApiTestSuite::kv_update() start");
@@ -361,6 +436,36 @@ impl kvapi::TestSuite {
         Ok(())
     }
 
+    #[minitrace::trace]
+    pub async fn kv_ext_upsert_with_ttl<KV: kvapi::KVApi>(&self, kv: &KV) -> anyhow::Result<()> {
+        info!("--- {}", full_name!());
+
+        kv.upsert_kv_with_ttl("k1", b("v1"), Duration::from_millis(2_000))
+            .await?;
+        kv.upsert_kv(UpsertKVReq::update("k2", b"v2")).await?;
+
+        info!("---get unexpired");
+        {
+            assert!(kv.get_kv("k1").await?.is_some(), "got unexpired");
+        }
+
+        info!("---get, mget and list expired");
+        {
+            tokio::time::sleep(Duration::from_millis(2_100)).await;
+
+            assert!(kv.get_kv("k1").await?.is_none(), "got expired");
+
+            let res = kv.mget_kv(&["k1".to_string(), "k2".to_string()]).await?;
+            assert_eq!(res, vec![None, Some(SeqV::new(2, b("v2")))]);
+
+            let res = kv.prefix_list_kv("k").await?;
+            let keys = res.iter().map(|(key, _)| key.clone()).collect::<Vec<_>>();
+            assert_eq!(keys, vec!["k2".to_string()]);
+        }
+
+        Ok(())
+    }
+
     #[minitrace::trace]
     pub async fn kv_meta<KV: kvapi::KVApi>(&self, kv: &KV) -> anyhow::Result<()> {
         info!("--- kvapi::KVApiTestSuite::kv_meta() start");
@@ -452,6 +557,150 @@ impl kvapi::TestSuite {
         Ok(())
     }
 
+    #[minitrace::trace]
+    pub async fn kv_list_keys<KV: kvapi::KVApi>(&self, kv: &KV) -> anyhow::Result<()> {
+        info!("--- kvapi::KVApiTestSuite::kv_list_keys() start");
+
+        {
+            kv.upsert_kv(UpsertKVReq::update("t", b"")).await?;
+
+            for i in [3, 1, 2, 0] {
+                let key = format!("__users/{}", i);
+                let val = format!("val_{}", i);
+                kv.upsert_kv(UpsertKVReq::update(&key, val.as_bytes()))
+                    .await?;
+            }
+            kv.upsert_kv(UpsertKVReq::update("v", b"")).await?;
+        }
+
+        let keys = kv.prefix_list_keys("__users/").await?;
+
+        let want = kv
+            .prefix_list_kv("__users/")
+            .await?
+            .into_iter()
+            .map(|(k, _)| k)
+            .collect::<Vec<_>>();
+
+        assert_eq!(want, keys);
+        assert_eq!(
+            vec!["__users/0", "__users/1", "__users/2", "__users/3"],
+            keys
+        );
+
+        Ok(())
+    }
+
+    #[minitrace::trace]
+    pub async fn kv_list_paged<KV: kvapi::KVApi>(&self, kv: &KV) -> anyhow::Result<()> {
+        info!("--- kvapi::KVApiTestSuite::kv_list_paged() start");
+
+        {
+            kv.upsert_kv(UpsertKVReq::update("t", b"")).await?;
+
+            for i in 0..9 {
+                let key = format!("__users/{}", i);
+                kv.upsert_kv(UpsertKVReq::update(&key, b"")).await?;
+            }
+            kv.upsert_kv(UpsertKVReq::update("v", b"")).await?;
+        }
+
+        info!("--- list all pages by feeding back the last key");
+        {
+            let mut keys = vec![];
+            let mut after: Option<String> = None;
+            loop {
+                let res = kv
+                    .prefix_list_kv_paged("__users/", after.as_deref(), 4)
+                    .await?;
+                let n = res.len();
+                keys.extend(res.into_iter().map(|(k, _)| k));
+
+                if n < 4 {
+                    break;
+                }
+                after = keys.last().cloned();
+            }
+
+            let want = (0..9).map(|i| format!("__users/{}", i)).collect::<Vec<_>>();
+            assert_eq!(want, keys);
+        }
+
+        info!("--- cursor is exclusive");
+        {
+            let res = kv
+                .prefix_list_kv_paged("__users/", Some("__users/7"), 10)
+                .await?;
+            let keys = res.into_iter().map(|(k, _)| k).collect::<Vec<_>>();
+            assert_eq!(vec!["__users/8".to_string()], keys);
+        }
+
+        info!("--- cursor not sharing the prefix is an error");
+        {
+            let res = kv.prefix_list_kv_paged("__users/", Some("t"), 10).await;
+            assert!(res.is_err());
+        }
+
+        Ok(())
+    }
+
+    #[minitrace::trace]
+    pub async fn kv_list_stream<KV: kvapi::KVApi>(&self, kv: &KV) -> anyhow::Result<()> {
+        info!("--- kvapi::KVApiTestSuite::kv_list_stream() start");
+
+        {
+            kv.upsert_kv(UpsertKVReq::update("t", b"")).await?;
+
+            for i in 0..9 {
+                let key = format!("__users/{}", i);
+                let val = format!("val_{}", i);
+                kv.upsert_kv(UpsertKVReq::update(&key, val.as_bytes()))
+                    .await?;
+            }
+            kv.upsert_kv(UpsertKVReq::update("v", b"")).await?;
+        }
+
+        let strm = kv.prefix_list_kv_stream("__users/").await?;
+        let got = strm.try_collect::<Vec<_>>().await?;
+
+        let want = kv.prefix_list_kv("__users/").await?;
+        assert_eq!(9, got.len());
+        assert_eq!(want, got);
+
+        Ok(())
+    }
+
+    #[minitrace::trace]
+    pub async fn kv_range_list<KV: kvapi::KVApi>(&self, kv: &KV) -> anyhow::Result<()> {
+        info!("--- kvapi::KVApiTestSuite::kv_range_list() start");
+
+        for key in ["a", "a/1", "a/2", "a/3", "b/1", "b/2", "c"] {
+            kv.upsert_kv(UpsertKVReq::update(key, b"")).await?;
+        }
+
+        let list_keys = |start: &'static str, end: &'static str| async move {
+            let res = kv.range_list_kv(start, end).await?;
+            let keys = res.into_iter().map(|(k, _)| k).collect::<Vec<_>>();
+            Ok::<_, KV::Error>(keys)
+        };
+
+        assert_eq!(vec!["a/2", "a/3"], list_keys("a/2", "a/4").await?);
+        assert_eq!(vec!["a/3", "b/1"], list_keys("a/3", "b/2").await?);
+        assert_eq!(
+            vec!["a", "a/1", "a/2", "a/3", "b/1", "b/2"],
+            list_keys("", "c").await?
+        );
+        assert!(list_keys("a/2", "a/2").await?.is_empty());
+
+        info!("--- start > end is an error");
+        {
+            let res = kv.range_list_kv("b", "a").await;
+            assert!(res.is_err());
+        }
+
+        Ok(())
+    }
+
     #[minitrace::trace]
     pub async fn kv_mget<KV: kvapi::KVApi>(&self, kv: &KV) -> anyhow::Result<()> {
         info!("--- kvapi::KVApiTestSuite::kv_mget() start");
@@ -1034,6 +1283,721 @@ impl kvapi::TestSuite {
 
         Ok(())
     }
+
+    pub async fn kv_transaction_reply_by_key<KV: kvapi::KVApi>(
+        &self,
+        kv: &KV,
+    ) -> anyhow::Result<()> {
+        info!("--- {}", func_name!());
+
+        kv.upsert_kv(UpsertKVReq::update("txn_k1", b"v1")).await?;
+        kv.upsert_kv(UpsertKVReq::update("txn_k2", b"v2")).await?;
+
+        let txn = TxnRequest {
+            condition: vec![TxnCondition::eq_seq("txn_k1", 1)],
+            if_then: vec![
+                TxnOp::get("txn_k1"),
+                TxnOp::put("txn_k1", b("v3")),
+                TxnOp::get("txn_k1"),
+                TxnOp::delete("txn_k2"),
+                TxnOp::get("txn_k2"),
+                TxnOp::get("txn_absent"),
+            ],
+            else_then: vec![],
+        };
+
+        let resp = kv.transaction(txn).await?;
+        assert!(resp.success());
+
+        // The last read of txn_k1 sees the put before it.
+        let got = resp.get_response("txn_k1").unwrap();
+        assert_eq!((3, b("v3")), (got.seq, got.data.clone()));
+
+        let put = resp.put_response("txn_k1").unwrap();
+        let prev = put.prev_value.as_ref().unwrap();
+        assert_eq!((1, b("v1")), (prev.seq, prev.data.clone()));
+
+        let del = resp.delete_response("txn_k2").unwrap();
+        assert!(del.success);
+        assert_eq!(None, resp.get_response("txn_k2"));
+
+        assert_eq!(None, resp.get_response("txn_absent"));
+        assert!(resp.delete_response("txn_k1").is_none());
+
+        info!("--- else_then is not a success");
+        {
+            let txn = TxnRequest {
+                condition: vec![TxnCondition::eq_seq("txn_k1", 1)],
+                if_then: vec![],
+                else_then: vec![TxnOp::get("txn_k1")],
+            };
+
+            let resp = kv.transaction(txn).await?;
+            assert!(!resp.success());
+
+            let got = resp.get_response("txn_k1").unwrap();
+            assert_eq!(3, got.seq);
+        }
+
+        Ok(())
+    }
+
+    pub async fn kv_cas<KV: kvapi::KVApi>(&self, kv: &KV) -> anyhow::Result<()> {
+        info!("--- {}", func_name!());
+        let key = "cas_k1";
+
+        info!("--- key absent, seq mismatch");
+        {
+            let swapped = kv.cas_kv(key, 1, b("v1")).await?;
+            assert!(!swapped);
+            assert_eq!(None, kv.get_kv(key).await?);
+        }
+
+        info!("--- key absent, expected seq 0");
+        {
+            let swapped = kv.cas_kv(key, 0, b("v1")).await?;
+            assert!(swapped);
+            assert_eq!(Some(SeqV::new(1, b("v1"))), kv.get_kv(key).await?);
+        }
+
+        info!("--- key present, seq mismatch");
+        {
+            let swapped = kv.cas_kv(key, 0, b("v2")).await?;
+            assert!(!swapped);
+
+            let swapped = kv.cas_kv(key, 2, b("v2")).await?;
+            assert!(!swapped);
+            assert_eq!(Some(SeqV::new(1, b("v1"))), kv.get_kv(key).await?);
+        }
+
+        info!("--- key present, seq match");
+        {
+            let swapped = kv.cas_kv(key, 1, b("v2")).await?;
+            assert!(swapped);
+            assert_eq!(Some(SeqV::new(2, b("v2"))), kv.get_kv(key).await?);
+        }
+
+        Ok(())
+    }
+
+    /// Concurrently increment a counter and assert no increment is lost.
+    pub async fn kv_increment<KV>(&self, kv: &KV) -> anyhow::Result<()>
+    where
+        KV: kvapi::KVApi,
+        KV::Error: From<MetaStorageError>,
+    {
+        info!("--- {}", func_name!());
+        let key = "counter_k1";
+
+        info!("--- absent key is treated as 0");
+        {
+            let v = kv.increment(key, 3).await?;
+            assert_eq!(3, v);
+
+            let v = kv.increment(key, -1).await?;
+            assert_eq!(2, v);
+        }
+
+        info!("--- concurrent increments are not lost");
+        {
+            let n_tasks = 4;
+            let n_incr = 25;
+
+            let tasks = (0..n_tasks).map(|_| async {
+                for _ in 0..n_incr {
+                    kv.increment(key, 1).await?;
+                }
+                Ok::<(), KV::Error>(())
+            });
+
+            for res in futures_util::future::join_all(tasks).await {
+                res?;
+            }
+
+            let got = kv.get_kv(key).await?.unwrap();
+            assert_eq!(2 + n_tasks * n_incr, decode_counter(&got.data)?);
+        }
+
+        info!("--- non-counter value is an error");
+        {
+            kv.upsert_kv(UpsertKVReq::update(key, b"foo")).await?;
+            let res = kv.increment(key, 1).await;
+            assert!(res.is_err());
+        }
+
+        Ok(())
+    }
+
+    #[minitrace::trace]
+    pub async fn kv_mset<KV: kvapi::KVApi>(&self, kv: &KV) -> anyhow::Result<()> {
+        info!("--- kvapi::KVApiTestSuite::kv_mset() start");
+
+        kv.upsert_kv(UpsertKVReq::update("k1", b"v1")).await?;
+
+        info!("--- all upserts are applied, replies are in request order");
+        {
+            let res = kv
+                .mset_kv(vec![
+                    UpsertKVReq::update("k2", b"v2"),
+                    UpsertKVReq::update("k1", b"v1_2").with(MatchSeq::Exact(1)),
+                    UpsertKVReq::delete("k3"),
+                ])
+                .await?;
+
+            assert_eq!(3, res.len());
+            assert_eq!(
+                (None, Some(b("v2"))),
+                (res[0].prev.clone(), res[0].result.clone().map(|x| x.data))
+            );
+            assert_eq!(Some(b("v1")), res[1].prev.clone().map(|x| x.data));
+            assert_eq!(Some(b("v1_2")), res[1].result.clone().map(|x| x.data));
+            assert_eq!((None, None), (res[2].prev.clone(), res[2].result.clone()));
+
+            let got = kv.mget_kv(&["k1".to_string(), "k2".to_string()]).await?;
+            assert_eq!(
+                vec![Some(b("v1_2")), Some(b("v2"))],
+                got.into_iter()
+                    .map(|x| x.map(|y| y.data))
+                    .collect::<Vec<_>>()
+            );
+        }
+
+        info!("--- seq mismatch: none is applied");
+        {
+            let res = kv
+                .mset_kv(vec![
+                    UpsertKVReq::update("k4", b"v4"),
+                    UpsertKVReq::update("k1", b"v1_3").with(MatchSeq::Exact(1)),
+                ])
+                .await?;
+
+            assert_eq!(2, res.len());
+            for r in res.iter() {
+                assert_eq!(r.prev, r.result);
+            }
+
+            assert!(kv.get_kv("k4").await?.is_none());
+            assert_eq!(Some(b("v1_2")), kv.get_kv("k1").await?.map(|x| x.data));
+        }
+
+        info!("--- empty batch");
+        {
+            let res = kv.mset_kv(vec![]).await?;
+            assert!(res.is_empty());
+        }
+
+        Ok(())
+    }
+
+    #[minitrace::trace]
+    pub async fn kv_delete_prefix_kv<KV: kvapi::KVApi>(&self, kv: &KV) -> anyhow::Result<()> {
+        info!("--- kvapi::KVApiTestSuite::kv_delete_prefix_kv() start");
+
+        let keys = ["db/a/1", "db/a/2", "db/a/3/x", "db/ab", "db/b/1", "db/a"];
+        for key in keys {
+            kv.upsert_kv(UpsertKVReq::update(key, b"v")).await?;
+        }
+
+        let n = kv.delete_prefix_kv("db/a/").await?;
+        assert_eq!(3, n);
+
+        let got = kv.prefix_list_keys("db/").await?;
+        assert_eq!(
+            vec!["db/a", "db/ab", "db/b/1"],
+            got,
+            "sibling prefixes are untouched"
+        );
+
+        // Nothing to delete.
+        let n = kv.delete_prefix_kv("db/a/").await?;
+        assert_eq!(0, n);
+
+        // Empty prefix is rejected.
+        let res = kv.delete_prefix_kv("").await;
+        assert!(res.is_err());
+        assert_eq!(3, kv.prefix_list_keys("db/").await?.len());
+
+        Ok(())
+    }
+
+    #[minitrace::trace]
+    pub async fn kv_ping<KV: kvapi::KVApi>(&self, kv: &KV) -> anyhow::Result<()> {
+        info!("--- kvapi::KVApiTestSuite::kv_ping() start");
+
+        kv.ping().await?;
+
+        kv.upsert_kv(UpsertKVReq::update("test_key_for_ping", b"v1"))
+            .await?;
+        let before = kv.prefix_list_kv("").await?;
+
+        kv.ping().await?;
+        kv.ping().await?;
+
+        let after = kv.prefix_list_kv("").await?;
+        assert_eq!(before, after, "ping does not change the state");
+
+        Ok(())
+    }
+
+    #[minitrace::trace]
+    pub async fn kv_count_prefix<KV: kvapi::KVApi>(&self, kv: &KV) -> anyhow::Result<()> {
+        info!("--- kvapi::KVApiTestSuite::kv_count_prefix() start");
+
+        for key in ["db/1/t1", "db/1/t2", "db/1/t3", "db/10/t1", "db/2/t1"] {
+            kv.upsert_kv(UpsertKVReq::update(key, b"v")).await?;
+        }
+
+        assert_eq!(3, kv.count_prefix_kv("db/1/").await?);
+        assert_eq!(4, kv.count_prefix_kv("db/1").await?);
+        assert_eq!(5, kv.count_prefix_kv("db/").await?);
+        assert_eq!(0, kv.count_prefix_kv("db/3/").await?);
+
+        kv.upsert_kv(UpsertKVReq::delete("db/1/t2")).await?;
+        assert_eq!(2, kv.count_prefix_kv("db/1/").await?);
+
+        Ok(())
+    }
+
+    #[minitrace::trace]
+    pub async fn kv_ext_mget_map<KV: kvapi::KVApi>(&self, kv: &KV) -> anyhow::Result<()> {
+        info!("--- {}", full_name!());
+
+        kv.upsert_kv(UpsertKVReq::update("mget_map_k1", b"v1"))
+            .await?;
+        kv.upsert_kv(UpsertKVReq::update("mget_map_k3", b"v3"))
+            .await?;
+
+        let keys =
+            ["mget_map_k1", "mget_map_k2", "mget_map_k3", "mget_map_k1"].map(|x| x.to_string());
+        let got = kv.mget_kv_map(&keys).await?;
+
+        assert_eq!(
+            vec![
+                ("mget_map_k1".to_string(), b("v1")),
+                ("mget_map_k3".to_string(), b("v3")),
+            ],
+            got.into_iter()
+                .map(|(k, seq_v)| (k, seq_v.data))
+                .collect::<Vec<_>>(),
+            "absent key is omitted"
+        );
+
+        let got = kv.mget_kv_map(&[]).await?;
+        assert!(got.is_empty());
+
+        Ok(())
+    }
+
+    #[minitrace::trace]
+    pub async fn kv_ext_mget_ordered<KV: kvapi::KVApi>(&self, kv: &KV) -> anyhow::Result<()> {
+        info!("--- {}", full_name!());
+
+        kv.upsert_kv(UpsertKVReq::update("mget_ordered_k1", b"v1"))
+            .await?;
+        kv.upsert_kv(UpsertKVReq::update("mget_ordered_k3", b""))
+            .await?;
+
+        let keys = [
+            "mget_ordered_k3",
+            "mget_ordered_k2",
+            "mget_ordered_k1",
+            "mget_ordered_k3",
+        ]
+        .map(|x| x.to_string());
+        let got = kv.mget_kv_ordered(&keys).await?;
+
+        assert_eq!(
+            vec![Some(b("")), None, Some(b("v1")), Some(b(""))],
+            got.into_iter()
+                .map(|x| x.map(|seq_v| seq_v.data))
+                .collect::<Vec<_>>(),
+            "one entry per key in request order; absent is None, empty value is Some"
+        );
+
+        let got = kv.mget_kv_ordered(&[]).await?;
+        assert!(got.is_empty());
+
+        Ok(())
+    }
+
+    #[minitrace::trace]
+    pub async fn kv_ext_mget_sorted_unique<KV: kvapi::KVApi>(&self, kv: &KV) -> anyhow::Result<()> {
+        info!("--- {}", full_name!());
+
+        kv.upsert_kv(UpsertKVReq::update("mget_unique_k1", b"v1"))
+            .await?;
+        kv.upsert_kv(UpsertKVReq::update("mget_unique_k3", b"v3"))
+            .await?;
+        kv.upsert_kv(UpsertKVReq::update("mget_unique_k4", b""))
+            .await?;
+
+        let keys = [
+            "mget_unique_k4",
+            "mget_unique_k1",
+            "mget_unique_k3",
+            "mget_unique_k2",
+            "mget_unique_k1",
+            "mget_unique_k4",
+        ]
+        .map(|x| x.to_string());
+        let got = kv.mget_kv_sorted_unique(&keys).await?;
+
+        assert_eq!(
+            vec![
+                ("mget_unique_k1".to_string(), b("v1")),
+                ("mget_unique_k3".to_string(), b("v3")),
+                ("mget_unique_k4".to_string(), b("")),
+            ],
+            got.into_iter()
+                .map(|(k, seq_v)| (k, seq_v.data))
+                .collect::<Vec<_>>(),
+            "sorted by key, every key once; absent key is omitted"
+        );
+
+        let got = kv.mget_kv_sorted_unique(&[]).await?;
+        assert!(got.is_empty());
+
+        Ok(())
+    }
+
+    #[minitrace::trace]
+    pub async fn kv_ext_prefix_list_excluding<KV: kvapi::KVApi>(
+        &self,
+        kv: &KV,
+    ) -> anyhow::Result<()> {
+        info!("--- {}", full_name!());
+
+        for k in ["a", "b", "c", "d"] {
+            let key = format!("excluding/{}", k);
+            kv.upsert_kv(UpsertKVReq::update(&key, k.as_bytes()))
+                .await?;
+        }
+        kv.upsert_kv(UpsertKVReq::update("excluding_x", b"x"))
+            .await?;
+
+        let exclude = ["excluding/b", "excluding/d", "excluding/z", "excluding_x"]
+            .map(|x| x.to_string())
+            .into_iter()
+            .collect::<HashSet<_>>();
+
+        let got = kv.prefix_list_kv_excluding("excluding/", &exclude).await?;
+        assert_eq!(
+            vec![
+                ("excluding/a".to_string(), b("a")),
+                ("excluding/c".to_string(), b("c")),
+            ],
+            got.into_iter()
+                .map(|(k, seq_v)| (k, seq_v.data))
+                .collect::<Vec<_>>(),
+            "excluded keys are absent even if they exist under the prefix"
+        );
+
+        let got = kv
+            .prefix_list_kv_excluding("excluding/", &HashSet::new())
+            .await?;
+        assert_eq!(4, got.len(), "nothing excluded");
+
+        Ok(())
+    }
+
+    #[minitrace::trace]
+    pub async fn kv_list_rev<KV: kvapi::KVApi>(&self, kv: &KV) -> anyhow::Result<()> {
+        info!("--- {}", full_name!());
+
+        // Snapshots keyed by a fixed-width timestamp, so that the key order is the time order.
+        let ts = [
+            1700000000u64,
+            1700000060,
+            1700000120,
+            1700000180,
+            1700000240,
+        ];
+        for t in ts {
+            let key = format!("list_rev/snapshot/{:020}", t);
+            kv.upsert_kv(UpsertKVReq::update(&key, t.to_string().as_bytes()))
+                .await?;
+        }
+        kv.upsert_kv(UpsertKVReq::update("list_rev/snapshot_other", b"x"))
+            .await?;
+
+        let latest = |n: usize| async move {
+            let got = kv.prefix_list_kv_rev("list_rev/snapshot/", n).await?;
+            let got = got
+                .into_iter()
+                .map(|(_k, seq_v)| String::from_utf8(seq_v.data).unwrap())
+                .collect::<Vec<_>>();
+            Ok::<_, anyhow::Error>(got)
+        };
+
+        assert_eq!(vec!["1700000240", "1700000180"], latest(2).await?);
+        assert_eq!(
+            vec![
+                "1700000240",
+                "1700000180",
+                "1700000120",
+                "1700000060",
+                "1700000000"
+            ],
+            latest(10).await?,
+            "limit greater than the number of keys"
+        );
+        assert!(latest(0).await?.is_empty());
+
+        let got = kv.prefix_list_kv_rev("list_rev/none/", 3).await?;
+        assert!(got.is_empty());
+
+        Ok(())
+    }
+
+    #[minitrace::trace]
+    pub async fn kv_transaction_chunked<KV: kvapi::KVApi>(&self, kv: &KV) -> anyhow::Result<()> {
+        info!("--- {}", full_name!());
+
+        let n = 10_000;
+        let key = |i: usize| format!("txn_chunked/{:05}", i);
+
+        let puts = (0..n).map(|i| TxnOp::put(key(i), b("v"))).collect();
+        let replies = kv
+            .transaction_chunked(TxnRequest::unconditional(puts), 1_000)
+            .await?;
+        assert_eq!(10, replies.len());
+        assert_eq!(n as u64, kv.count_prefix_kv("txn_chunked/").await?);
+
+        // 10k deletes in chunks of 3k: the last chunk is smaller.
+        let deletes = (0..n).map(|i| TxnOp::delete(key(i))).collect();
+        let replies = kv
+            .transaction_chunked(TxnRequest::unconditional(deletes), 3_000)
+            .await?;
+        assert_eq!(4, replies.len());
+        assert!(replies.iter().all(|r| r.success));
+        assert_eq!(
+            vec![3_000, 3_000, 3_000, 1_000],
+            replies
+                .iter()
+                .map(|r| r.responses.len())
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(0, kv.count_prefix_kv("txn_chunked/").await?);
+
+        // A conditional txn can not be split.
+        {
+            let txn = TxnRequest {
+                condition: vec![TxnCondition::eq_seq(key(0), 0)],
+                if_then: vec![TxnOp::put(key(0), b("v"))],
+                else_then: vec![],
+            };
+            let res = kv.transaction_chunked(txn, 1).await;
+            assert!(res.is_err());
+            assert_eq!(None, kv.get_kv(&key(0)).await?);
+        }
+
+        let res = kv
+            .transaction_chunked(TxnRequest::unconditional(vec![]), 0)
+            .await;
+        assert!(res.is_err());
+
+        Ok(())
+    }
+
+    #[minitrace::trace]
+    pub async fn kv_transaction_many<KV: kvapi::KVApi>(&self, kv: &KV) -> anyhow::Result<()> {
+        info!("--- {}", full_name!());
+
+        let key = |i: usize| format!("txn_many/{:02}", i);
+
+        kv.upsert_kv(UpsertKVReq::update(key(3), b"v")).await?;
+
+        // Every txn creates its key if it is absent; key(3) exists, thus the 4th txn fails.
+        let txns = (0..10)
+            .map(|i| TxnRequest {
+                condition: vec![TxnCondition::eq_seq(key(i), 0)],
+                if_then: vec![TxnOp::put(key(i), format!("v{}", i).into_bytes())],
+                else_then: vec![TxnOp::get(key(i))],
+            })
+            .collect::<Vec<_>>();
+
+        let replies = kv
+            .transaction_many(txns)
+            .await
+            .into_iter()
+            .collect::<Result<Vec<_>, _>>()?;
+        assert_eq!(10, replies.len());
+
+        for (i, reply) in replies.iter().enumerate() {
+            if i == 3 {
+                assert!(!reply.success);
+                assert_eq!(b"v".to_vec(), reply.get_response(&key(i)).unwrap().data);
+            } else {
+                assert!(reply.success, "{}-th txn", i);
+                assert!(reply.put_response(&key(i)).is_some(), "{}-th txn", i);
+            }
+        }
+
+        for i in 0..10 {
+            let got = kv.get_kv(&key(i)).await?.unwrap();
+            let want = if i == 3 {
+                b"v".to_vec()
+            } else {
+                format!("v{}", i).into_bytes()
+            };
+            assert_eq!(want, got.data);
+        }
+
+        // No txn
+        let replies = kv.transaction_many(vec![]).await;
+        assert!(replies.is_empty());
+
+        Ok(())
+    }
+
+    #[minitrace::trace]
+    pub async fn kv_ext_get_if_newer<KV: kvapi::KVApi>(&self, kv: &KV) -> anyhow::Result<()> {
+        info!("--- {}", full_name!());
+
+        let key = "get_if_newer_k";
+
+        // Absent and unchanged.
+        assert_eq!(None, kv.get_kv_if_newer(key, 0).await?);
+
+        let seq = kv
+            .upsert_kv(UpsertKVReq::update(key, b"v1"))
+            .await?
+            .result
+            .unwrap()
+            .seq;
+
+        // Unchanged: the payload is not returned.
+        assert_eq!(None, kv.get_kv_if_newer(key, seq).await?);
+
+        // Changed since the caller read it.
+        {
+            let got = kv.get_kv_if_newer(key, 0).await?;
+            assert_eq!(Some(b("v1")), got.flatten().map(|x| x.data));
+
+            kv.upsert_kv(UpsertKVReq::update(key, b"v2")).await?;
+
+            let got = kv.get_kv_if_newer(key, seq).await?.flatten().unwrap();
+            assert_eq!(b("v2"), got.data);
+            assert!(got.seq > seq);
+        }
+
+        // Deleted since the caller read it.
+        {
+            let prev = kv.get_kv(key).await?.unwrap();
+            kv.upsert_kv(UpsertKVReq::delete(key)).await?;
+
+            assert_eq!(Some(None), kv.get_kv_if_newer(key, prev.seq).await?);
+        }
+
+        Ok(())
+    }
+
+    #[minitrace::trace]
+    pub async fn kv_ext_get_history<KV: kvapi::KVApi>(&self, kv: &KV) -> anyhow::Result<()> {
+        info!("--- {}", full_name!());
+
+        let key = "get_history_k";
+
+        assert!(kv.get_kv_history(key, 10).await?.is_empty());
+
+        kv.upsert_kv(UpsertKVReq::update(key, b"v1")).await?;
+        kv.upsert_kv(UpsertKVReq::update(key, b"v2")).await?;
+
+        let current = kv.get_kv(key).await?.unwrap();
+
+        // The latest version comes first, the older ones are returned only if the backend retains them.
+        {
+            let got = kv.get_kv_history(key, 10).await?;
+            assert!(!got.is_empty() && got.len() <= 2);
+            assert_eq!(current, got[0]);
+            assert!(got.windows(2).all(|w| w[0].seq > w[1].seq));
+        }
+
+        assert_eq!(vec![current], kv.get_kv_history(key, 1).await?);
+        assert!(kv.get_kv_history(key, 0).await?.is_empty());
+
+        Ok(())
+    }
+
+    #[minitrace::trace]
+    pub async fn kv_ext_upsert_changed<KV: kvapi::KVApi>(&self, kv: &KV) -> anyhow::Result<()> {
+        info!("--- {}", full_name!());
+
+        let key = "upsert_changed_k";
+
+        // Create
+        assert!(
+            kv.upsert_kv_changed(UpsertKVReq::update(key, b"v1"))
+                .await?
+        );
+
+        // No-op: the same data, although the seq is bumped.
+        {
+            let seq = kv.get_kv(key).await?.unwrap().seq;
+            assert!(
+                !kv.upsert_kv_changed(UpsertKVReq::update(key, b"v1"))
+                    .await?
+            );
+            assert!(kv.get_kv(key).await?.unwrap().seq > seq);
+        }
+
+        // Change
+        assert!(
+            kv.upsert_kv_changed(UpsertKVReq::update(key, b"v2"))
+                .await?
+        );
+        assert_eq!(b("v2"), kv.get_kv(key).await?.unwrap().data);
+
+        // Not applied: the seq does not match.
+        assert!(
+            !kv.upsert_kv_changed(UpsertKVReq::update(key, b"v3").with(MatchSeq::Exact(0)))
+                .await?
+        );
+
+        // Delete
+        assert!(kv.upsert_kv_changed(UpsertKVReq::delete(key)).await?);
+        assert!(kv.get_kv(key).await?.is_none());
+
+        // Delete an absent key
+        assert!(!kv.upsert_kv_changed(UpsertKVReq::delete(key)).await?);
+
+        // Create with empty data
+        assert!(kv.upsert_kv_changed(UpsertKVReq::update(key, b"")).await?);
+
+        Ok(())
+    }
+
+    #[minitrace::trace]
+    pub async fn kv_ext_prefix_list_json<KV>(&self, kv: &KV) -> anyhow::Result<()>
+    where
+        KV: kvapi::KVApi,
+        KV::Error: From<MetaStorageError>,
+    {
+        info!("--- {}", full_name!());
+
+        kv.upsert_kv(UpsertKVReq::update("json/a", b"1")).await?;
+        kv.upsert_kv(UpsertKVReq::update("json/b", b"2")).await?;
+
+        let got = kv.prefix_list_json::<u64>("json/").await?;
+        assert_eq!(
+            vec![("json/a".to_string(), 1), ("json/b".to_string(), 2)],
+            got
+        );
+
+        assert!(kv.prefix_list_json::<u64>("absent/").await?.is_empty());
+
+        info!("--- a corrupt value fails the listing, tagged with its key");
+        {
+            kv.upsert_kv(UpsertKVReq::update("json/c", b"not-json"))
+                .await?;
+
+            let res = kv.prefix_list_json::<u64>("json/").await;
+            let err = res.unwrap_err().to_string();
+            assert!(err.contains("json/c"), "error: {}", err);
+        }
+
+        Ok(())
+    }
 }
 
 /// Test that write and read should be forwarded to leader
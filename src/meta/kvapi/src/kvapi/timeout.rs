@@ -0,0 +1,232 @@
+// Copyright 2021 Datafuse Labs
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! A kvapi::KVApi decorator that applies a deadline to every request.
+
+use std::collections::BTreeMap;
+use std::future::Future;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use databend_common_meta_types::RequestTimeout;
+use databend_common_meta_types::TxnReply;
+use databend_common_meta_types::TxnRequest;
+
+use crate::kvapi;
+use crate::kvapi::GetKVReply;
+use crate::kvapi::KVStream;
+use crate::kvapi::ListKVReply;
+use crate::kvapi::MGetKVReply;
+use crate::kvapi::UpsertKVReply;
+use crate::kvapi::UpsertKVReq;
+
+/// A kvapi::KVApi impl that delegates to an inner impl and fails a request with [`RequestTimeout`]
+/// if it does not finish in time.
+///
+/// The timeout can be overridden per method, by the method name such as `prefix_list_kv`.
+/// For stream based methods such as `list_kv()`, only opening the stream is subject to the timeout.
+pub struct TimeoutKVApi<T: kvapi::KVApi> {
+    inner: T,
+    timeout: Duration,
+    method_timeouts: BTreeMap<&'static str, Duration>,
+}
+
+impl<T> TimeoutKVApi<T>
+where
+    T: kvapi::KVApi,
+    T::Error: From<RequestTimeout>,
+{
+    pub fn new(inner: T, timeout: Duration) -> Self {
+        Self {
+            inner,
+            timeout,
+            method_timeouts: BTreeMap::new(),
+        }
+    }
+
+    /// Override the timeout of the method named `method`.
+    pub fn with_method_timeout(mut self, method: &'static str, timeout: Duration) -> Self {
+        self.method_timeouts.insert(method, timeout);
+        self
+    }
+
+    pub fn inner(&self) -> &T {
+        &self.inner
+    }
+
+    /// Returns the timeout that applies to `method`.
+    pub fn timeout_of(&self, method: &str) -> Duration {
+        self.method_timeouts
+            .get(method)
+            .copied()
+            .unwrap_or(self.timeout)
+    }
+
+    async fn timeout<R>(
+        &self,
+        method: &'static str,
+        fu: impl Future<Output = Result<R, T::Error>>,
+    ) -> Result<R, T::Error> {
+        let timeout = self.timeout_of(method);
+
+        match tokio::time::timeout(timeout, fu).await {
+            Ok(res) => res,
+            Err(_elapsed) => Err(RequestTimeout::new(timeout, method).into()),
+        }
+    }
+}
+
+#[async_trait]
+impl<T> kvapi::KVApi for TimeoutKVApi<T>
+where
+    T: kvapi::KVApi,
+    T::Error: From<RequestTimeout>,
+{
+    type Error = T::Error;
+
+    async fn upsert_kv(&self, req: UpsertKVReq) -> Result<UpsertKVReply, Self::Error> {
+        self.timeout("upsert_kv", self.inner.upsert_kv(req)).await
+    }
+
+    async fn mset_kv(&self, reqs: Vec<UpsertKVReq>) -> Result<Vec<UpsertKVReply>, Self::Error> {
+        self.timeout("mset_kv", self.inner.mset_kv(reqs)).await
+    }
+
+    async fn get_kv(&self, key: &str) -> Result<GetKVReply, Self::Error> {
+        self.timeout("get_kv", self.inner.get_kv(key)).await
+    }
+
+    async fn mget_kv(&self, keys: &[String]) -> Result<MGetKVReply, Self::Error> {
+        self.timeout("mget_kv", self.inner.mget_kv(keys)).await
+    }
+
+    async fn get_kv_stream(&self, keys: &[String]) -> Result<KVStream<Self::Error>, Self::Error> {
+        self.timeout("get_kv_stream", self.inner.get_kv_stream(keys))
+            .await
+    }
+
+    async fn list_kv(&self, prefix: &str) -> Result<KVStream<Self::Error>, Self::Error> {
+        self.timeout("list_kv", self.inner.list_kv(prefix)).await
+    }
+
+    async fn prefix_list_kv(&self, prefix: &str) -> Result<ListKVReply, Self::Error> {
+        self.timeout("prefix_list_kv", self.inner.prefix_list_kv(prefix))
+            .await
+    }
+
+    async fn transaction(&self, txn: TxnRequest) -> Result<TxnReply, Self::Error> {
+        self.timeout("transaction", self.inner.transaction(txn))
+            .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+    use std::time::Instant;
+
+    use async_trait::async_trait;
+    use databend_common_meta_types::MetaError;
+    use databend_common_meta_types::MetaNetworkError;
+    use databend_common_meta_types::TxnReply;
+    use databend_common_meta_types::TxnRequest;
+    use futures_util::stream;
+    use futures_util::StreamExt;
+
+    use crate::kvapi;
+    use crate::kvapi::KVApi;
+    use crate::kvapi::KVStream;
+    use crate::kvapi::TimeoutKVApi;
+    use crate::kvapi::UpsertKVReply;
+    use crate::kvapi::UpsertKVReq;
+
+    /// A KVApi that sleeps for `delay` before replying.
+    struct SlowKV {
+        delay: Duration,
+    }
+
+    #[async_trait]
+    impl kvapi::KVApi for SlowKV {
+        type Error = MetaError;
+
+        async fn upsert_kv(&self, _req: UpsertKVReq) -> Result<UpsertKVReply, Self::Error> {
+            tokio::time::sleep(self.delay).await;
+            Ok(UpsertKVReply::new(None, None))
+        }
+
+        async fn get_kv_stream(
+            &self,
+            _keys: &[String],
+        ) -> Result<KVStream<Self::Error>, Self::Error> {
+            tokio::time::sleep(self.delay).await;
+            Ok(stream::empty().boxed())
+        }
+
+        async fn list_kv(&self, _prefix: &str) -> Result<KVStream<Self::Error>, Self::Error> {
+            tokio::time::sleep(self.delay).await;
+            Ok(stream::empty().boxed())
+        }
+
+        async fn transaction(&self, _txn: TxnRequest) -> Result<TxnReply, Self::Error> {
+            tokio::time::sleep(self.delay).await;
+            Ok(TxnReply::default())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_timeout() -> anyhow::Result<()> {
+        let kv = TimeoutKVApi::new(
+            SlowKV {
+                delay: Duration::from_secs(10),
+            },
+            Duration::from_millis(50),
+        );
+
+        let start = Instant::now();
+        let res = kv.upsert_kv(UpsertKVReq::update("a", b"b")).await;
+
+        assert!(start.elapsed() < Duration::from_secs(5));
+
+        let err = res.unwrap_err();
+        match err {
+            MetaError::NetworkError(MetaNetworkError::RequestTimeout(t)) => {
+                assert_eq!(Duration::from_millis(50), t.timeout());
+            }
+            _ => panic!("expect RequestTimeout, got: {:?}", err),
+        }
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_method_timeout() -> anyhow::Result<()> {
+        let kv = TimeoutKVApi::new(
+            SlowKV {
+                delay: Duration::from_millis(100),
+            },
+            Duration::from_millis(10),
+        )
+        .with_method_timeout("transaction", Duration::from_secs(10));
+
+        assert_eq!(Duration::from_millis(10), kv.timeout_of("upsert_kv"));
+        assert_eq!(Duration::from_secs(10), kv.timeout_of("transaction"));
+
+        let res = kv.upsert_kv(UpsertKVReq::update("a", b"b")).await;
+        assert!(res.is_err());
+
+        kv.transaction(TxnRequest::default()).await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,170 @@
+// Copyright 2021 Datafuse Labs
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! A kvapi::KVApi decorator that opens a tracing span for every request.
+
+use std::future::Future;
+
+use async_trait::async_trait;
+use databend_common_meta_types::TxnReply;
+use databend_common_meta_types::TxnRequest;
+use minitrace::prelude::*;
+
+use crate::kvapi;
+use crate::kvapi::GetKVReply;
+use crate::kvapi::KVStream;
+use crate::kvapi::ListKVReply;
+use crate::kvapi::MGetKVReply;
+use crate::kvapi::UpsertKVReply;
+use crate::kvapi::UpsertKVReq;
+
+/// A kvapi::KVApi impl that delegates to an inner impl and wraps every method in a span
+/// named after the method, such as `KVApi::get_kv`.
+///
+/// The span records the key or prefix of the request as a property,
+/// and an event for the outcome: `reply` with the resulting `seq` if there is one, or `error`.
+///
+/// The span is a child of the current local parent. The meta-service client carries it
+/// into the RPC metadata, so the spans on the server side join the same trace.
+pub struct TracedKVApi<T: kvapi::KVApi> {
+    inner: T,
+}
+
+impl<T: kvapi::KVApi> TracedKVApi<T> {
+    pub fn new(inner: T) -> Self {
+        Self { inner }
+    }
+
+    pub fn inner(&self) -> &T {
+        &self.inner
+    }
+
+    async fn traced<R>(
+        name: &'static str,
+        target: (&'static str, String),
+        fu: impl Future<Output = Result<R, T::Error>>,
+        seq_of: impl FnOnce(&R) -> Option<u64>,
+    ) -> Result<R, T::Error> {
+        let span = Span::enter_with_local_parent(name).with_property(|| target);
+
+        async move {
+            let res = fu.await;
+            match &res {
+                Ok(reply) => {
+                    let seq = seq_of(reply);
+                    Event::add_to_local_parent("reply", || {
+                        [("seq", seq.map(|x| x.to_string()).unwrap_or_default())]
+                    });
+                }
+                Err(e) => {
+                    Event::add_to_local_parent("error", || [("error", e.to_string())]);
+                }
+            }
+            res
+        }
+        .in_span(span)
+        .await
+    }
+}
+
+#[async_trait]
+impl<T: kvapi::KVApi> kvapi::KVApi for TracedKVApi<T> {
+    type Error = T::Error;
+
+    async fn upsert_kv(&self, req: UpsertKVReq) -> Result<UpsertKVReply, Self::Error> {
+        let target = ("key", req.key.clone());
+        Self::traced("KVApi::upsert_kv", target, self.inner.upsert_kv(req), |r| {
+            r.result.as_ref().map(|x| x.seq)
+        })
+        .await
+    }
+
+    async fn mset_kv(&self, reqs: Vec<UpsertKVReq>) -> Result<Vec<UpsertKVReply>, Self::Error> {
+        let keys = reqs.iter().map(|r| r.key.as_str()).collect::<Vec<_>>();
+        let target = ("keys", keys.join(","));
+        Self::traced("KVApi::mset_kv", target, self.inner.mset_kv(reqs), |_| None).await
+    }
+
+    async fn get_kv(&self, key: &str) -> Result<GetKVReply, Self::Error> {
+        let target = ("key", key.to_string());
+        Self::traced("KVApi::get_kv", target, self.inner.get_kv(key), |r| {
+            r.as_ref().map(|x| x.seq)
+        })
+        .await
+    }
+
+    async fn mget_kv(&self, keys: &[String]) -> Result<MGetKVReply, Self::Error> {
+        let target = ("keys", keys.join(","));
+        Self::traced("KVApi::mget_kv", target, self.inner.mget_kv(keys), |_| None).await
+    }
+
+    async fn get_kv_stream(&self, keys: &[String]) -> Result<KVStream<Self::Error>, Self::Error> {
+        let target = ("keys", keys.join(","));
+        let fu = self.inner.get_kv_stream(keys);
+        Self::traced("KVApi::get_kv_stream", target, fu, |_| None).await
+    }
+
+    async fn list_kv(&self, prefix: &str) -> Result<KVStream<Self::Error>, Self::Error> {
+        let target = ("prefix", prefix.to_string());
+        Self::traced("KVApi::list_kv", target, self.inner.list_kv(prefix), |_| {
+            None
+        })
+        .await
+    }
+
+    async fn prefix_list_kv(&self, prefix: &str) -> Result<ListKVReply, Self::Error> {
+        let target = ("prefix", prefix.to_string());
+        let fu = self.inner.prefix_list_kv(prefix);
+        Self::traced("KVApi::prefix_list_kv", target, fu, |_| None).await
+    }
+
+    async fn transaction(&self, txn: TxnRequest) -> Result<TxnReply, Self::Error> {
+        let keys = txn
+            .condition
+            .iter()
+            .map(|c| c.key.as_str())
+            .collect::<Vec<_>>();
+        let target = ("keys", keys.join(","));
+        Self::traced(
+            "KVApi::transaction",
+            target,
+            self.inner.transaction(txn),
+            |_| None,
+        )
+        .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::kvapi;
+    use crate::kvapi::MemKVApi;
+    use crate::kvapi::TracedKVApi;
+
+    #[tokio::test]
+    async fn test_traced_delegates_to_inner() -> anyhow::Result<()> {
+        let suite = kvapi::TestSuite {};
+
+        suite
+            .kv_write_read(&TracedKVApi::new(MemKVApi::new()))
+            .await?;
+        suite.kv_list(&TracedKVApi::new(MemKVApi::new())).await?;
+        suite.kv_mget(&TracedKVApi::new(MemKVApi::new())).await?;
+        suite
+            .kv_transaction(&TracedKVApi::new(MemKVApi::new()))
+            .await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,218 @@
+// Copyright 2021 Datafuse Labs
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! A fluent builder of `TxnRequest`.
+
+use databend_common_meta_types::TxnCondition;
+use databend_common_meta_types::TxnOp;
+use databend_common_meta_types::TxnRequest;
+
+/// Build a `TxnRequest` from conditions, `if_then` operations and `else_then` operations.
+///
+/// `when_*()` adds a condition, all of the conditions must be met to run the `then_*()` operations,
+/// otherwise the `or_else_*()` operations are run.
+///
+/// # Example
+///
+/// Compare-and-swap: update `k` only if it has not been changed since it was read at `seq`,
+/// otherwise read the current value of it:
+/// ```
+/// let txn = TxnBuilder::new()
+///     .when_seq_eq("k", seq)
+///     .then_put("k", b"v2".to_vec())
+///     .or_else_get("k")
+///     .build();
+///
+/// let reply = kv.transaction(txn).await?;
+/// ```
+///
+/// Atomic swap of two keys: exchange the values of `a` and `b`,
+/// if neither of them has been changed since they were read:
+/// ```
+/// let txn = TxnBuilder::new()
+///     .when_seq_eq("a", a.seq)
+///     .when_seq_eq("b", b.seq)
+///     .then_put("a", b.data)
+///     .then_put("b", a.data)
+///     .or_else_get("a")
+///     .or_else_get("b")
+///     .build();
+///
+/// let reply = kv.transaction(txn).await?;
+/// assert!(reply.success);
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct TxnBuilder {
+    condition: Vec<TxnCondition>,
+    if_then: Vec<TxnOp>,
+    else_then: Vec<TxnOp>,
+}
+
+impl TxnBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Require the `seq` of `key` to be `seq`. `seq == 0` requires `key` to be absent.
+    pub fn when_seq_eq(mut self, key: impl ToString, seq: u64) -> Self {
+        self.condition.push(TxnCondition::eq_seq(key, seq));
+        self
+    }
+
+    /// Require the value of `key` to be `value`.
+    pub fn when_value_eq(mut self, key: impl ToString, value: Vec<u8>) -> Self {
+        self.condition.push(TxnCondition::eq_value(key, value));
+        self
+    }
+
+    /// Put `key` if all of the conditions are met.
+    pub fn then_put(mut self, key: impl ToString, value: Vec<u8>) -> Self {
+        self.if_then.push(TxnOp::put(key, value));
+        self
+    }
+
+    /// Delete `key` if all of the conditions are met.
+    pub fn then_delete(mut self, key: impl ToString) -> Self {
+        self.if_then.push(TxnOp::delete(key));
+        self
+    }
+
+    /// Read `key` if all of the conditions are met.
+    pub fn then_get(mut self, key: impl ToString) -> Self {
+        self.if_then.push(TxnOp::get(key));
+        self
+    }
+
+    /// Put `key` if any of the conditions is not met.
+    pub fn or_else_put(mut self, key: impl ToString, value: Vec<u8>) -> Self {
+        self.else_then.push(TxnOp::put(key, value));
+        self
+    }
+
+    /// Delete `key` if any of the conditions is not met.
+    pub fn or_else_delete(mut self, key: impl ToString) -> Self {
+        self.else_then.push(TxnOp::delete(key));
+        self
+    }
+
+    /// Read `key` if any of the conditions is not met.
+    pub fn or_else_get(mut self, key: impl ToString) -> Self {
+        self.else_then.push(TxnOp::get(key));
+        self
+    }
+
+    pub fn build(self) -> TxnRequest {
+        TxnRequest {
+            condition: self.condition,
+            if_then: self.if_then,
+            else_then: self.else_then,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use databend_common_meta_types::SeqV;
+    use databend_common_meta_types::TxnCondition;
+    use databend_common_meta_types::TxnOp;
+    use databend_common_meta_types::TxnRequest;
+
+    use crate::kvapi::KVApi;
+    use crate::kvapi::MemKVApi;
+    use crate::kvapi::TxnBuilder;
+    use crate::kvapi::UpsertKVReq;
+
+    #[test]
+    fn test_txn_builder_build() {
+        let txn = TxnBuilder::new()
+            .when_seq_eq("a", 1)
+            .when_value_eq("b", b"x".to_vec())
+            .then_put("a", b"v".to_vec())
+            .then_delete("b")
+            .or_else_get("a")
+            .build();
+
+        assert_eq!(
+            TxnRequest {
+                condition: vec![
+                    TxnCondition::eq_seq("a", 1),
+                    TxnCondition::eq_value("b", b"x".to_vec())
+                ],
+                if_then: vec![TxnOp::put("a", b"v".to_vec()), TxnOp::delete("b")],
+                else_then: vec![TxnOp::get("a")],
+            },
+            txn
+        );
+    }
+
+    #[tokio::test]
+    async fn test_txn_builder_cas() -> anyhow::Result<()> {
+        let kv = MemKVApi::new();
+
+        let seq = kv
+            .upsert_kv(UpsertKVReq::update("k", b"v1"))
+            .await?
+            .result
+            .unwrap()
+            .seq;
+
+        let cas = |seq: u64| {
+            TxnBuilder::new()
+                .when_seq_eq("k", seq)
+                .then_put("k", b"v2".to_vec())
+                .or_else_get("k")
+                .build()
+        };
+
+        let reply = kv.transaction(cas(seq)).await?;
+        assert!(reply.success);
+
+        // `k` has been changed, the stale seq does not match any more.
+        let reply = kv.transaction(cas(seq)).await?;
+        assert!(!reply.success);
+
+        let got = kv.get_kv("k").await?;
+        assert_eq!(Some(b"v2".to_vec()), got.map(|x| x.data));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_txn_builder_swap() -> anyhow::Result<()> {
+        let kv = MemKVApi::new();
+
+        kv.upsert_kv(UpsertKVReq::update("a", b"1")).await?;
+        kv.upsert_kv(UpsertKVReq::update("b", b"2")).await?;
+
+        let a: SeqV = kv.get_kv("a").await?.unwrap();
+        let b: SeqV = kv.get_kv("b").await?.unwrap();
+
+        let txn = TxnBuilder::new()
+            .when_seq_eq("a", a.seq)
+            .when_seq_eq("b", b.seq)
+            .then_put("a", b.data)
+            .then_put("b", a.data)
+            .or_else_get("a")
+            .or_else_get("b")
+            .build();
+
+        let reply = kv.transaction(txn).await?;
+        assert!(reply.success);
+
+        assert_eq!(Some(b"2".to_vec()), kv.get_kv("a").await?.map(|x| x.data));
+        assert_eq!(Some(b"1".to_vec()), kv.get_kv("b").await?.map(|x| x.data));
+
+        Ok(())
+    }
+}
@@ -13,5 +13,6 @@
 // limitations under the License.
 
 #![allow(clippy::uninlined_format_args)]
+#![feature(lazy_cell)]
 
 pub mod kvapi;
@@ -79,6 +79,7 @@ impl FromToProto for mt::DatabaseMeta {
                 Some(from_share) => Some(share::ShareNameIdent::from_pb(from_share)?),
                 None => None,
             },
+            max_tables: p.max_tables,
         };
         Ok(v)
     }
@@ -102,6 +103,7 @@ impl FromToProto for mt::DatabaseMeta {
                 Some(from_share) => Some(from_share.to_pb()?),
                 None => None,
             },
+            max_tables: self.max_tables,
         };
         Ok(p)
     }
@@ -218,6 +218,7 @@ impl FromToProto for mt::TableMeta {
             } else {
                 Some(p.column_mask_policy)
             },
+            schema_version: p.schema_version,
         };
         Ok(v)
     }
@@ -254,6 +255,7 @@ impl FromToProto for mt::TableMeta {
             statistics: Some(self.statistics.to_pb()?),
             shared_by: Vec::from_iter(self.shared_by.clone()),
             column_mask_policy: self.column_mask_policy.clone().unwrap_or_default(),
+            schema_version: self.schema_version,
         };
         Ok(p)
     }
@@ -79,3 +79,5 @@ mod v072_csv_format_params;
 mod v073_huggingface_config;
 mod v074_table_db_meta;
 mod v075_csv_format_params;
+mod v076_table_meta_schema_version;
+mod v077_database_meta_max_tables;
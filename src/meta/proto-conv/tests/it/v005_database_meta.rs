@@ -57,6 +57,7 @@ fn test_decode_v5_database_meta() -> anyhow::Result<()> {
             tenant: "tenant".to_string(),
             share_name: "share".to_string(),
         }),
+        max_tables: None,
     };
 
     common::test_pb_from_to(func_name!(), want())?;
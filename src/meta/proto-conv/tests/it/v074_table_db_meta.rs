@@ -92,6 +92,7 @@ fn test_decode_v74_table_meta() -> anyhow::Result<()> {
         statistics: Default::default(),
         shared_by: btreeset! {1},
         column_mask_policy: Some(btreemap! {s("a") => s("b")}),
+        schema_version: 0,
     };
     common::test_pb_from_to(func_name!(), want())?;
     common::test_load_old(func_name!(), table_meta_v74.as_slice(), 74, want())?;
@@ -123,6 +124,7 @@ fn test_decode_v74_database_meta() -> anyhow::Result<()> {
             tenant: "tenant".to_string(),
             share_name: "share".to_string(),
         }),
+        max_tables: None,
     };
 
     common::test_pb_from_to(func_name!(), want())?;
@@ -46,8 +46,12 @@ use databend_common_meta_types::TxnPutRequest;
 use databend_common_meta_types::TxnPutResponse;
 use databend_common_meta_types::TxnReply;
 use databend_common_meta_types::TxnRequest;
+use databend_common_meta_types::TxnReserveSeqRequest;
 use databend_common_meta_types::UpsertKV;
 use databend_common_meta_types::With;
+use databend_common_meta_types::FEATURE_IDEMPOTENCY_TOKEN;
+use databend_common_meta_types::FEATURE_RESERVE_SEQ;
+use databend_common_meta_types::IDEMPOTENCY_TOKEN_TTL;
 use futures::stream::TryStreamExt;
 use log::as_debug;
 use log::as_display;
@@ -56,7 +60,9 @@ use log::error;
 use log::info;
 use num::FromPrimitive;
 
+use crate::sm_v002::leveled_store::sys_data_api::SysDataApiRO;
 use crate::sm_v002::SMV002;
+use crate::state_machine::IdempotencyTokenValue;
 
 /// A helper that applies raft log `Entry` to the state machine.
 pub struct Applier<'a> {
@@ -91,6 +97,7 @@ impl<'a> Applier<'a> {
         self.cmd_ctx = CmdContext::from_millis(log_time_ms);
 
         self.clean_expired_kvs(log_time_ms).await?;
+        self.clean_expired_idempotency_tokens(log_time_ms);
 
         *self.sm.sys_data_mut().last_applied_mut() = Some(*log_id);
 
@@ -146,6 +153,8 @@ impl<'a> Applier<'a> {
             Cmd::UpsertKV(ref upsert_kv) => self.apply_upsert_kv(upsert_kv).await?,
 
             Cmd::Transaction(txn) => self.apply_txn(txn).await?,
+
+            Cmd::SetFeature { feature, enable } => self.apply_set_feature(feature, *enable),
         };
 
         info!("apply_result: cmd: {}; res: {}", cmd, res);
@@ -199,10 +208,58 @@ impl<'a> Applier<'a> {
     async fn apply_upsert_kv(&mut self, upsert_kv: &UpsertKV) -> Result<AppliedState, io::Error> {
         debug!(upsert_kv = as_debug!(upsert_kv); "apply_update_kv_cmd");
 
+        let now_ms = self.cmd_ctx.time().millis();
+
+        // A token is ignored unless every node de-duplicates by it.
+        let token = upsert_kv.idempotency_token.as_ref().filter(|_| {
+            self.sm
+                .sys_data_ref()
+                .features_ref()
+                .contains(FEATURE_IDEMPOTENCY_TOKEN)
+        });
+
+        // A retry of an applied upsert returns the recorded reply.
+        if let Some(token) = token {
+            let recorded = self.sm.sys_data_ref().idempotency_tokens_ref().get(token);
+            if let Some(recorded) = recorded.filter(|r| r.expire_at_ms > now_ms) {
+                info!(
+                    "UpsertKV with idempotency token is already applied: {}",
+                    token
+                );
+                return Ok(recorded.change.clone().into());
+            }
+        }
+
         let (prev, result) = self.upsert_kv(upsert_kv).await?;
+        let change = Change::new(prev, result);
+
+        // The record is system data: it does not consume a seq and does not emit an event.
+        if let Some(token) = token {
+            let recorded = IdempotencyTokenValue {
+                expire_at_ms: now_ms + IDEMPOTENCY_TOKEN_TTL.as_millis() as u64,
+                change: change.clone(),
+            };
+            self.sm
+                .sys_data_mut()
+                .idempotency_tokens_mut()
+                .insert(token.clone(), recorded);
+        }
+
+        Ok(change.into())
+    }
+
+    /// Enable or disable a state machine feature.
+    #[minitrace::trace]
+    fn apply_set_feature(&mut self, feature: &str, enable: bool) -> AppliedState {
+        let features = self.sm.sys_data_mut().features_mut();
+        if enable {
+            features.insert(feature.to_string());
+        } else {
+            features.remove(feature);
+        }
+        info!("applied SetFeature: {}={}", feature, enable);
 
-        let st = Change::new(prev, result).into();
-        Ok(st)
+        AppliedState::None
     }
 
     /// Update or insert a kv entry.
@@ -362,6 +419,9 @@ impl<'a> Applier<'a> {
                 self.txn_execute_delete_by_prefix(delete_by_prefix, resp)
                     .await?;
             }
+            Some(txn_op::Request::ReserveSeq(reserve_seq)) => {
+                self.txn_execute_reserve_seq(reserve_seq, resp).await?;
+            }
             None => {}
         }
         Ok(())
@@ -441,6 +501,43 @@ impl<'a> Applier<'a> {
         Ok(())
     }
 
+    /// Reserve `count` seqs by advancing the seq and updating the record of the key with the last one.
+    ///
+    /// Unless the feature [`FEATURE_RESERVE_SEQ`] is enabled it is a no-op without response,
+    /// the leader does not propose it in this case but the operation is not trusted to be absent.
+    async fn txn_execute_reserve_seq(
+        &mut self,
+        reserve_seq: &TxnReserveSeqRequest,
+        resp: &mut TxnReply,
+    ) -> Result<(), io::Error> {
+        let enabled = self
+            .sm
+            .sys_data_ref()
+            .features_ref()
+            .contains(FEATURE_RESERVE_SEQ);
+        if !enabled {
+            return Ok(());
+        }
+
+        let count = reserve_seq.count.max(1);
+        let curr_seq = self.sm.sys_data_ref().curr_seq();
+
+        // The upsert takes the next seq: the last of the reserved ones.
+        self.sm.sys_data_mut().update_seq(curr_seq + count - 1);
+        let (_prev, result) = self
+            .upsert_kv(&UpsertKV::update(&reserve_seq.key, b""))
+            .await?;
+
+        let end = result.seq() + 1;
+        resp.responses.push(TxnOpResponse::reserve_seq(
+            &reserve_seq.key,
+            end - count,
+            end,
+        ));
+
+        Ok(())
+    }
+
     async fn txn_execute_delete_by_prefix(
         &mut self,
         delete_by_prefix: &TxnDeleteByPrefixRequest,
@@ -512,6 +609,18 @@ impl<'a> Applier<'a> {
         Ok(())
     }
 
+    /// Remove the recorded replies of idempotency tokens that expire before the log time.
+    fn clean_expired_idempotency_tokens(&mut self, log_time_ms: u64) {
+        if log_time_ms == 0 {
+            return;
+        }
+
+        self.sm
+            .sys_data_mut()
+            .idempotency_tokens_mut()
+            .retain(|_, v| v.expire_at_ms > log_time_ms);
+    }
+
     /// Push a **change** that is applied to `key`.
     ///
     /// It does nothing if `prev == result`
@@ -36,6 +36,7 @@ use crate::state::RaftStateValue;
 use crate::state_machine::ClientLastRespValue;
 use crate::state_machine::ExpireKey;
 use crate::state_machine::ExpireValue;
+use crate::state_machine::IdempotencyTokenValue;
 use crate::state_machine::LogMetaKey;
 use crate::state_machine::LogMetaValue;
 use crate::state_machine::StateMachineMetaKey;
@@ -124,6 +125,17 @@ impl SledKeySpace for ClientLastResps {
     type V = ClientLastRespValue;
 }
 
+/// Stores the recorded replies of upserts with idempotency tokens.
+///
+/// It is system data, thus it is not part of the user kv and does not consume a seq.
+pub struct IdempotencyTokens {}
+impl SledKeySpace for IdempotencyTokens {
+    const PREFIX: u8 = 14;
+    const NAME: &'static str = "idempotency-token";
+    type K = String;
+    type V = IdempotencyTokenValue;
+}
+
 pub struct DataHeader {}
 impl SledKeySpace for DataHeader {
     const PREFIX: u8 = 11;
@@ -146,6 +158,7 @@ pub enum RaftStoreEntry {
     Sequences        { key: <Sequences        as SledKeySpace>::K, value: <Sequences        as SledKeySpace>::V, },
     ClientLastResps  { key: <ClientLastResps  as SledKeySpace>::K, value: <ClientLastResps  as SledKeySpace>::V, },
     LogMeta          { key: <LogMeta          as SledKeySpace>::K, value: <LogMeta          as SledKeySpace>::V, },
+    IdempotencyTokens { key: <IdempotencyTokens as SledKeySpace>::K, value: <IdempotencyTokens as SledKeySpace>::V, },
 }
 
 impl RaftStoreEntry {
@@ -169,6 +182,7 @@ impl RaftStoreEntry {
             Self::Sequences        { key, value } => ser!(Sequences,        key, value),
             Self::ClientLastResps  { key, value } => ser!(ClientLastResps,  key, value),
             Self::LogMeta          { key, value } => ser!(LogMeta,          key, value),
+            Self::IdempotencyTokens { key, value } => ser!(IdempotencyTokens, key, value),
         }
     }
 
@@ -212,7 +226,8 @@ impl RaftStoreEntry {
             GenericKV,
             Sequences,
             ClientLastResps,
-            LogMeta
+            LogMeta,
+            IdempotencyTokens
         );
 
         unreachable!("unknown prefix: {}", prefix);
@@ -234,6 +249,7 @@ pub enum RaftStoreEntryCompat {
     Sequences        { key: <Sequences        as SledKeySpace>::K, value: <Sequences as SledKeySpace>::V,         },
     ClientLastResps  { key: <ClientLastResps  as SledKeySpace>::K, value: <ClientLastResps as SledKeySpace>::V,   },
     LogMeta          { key: <LogMeta          as SledKeySpace>::K, value: crate::compat07::LogMetaValue,          },
+    IdempotencyTokens { key: <IdempotencyTokens as SledKeySpace>::K, value: <IdempotencyTokens as SledKeySpace>::V, },
 }
 
 impl openraft::compat::Upgrade<RaftStoreEntry> for RaftStoreEntryCompat {
@@ -250,6 +266,7 @@ impl openraft::compat::Upgrade<RaftStoreEntry> for RaftStoreEntryCompat {
             RaftStoreEntryCompat::Sequences        { key, value } => RaftStoreEntry::Sequences        { key, value, },
             RaftStoreEntryCompat::ClientLastResps  { key, value } => RaftStoreEntry::ClientLastResps  { key, value, },
             RaftStoreEntryCompat::LogMeta          { key, value } => RaftStoreEntry::LogMeta          { key, value: value.upgrade(), },
+            RaftStoreEntryCompat::IdempotencyTokens { key, value } => RaftStoreEntry::IdempotencyTokens { key, value, },
         }
     }
 }
@@ -299,7 +316,8 @@ impl RaftStoreEntryCompat {
             GenericKV,
             Sequences,
             ClientLastResps,
-            LogMeta
+            LogMeta,
+            IdempotencyTokens
         );
 
         unreachable!("unknown prefix: {}", prefix);
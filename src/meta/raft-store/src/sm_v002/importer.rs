@@ -13,6 +13,7 @@
 // limitations under the License.
 
 use std::collections::BTreeMap;
+use std::collections::BTreeSet;
 use std::io;
 
 use databend_common_meta_types::anyerror::AnyError;
@@ -88,8 +89,22 @@ impl Importer {
                             })?;
                         *d.sys_data_mut().last_membership_mut() = membership;
                     }
+                    StateMachineMetaKey::Features => {
+                        let features =
+                            TryInto::<BTreeSet<String>>::try_into(value).map_err(|e| {
+                                io::Error::new(
+                                    io::ErrorKind::InvalidData,
+                                    AnyError::error(e)
+                                        .add_context(|| "import StateMachineMetaKey::Features"),
+                                )
+                            })?;
+                        *d.sys_data_mut().features_mut() = features;
+                    }
                 }
             }
+            RaftStoreEntry::IdempotencyTokens { key, value } => {
+                d.sys_data_mut().idempotency_tokens_mut().insert(key, value);
+            }
             RaftStoreEntry::Expire { key, mut value } => {
                 // Old version ExpireValue has seq to be 0. replace it with 1.
                 // `1` is a valid seq. `0` is used by tombstone.
@@ -13,6 +13,7 @@
 // limitations under the License.
 
 use std::collections::BTreeMap;
+use std::collections::BTreeSet;
 
 use databend_common_meta_types::LogId;
 use databend_common_meta_types::Node;
@@ -21,6 +22,7 @@ use databend_common_meta_types::StoredMembership;
 use log::debug;
 
 use crate::sm_v002::leveled_store::sys_data_api::SysDataApiRO;
+use crate::state_machine::IdempotencyTokenValue;
 
 /// System data(non-user data).
 ///
@@ -40,6 +42,14 @@ pub struct SysData {
     ///
     /// A seq is globally unique and monotonically increasing.
     sequence: u64,
+
+    /// The enabled state machine features, see `Cmd::SetFeature`.
+    features: BTreeSet<String>,
+
+    /// The recorded replies of upserts with idempotency tokens, by token.
+    ///
+    /// Expired records are removed when applying a log.
+    idempotency_tokens: BTreeMap<String, IdempotencyTokenValue>,
 }
 
 impl SysDataApiRO for SysData {
@@ -58,6 +68,14 @@ impl SysDataApiRO for SysData {
     fn nodes_ref(&self) -> &BTreeMap<NodeId, Node> {
         &self.nodes
     }
+
+    fn features_ref(&self) -> &BTreeSet<String> {
+        &self.features
+    }
+
+    fn idempotency_tokens_ref(&self) -> &BTreeMap<String, IdempotencyTokenValue> {
+        &self.idempotency_tokens
+    }
 }
 
 impl<T> SysDataApiRO for T
@@ -78,6 +96,14 @@ where T: AsRef<SysData>
     fn nodes_ref(&self) -> &BTreeMap<NodeId, Node> {
         self.as_ref().nodes_ref()
     }
+
+    fn features_ref(&self) -> &BTreeSet<String> {
+        self.as_ref().features_ref()
+    }
+
+    fn idempotency_tokens_ref(&self) -> &BTreeMap<String, IdempotencyTokenValue> {
+        self.as_ref().idempotency_tokens_ref()
+    }
 }
 
 impl SysData {
@@ -105,4 +131,12 @@ impl SysData {
     pub fn nodes_mut(&mut self) -> &mut BTreeMap<NodeId, Node> {
         &mut self.nodes
     }
+
+    pub fn features_mut(&mut self) -> &mut BTreeSet<String> {
+        &mut self.features
+    }
+
+    pub fn idempotency_tokens_mut(&mut self) -> &mut BTreeMap<String, IdempotencyTokenValue> {
+        &mut self.idempotency_tokens
+    }
 }
@@ -13,12 +13,15 @@
 // limitations under the License.
 
 use std::collections::BTreeMap;
+use std::collections::BTreeSet;
 
 use databend_common_meta_types::LogId;
 use databend_common_meta_types::Node;
 use databend_common_meta_types::NodeId;
 use databend_common_meta_types::StoredMembership;
 
+use crate::state_machine::IdempotencyTokenValue;
+
 /// APIs to access the non-user-data of the state machine(leveled map).
 pub trait SysDataApiRO {
     fn curr_seq(&self) -> u64;
@@ -28,4 +31,8 @@ pub trait SysDataApiRO {
     fn last_membership_ref(&self) -> &StoredMembership;
 
     fn nodes_ref(&self) -> &BTreeMap<NodeId, Node>;
+
+    fn features_ref(&self) -> &BTreeSet<String>;
+
+    fn idempotency_tokens_ref(&self) -> &BTreeMap<String, IdempotencyTokenValue>;
 }
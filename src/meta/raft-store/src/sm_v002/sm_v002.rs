@@ -36,6 +36,7 @@ use databend_common_meta_types::TxnReply;
 use databend_common_meta_types::TxnRequest;
 use databend_common_meta_types::UpsertKV;
 use futures::Stream;
+use futures_util::stream::BoxStream;
 use futures_util::StreamExt;
 use futures_util::TryStreamExt;
 use log::debug;
@@ -105,6 +106,21 @@ impl<'a> kvapi::KVApi for SMV002KVApi<'a> {
         Ok(strm.boxed())
     }
 
+    async fn prefix_list_kv_stream(
+        &self,
+        prefix: &str,
+    ) -> Result<BoxStream<'_, Result<(String, SeqV), Self::Error>>, Self::Error> {
+        let local_now_ms = SeqV::<()>::now_ms();
+
+        let strm = self
+            .sm
+            .range_kv(prefix)
+            .await?
+            .try_filter(move |(_k, v)| future::ready(!v.is_expired(local_now_ms)));
+
+        Ok(strm.boxed())
+    }
+
     async fn transaction(&self, _txn: TxnRequest) -> Result<TxnReply, Self::Error> {
         unreachable!("write operation SM2KVApi::transaction is disabled")
     }
@@ -264,6 +280,19 @@ impl SMV002 {
     ///
     /// If a value is expired, it is not returned.
     pub async fn list_kv(&self, prefix: &str) -> Result<ResultStream<(String, SeqV)>, io::Error> {
+        let strm = self.range_kv(prefix).await?;
+
+        // Make it static
+
+        let vs = strm.collect::<Vec<_>>().await;
+        let strm = futures::stream::iter(vs);
+
+        Ok(strm.boxed())
+    }
+
+    /// Return a stream of the non-deleted key-values with the `prefix`,
+    /// which reads from the range of the leveled map lazily.
+    pub async fn range_kv(&self, prefix: &str) -> Result<ResultStream<(String, SeqV)>, io::Error> {
         let p = prefix.to_string();
 
         let strm = self.levels.str_map().range(p.clone()..).await?;
@@ -278,11 +307,6 @@ impl SMV002 {
                 future::ready(Ok(res))
             });
 
-        // Make it static
-
-        let vs = strm.collect::<Vec<_>>().await;
-        let strm = futures::stream::iter(vs);
-
         Ok(strm.boxed())
     }
 
@@ -12,14 +12,24 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use databend_common_meta_kvapi::kvapi::KVApi;
+use databend_common_meta_types::AppliedState;
+use databend_common_meta_types::Change;
+use databend_common_meta_types::Cmd;
 use databend_common_meta_types::SeqV;
 use databend_common_meta_types::SeqValue;
+use databend_common_meta_types::TxnOp;
+use databend_common_meta_types::TxnReply;
+use databend_common_meta_types::TxnRequest;
 use databend_common_meta_types::UpsertKV;
+use databend_common_meta_types::FEATURE_IDEMPOTENCY_TOKEN;
+use databend_common_meta_types::FEATURE_RESERVE_SEQ;
 use futures_util::TryStreamExt;
 use pretty_assertions::assert_eq;
 
 use crate::sm_v002::leveled_store::map_api::AsMap;
 use crate::sm_v002::leveled_store::map_api::MapApiRO;
+use crate::sm_v002::leveled_store::sys_data_api::SysDataApiRO;
 use crate::sm_v002::marked::Marked;
 use crate::sm_v002::SMV002;
 use crate::state_machine::ExpireKey;
@@ -132,6 +142,19 @@ async fn test_two_level_upsert_get_range() -> anyhow::Result<()> {
 
     let got = sm.list_kv("a").await?.try_collect::<Vec<_>>().await?;
     assert_eq!(got, vec![(s("a"), SeqV::new(1, b("a0"))),]);
+
+    // range_kv() and prefix_list_kv_stream()
+
+    let got = sm.range_kv("").await?.try_collect::<Vec<_>>().await?;
+    assert_eq!(got, sm.list_kv("").await?.try_collect::<Vec<_>>().await?);
+
+    let got = sm
+        .kv_api()
+        .prefix_list_kv_stream("a")
+        .await?
+        .try_collect::<Vec<_>>()
+        .await?;
+    assert_eq!(got, vec![(s("a"), SeqV::new(1, b("a0"))),]);
     Ok(())
 }
 
@@ -295,6 +318,123 @@ async fn test_inserting_expired_becomes_deleting() -> anyhow::Result<()> {
     Ok(())
 }
 
+#[tokio::test]
+async fn test_upsert_with_idempotency_token() -> anyhow::Result<()> {
+    let mut sm = SMV002::default();
+
+    let enable = Cmd::SetFeature {
+        feature: s(FEATURE_IDEMPOTENCY_TOKEN),
+        enable: true,
+    };
+    sm.new_applier().apply_cmd(&enable).await?;
+
+    let cmd = Cmd::UpsertKV(UpsertKV::update("a", b"a0").with_idempotency_token("t1"));
+    let want: AppliedState = Change::new(None, Some(SeqV::new(1, b("a0")))).into();
+
+    let mut a = sm.new_applier();
+    let got = a.apply_cmd(&cmd).await?;
+    assert_eq!(want, got);
+
+    // A duplicate submission is not applied and returns the original reply.
+    let mut a = sm.new_applier();
+    let got = a.apply_cmd(&cmd).await?;
+    assert_eq!(want, got);
+    assert_eq!(
+        sm.get_maybe_expired_kv("a").await?,
+        Some(SeqV::new(1, b("a0")))
+    );
+
+    // The token is recorded out of the user kv, without consuming a seq.
+    assert_eq!(1, sm.sys_data_ref().curr_seq());
+    let got = sm.list_kv("").await?.try_collect::<Vec<_>>().await?;
+    assert_eq!(got, vec![(s("a"), SeqV::new(1, b("a0")))]);
+
+    // Another token is applied.
+    let mut a = sm.new_applier();
+    let cmd = Cmd::UpsertKV(UpsertKV::update("a", b"a1").with_idempotency_token("t2"));
+    a.apply_cmd(&cmd).await?;
+    assert_eq!(sm.get_maybe_expired_kv("a").await?.value(), Some(&b("a1")));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_upsert_with_idempotency_token_feature_disabled() -> anyhow::Result<()> {
+    // Without the feature, a token is ignored, as an older node does.
+
+    let mut sm = SMV002::default();
+
+    let cmd = Cmd::UpsertKV(UpsertKV::update("a", b"a0").with_idempotency_token("t1"));
+
+    sm.new_applier().apply_cmd(&cmd).await?;
+    sm.new_applier().apply_cmd(&cmd).await?;
+
+    assert_eq!(
+        sm.get_maybe_expired_kv("a").await?,
+        Some(SeqV::new(2, b("a0")))
+    );
+    assert!(sm.sys_data_ref().idempotency_tokens_ref().is_empty());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_txn_reserve_seq() -> anyhow::Result<()> {
+    let mut sm = SMV002::default();
+
+    let enable = Cmd::SetFeature {
+        feature: s(FEATURE_RESERVE_SEQ),
+        enable: true,
+    };
+    sm.new_applier().apply_cmd(&enable).await?;
+
+    sm.new_applier()
+        .upsert_kv(&UpsertKV::update("a", b"a0"))
+        .await?;
+
+    let txn = Cmd::Transaction(TxnRequest::unconditional(vec![TxnOp::reserve_seq(
+        "gen", 10,
+    )]));
+    let got = sm.new_applier().apply_cmd(&txn).await?;
+    let reply: TxnReply = got.try_into().unwrap();
+
+    // Seqs 2..12 are reserved, the record takes the last one.
+    let reserved = reply.reserve_seq_response("gen").unwrap();
+    assert_eq!((2, 12), (reserved.start, reserved.end));
+    assert_eq!(
+        sm.get_maybe_expired_kv("gen").await?,
+        Some(SeqV::new(11, b("")))
+    );
+    assert_eq!(11, sm.sys_data_ref().curr_seq());
+
+    let (_prev, result) = sm
+        .new_applier()
+        .upsert_kv(&UpsertKV::update("a", b"a1"))
+        .await?;
+    assert_eq!(result, Some(SeqV::new(12, b("a1"))));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_txn_reserve_seq_feature_disabled() -> anyhow::Result<()> {
+    // Without the feature, the operation is not applied and has no response.
+
+    let mut sm = SMV002::default();
+
+    let txn = Cmd::Transaction(TxnRequest::unconditional(vec![TxnOp::reserve_seq(
+        "gen", 10,
+    )]));
+    let got = sm.new_applier().apply_cmd(&txn).await?;
+    let reply: TxnReply = got.try_into().unwrap();
+
+    assert!(reply.responses.is_empty());
+    assert_eq!(sm.get_maybe_expired_kv("gen").await?, None);
+    assert_eq!(0, sm.sys_data_ref().curr_seq());
+
+    Ok(())
+}
+
 fn s(x: impl ToString) -> String {
     x.to_string()
 }
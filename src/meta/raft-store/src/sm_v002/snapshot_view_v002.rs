@@ -165,6 +165,24 @@ impl SnapshotViewV002 {
             })
         }
 
+        // Features
+
+        if !d.features_ref().is_empty() {
+            sm_meta.push(RaftStoreEntry::StateMachineMeta {
+                key: StateMachineMetaKey::Features,
+                value: StateMachineMetaValue::Features(d.features_ref().clone()),
+            })
+        }
+
+        // Idempotency tokens
+
+        for (token, value) in d.idempotency_tokens_ref().iter() {
+            sm_meta.push(RaftStoreEntry::IdempotencyTokens {
+                key: token.clone(),
+                value: value.clone(),
+            })
+        }
+
         // kv
 
         let strm = self.compacted.str_map().range(..).await?;
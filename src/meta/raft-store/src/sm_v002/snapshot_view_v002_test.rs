@@ -228,6 +228,49 @@ async fn test_import() -> anyhow::Result<()> {
     Ok(())
 }
 
+#[tokio::test]
+async fn test_import_features_and_idempotency_tokens() -> anyhow::Result<()> {
+    let exported = vec![
+        r#"{"DataHeader":{"key":"header","value":{"version":"V002","upgrading":null}}}"#,
+        r#"{"StateMachineMeta":{"key":"LastMembership","value":{"Membership":{"log_id":null,"membership":{"configs":[],"nodes":{}}}}}}"#,
+        r#"{"Sequences":{"key":"generic-kv","value":1}}"#,
+        r#"{"StateMachineMeta":{"key":"Features","value":{"Features":["idempotency_token"]}}}"#,
+        r#"{"IdempotencyTokens":{"key":"t1","value":{"expire_at_ms":600000,"change":{"ident":null,"prev":null,"result":{"seq":1,"meta":null,"data":[97]}}}}}"#,
+        r#"{"GenericKV":{"key":"a","value":{"seq":1,"meta":null,"data":[97]}}}"#,
+    ];
+    let data = exported
+        .iter()
+        .map(|x| serde_json::from_str::<RaftStoreEntry>(x).unwrap());
+
+    let d = SMV002::import(data)?;
+
+    assert!(
+        d.sys_data_ref()
+            .features_ref()
+            .contains("idempotency_token")
+    );
+    assert_eq!(
+        Some(600_000),
+        d.sys_data_ref()
+            .idempotency_tokens_ref()
+            .get("t1")
+            .map(|x| x.expire_at_ms)
+    );
+
+    let snapshot = SnapshotViewV002::new(StaticLevels::new([Arc::new(d)]));
+
+    let got = snapshot
+        .export()
+        .await?
+        .map_ok(|x| serde_json::to_string(&x).unwrap())
+        .try_collect::<Vec<_>>()
+        .await?;
+
+    assert_eq!(got, exported);
+
+    Ok(())
+}
+
 /// Create multi levels store:
 ///
 /// l2 |         c(D) d
@@ -0,0 +1,41 @@
+// Copyright 2021 Datafuse Labs
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use databend_common_meta_sled_store::SledBytesError;
+use databend_common_meta_sled_store::SledSerde;
+use databend_common_meta_types::Change;
+use serde::Deserialize;
+use serde::Serialize;
+
+/// The recorded reply of an upsert with an idempotency token,
+/// for replying to a retry of it without applying it again.
+///
+/// It is system data of the state machine: recording it does not consume a seq,
+/// and it is not visible to a kv reader or watcher.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct IdempotencyTokenValue {
+    /// The time in milli seconds since 1970-01-01 after which the record is removed.
+    pub expire_at_ms: u64,
+
+    /// The reply of the upsert.
+    pub change: Change<Vec<u8>>,
+}
+
+impl SledSerde for IdempotencyTokenValue {
+    fn de<T: AsRef<[u8]>>(v: T) -> Result<Self, SledBytesError>
+    where Self: Sized {
+        let s = serde_json::from_slice(v.as_ref())?;
+        Ok(s)
+    }
+}
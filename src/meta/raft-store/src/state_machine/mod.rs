@@ -15,6 +15,7 @@
 pub use client_last_resp::ClientLastRespValue;
 pub use expire::ExpireKey;
 pub use expire::ExpireValue;
+pub use idempotency_token::IdempotencyTokenValue;
 pub use log_meta::LogMetaKey;
 pub use log_meta::LogMetaValue;
 pub use sm::SerializableSnapshot;
@@ -28,6 +29,7 @@ pub use state_machine_meta::StateMachineMetaValue;
 
 pub mod client_last_resp;
 mod expire;
+pub mod idempotency_token;
 pub mod log_meta;
 pub mod sm;
 mod sm_kv_api_impl;
@@ -62,6 +62,7 @@ use databend_common_meta_types::TxnPutRequest;
 use databend_common_meta_types::TxnPutResponse;
 use databend_common_meta_types::TxnReply;
 use databend_common_meta_types::TxnRequest;
+use databend_common_meta_types::TxnReserveSeqRequest;
 use databend_common_meta_types::UpsertKV;
 use databend_common_meta_types::With;
 use log::as_debug;
@@ -563,6 +564,46 @@ impl StateMachine {
         Ok(())
     }
 
+    /// Reserve `count` seqs by advancing the seq and updating the record of the key with the last one.
+    ///
+    /// It is always applied: this state machine is only used by a single process, there is no older node.
+    fn txn_execute_reserve_seq_operation(
+        &self,
+        txn_tree: &mut TransactionSledTree,
+        reserve_seq: &TxnReserveSeqRequest,
+        resp: &mut TxnReply,
+        log_time_ms: u64,
+    ) -> Result<(), MetaStorageError> {
+        let count = reserve_seq.count.max(1);
+
+        // The upsert takes the next seq: the last of the reserved ones.
+        let seqs = txn_tree.key_space::<Sequences>();
+        let seq_key = GenericKV::NAME.to_string();
+        let curr = seqs.get(&seq_key)?.unwrap_or_default();
+        seqs.insert(&seq_key, &(curr + (count - 1)))?;
+
+        let (expired, prev, result) = Self::txn_upsert_kv(
+            txn_tree,
+            &UpsertKV::update(&reserve_seq.key, b""),
+            log_time_ms,
+        )?;
+
+        if expired.is_some() {
+            txn_tree.push_change(&reserve_seq.key, expired, None);
+        }
+
+        let end = result.seq() + 1;
+        txn_tree.push_change(&reserve_seq.key, prev, result);
+
+        resp.responses.push(TxnOpResponse::reserve_seq(
+            &reserve_seq.key,
+            end - count,
+            end,
+        ));
+
+        Ok(())
+    }
+
     fn txn_execute_delete_operation(
         &self,
         txn_tree: &mut TransactionSledTree,
@@ -670,6 +711,9 @@ impl StateMachine {
                     log_time_ms,
                 )?;
             }
+            Some(txn_op::Request::ReserveSeq(reserve_seq)) => {
+                self.txn_execute_reserve_seq_operation(txn_tree, reserve_seq, resp, log_time_ms)?;
+            }
             None => {}
         }
 
@@ -752,6 +796,9 @@ impl StateMachine {
             }
 
             Cmd::Transaction(txn) => self.apply_txn_cmd(txn, txn_tree, kv_pairs, log_time_ms),
+
+            // No state machine feature is supported by this state machine.
+            Cmd::SetFeature { .. } => Ok(AppliedState::None),
         };
 
         let elapsed = now.elapsed().as_micros();
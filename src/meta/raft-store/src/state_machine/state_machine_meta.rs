@@ -12,6 +12,7 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::collections::BTreeSet;
 use std::fmt;
 
 use databend_common_meta_sled_store::sled;
@@ -36,12 +37,16 @@ pub enum StateMachineMetaKey {
 
     /// The last membership config
     LastMembership,
+
+    /// The enabled state machine features, see `Cmd::SetFeature`.
+    Features,
 }
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, derive_more::TryInto)]
 pub enum StateMachineMetaValue {
     LogId(LogId),
     Bool(bool),
     Membership(StoredMembership),
+    Features(BTreeSet<String>),
 }
 
 impl fmt::Display for StateMachineMetaKey {
@@ -56,6 +61,9 @@ impl fmt::Display for StateMachineMetaKey {
             StateMachineMetaKey::LastMembership => {
                 write!(f, "last-membership")
             }
+            StateMachineMetaKey::Features => {
+                write!(f, "features")
+            }
         }
     }
 }
@@ -66,6 +74,7 @@ impl SledOrderedSerde for StateMachineMetaKey {
             StateMachineMetaKey::LastApplied => 1,
             StateMachineMetaKey::Initialized => 2,
             StateMachineMetaKey::LastMembership => 3,
+            StateMachineMetaKey::Features => 4,
         };
 
         Ok(IVec::from(&[i]))
@@ -80,6 +89,8 @@ impl SledOrderedSerde for StateMachineMetaKey {
             return Ok(StateMachineMetaKey::Initialized);
         } else if slice[0] == 3 {
             return Ok(StateMachineMetaKey::LastMembership);
+        } else if slice[0] == 4 {
+            return Ok(StateMachineMetaKey::Features);
         }
 
         Err(SledBytesError::new(&AnyError::error("invalid key IVec")))
@@ -87,6 +98,8 @@ impl SledOrderedSerde for StateMachineMetaKey {
 }
 
 pub(crate) mod compat_with_07 {
+    use std::collections::BTreeSet;
+
     use databend_common_meta_sled_store::SledBytesError;
     use databend_common_meta_sled_store::SledSerde;
     use databend_common_meta_types::compat07;
@@ -99,6 +112,7 @@ pub(crate) mod compat_with_07 {
         LogId(compat07::LogId),
         Bool(bool),
         Membership(compat07::StoredMembership),
+        Features(BTreeSet<String>),
     }
 
     impl Upgrade<StateMachineMetaValue> for StateMachineMetaValueCompat {
@@ -108,6 +122,7 @@ pub(crate) mod compat_with_07 {
                 Self::LogId(lid)    => StateMachineMetaValue::LogId(lid.upgrade()),
                 Self::Bool(b)       => StateMachineMetaValue::Bool(b),
                 Self::Membership(m) => StateMachineMetaValue::Membership(m.upgrade()),
+                Self::Features(f)   => StateMachineMetaValue::Features(f),
             }
         }
     }
@@ -225,6 +225,7 @@ async fn test_state_machine_apply_non_dup_generic_kv_upsert_get() -> anyhow::Res
                         seq: c.seq,
                         value: Operation::Update(c.value.clone()),
                         value_meta: c.value_meta.clone(),
+                        idempotency_token: None,
                     }),
                     &mut t,
                     None,
@@ -291,6 +292,7 @@ async fn test_state_machine_apply_non_dup_generic_kv_value_meta() -> anyhow::Res
                     seq: MatchSeq::GE(0),
                     value: Operation::AsIs,
                     value_meta: Some(MetaSpec::new_expire(now + 10)),
+                    idempotency_token: None,
                 }),
                 &mut t,
                 None,
@@ -316,6 +318,7 @@ async fn test_state_machine_apply_non_dup_generic_kv_value_meta() -> anyhow::Res
                     seq: MatchSeq::GE(0),
                     value: Operation::Update(b"value_meta_bar".to_vec()),
                     value_meta: Some(MetaSpec::new_expire(now + 10)),
+                    idempotency_token: None,
                 }),
                 &mut t,
                 None,
@@ -333,6 +336,7 @@ async fn test_state_machine_apply_non_dup_generic_kv_value_meta() -> anyhow::Res
                     seq: MatchSeq::GE(0),
                     value: Operation::AsIs,
                     value_meta: Some(MetaSpec::new_expire(now + 20)),
+                    idempotency_token: None,
                 }),
                 &mut t,
                 None,
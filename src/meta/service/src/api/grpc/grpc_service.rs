@@ -15,6 +15,7 @@
 use std::io;
 use std::pin::Pin;
 use std::sync::Arc;
+use std::time::Duration;
 
 use databend_common_arrow::arrow_format::flight::data::BasicAuth;
 use databend_common_base::base::tokio::sync::mpsc;
@@ -65,6 +66,7 @@ use tonic::Response;
 use tonic::Status;
 use tonic::Streaming;
 
+use crate::configs::Config;
 use crate::message::ForwardRequest;
 use crate::message::ForwardRequestBody;
 use crate::meta_service::MetaNode;
@@ -79,13 +81,21 @@ use crate::watcher::WatchStream;
 pub struct MetaServiceImpl {
     token: GrpcToken,
     pub(crate) meta_node: Arc<MetaNode>,
+
+    /// The number of events buffered for a watch stream.
+    watch_buffer_size: usize,
+
+    /// How long to wait for a watcher with a full buffer before reporting it as lagging.
+    watch_send_timeout: Duration,
 }
 
 impl MetaServiceImpl {
-    pub fn create(meta_node: Arc<MetaNode>) -> Self {
+    pub fn create(meta_node: Arc<MetaNode>, conf: &Config) -> Self {
         Self {
             token: GrpcToken::create(),
             meta_node,
+            watch_buffer_size: conf.grpc_watch_buffer_size,
+            watch_send_timeout: Duration::from_millis(conf.grpc_watch_send_timeout),
         }
     }
 
@@ -363,11 +373,13 @@ impl MetaService for MetaServiceImpl {
         &self,
         request: Request<WatchRequest>,
     ) -> Result<Response<Self::WatchStream>, Status> {
-        let (tx, rx) = mpsc::channel(4);
+        let (tx, rx) = mpsc::channel(self.watch_buffer_size);
 
         let mn = &self.meta_node;
 
-        let add_res = mn.add_watcher(request.into_inner(), tx).await;
+        let add_res = mn
+            .add_watcher(request.into_inner(), tx, self.watch_send_timeout)
+            .await;
 
         match add_res {
             Ok(watcher) => {
@@ -93,7 +93,7 @@ impl GrpcServer {
 
         info!("gRPC addr: {}", addr);
 
-        let grpc_impl = MetaServiceImpl::create(meta_node.clone());
+        let grpc_impl = MetaServiceImpl::create(meta_node.clone(), &conf);
         let grpc_srv = MetaServiceServer::new(grpc_impl)
             .max_decoding_message_size(GrpcConfig::MAX_DECODING_SIZE)
             .max_encoding_message_size(GrpcConfig::MAX_ENCODING_SIZE);
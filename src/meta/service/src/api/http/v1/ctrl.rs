@@ -15,10 +15,16 @@
 use std::sync::Arc;
 use std::time::Duration;
 
+use databend_common_meta_types::Cmd;
+use databend_common_meta_types::LogEntry;
+use databend_common_meta_types::FEATURE_IDEMPOTENCY_TOKEN;
+use databend_common_meta_types::FEATURE_RESERVE_SEQ;
 use poem::http::StatusCode;
 use poem::web::Data;
 use poem::web::IntoResponse;
 use poem::web::Json;
+use poem::web::Query;
+use serde::Deserialize;
 
 use crate::meta_service::MetaNode;
 
@@ -53,3 +59,35 @@ pub async fn block_compact_snapshot(
     sm.blocking_config_mut().compact_snapshot = Duration::from_millis(1_000_000);
     Ok(Json(()))
 }
+
+#[derive(Debug, Deserialize)]
+pub struct SetFeatureQuery {
+    pub(crate) feature: String,
+    pub(crate) enable: bool,
+}
+
+/// Enable or disable a state machine feature on every node of the cluster.
+///
+/// A feature must be enabled only after every node is upgraded to a version that supports it.
+#[poem::handler]
+pub async fn set_feature(
+    meta_node: Data<&Arc<MetaNode>>,
+    query: Query<SetFeatureQuery>,
+) -> poem::Result<impl IntoResponse> {
+    if ![FEATURE_IDEMPOTENCY_TOKEN, FEATURE_RESERVE_SEQ].contains(&query.feature.as_str()) {
+        return Err(poem::Error::from_string(
+            format!("unknown feature: {}", query.feature),
+            StatusCode::BAD_REQUEST,
+        ));
+    }
+
+    let cmd = Cmd::SetFeature {
+        feature: query.feature.clone(),
+        enable: query.enable,
+    };
+    meta_node
+        .write(LogEntry::new(cmd))
+        .await
+        .map_err(|e| poem::Error::from_string(e.to_string(), StatusCode::INTERNAL_SERVER_ERROR))?;
+    Ok(Json(()))
+}
@@ -70,6 +70,10 @@ impl HttpService {
                 "/v1/ctrl/block_compact_snapshot",
                 get(super::http::v1::ctrl::block_compact_snapshot),
             )
+            .at(
+                "/v1/ctrl/set_feature",
+                get(super::http::v1::ctrl::set_feature),
+            )
             .at(
                 "/v1/cluster/nodes",
                 get(super::http::v1::cluster_state::nodes_handler),
@@ -40,6 +40,10 @@ pub struct Config {
     /// Certificate for server to identify itself
     pub grpc_tls_server_cert: String,
     pub grpc_tls_server_key: String,
+    /// The number of events buffered for a watch stream.
+    pub grpc_watch_buffer_size: usize,
+    /// The time in milli seconds to wait for a watcher with a full buffer before reporting it as lagging.
+    pub grpc_watch_send_timeout: u64,
     pub raft_config: RaftConfig,
 }
 
@@ -62,6 +66,8 @@ impl Default for Config {
             grpc_api_advertise_host: None,
             grpc_tls_server_cert: "".to_string(),
             grpc_tls_server_key: "".to_string(),
+            grpc_watch_buffer_size: 1024,
+            grpc_watch_send_timeout: 1000,
             raft_config: Default::default(),
         }
     }
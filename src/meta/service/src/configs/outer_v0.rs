@@ -127,6 +127,15 @@ pub struct Config {
     #[clap(long, default_value = "")]
     pub grpc_tls_server_key: String,
 
+    /// The number of events buffered for a watch stream
+    #[clap(long, default_value = "1024")]
+    pub grpc_watch_buffer_size: usize,
+
+    /// The time in milli seconds to wait for a watcher with a full buffer,
+    /// before dropping its events and reporting it as lagging
+    #[clap(long, default_value = "1000")]
+    pub grpc_watch_send_timeout: u64,
+
     #[clap(flatten)]
     pub raft_config: RaftConfig,
 }
@@ -164,6 +173,8 @@ impl From<Config> for InnerConfig {
             grpc_api_advertise_host: outer.grpc_api_advertise_host,
             grpc_tls_server_cert: outer.grpc_tls_server_cert,
             grpc_tls_server_key: outer.grpc_tls_server_key,
+            grpc_watch_buffer_size: outer.grpc_watch_buffer_size,
+            grpc_watch_send_timeout: outer.grpc_watch_send_timeout,
             raft_config: outer.raft_config.into(),
         }
     }
@@ -190,6 +201,8 @@ impl From<InnerConfig> for Config {
             grpc_api_advertise_host: inner.grpc_api_advertise_host,
             grpc_tls_server_cert: inner.grpc_tls_server_cert,
             grpc_tls_server_key: inner.grpc_tls_server_key,
+            grpc_watch_buffer_size: inner.grpc_watch_buffer_size,
+            grpc_watch_send_timeout: inner.grpc_watch_send_timeout,
             raft_config: inner.raft_config.into(),
         }
     }
@@ -277,6 +290,8 @@ pub struct ConfigViaEnv {
     pub metasrv_grpc_api_advertise_host: Option<String>,
     pub grpc_tls_server_cert: String,
     pub grpc_tls_server_key: String,
+    pub metasrv_grpc_watch_buffer_size: usize,
+    pub metasrv_grpc_watch_send_timeout: u64,
 
     pub config_id: String,
     pub kvsrv_listen_host: String,
@@ -324,6 +339,8 @@ impl From<Config> for ConfigViaEnv {
             metasrv_grpc_api_advertise_host: cfg.grpc_api_advertise_host,
             grpc_tls_server_cert: cfg.grpc_tls_server_cert,
             grpc_tls_server_key: cfg.grpc_tls_server_key,
+            metasrv_grpc_watch_buffer_size: cfg.grpc_watch_buffer_size,
+            metasrv_grpc_watch_send_timeout: cfg.grpc_watch_send_timeout,
             config_id: cfg.raft_config.config_id,
             kvsrv_listen_host: cfg.raft_config.raft_listen_host,
             kvsrv_advertise_host: cfg.raft_config.raft_advertise_host,
@@ -408,6 +425,8 @@ impl Into<Config> for ConfigViaEnv {
             grpc_api_advertise_host: self.metasrv_grpc_api_advertise_host,
             grpc_tls_server_cert: self.grpc_tls_server_cert,
             grpc_tls_server_key: self.grpc_tls_server_key,
+            grpc_watch_buffer_size: self.metasrv_grpc_watch_buffer_size,
+            grpc_watch_send_timeout: self.metasrv_grpc_watch_send_timeout,
             raft_config,
         }
     }
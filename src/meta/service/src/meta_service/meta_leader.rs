@@ -34,6 +34,7 @@ use databend_common_meta_types::Node;
 use databend_common_meta_types::NodeId;
 use databend_common_meta_types::RaftError;
 use databend_common_meta_types::SeqV;
+use databend_common_meta_types::FEATURE_RESERVE_SEQ;
 use databend_common_metrics::count::Count;
 use futures::StreamExt;
 use log::as_debug;
@@ -85,7 +86,8 @@ impl<'a> Handler<ForwardRequestBody> for MetaLeader<'a> {
                 Ok(ForwardResponse::Leave(()))
             }
             ForwardRequestBody::Write(entry) => {
-                let res = self.write(entry.clone()).await?;
+                let entry = self.remove_unsupported_ops(entry).await;
+                let res = self.write(entry).await?;
                 Ok(ForwardResponse::AppliedState(res))
             }
 
@@ -157,6 +159,33 @@ impl<'a> Handler<MetaGrpcReadReq> for MetaLeader<'a> {
 
                 Ok(strm.boxed())
             }
+
+            MetaGrpcReadReq::HasKV(req) => {
+                // safe unwrap(): Infallible
+                let got = kv_api.get_kv(&req.key).await.unwrap();
+
+                // Do not send back the value, only the seq and meta.
+                let got = got.map(|seqv| SeqV {
+                    data: vec![],
+                    ..seqv
+                });
+
+                let items = got.map(|seqv| Ok(StreamItem::from((req.key.clone(), seqv))));
+                let strm = futures::stream::iter(items);
+
+                Ok(strm.boxed())
+            }
+
+            MetaGrpcReadReq::ListKeys(req) => {
+                // safe unwrap(): Infallible
+                let keys = kv_api.prefix_list_keys(&req.prefix).await.unwrap();
+
+                let key_iter = keys.into_iter().map(|k| Ok(StreamItem::new(k, None)));
+
+                let strm = futures::stream::iter(key_iter);
+
+                Ok(strm.boxed())
+            }
         }
     }
 }
@@ -301,6 +330,28 @@ impl<'a> MetaLeader<'a> {
         Ok(Ok(()))
     }
 
+    /// Remove the operations an older node fails to decode, unless the state machine feature is enabled.
+    ///
+    /// A removed operation is not applied and has no response in the reply.
+    async fn remove_unsupported_ops(&self, mut entry: LogEntry) -> LogEntry {
+        if let Cmd::Transaction(txn) = &mut entry.cmd {
+            let enabled = {
+                let sm = self.get_state_machine().await;
+                sm.sys_data_ref()
+                    .features_ref()
+                    .contains(FEATURE_RESERVE_SEQ)
+            };
+
+            if !enabled && txn.remove_reserve_seq() {
+                info!(
+                    "feature {} is disabled, ReserveSeq is removed from txn: {}",
+                    FEATURE_RESERVE_SEQ, txn
+                );
+            }
+        }
+        entry
+    }
+
     async fn get_state_machine(&self) -> RwLockReadGuard<'_, SMV002> {
         self.sto.state_machine.read().await
     }
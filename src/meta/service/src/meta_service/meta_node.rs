@@ -1176,13 +1176,15 @@ impl MetaNode {
         &self,
         request: WatchRequest,
         tx: WatcherSender,
+        send_timeout: Duration,
     ) -> Result<Watcher, &'static str> {
         let (resp_tx, resp_rx) = oneshot::channel();
 
-        self.dispatcher_handle.request(|d: &mut EventDispatcher| {
-            let add_res = d.add_watcher(request, tx);
-            let _ = resp_tx.send(add_res);
-        });
+        self.dispatcher_handle
+            .request(move |d: &mut EventDispatcher| {
+                let add_res = d.add_watcher(request, tx, send_timeout);
+                let _ = resp_tx.send(add_res);
+            });
 
         let recv_res = resp_rx.await;
         match recv_res {
@@ -20,6 +20,7 @@ pub(crate) use watcher_manager::EventDispatcher;
 pub use watcher_manager::EventDispatcherHandle;
 pub use watcher_manager::WatcherId;
 pub use watcher_manager::WatcherSender;
+pub use watcher_stream::KeyBound;
 pub use watcher_stream::WatchStream;
 pub use watcher_stream::WatchStreamHandle;
 pub use watcher_stream::Watcher;
@@ -13,6 +13,7 @@
 // limitations under the License.
 
 use core::ops::Range;
+use std::time::Duration;
 
 use databend_common_base::base::tokio;
 use databend_common_base::base::tokio::sync::mpsc;
@@ -31,6 +32,7 @@ use log::warn;
 use prost::Message;
 use tonic::Status;
 
+use super::KeyBound;
 use super::WatchStreamHandle;
 use crate::metrics::network_metrics;
 use crate::metrics::server_metrics;
@@ -94,7 +96,7 @@ pub struct EventDispatcher {
     event_rx: mpsc::UnboundedReceiver<WatchEvent>,
 
     /// map range to WatcherId
-    watcher_range_map: RangeMap<String, WatcherId, WatchStreamHandle>,
+    watcher_range_map: RangeMap<KeyBound, WatcherId, WatchStreamHandle>,
 
     current_watcher_id: WatcherId,
 }
@@ -133,9 +135,14 @@ impl EventDispatcher {
     }
 
     /// Dispatch a kv change event to interested watchers.
+    ///
+    /// A watcher with a full buffer blocks the dispatching for at most its send timeout,
+    /// after which it is either reported as lagging or closed, see [`WatchStreamHandle::send`].
     async fn dispatch_event(&mut self, change: Change<Vec<u8>, String>) {
         let k = change.ident.as_ref().unwrap();
-        let set = self.watcher_range_map.get_by_point(k);
+        let set = self
+            .watcher_range_map
+            .get_by_point(&KeyBound::Key(k.to_string()));
         if set.is_empty() {
             return;
         }
@@ -144,7 +151,7 @@ impl EventDispatcher {
         let prev = change.prev;
 
         let is_delete_event = current.is_none();
-        let mut remove_range_keys: Vec<RangeMapKey<String, WatcherId>> = vec![];
+        let mut remove_range_keys: Vec<RangeMapKey<KeyBound, WatcherId>> = vec![];
 
         for range_key_stream in set.iter() {
             let filter = range_key_stream.1.watcher.filter_type;
@@ -165,6 +172,7 @@ impl EventDispatcher {
                     current: current.clone().map(pb::SeqV::from),
                     prev: prev.clone().map(pb::SeqV::from),
                 }),
+                lagged: 0,
             };
 
             network_metrics::incr_sent_bytes(resp.encoded_len() as u64);
@@ -192,6 +200,7 @@ impl EventDispatcher {
         &mut self,
         create: WatchRequest,
         tx: WatcherSender,
+        send_timeout: Duration,
     ) -> Result<Watcher, &'static str> {
         info!("add_watcher: {:?}", create);
 
@@ -205,7 +214,8 @@ impl EventDispatcher {
         let filter: FilterType = create.filter_type();
 
         let watcher = Watcher::new(watcher_id, filter, range.clone());
-        let stream_handle = WatchStreamHandle::new(watcher.clone(), tx);
+        let stream_handle =
+            WatchStreamHandle::new(watcher.clone(), tx, send_timeout, create.report_lag);
 
         self.watcher_range_map
             .insert(range, watcher_id, stream_handle);
@@ -216,7 +226,7 @@ impl EventDispatcher {
     }
 
     #[minitrace::trace]
-    pub fn remove_watcher(&mut self, key: &RangeMapKey<String, WatcherId>) {
+    pub fn remove_watcher(&mut self, key: &RangeMapKey<KeyBound, WatcherId>) {
         info!("remove_watcher: {:?}", key);
 
         self.watcher_range_map.remove_by_key(key);
@@ -225,22 +235,26 @@ impl EventDispatcher {
         server_metrics::incr_watchers(-1);
     }
 
+    /// Build the key range to watch: `[key, key_end)`, or only `key` if `key_end` is `None`.
+    ///
+    /// An empty `key_end` means the range is unbounded on the right.
     fn build_key_range(
         key: String,
         key_end: &Option<String>,
-    ) -> Result<Range<String>, &'static str> {
+    ) -> Result<Range<KeyBound>, &'static str> {
         match key_end {
+            Some(key_end) if key_end.is_empty() => Ok(KeyBound::Key(key)..KeyBound::Unbounded),
             Some(key_end) => {
                 if &key > key_end {
                     return Err("empty range");
                 }
-                Ok(key..key_end.to_string())
+                Ok(KeyBound::Key(key)..KeyBound::Key(key_end.to_string()))
             }
-            None => Ok(key.clone()..key),
+            None => Ok(KeyBound::Key(key.clone())..KeyBound::Key(key)),
         }
     }
 
-    pub fn watchers(&self) -> impl Iterator<Item = &RangeMapKey<String, WatcherId>> {
+    pub fn watchers(&self) -> impl Iterator<Item = &RangeMapKey<KeyBound, WatcherId>> {
         self.watcher_range_map.keys()
     }
 }
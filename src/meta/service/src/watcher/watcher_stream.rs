@@ -14,19 +14,27 @@
 
 use std::ops::Range;
 use std::pin::Pin;
+use std::sync::Arc;
+use std::sync::Mutex;
 use std::task::Context;
 use std::task::Poll;
+use std::time::Duration;
 
+use databend_common_base::base::tokio;
 use databend_common_base::base::tokio::sync::mpsc::error::SendError;
+use databend_common_base::base::tokio::sync::mpsc::error::SendTimeoutError;
+use databend_common_base::base::tokio::sync::mpsc::error::TrySendError;
 use databend_common_base::base::tokio::sync::mpsc::Receiver;
 use databend_common_base::rangemap::RangeMapKey;
 use databend_common_meta_types::protobuf::watch_request::FilterType;
 use databend_common_meta_types::protobuf::WatchResponse;
 use futures::Stream;
-use tonic::Status;
+use log::warn;
+use prost::Message;
 
 use super::WatcherId;
 use super::WatcherSender;
+use crate::metrics::network_metrics;
 use crate::watcher::EventDispatcherHandle;
 
 /// Attributes of a watcher that is interested in kv change events.
@@ -38,11 +46,20 @@ pub struct Watcher {
     pub filter_type: FilterType,
 
     /// The range of key this watcher is interested in.
-    pub key_range: Range<String>,
+    pub key_range: Range<KeyBound>,
+}
+
+/// A bound of the key range of a watcher.
+///
+/// `Unbounded` is bigger than every key: it is the end of a range that is unbounded on the right.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum KeyBound {
+    Key(String),
+    Unbounded,
 }
 
 impl Watcher {
-    pub fn new(id: WatcherId, filter_type: FilterType, key_range: Range<String>) -> Self {
+    pub fn new(id: WatcherId, filter_type: FilterType, key_range: Range<KeyBound>) -> Self {
         Self {
             id,
             filter_type,
@@ -52,21 +69,110 @@ impl Watcher {
 }
 
 /// A handle of a watching stream, for feeding messages to the stream.
+///
+/// When the stream buffer is full, sending waits for the receiver for at most `send_timeout`,
+/// so that a slow watcher does not block the dispatcher for ever.
+/// Then, if the watcher sets `report_lag`, the events are dropped and counted,
+/// until a response with `lagged` set to the count is delivered, in place of the dropped events.
+/// Otherwise the watcher can not tell an event is lost, and the stream is closed.
 pub struct WatchStreamHandle {
     pub watcher: Watcher,
     tx: WatcherSender,
+
+    /// How long to wait for the receiver when the stream buffer is full.
+    send_timeout: Duration,
+
+    /// Whether the watcher understands `WatchResponse.lagged`.
+    report_lag: bool,
+
+    /// The number of events dropped since the stream buffer became full, 0 if it is not lagging.
+    lagged: Arc<Mutex<u64>>,
 }
 
 impl WatchStreamHandle {
-    pub fn new(watcher: Watcher, tx: WatcherSender) -> Self {
-        WatchStreamHandle { watcher, tx }
+    pub fn new(
+        watcher: Watcher,
+        tx: WatcherSender,
+        send_timeout: Duration,
+        report_lag: bool,
+    ) -> Self {
+        WatchStreamHandle {
+            watcher,
+            tx,
+            send_timeout,
+            report_lag,
+            lagged: Arc::new(Mutex::new(0)),
+        }
+    }
+
+    /// Send a response, or drop it if the stream is lagging.
+    ///
+    /// It returns an error if the stream is closed,
+    /// or if the watcher can not keep up and does not accept a lag report.
+    pub async fn send(&self, resp: WatchResponse) -> Result<(), SendError<()>> {
+        {
+            let mut lagged = self.lagged.lock().unwrap();
+
+            // Do not send an event before the lag is delivered.
+            if *lagged > 0 {
+                *lagged += 1;
+                return Ok(());
+            }
+        }
+
+        let resp = match self.tx.try_send(Ok(resp)) {
+            Ok(()) => return Ok(()),
+            Err(TrySendError::Full(resp)) => resp,
+            Err(TrySendError::Closed(_)) => return Err(SendError(())),
+        };
+
+        // A burst of events may just fill up the buffer; give the watcher a chance to catch up.
+        match self.tx.send_timeout(resp, self.send_timeout).await {
+            Ok(()) => Ok(()),
+            Err(SendTimeoutError::Closed(_)) => Err(SendError(())),
+            Err(SendTimeoutError::Timeout(_)) => {
+                if !self.report_lag {
+                    warn!(
+                        "watcher {} lags behind and does not accept a lag report, close it",
+                        self.watcher.id
+                    );
+                    return Err(SendError(()));
+                }
+
+                warn!(
+                    "watcher {} lags behind, drop events until it catches up",
+                    self.watcher.id
+                );
+                *self.lagged.lock().unwrap() = 1;
+                self.spawn_send_lagged();
+                Ok(())
+            }
+        }
     }
 
-    pub async fn send(
-        &self,
-        resp: WatchResponse,
-    ) -> Result<(), SendError<Result<WatchResponse, Status>>> {
-        self.tx.send(Ok(resp)).await
+    /// Spawn a task that delivers the lag when the stream buffer has room.
+    fn spawn_send_lagged(&self) {
+        let tx = self.tx.clone();
+        let lagged = self.lagged.clone();
+
+        let _h = tokio::spawn(async move {
+            let Ok(permit) = tx.reserve().await else {
+                // The stream is closed.
+                return;
+            };
+
+            // Events dropped until the lag is sent are all counted in it.
+            let mut lagged = lagged.lock().unwrap();
+
+            let resp = WatchResponse {
+                event: None,
+                lagged: *lagged,
+            };
+            network_metrics::incr_sent_bytes(resp.encoded_len() as u64);
+            permit.send(Ok(resp));
+
+            *lagged = 0;
+        });
     }
 }
 
@@ -48,6 +48,8 @@ admin_tls_server_key = "admin tls key"
 grpc_api_address = "127.0.0.1:10000"
 grpc_tls_server_cert = "grpc server cert"
 grpc_tls_server_key = "grpc server key"
+grpc_watch_buffer_size = 16
+grpc_watch_send_timeout = 200
 
 [raft_config]
 config_id = "raft config id"
@@ -78,6 +80,8 @@ cluster_name = "foo_cluster"
         assert_eq!(cfg.grpc_api_address, "127.0.0.1:10000");
         assert_eq!(cfg.grpc_tls_server_cert, "grpc server cert");
         assert_eq!(cfg.grpc_tls_server_key, "grpc server key");
+        assert_eq!(cfg.grpc_watch_buffer_size, 16);
+        assert_eq!(cfg.grpc_watch_send_timeout, 200);
         assert_eq!(cfg.raft_config.config_id, "raft config id");
         assert_eq!(cfg.raft_config.raft_listen_host, "127.0.0.1");
         assert_eq!(cfg.raft_config.raft_api_port, 11000);
@@ -25,6 +25,9 @@ use databend_common_meta_client::MetaGrpcClient;
 use databend_common_meta_kvapi::kvapi::KVApi;
 use databend_common_meta_kvapi::kvapi::UpsertKVReq;
 use databend_common_meta_types::MetaClientError;
+use databend_common_meta_types::TxnCondition;
+use databend_common_meta_types::TxnOp;
+use databend_common_meta_types::TxnRequest;
 use log::info;
 use test_harness::test;
 
@@ -144,6 +147,59 @@ async fn test_metasrv_one_client_leader_down() -> anyhow::Result<()> {
     Ok(())
 }
 
+/// Ensure a transaction fails over to the next endpoint, which is then remembered.
+///
+/// - Start a cluster of 3.
+/// - Shutdown node 1.
+/// - Create a client to node 1 and 2, node 1 is the first to try.
+/// - Test read-only and conditional transactions, expect them served by node 2.
+#[test(harness = meta_service_test_harness)]
+#[minitrace::trace]
+async fn test_metasrv_transaction_failover() -> anyhow::Result<()> {
+    info!("--- Start cluster 0,1,2");
+    let mut tcs = start_metasrv_cluster(&[0, 1, 2]).await?;
+
+    let addresses = tcs
+        .iter()
+        .map(|tc| tc.config.grpc_api_address.clone())
+        .collect::<Vec<_>>();
+
+    let a1 = || addresses[1].clone();
+    let a2 = || addresses[2].clone();
+
+    let mut stopped = tcs.remove(1);
+    { stopped }.grpc_srv.take().unwrap().stop(None).await?;
+
+    let client = make_client(vec![a1(), a2()])?;
+
+    info!("--- conditional write, served by the secondary");
+    {
+        let txn = TxnRequest {
+            condition: vec![TxnCondition::eq_seq("failover-k", 0)],
+            if_then: vec![TxnOp::put("failover-k", b("v"))],
+            else_then: vec![],
+        };
+        let reply = client.transaction(txn).await?;
+        assert!(reply.success);
+    }
+
+    info!("--- read-only transaction");
+    {
+        let txn = TxnRequest::unconditional(vec![TxnOp::get("failover-k")]);
+        let reply = client.transaction(txn).await?;
+        assert!(reply.success);
+        assert_eq!(1, reply.responses.len());
+    }
+
+    info!("--- the endpoint that works is remembered");
+    {
+        let established = client.make_established_client().await?;
+        assert_eq!(a2(), established.target_endpoint());
+    }
+
+    Ok(())
+}
+
 fn make_client(addresses: Vec<String>) -> Result<Arc<ClientHandle>, MetaClientError> {
     let client = MetaGrpcClient::try_create(
         addresses, // a1() will be shut down
@@ -0,0 +1,60 @@
+// Copyright 2021 Datafuse Labs
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::time::Duration;
+
+use databend_common_grpc::KeepAliveConf;
+use databend_common_grpc::RpcClientConf;
+use databend_common_meta_client::MetaGrpcClient;
+use databend_common_meta_kvapi::kvapi::KVApi;
+use databend_common_meta_kvapi::kvapi::UpsertKVReq;
+use log::info;
+use test_harness::test;
+
+use crate::testing::meta_service_test_harness;
+
+#[test(harness = meta_service_test_harness)]
+#[minitrace::trace]
+async fn test_keep_alive_idle_connection() -> anyhow::Result<()> {
+    // - Start a metasrv server.
+    // - Create a client that pings every second.
+    // - Stay idle for several keep-alive intervals, i.e., a load balancer would drop a connection without PING.
+    // - The connection is still usable.
+
+    let (_tc, addr) = crate::tests::start_metasrv().await?;
+
+    let conf = RpcClientConf {
+        endpoints: vec![addr],
+        username: "root".to_string(),
+        password: "xxx".to_string(),
+        timeout: Some(Duration::from_secs(10)),
+        keep_alive: Some(KeepAliveConf::new(
+            Duration::from_secs(1),
+            Duration::from_secs(1),
+        )),
+        ..Default::default()
+    };
+
+    let client = MetaGrpcClient::try_new(&conf)?;
+
+    client.upsert_kv(UpsertKVReq::update("foo", b"1")).await?;
+
+    info!("--- idle for 3 keep-alive intervals");
+    tokio::time::sleep(Duration::from_secs(3)).await;
+
+    let got = client.get_kv("foo").await?;
+    assert_eq!(Some(b"1".to_vec()), got.map(|v| v.data));
+
+    Ok(())
+}
@@ -18,8 +18,10 @@ use std::sync::Arc;
 
 use databend_common_meta_client::ClientHandle;
 use databend_common_meta_client::Streamed;
+use databend_common_meta_kvapi::kvapi::HasKVReq;
 use databend_common_meta_kvapi::kvapi::KVApi;
 use databend_common_meta_kvapi::kvapi::ListKVReq;
+use databend_common_meta_kvapi::kvapi::ListKeysReq;
 use databend_common_meta_kvapi::kvapi::MGetKVReq;
 use databend_common_meta_kvapi::kvapi::UpsertKVReq;
 use databend_common_meta_types::protobuf as pb;
@@ -48,6 +50,8 @@ async fn test_kv_read_v1_on_leader() -> anyhow::Result<()> {
     initialize_kvs(&client, now_sec).await?;
     test_streamed_mget(&client, now_sec).await?;
     test_streamed_list(&client, now_sec).await?;
+    test_streamed_has_kv(&client, now_sec).await?;
+    test_streamed_list_keys(&client, now_sec).await?;
 
     Ok(())
 }
@@ -66,6 +70,8 @@ async fn test_kv_read_v1_on_follower() -> anyhow::Result<()> {
     let client = tcs[1].grpc_client().await?;
     test_streamed_mget(&client, now_sec).await?;
     test_streamed_list(&client, now_sec).await?;
+    test_streamed_has_kv(&client, now_sec).await?;
+    test_streamed_list_keys(&client, now_sec).await?;
 
     Ok(())
 }
@@ -196,6 +202,60 @@ async fn test_streamed_list(client: &Arc<ClientHandle>, _now_sec: u64) -> anyhow
     Ok(())
 }
 
+/// Test streamed has_kv on a grpc meta-service client
+async fn test_streamed_has_kv(client: &Arc<ClientHandle>, now_sec: u64) -> anyhow::Result<()> {
+    info!("--- test streamed has_kv");
+
+    let strm = client.request(Streamed(HasKVReq::new("a"))).await?;
+
+    // The value is not sent back.
+    let got = strm.map_err(|e| e.to_string()).collect::<Vec<_>>().await;
+    assert_eq!(
+        vec![Ok(pb::StreamItem::new(
+            s("a"),
+            Some(pb::SeqV::with_meta(
+                1,
+                Some(KvMeta::new_expire(now_sec + 10)),
+                vec![]
+            ))
+        ))],
+        got
+    );
+
+    let strm = client.request(Streamed(HasKVReq::new("b"))).await?;
+    let got = strm.map_err(|e| e.to_string()).collect::<Vec<_>>().await;
+    assert!(got.is_empty());
+
+    assert!(client.has_kv("c1").await?);
+    assert!(!client.has_kv("c3").await?);
+    Ok(())
+}
+
+/// Test streamed list_keys on a grpc meta-service client
+async fn test_streamed_list_keys(client: &Arc<ClientHandle>, _now_sec: u64) -> anyhow::Result<()> {
+    info!("--- test streamed list_keys");
+
+    let strm = client.request(Streamed(ListKeysReq::new("c"))).await?;
+
+    // The values are not sent back.
+    let got = strm.map_err(|e| e.to_string()).collect::<Vec<_>>().await;
+    assert_eq!(
+        vec![
+            Ok(pb::StreamItem::new(s("c"), None)),
+            Ok(pb::StreamItem::new(s("c1"), None)),
+            Ok(pb::StreamItem::new(s("c2"), None)),
+        ],
+        got
+    );
+
+    assert_eq!(
+        vec![s("c"), s("c1"), s("c2")],
+        client.prefix_list_keys("c").await?
+    );
+    assert!(client.prefix_list_keys("d").await?.is_empty());
+    Ok(())
+}
+
 fn s(x: &str) -> String {
     x.to_string()
 }
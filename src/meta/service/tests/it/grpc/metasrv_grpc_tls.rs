@@ -27,6 +27,8 @@ use crate::testing::meta_service_test_harness;
 use crate::tests::service::MetaSrvTestContext;
 use crate::tests::start_metasrv_with_context;
 use crate::tests::tls_constants::TEST_CA_CERT;
+use crate::tests::tls_constants::TEST_CLIENT_CERT;
+use crate::tests::tls_constants::TEST_CLIENT_KEY;
 use crate::tests::tls_constants::TEST_CN_NAME;
 use crate::tests::tls_constants::TEST_SERVER_CERT;
 use crate::tests::tls_constants::TEST_SERVER_KEY;
@@ -47,6 +49,7 @@ async fn test_tls_server() -> anyhow::Result<()> {
     let tls_conf = RpcClientTlsConfig {
         rpc_tls_server_root_ca_cert: TEST_CA_CERT.to_string(),
         domain_name: TEST_CN_NAME.to_string(),
+        ..Default::default()
     };
 
     let client = MetaGrpcClient::try_create(
@@ -66,6 +69,53 @@ async fn test_tls_server() -> anyhow::Result<()> {
     Ok(())
 }
 
+#[test(harness = meta_service_test_harness)]
+#[minitrace::trace]
+async fn test_tls_client_identity() -> anyhow::Result<()> {
+    let mut tc = MetaSrvTestContext::new(0);
+
+    tc.config.grpc_tls_server_key = TEST_SERVER_KEY.to_owned();
+    tc.config.grpc_tls_server_cert = TEST_SERVER_CERT.to_owned();
+
+    start_metasrv_with_context(&mut tc).await?;
+
+    let addr = tc.config.grpc_api_address.clone();
+
+    let tls_conf = RpcClientTlsConfig {
+        rpc_tls_server_root_ca_cert: TEST_CA_CERT.to_string(),
+        domain_name: TEST_CN_NAME.to_string(),
+        ..Default::default()
+    }
+    .with_client_identity(TEST_CLIENT_CERT, TEST_CLIENT_KEY);
+
+    let client = MetaGrpcClient::try_create(
+        vec![addr.clone()],
+        "root",
+        "xxx",
+        None,
+        Some(Duration::from_secs(10)),
+        Some(tls_conf),
+    )?;
+
+    let got = client.get_kv("foo").await?;
+    assert!(got.is_none());
+
+    // A plaintext client can not talk to a tls server.
+    let client = MetaGrpcClient::try_create(
+        vec![addr],
+        "root",
+        "xxx",
+        None,
+        Some(Duration::from_secs(3)),
+        None,
+    )?;
+
+    let res = client.get_kv("foo").await;
+    assert!(res.is_err(), "expect error: {:?}", res);
+
+    Ok(())
+}
+
 #[test(harness = meta_service_test_harness)]
 #[minitrace::trace]
 async fn test_tls_server_config_failure() -> anyhow::Result<()> {
@@ -85,6 +135,7 @@ async fn test_tls_client_config_failure() -> anyhow::Result<()> {
     let tls_conf = RpcClientTlsConfig {
         rpc_tls_server_root_ca_cert: "../tests/data/certs/not_exist.pem".to_string(),
         domain_name: TEST_CN_NAME.to_string(),
+        ..Default::default()
     };
 
     let r = MetaGrpcClient::try_create(
@@ -24,6 +24,7 @@ use databend_common_meta_client::MetaGrpcClient;
 use databend_common_meta_kvapi::kvapi;
 use databend_common_meta_kvapi::kvapi::KVApi;
 use databend_common_meta_kvapi::kvapi::UpsertKVReq;
+use databend_common_meta_kvapi::kvapi::WatchEventKind;
 use databend_common_meta_types::protobuf::watch_request::FilterType;
 use databend_common_meta_types::protobuf::Event;
 use databend_common_meta_types::protobuf::KvMeta;
@@ -39,10 +40,13 @@ use databend_common_meta_types::TxnCondition;
 use databend_common_meta_types::TxnDeleteByPrefixRequest;
 use databend_common_meta_types::TxnOp;
 use databend_meta::meta_service::MetaNode;
+use futures::TryStreamExt;
 use log::info;
 use test_harness::test;
 
 use crate::testing::meta_service_test_harness;
+use crate::tests::service::MetaSrvTestContext;
+use crate::tests::start_metasrv_with_context;
 
 async fn test_watch_main(
     addr: String,
@@ -131,6 +135,7 @@ async fn test_watch() -> anyhow::Result<()> {
             key: "a".to_string(),
             key_end: Some("z".to_string()),
             filter_type: FilterType::All.into(),
+            report_lag: false,
         };
 
         let key_a = s("a");
@@ -189,6 +194,7 @@ async fn test_watch() -> anyhow::Result<()> {
             key_end: None,
             // filter only delete events
             filter_type: FilterType::Delete.into(),
+            report_lag: false,
         };
 
         let key = s(key_str);
@@ -252,6 +258,7 @@ async fn test_watch() -> anyhow::Result<()> {
             key: start,
             key_end: Some(end),
             filter_type: FilterType::All.into(),
+            report_lag: false,
         };
 
         let conditions = vec![TxnCondition {
@@ -363,6 +370,7 @@ async fn test_watch_expired_events() -> anyhow::Result<()> {
             key: start,
             key_end: Some(end),
             filter_type: FilterType::All.into(),
+            report_lag: false,
         };
         watch_client.request(watch).await?
     };
@@ -448,6 +456,7 @@ async fn test_watch_stream_count() -> anyhow::Result<()> {
         key: "a".to_string(),
         key_end: Some("z".to_string()),
         filter_type: FilterType::All.into(),
+        report_lag: false,
     };
 
     let client1 = make_client(&addr)?;
@@ -505,6 +514,239 @@ async fn test_watch_stream_count() -> anyhow::Result<()> {
     Ok(())
 }
 
+#[test(harness = meta_service_test_harness)]
+#[minitrace::trace]
+async fn test_kv_api_watch() -> anyhow::Result<()> {
+    // KVApi::watch() on a ClientHandle subscribes to changes under a prefix.
+
+    let (_tc, addr) = crate::tests::start_metasrv().await?;
+
+    let client = make_client(&addr)?;
+    let mut watch_stream = client.watch("w/").await?;
+
+    info!("--- update keys inside and outside the prefix");
+    {
+        let writer = make_client(&addr)?;
+        writer.upsert_kv(UpsertKVReq::update("w/a", b"1")).await?;
+        writer.upsert_kv(UpsertKVReq::update("x/a", b"1")).await?;
+        writer.upsert_kv(UpsertKVReq::update("w/a", b"2")).await?;
+        writer.upsert_kv(UpsertKVReq::delete("w/a")).await?;
+    }
+
+    let mut got = vec![];
+    for _ in 0..3 {
+        let ev = watch_stream.try_next().await?.unwrap();
+        got.push((ev.key, ev.kind, ev.value.map(|v| v.data)));
+    }
+
+    assert_eq!(
+        vec![
+            (s("w/a"), WatchEventKind::Create, Some(b("1"))),
+            (s("w/a"), WatchEventKind::Update, Some(b("2"))),
+            (s("w/a"), WatchEventKind::Delete, None),
+        ],
+        got
+    );
+
+    Ok(())
+}
+
+#[test(harness = meta_service_test_harness)]
+#[minitrace::trace]
+async fn test_kv_api_watch_all() -> anyhow::Result<()> {
+    // KVApi::watch("") subscribes to changes of every key.
+
+    let (_tc, addr) = crate::tests::start_metasrv().await?;
+
+    let client = make_client(&addr)?;
+    let mut watch_stream = client.watch("").await?;
+
+    let max_key = format!("{}a", char::MAX);
+    let keys = vec![s("w/a"), s("x/a"), max_key.clone()];
+
+    info!("--- update keys with different prefixes");
+    {
+        let writer = make_client(&addr)?;
+        for k in keys.iter() {
+            writer.upsert_kv(UpsertKVReq::update(k, b"1")).await?;
+        }
+    }
+
+    let mut got = vec![];
+    while got.len() < keys.len() {
+        let ev = tokio::time::timeout(Duration::from_secs(5), watch_stream.try_next())
+            .await??
+            .unwrap();
+
+        // Ignore the keys written by the meta-service itself.
+        if keys.contains(&ev.key) {
+            got.push((ev.key, ev.kind));
+        }
+    }
+
+    assert_eq!(
+        vec![
+            (s("w/a"), WatchEventKind::Create),
+            (s("x/a"), WatchEventKind::Create),
+            (max_key, WatchEventKind::Create),
+        ],
+        got
+    );
+
+    Ok(())
+}
+
+#[test(harness = meta_service_test_harness)]
+#[minitrace::trace]
+async fn test_kv_api_watch_lagged() -> anyhow::Result<()> {
+    // A consumer that does not read its watch stream receives a `Lagged` event,
+    // instead of the meta-service buffering all the events for it.
+
+    let mut tc = MetaSrvTestContext::new(0);
+    tc.config.grpc_watch_buffer_size = 4;
+    tc.config.grpc_watch_send_timeout = 100;
+    start_metasrv_with_context(&mut tc).await?;
+    let addr = tc.config.grpc_api_address.clone();
+
+    let client = make_client(&addr)?;
+    let mut watch_stream = client.watch("w/").await?;
+
+    // Large values, so that the events can not all be buffered by the transport.
+    let n = 100;
+    let value = vec![b'x'; 64 * 1024];
+
+    info!("--- update keys without reading the watch stream");
+    {
+        let writer = make_client(&addr)?;
+        for i in 0..n {
+            let key = format!("w/{:03}", i);
+            writer.upsert_kv(UpsertKVReq::update(&key, &value)).await?;
+        }
+    }
+
+    // Let the meta-service dispatch all the events before the consumer starts reading.
+    sleep(Duration::from_secs(1)).await;
+
+    info!("--- every event is either received or counted in a lag");
+    let mut received = 0;
+    let mut lagged = 0;
+    while received + lagged < n {
+        let ev = tokio::time::timeout(Duration::from_secs(5), watch_stream.try_next())
+            .await??
+            .unwrap();
+
+        match ev.kind {
+            WatchEventKind::Lagged(x) => {
+                assert!(x > 0);
+                assert_eq!("", ev.key);
+                lagged += x;
+            }
+            kind => {
+                assert_eq!(WatchEventKind::Create, kind);
+                received += 1;
+            }
+        }
+    }
+
+    assert_eq!(n, received + lagged);
+    assert!(lagged > 0, "the slow consumer is expected to lag");
+
+    Ok(())
+}
+
+#[test(harness = meta_service_test_harness)]
+#[minitrace::trace]
+async fn test_kv_api_watch_burst() -> anyhow::Result<()> {
+    // A burst of events more than the stream buffer does not make a reading consumer lag.
+
+    let mut tc = MetaSrvTestContext::new(0);
+    tc.config.grpc_watch_buffer_size = 4;
+    start_metasrv_with_context(&mut tc).await?;
+    let addr = tc.config.grpc_api_address.clone();
+
+    let client = make_client(&addr)?;
+    let mut watch_stream = client.watch("w/").await?;
+
+    let n = 100;
+
+    info!("--- update all keys in one txn");
+    {
+        let txn = TxnRequest {
+            condition: vec![],
+            if_then: (0..n)
+                .map(|i| TxnOp::put(format!("w/{:03}", i), b("v")))
+                .collect(),
+            else_then: vec![],
+        };
+        client.transaction(txn).await?;
+    }
+
+    info!("--- every event is received in order");
+    for i in 0..n {
+        let ev = tokio::time::timeout(Duration::from_secs(5), watch_stream.try_next())
+            .await??
+            .unwrap();
+
+        assert_eq!(WatchEventKind::Create, ev.kind);
+        assert_eq!(format!("w/{:03}", i), ev.key);
+    }
+
+    Ok(())
+}
+
+#[test(harness = meta_service_test_harness)]
+#[minitrace::trace]
+async fn test_watch_lagged_without_report_lag() -> anyhow::Result<()> {
+    // A watcher that does not set `report_lag` is closed when it lags,
+    // instead of receiving a response without event.
+
+    let mut tc = MetaSrvTestContext::new(0);
+    tc.config.grpc_watch_buffer_size = 4;
+    tc.config.grpc_watch_send_timeout = 100;
+    start_metasrv_with_context(&mut tc).await?;
+    let addr = tc.config.grpc_api_address.clone();
+
+    let client = make_client(&addr)?;
+    let mut watch_stream = {
+        let (start, end) = kvapi::prefix_to_range("w/")?;
+        let watch = WatchRequest {
+            key: start,
+            key_end: Some(end),
+            filter_type: FilterType::All.into(),
+            report_lag: false,
+        };
+        client.request(watch).await?
+    };
+
+    let n = 100;
+    let value = vec![b'x'; 64 * 1024];
+
+    info!("--- update keys without reading the watch stream");
+    {
+        let writer = make_client(&addr)?;
+        for i in 0..n {
+            let key = format!("w/{:03}", i);
+            writer.upsert_kv(UpsertKVReq::update(&key, &value)).await?;
+        }
+    }
+
+    sleep(Duration::from_secs(1)).await;
+
+    info!("--- the buffered events are received, then the stream is closed");
+    let mut received = 0;
+    while let Some(resp) =
+        tokio::time::timeout(Duration::from_secs(5), watch_stream.message()).await??
+    {
+        assert!(resp.event.is_some());
+        assert_eq!(0, resp.lagged);
+        received += 1;
+    }
+
+    assert!(received < n, "the slow consumer is expected to be closed");
+
+    Ok(())
+}
+
 fn s(x: &str) -> String {
     x.to_string()
 }
@@ -19,6 +19,7 @@ pub mod metasrv_grpc_get_client_info;
 pub mod metasrv_grpc_handshake;
 pub mod metasrv_grpc_kv_api;
 pub mod metasrv_grpc_kv_api_restart_cluster;
+pub mod metasrv_grpc_keep_alive;
 pub mod metasrv_grpc_kv_read_v1;
 pub mod metasrv_grpc_schema_api;
 pub mod metasrv_grpc_schema_api_follower_follower;
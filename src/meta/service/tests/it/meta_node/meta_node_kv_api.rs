@@ -24,6 +24,7 @@ use test_harness::test;
 use crate::testing::meta_service_test_harness;
 use crate::tests::meta_node::start_meta_node_cluster;
 use crate::tests::meta_node::start_meta_node_leader;
+use crate::tests::meta_node::timeout;
 use crate::tests::service::MetaSrvTestContext;
 
 #[derive(Clone)]
@@ -66,6 +67,34 @@ impl kvapi::ApiBuilder<Arc<MetaNode>> for MetaNodeUnitTestBuilder {
 
         cluster
     }
+
+    /// Wait until every node observes a leader and the same membership as the others.
+    async fn build_cluster_ready(&self) -> anyhow::Result<Vec<Arc<MetaNode>>> {
+        let cluster = self.build_cluster().await;
+
+        let leader_metrics = cluster[0]
+            .raft
+            .wait(timeout())
+            .metrics(|m| m.current_leader.is_some(), "a leader is observed")
+            .await?;
+        let membership = leader_metrics.membership_config.membership().clone();
+
+        for (i, meta_node) in cluster.iter().enumerate() {
+            meta_node
+                .raft
+                .wait(timeout())
+                .metrics(
+                    |m| {
+                        m.current_leader.is_some()
+                            && m.membership_config.membership() == &membership
+                    },
+                    format!("node-{} agrees on membership {:?}", i, membership),
+                )
+                .await?;
+        }
+
+        Ok(cluster)
+    }
 }
 
 #[test(harness = meta_service_test_harness)]
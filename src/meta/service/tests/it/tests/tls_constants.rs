@@ -16,3 +16,5 @@ pub const TEST_CA_CERT: &str = "../../../tests/certs/ca.pem";
 pub const TEST_SERVER_CERT: &str = "../../../tests/certs/server.pem";
 pub const TEST_SERVER_KEY: &str = "../../../tests/certs/server.key";
 pub const TEST_CN_NAME: &str = "localhost";
+pub const TEST_CLIENT_CERT: &str = "../../../tests/certs/tls/cfssl/client/client.pem";
+pub const TEST_CLIENT_KEY: &str = "../../../tests/certs/tls/cfssl/client/pkcs8-client-key.pem";
@@ -25,11 +25,13 @@ use databend_common_meta_kvapi::kvapi;
 use databend_common_meta_kvapi::kvapi::KVStream;
 use databend_common_meta_kvapi::kvapi::UpsertKVReply;
 use databend_common_meta_kvapi::kvapi::UpsertKVReq;
+use databend_common_meta_kvapi::kvapi::WatchEvent;
 use databend_common_meta_types::protobuf::WatchRequest;
 use databend_common_meta_types::protobuf::WatchResponse;
 use databend_common_meta_types::MetaError;
 use databend_common_meta_types::TxnReply;
 use databend_common_meta_types::TxnRequest;
+use futures::stream::BoxStream;
 use log::as_debug;
 use log::info;
 use tokio_stream::Stream;
@@ -80,6 +82,16 @@ impl MetaStore {
             }
         }
     }
+
+    /// Wait for the in-flight requests to complete and reject new ones, before the process exits.
+    ///
+    /// It does nothing for a local store.
+    pub async fn close(&self) {
+        match self {
+            MetaStore::L(_) => {}
+            MetaStore::R(grpc_client) => grpc_client.close().await,
+        }
+    }
 }
 
 #[async_trait::async_trait]
@@ -93,6 +105,13 @@ impl kvapi::KVApi for MetaStore {
         }
     }
 
+    async fn ping(&self) -> Result<(), Self::Error> {
+        match self {
+            MetaStore::L(x) => x.ping().await,
+            MetaStore::R(x) => x.ping().await,
+        }
+    }
+
     async fn get_kv_stream(&self, keys: &[String]) -> Result<KVStream<Self::Error>, Self::Error> {
         match self {
             MetaStore::L(x) => x.get_kv_stream(keys).await,
@@ -113,6 +132,16 @@ impl kvapi::KVApi for MetaStore {
             MetaStore::R(x) => x.transaction(txn).await,
         }
     }
+
+    async fn watch(
+        &self,
+        prefix: &str,
+    ) -> Result<BoxStream<'_, Result<WatchEvent, Self::Error>>, Self::Error> {
+        match self {
+            MetaStore::L(x) => x.watch(prefix).await,
+            MetaStore::R(x) => x.watch(prefix).await,
+        }
+    }
 }
 
 impl MetaStoreProvider {
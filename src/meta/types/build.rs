@@ -65,6 +65,10 @@ fn build_proto() {
             "TxnDeleteByPrefixRequest",
             "#[derive(Eq, PartialOrd, Ord, serde::Serialize, serde::Deserialize)]",
         )
+        .type_attribute(
+            "TxnReserveSeqRequest",
+            "#[derive(Eq, serde::Serialize, serde::Deserialize)]",
+        )
         .type_attribute(
             "TxnCondition.ConditionResult",
             "#[derive(serde::Serialize, serde::Deserialize, num_derive::FromPrimitive)]",
@@ -105,6 +109,10 @@ fn build_proto() {
             "TxnDeleteByPrefixResponse",
             "#[derive(Eq, serde::Serialize, serde::Deserialize)]",
         )
+        .type_attribute(
+            "TxnReserveSeqResponse",
+            "#[derive(Eq, serde::Serialize, serde::Deserialize)]",
+        )
         .type_attribute(
             "TxnOpResponse.response",
             "#[derive(Eq, serde::Serialize, serde::Deserialize, derive_more::TryInto)]",
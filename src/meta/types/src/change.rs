@@ -86,6 +86,14 @@ where
         self.prev != self.result
     }
 
+    /// Whether the data is changed, regardless of the seq and meta.
+    ///
+    /// Unlike [`Self::is_changed`], rewriting the same data, which bumps the seq, is not a change.
+    /// Creating or deleting a record is always a change, even if the data is empty.
+    pub fn is_data_changed(&self) -> bool {
+        self.prev.as_ref().map(|x| &x.data) != self.result.as_ref().map(|x| &x.data)
+    }
+
     /// Assumes it is a state transition of an add operation and return Ok if the add operation succeed.
     /// Otherwise it returns an error that is built by provided function.
     ///
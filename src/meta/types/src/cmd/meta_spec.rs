@@ -62,6 +62,16 @@ impl MetaSpec {
         }
     }
 
+    /// Returns the absolute expiration time in second since 1970-01-01, if set.
+    pub fn get_expire_at(&self) -> Option<u64> {
+        self.expire_at
+    }
+
+    /// Returns the relative expiration time interval, if set.
+    pub fn get_ttl(&self) -> Option<Interval> {
+        self.ttl
+    }
+
     /// Convert meta spec into a [`KVMeta`] to be stored in storage.
     pub fn to_kv_meta(&self, cmd_ctx: &CmdContext) -> KVMeta {
         // If `ttl` is set, override `expire_at`
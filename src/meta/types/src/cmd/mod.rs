@@ -52,6 +52,13 @@ pub enum Cmd {
 
     /// Update one or more kv with a transaction.
     Transaction(TxnRequest),
+
+    /// Enable or disable a state machine feature, which changes how the following logs are applied.
+    ///
+    /// A feature must be enabled only after every node in the cluster supports it:
+    /// an older node fails to decode this command and stops applying logs,
+    /// instead of silently applying them in another way.
+    SetFeature { feature: String, enable: bool },
 }
 
 /// Update or insert a general purpose kv store
@@ -70,8 +77,34 @@ pub struct UpsertKV {
 
     /// Meta data of a value.
     pub value_meta: Option<MetaSpec>,
+
+    /// A token to identify one logical write among several attempts to send it.
+    ///
+    /// If the state machine feature [`FEATURE_IDEMPOTENCY_TOKEN`] is enabled,
+    /// the state machine records the reply of an upsert with a token for [`IDEMPOTENCY_TOKEN_TTL`],
+    /// and another upsert with the same token in this window is not applied but returns the recorded reply.
+    /// Otherwise the token is ignored.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub idempotency_token: Option<String>,
 }
 
+/// The state machine feature to de-duplicate upserts by [`UpsertKV::idempotency_token`].
+///
+/// It is enabled by [`Cmd::SetFeature`].
+pub const FEATURE_IDEMPOTENCY_TOKEN: &str = "idempotency_token";
+
+/// The state machine feature to apply `TxnReserveSeqRequest` in a transaction.
+///
+/// It is enabled by [`Cmd::SetFeature`].
+/// An older node fails to decode a log with this operation,
+/// thus the leader removes it from a transaction before proposing, unless the feature is enabled.
+pub const FEATURE_RESERVE_SEQ: &str = "reserve_seq";
+
+/// How long the reply of an upsert with an idempotency token is retained.
+///
+/// A retry sent after this window is applied again.
+pub const IDEMPOTENCY_TOKEN_TTL: Duration = Duration::from_secs(600);
+
 impl fmt::Display for Cmd {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
@@ -95,6 +128,9 @@ impl fmt::Display for Cmd {
             Cmd::Transaction(txn) => {
                 write!(f, "txn:{}", txn)
             }
+            Cmd::SetFeature { feature, enable } => {
+                write!(f, "set_feature:{}={}", feature, enable)
+            }
         }
     }
 }
@@ -121,6 +157,7 @@ impl UpsertKV {
             seq,
             value,
             value_meta,
+            idempotency_token: None,
         }
     }
 
@@ -130,6 +167,7 @@ impl UpsertKV {
             seq: MatchSeq::Exact(0),
             value: Operation::Update(value.to_vec()),
             value_meta: None,
+            idempotency_token: None,
         }
     }
 
@@ -139,6 +177,7 @@ impl UpsertKV {
             seq: MatchSeq::GE(0),
             value: Operation::Update(value.to_vec()),
             value_meta: None,
+            idempotency_token: None,
         }
     }
 
@@ -148,6 +187,7 @@ impl UpsertKV {
             seq: MatchSeq::GE(0),
             value: Operation::Delete,
             value_meta: None,
+            idempotency_token: None,
         }
     }
 
@@ -160,6 +200,12 @@ impl UpsertKV {
     pub fn with_ttl(self, ttl: Duration) -> Self {
         self.with(MetaSpec::new_ttl(ttl))
     }
+
+    /// Set the idempotency token so that a retry of this upsert is applied at most once.
+    pub fn with_idempotency_token(mut self, token: impl ToString) -> Self {
+        self.idempotency_token = Some(token.to_string());
+        self
+    }
 }
 
 impl With<MatchSeq> for UpsertKV {
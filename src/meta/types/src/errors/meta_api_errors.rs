@@ -23,9 +23,11 @@ use crate::raft_types::ChangeMembershipError;
 use crate::raft_types::Fatal;
 use crate::raft_types::ForwardToLeader;
 use crate::ClientWriteError;
+use crate::InvalidArgument;
 use crate::InvalidReply;
 use crate::MetaNetworkError;
 use crate::RaftError;
+use crate::RequestTimeout;
 
 /// Errors raised when meta-service handling a request.
 #[derive(thiserror::Error, serde::Serialize, serde::Deserialize, Debug, Clone, PartialEq, Eq)]
@@ -187,6 +189,20 @@ impl From<errors::IncompleteStream> for MetaAPIError {
     }
 }
 
+impl From<InvalidArgument> for MetaAPIError {
+    fn from(e: InvalidArgument) -> Self {
+        let net_err = MetaNetworkError::from(e);
+        Self::NetworkError(net_err)
+    }
+}
+
+impl From<RequestTimeout> for MetaAPIError {
+    fn from(e: RequestTimeout) -> Self {
+        let net_err = MetaNetworkError::from(e);
+        Self::NetworkError(net_err)
+    }
+}
+
 impl From<RaftError<ClientWriteError>> for MetaAPIError {
     fn from(value: RaftError<ClientWriteError>) -> Self {
         match value {
@@ -12,6 +12,8 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::fmt::Display;
+
 use anyerror::AnyError;
 
 use crate::MetaHandshakeError;
@@ -31,6 +33,18 @@ pub enum MetaClientError {
 
     #[error(transparent)]
     HandshakeError(#[from] MetaHandshakeError),
+
+    #[error(transparent)]
+    PermissionDenied(#[from] PermissionDenied),
+
+    #[error(transparent)]
+    ValueTooLarge(#[from] ValueTooLarge),
+
+    #[error(transparent)]
+    FencedOut(#[from] FencedOut),
+
+    #[error(transparent)]
+    TxnTooLarge(#[from] TxnTooLarge),
 }
 
 impl MetaClientError {
@@ -40,6 +54,141 @@ impl MetaClientError {
             MetaClientError::ConfigError(_) => "ConfigError",
             MetaClientError::NetworkError(err) => err.name(),
             MetaClientError::HandshakeError(_) => "MetaHandshakeError",
+            MetaClientError::PermissionDenied(_) => "PermissionDenied",
+            MetaClientError::ValueTooLarge(_) => "ValueTooLarge",
+            MetaClientError::FencedOut(_) => "FencedOut",
+            MetaClientError::TxnTooLarge(_) => "TxnTooLarge",
+        }
+    }
+}
+
+/// A request is rejected by the client because the principal is not allowed to access a key.
+#[derive(thiserror::Error, serde::Serialize, serde::Deserialize, Debug, Clone, PartialEq, Eq)]
+#[error("PermissionDenied: {principal} is not allowed to {access} '{key}'")]
+pub struct PermissionDenied {
+    principal: String,
+    access: String,
+    key: String,
+}
+
+impl PermissionDenied {
+    pub fn new(principal: impl Display, access: impl Display, key: impl Display) -> Self {
+        Self {
+            principal: principal.to_string(),
+            access: access.to_string(),
+            key: key.to_string(),
         }
     }
+
+    pub fn principal(&self) -> &str {
+        &self.principal
+    }
+
+    pub fn key(&self) -> &str {
+        &self.key
+    }
+}
+
+/// A write is rejected by the client because the value exceeds the size limit.
+#[derive(thiserror::Error, serde::Serialize, serde::Deserialize, Debug, Clone, PartialEq, Eq)]
+#[error("ValueTooLarge: value of '{key}' is {size} bytes, exceeds the limit of {limit} bytes")]
+pub struct ValueTooLarge {
+    key: String,
+    size: usize,
+    limit: usize,
+}
+
+impl ValueTooLarge {
+    pub fn new(key: impl Display, size: usize, limit: usize) -> Self {
+        Self {
+            key: key.to_string(),
+            size,
+            limit,
+        }
+    }
+
+    pub fn key(&self) -> &str {
+        &self.key
+    }
+
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    pub fn limit(&self) -> usize {
+        self.limit
+    }
+}
+
+/// A transaction is rejected by the client because its encoded size or its number of operations exceeds the limit.
+#[derive(thiserror::Error, serde::Serialize, serde::Deserialize, Debug, Clone, PartialEq, Eq)]
+#[error(
+    "TxnTooLarge: transaction is {size} bytes with {ops} operations, exceeds the limit of {max_size} bytes or {max_ops} operations"
+)]
+pub struct TxnTooLarge {
+    size: usize,
+    ops: usize,
+    max_size: usize,
+    max_ops: usize,
+}
+
+impl TxnTooLarge {
+    pub fn new(size: usize, ops: usize, max_size: usize, max_ops: usize) -> Self {
+        Self {
+            size,
+            ops,
+            max_size,
+            max_ops,
+        }
+    }
+
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    pub fn ops(&self) -> usize {
+        self.ops
+    }
+
+    pub fn max_size(&self) -> usize {
+        self.max_size
+    }
+
+    pub fn max_ops(&self) -> usize {
+        self.max_ops
+    }
+}
+
+/// A write is rejected because it carries an epoch that is not the current one of the fence,
+/// i.e., it is issued by a holder that has been superseded.
+#[derive(thiserror::Error, serde::Serialize, serde::Deserialize, Debug, Clone, PartialEq, Eq)]
+#[error("FencedOut: epoch {epoch} is fenced out by '{fence_key}' at epoch {current:?}")]
+pub struct FencedOut {
+    fence_key: String,
+    epoch: u64,
+    current: Option<u64>,
+}
+
+impl FencedOut {
+    pub fn new(fence_key: impl Display, epoch: u64, current: Option<u64>) -> Self {
+        Self {
+            fence_key: fence_key.to_string(),
+            epoch,
+            current,
+        }
+    }
+
+    pub fn fence_key(&self) -> &str {
+        &self.fence_key
+    }
+
+    /// The epoch the rejected write carries.
+    pub fn epoch(&self) -> u64 {
+        self.epoch
+    }
+
+    /// The epoch of the fence when the write is rejected, `None` if there is no fence.
+    pub fn current(&self) -> Option<u64> {
+        self.current
+    }
 }
@@ -19,10 +19,16 @@ use serde::Serialize;
 use thiserror::Error;
 
 use crate::errors;
+use crate::FencedOut;
+use crate::InvalidArgument;
 use crate::InvalidReply;
 use crate::MetaAPIError;
 use crate::MetaClientError;
 use crate::MetaNetworkError;
+use crate::PermissionDenied;
+use crate::RequestTimeout;
+use crate::TxnTooLarge;
+use crate::ValueTooLarge;
 
 /// Top level error MetaNode would return.
 #[derive(Error, Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
@@ -74,6 +80,48 @@ impl From<errors::IncompleteStream> for MetaError {
     }
 }
 
+impl From<InvalidArgument> for MetaError {
+    fn from(e: InvalidArgument) -> Self {
+        let net_err = MetaNetworkError::from(e);
+        Self::NetworkError(net_err)
+    }
+}
+
+impl From<RequestTimeout> for MetaError {
+    fn from(e: RequestTimeout) -> Self {
+        let net_err = MetaNetworkError::from(e);
+        Self::NetworkError(net_err)
+    }
+}
+
+impl From<PermissionDenied> for MetaError {
+    fn from(e: PermissionDenied) -> Self {
+        let client_err = MetaClientError::from(e);
+        Self::ClientError(client_err)
+    }
+}
+
+impl From<ValueTooLarge> for MetaError {
+    fn from(e: ValueTooLarge) -> Self {
+        let client_err = MetaClientError::from(e);
+        Self::ClientError(client_err)
+    }
+}
+
+impl From<FencedOut> for MetaError {
+    fn from(e: FencedOut) -> Self {
+        let client_err = MetaClientError::from(e);
+        Self::ClientError(client_err)
+    }
+}
+
+impl From<TxnTooLarge> for MetaError {
+    fn from(e: TxnTooLarge) -> Self {
+        let client_err = MetaClientError::from(e);
+        Self::ClientError(client_err)
+    }
+}
+
 impl From<MetaError> for ErrorCode {
     fn from(e: MetaError) -> Self {
         ErrorCode::MetaServiceError(e.to_string())
@@ -13,6 +13,7 @@
 // limitations under the License.
 
 use std::fmt::Display;
+use std::time::Duration;
 
 use anyerror::AnyError;
 use serde::Deserialize;
@@ -45,6 +46,9 @@ pub enum MetaNetworkError {
 
     #[error(transparent)]
     InvalidReply(#[from] InvalidReply),
+
+    #[error(transparent)]
+    RequestTimeout(#[from] RequestTimeout),
 }
 
 impl MetaNetworkError {
@@ -57,6 +61,7 @@ impl MetaNetworkError {
             Self::BadAddressFormat(e) => Self::BadAddressFormat(e.add_context(|| context)),
             Self::InvalidArgument(e) => e.add_context(context).into(),
             Self::InvalidReply(e) => e.add_context(context).into(),
+            Self::RequestTimeout(e) => e.add_context(context).into(),
         }
     }
 
@@ -69,6 +74,7 @@ impl MetaNetworkError {
             MetaNetworkError::BadAddressFormat(_) => "BadAddressFormat",
             MetaNetworkError::InvalidArgument(_) => "InvalidArgument",
             MetaNetworkError::InvalidReply(_) => "InvalidReply",
+            MetaNetworkError::RequestTimeout(_) => "RequestTimeout",
         }
     }
 }
@@ -118,6 +124,12 @@ impl InvalidArgument {
     }
 }
 
+impl From<InvalidArgument> for std::io::Error {
+    fn from(e: InvalidArgument) -> Self {
+        std::io::Error::new(std::io::ErrorKind::InvalidInput, e)
+    }
+}
+
 #[derive(thiserror::Error, serde::Serialize, serde::Deserialize, Debug, Clone, PartialEq, Eq)]
 #[error("InvalidReply: {msg} source: {source}")]
 pub struct InvalidReply {
@@ -140,6 +152,38 @@ impl InvalidReply {
     }
 }
 
+/// A request does not finish before the deadline.
+#[derive(thiserror::Error, serde::Serialize, serde::Deserialize, Debug, Clone, PartialEq, Eq)]
+#[error("RequestTimeout: {msg} does not finish in {timeout:?}")]
+pub struct RequestTimeout {
+    msg: String,
+    timeout: Duration,
+}
+
+impl RequestTimeout {
+    pub fn new(timeout: Duration, msg: impl Display) -> Self {
+        Self {
+            msg: msg.to_string(),
+            timeout,
+        }
+    }
+
+    pub fn timeout(&self) -> Duration {
+        self.timeout
+    }
+
+    pub fn add_context(mut self, context: impl Display) -> Self {
+        self.msg = format!("{}: {}", self.msg, context);
+        self
+    }
+}
+
+impl From<RequestTimeout> for std::io::Error {
+    fn from(e: RequestTimeout) -> Self {
+        std::io::Error::new(std::io::ErrorKind::TimedOut, e)
+    }
+}
+
 impl From<errors::IncompleteStream> for InvalidReply {
     fn from(e: errors::IncompleteStream) -> Self {
         Self::new("Invalid reply", &e)
@@ -65,7 +65,11 @@ pub use errors::meta_api_errors::MetaAPIError;
 pub use errors::meta_api_errors::MetaDataError;
 pub use errors::meta_api_errors::MetaDataReadError;
 pub use errors::meta_api_errors::MetaOperationError;
+pub use errors::meta_client_errors::FencedOut;
 pub use errors::meta_client_errors::MetaClientError;
+pub use errors::meta_client_errors::PermissionDenied;
+pub use errors::meta_client_errors::TxnTooLarge;
+pub use errors::meta_client_errors::ValueTooLarge;
 pub use errors::meta_errors::MetaError;
 pub use errors::meta_handshake_errors::MetaHandshakeError;
 pub use errors::meta_management_error::MetaManagementError;
@@ -74,6 +78,7 @@ pub use errors::meta_network_errors::InvalidArgument;
 pub use errors::meta_network_errors::InvalidReply;
 pub use errors::meta_network_errors::MetaNetworkError;
 pub use errors::meta_network_errors::MetaNetworkResult;
+pub use errors::meta_network_errors::RequestTimeout;
 pub use errors::meta_startup_errors::MetaStartupError;
 pub use errors::rpc_errors::ForwardRPCError;
 pub use eval_expire_time::EvalExpireTime;
@@ -100,6 +105,8 @@ pub use protobuf::TxnPutRequest;
 pub use protobuf::TxnPutResponse;
 pub use protobuf::TxnReply;
 pub use protobuf::TxnRequest;
+pub use protobuf::TxnReserveSeqRequest;
+pub use protobuf::TxnReserveSeqResponse;
 pub use raft_txid::RaftTxId;
 pub use seq_errors::ConflictSeq;
 pub use seq_num::SeqNum;
@@ -115,6 +122,9 @@ pub use crate::cmd::Cmd;
 pub use crate::cmd::CmdContext;
 pub use crate::cmd::MetaSpec;
 pub use crate::cmd::UpsertKV;
+pub use crate::cmd::FEATURE_IDEMPOTENCY_TOKEN;
+pub use crate::cmd::FEATURE_RESERVE_SEQ;
+pub use crate::cmd::IDEMPOTENCY_TOKEN_TTL;
 pub use crate::grpc_helper::GrpcHelper;
 pub use crate::raft_snapshot_data::SnapshotData;
 pub use crate::raft_types::compat07;
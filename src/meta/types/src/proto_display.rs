@@ -35,6 +35,8 @@ use crate::TxnPutRequest;
 use crate::TxnPutResponse;
 use crate::TxnReply;
 use crate::TxnRequest;
+use crate::TxnReserveSeqRequest;
+use crate::TxnReserveSeqResponse;
 
 struct OptionDisplay<'a, T: Display> {
     t: &'a Option<T>,
@@ -132,6 +134,9 @@ impl Display for txn_op::Request {
             Request::DeleteByPrefix(r) => {
                 write!(f, "DeleteByPrefix({})", r)
             }
+            Request::ReserveSeq(r) => {
+                write!(f, "ReserveSeq({})", r)
+            }
         }
     }
 }
@@ -168,6 +173,12 @@ impl Display for TxnDeleteByPrefixRequest {
     }
 }
 
+impl Display for TxnReserveSeqRequest {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "ReserveSeq key={}, count={}", self.key, self.count)
+    }
+}
+
 impl Display for Target {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         match self {
@@ -216,6 +227,9 @@ impl Display for Response {
             Response::DeleteByPrefix(r) => {
                 write!(f, "DeleteByPrefix: {}", r)
             }
+            Response::ReserveSeq(r) => {
+                write!(f, "ReserveSeq: {}", r)
+            }
         }
     }
 }
@@ -261,3 +275,13 @@ impl Display for TxnDeleteByPrefixResponse {
         )
     }
 }
+
+impl Display for TxnReserveSeqResponse {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "ReserveSeq-resp: key={}, seqs=[{}, {})",
+            self.key, self.start, self.end
+        )
+    }
+}
@@ -12,7 +12,14 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::collections::BTreeSet;
+
+use anyerror::AnyError;
+
 use crate::protobuf as pb;
+use crate::txn_op::Request;
+use crate::txn_op_response;
+use crate::InvalidArgument;
 use crate::SeqV;
 use crate::TxnRequest;
 
@@ -26,6 +33,87 @@ impl TxnRequest {
             else_then: vec![],
         }
     }
+
+    /// The size in bytes of the request encoded in protobuf, i.e., as it is sent to the meta-service.
+    pub fn encoded_size(&self) -> usize {
+        prost::Message::encoded_len(self)
+    }
+
+    /// The number of operations in both branches, conditions excluded.
+    pub fn op_count(&self) -> usize {
+        self.if_then.len() + self.else_then.len()
+    }
+
+    /// Remove every `ReserveSeq` operation, and return whether any is removed.
+    ///
+    /// It is used by the leader when the state machine feature `reserve_seq` is not enabled,
+    /// a removed operation has no response in the reply.
+    pub fn remove_reserve_seq(&mut self) -> bool {
+        let is_reserve_seq = |op: &pb::TxnOp| matches!(op.request, Some(Request::ReserveSeq(_)));
+        let n = self.op_count();
+        self.if_then.retain(|op| !is_reserve_seq(op));
+        self.else_then.retain(|op| !is_reserve_seq(op));
+        n != self.op_count()
+    }
+
+    /// Check that no key is written more than once in a branch, by a put, a delete or a delete-by-prefix.
+    ///
+    /// The outcome of such a transaction depends on the order in which the backend applies the operations.
+    /// Only one of `if_then` and `else_then` is run, thus writing a key once in each of them is allowed.
+    /// A get is not a write and does not conflict with any operation.
+    pub fn validate(&self) -> Result<(), InvalidArgument> {
+        Self::validate_branch("if_then", &self.if_then)?;
+        Self::validate_branch("else_then", &self.else_then)
+    }
+
+    fn validate_branch(branch: &str, ops: &[pb::TxnOp]) -> Result<(), InvalidArgument> {
+        let conflict = |msg: String| {
+            InvalidArgument::new(
+                AnyError::error(format!("{} in {}", msg, branch)),
+                "TxnRequest::validate",
+            )
+        };
+
+        let mut keys = BTreeSet::new();
+        let mut prefixes: Vec<&String> = vec![];
+
+        for op in ops {
+            let key = match &op.request {
+                Some(Request::Put(put)) => &put.key,
+                Some(Request::Delete(del)) => &del.key,
+                Some(Request::ReserveSeq(reserve)) => &reserve.key,
+                Some(Request::DeleteByPrefix(del)) => {
+                    if let Some(p) = prefixes
+                        .iter()
+                        .find(|p| p.starts_with(&del.prefix) || del.prefix.starts_with(p.as_str()))
+                    {
+                        return Err(conflict(format!(
+                            "prefix '{}' overlaps prefix '{}'",
+                            del.prefix, p
+                        )));
+                    }
+                    prefixes.push(&del.prefix);
+                    continue;
+                }
+                Some(Request::Get(_)) | None => continue,
+            };
+
+            if !keys.insert(key) {
+                return Err(conflict(format!("key '{}' is written more than once", key)));
+            }
+        }
+
+        for prefix in prefixes {
+            if let Some(key) = keys.iter().find(|k| k.starts_with(prefix.as_str())) {
+                return Err(conflict(format!(
+                    "key '{}' is also deleted by prefix '{}'",
+                    key, prefix
+                )));
+            }
+        }
+
+        Ok(())
+    }
 }
 
 impl pb::TxnCondition {
@@ -37,6 +125,15 @@ impl pb::TxnCondition {
             target: Some(pb::txn_condition::Target::Seq(seq)),
         }
     }
+
+    /// Create a txn condition that checks if the value matches.
+    pub fn eq_value(key: impl ToString, value: Vec<u8>) -> Self {
+        Self {
+            key: key.to_string(),
+            expected: pb::txn_condition::ConditionResult::Eq as i32,
+            target: Some(pb::txn_condition::Target::Value(value)),
+        }
+    }
 }
 
 impl pb::TxnOp {
@@ -93,6 +190,27 @@ impl pb::TxnOp {
         }
     }
 
+    /// Create a new `TxnOp` that deletes all of the records whose key starts with `prefix`.
+    pub fn delete_by_prefix(prefix: impl ToString) -> Self {
+        pb::TxnOp {
+            request: Some(pb::txn_op::Request::DeleteByPrefix(
+                pb::TxnDeleteByPrefixRequest {
+                    prefix: prefix.to_string(),
+                },
+            )),
+        }
+    }
+
+    /// Create a new `TxnOp` that reserves `count` consecutive seqs by updating the record of `key` once.
+    pub fn reserve_seq(key: impl ToString, count: u64) -> Self {
+        pb::TxnOp {
+            request: Some(pb::txn_op::Request::ReserveSeq(pb::TxnReserveSeqRequest {
+                key: key.to_string(),
+                count,
+            })),
+        }
+    }
+
     /// Create a new `TxnOp` with a `Get` operation.
     pub fn get(key: impl ToString) -> Self {
         pb::TxnOp {
@@ -135,6 +253,19 @@ impl pb::TxnOpResponse {
             })),
         }
     }
+
+    /// Create a new `TxnOpResponse` of a `ReserveSeq` operation, the reserved seqs are `[start, end)`.
+    pub fn reserve_seq(key: impl ToString, start: u64, end: u64) -> Self {
+        pb::TxnOpResponse {
+            response: Some(pb::txn_op_response::Response::ReserveSeq(
+                pb::TxnReserveSeqResponse {
+                    key: key.to_string(),
+                    start,
+                    end,
+                },
+            )),
+        }
+    }
 }
 
 impl pb::TxnGetResponse {
@@ -145,3 +276,203 @@ impl pb::TxnGetResponse {
         }
     }
 }
+
+impl pb::TxnReply {
+    /// Returns `true` if the condition is met and `if_then` is executed without error.
+    ///
+    /// `false` if `else_then` is executed, or the backend failed to execute the transaction.
+    pub fn success(&self) -> bool {
+        self.success && self.error.is_empty()
+    }
+
+    /// Returns the value read by a `get` operation on `key`.
+    ///
+    /// If `key` is read more than once, the last read is returned,
+    /// which reflects the writes preceding it in the same branch.
+    /// `None` if there is no `get` on `key` or the key is absent.
+    pub fn get_response(&self, key: &str) -> Option<&pb::SeqV> {
+        self.responses
+            .iter()
+            .rev()
+            .find_map(|r| match &r.response {
+                Some(txn_op_response::Response::Get(get)) if get.key == key => {
+                    Some(get.value.as_ref())
+                }
+                _ => None,
+            })?
+    }
+
+    /// Returns the response of the last `put` operation on `key`.
+    pub fn put_response(&self, key: &str) -> Option<&pb::TxnPutResponse> {
+        self.responses.iter().rev().find_map(|r| match &r.response {
+            Some(txn_op_response::Response::Put(put)) if put.key == key => Some(put),
+            _ => None,
+        })
+    }
+
+    /// Returns the response of the last `delete` operation on `key`.
+    pub fn delete_response(&self, key: &str) -> Option<&pb::TxnDeleteResponse> {
+        self.responses.iter().rev().find_map(|r| match &r.response {
+            Some(txn_op_response::Response::Delete(del)) if del.key == key => Some(del),
+            _ => None,
+        })
+    }
+
+    /// Returns the response of the last `reserve_seq` operation on `key`.
+    ///
+    /// `None` if there is no such operation or it is removed because the meta-service does not support it.
+    pub fn reserve_seq_response(&self, key: &str) -> Option<&pb::TxnReserveSeqResponse> {
+        self.responses.iter().rev().find_map(|r| match &r.response {
+            Some(txn_op_response::Response::ReserveSeq(reserve)) if reserve.key == key => {
+                Some(reserve)
+            }
+            _ => None,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::protobuf as pb;
+    use crate::SeqV;
+    use crate::TxnOp;
+    use crate::TxnOpResponse;
+    use crate::TxnReply;
+    use crate::TxnRequest;
+
+    #[test]
+    fn test_txn_reply_by_key() {
+        let reply = TxnReply {
+            success: true,
+            responses: vec![
+                TxnOpResponse::get("a", Some(SeqV::new(1, b"1".to_vec()))),
+                TxnOpResponse::put("a", Some(pb::SeqV::from(SeqV::new(1, b"1".to_vec())))),
+                TxnOpResponse::get("b", None),
+                TxnOpResponse::delete("c", true, Some(pb::SeqV::from(SeqV::new(2, b"3".to_vec())))),
+                TxnOpResponse::get("a", Some(SeqV::new(3, b"2".to_vec()))),
+            ],
+            error: "".to_string(),
+        };
+
+        assert!(reply.success());
+
+        // The last read of a key is returned.
+        assert_eq!(
+            Some(&pb::SeqV::from(SeqV::new(3, b"2".to_vec()))),
+            reply.get_response("a")
+        );
+        // Read but absent.
+        assert_eq!(None, reply.get_response("b"));
+        // Not read.
+        assert_eq!(None, reply.get_response("c"));
+
+        let put = reply.put_response("a").unwrap();
+        assert_eq!(
+            Some(pb::SeqV::from(SeqV::new(1, b"1".to_vec()))),
+            put.prev_value
+        );
+        assert!(reply.put_response("b").is_none());
+
+        let del = reply.delete_response("c").unwrap();
+        assert!(del.success);
+        assert!(reply.delete_response("a").is_none());
+
+        // else_then is executed
+        let reply = TxnReply {
+            success: false,
+            responses: vec![TxnOpResponse::get("a", None)],
+            error: "".to_string(),
+        };
+        assert!(!reply.success());
+
+        // The backend failed
+        let reply = TxnReply {
+            success: true,
+            responses: vec![],
+            error: "failed".to_string(),
+        };
+        assert!(!reply.success());
+    }
+
+    #[test]
+    fn test_txn_request_validate() {
+        let txn = |if_then: Vec<TxnOp>, else_then: Vec<TxnOp>| TxnRequest {
+            condition: vec![],
+            if_then,
+            else_then,
+        };
+
+        // Put and delete of the same key
+        let res = txn(
+            vec![TxnOp::put("a", b"1".to_vec()), TxnOp::delete("a")],
+            vec![],
+        )
+        .validate();
+        let err = res.unwrap_err().to_string();
+        assert!(
+            err.contains("key 'a' is written more than once in if_then"),
+            "{}",
+            err
+        );
+
+        // Double put
+        let res = txn(vec![], vec![
+            TxnOp::put("a", b"1".to_vec()),
+            TxnOp::put("a", b"2".to_vec()),
+        ])
+        .validate();
+        let err = res.unwrap_err().to_string();
+        assert!(
+            err.contains("key 'a' is written more than once in else_then"),
+            "{}",
+            err
+        );
+
+        // A key covered by a delete-by-prefix
+        let res = txn(
+            vec![TxnOp::delete_by_prefix("a/"), TxnOp::put("a/b", vec![])],
+            vec![],
+        )
+        .validate();
+        assert!(res.is_err());
+
+        let res = txn(
+            vec![
+                TxnOp::delete_by_prefix("a/"),
+                TxnOp::delete_by_prefix("a/b"),
+            ],
+            vec![],
+        )
+        .validate();
+        assert!(res.is_err());
+
+        // Valid: distinct keys, reads, and one write of a key in each branch
+        let res = txn(
+            vec![
+                TxnOp::put("a", b"1".to_vec()),
+                TxnOp::delete("b"),
+                TxnOp::delete_by_prefix("c/"),
+                TxnOp::get("a"),
+                TxnOp::get("a"),
+            ],
+            vec![TxnOp::put("a", b"2".to_vec()), TxnOp::get("b")],
+        )
+        .validate();
+        assert!(res.is_ok());
+    }
+
+    #[test]
+    fn test_txn_request_remove_reserve_seq() {
+        let mut txn = TxnRequest {
+            condition: vec![],
+            if_then: vec![TxnOp::reserve_seq("a", 3), TxnOp::put("b", vec![])],
+            else_then: vec![TxnOp::get("a"), TxnOp::reserve_seq("a", 1)],
+        };
+
+        assert!(txn.remove_reserve_seq());
+        assert_eq!(vec![TxnOp::put("b", vec![])], txn.if_then);
+        assert_eq!(vec![TxnOp::get("a")], txn.else_then);
+
+        assert!(!txn.remove_reserve_seq());
+    }
+}
@@ -0,0 +1,103 @@
+// Copyright 2021 Datafuse Labs
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::fmt;
+use std::fmt::Debug;
+use std::future::Future;
+
+use chrono::DateTime;
+use chrono::Utc;
+use databend_common_base::base::tokio;
+use databend_common_meta_types::MetaId;
+
+tokio::task_local! {
+    static AUDIT_PRINCIPAL: String;
+}
+
+/// Run `fut` with `principal` as the [`CatalogAuditEvent::principal`] of the DDLs it applies.
+///
+/// A catalog is shared by sessions, thus the user of a session is passed to it in a task local,
+/// which does not cross a spawned task.
+pub async fn with_audit_principal<F: Future>(principal: String, fut: F) -> F::Output {
+    AUDIT_PRINCIPAL.scope(principal, fut).await
+}
+
+/// Returns the principal set by [`with_audit_principal`] for the current task, if any.
+pub fn audit_principal() -> Option<String> {
+    AUDIT_PRINCIPAL.try_with(|p| p.clone()).ok()
+}
+
+/// The kind of DDL a [`CatalogAuditEvent`] records.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CatalogAuditOp {
+    CreateDatabase,
+    DropDatabase,
+    UndropDatabase,
+    RenameDatabase,
+    AlterDatabase,
+    CreateTable,
+    DropTable,
+    UndropTable,
+    RenameTable,
+    AlterTable,
+}
+
+impl fmt::Display for CatalogAuditOp {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let s = match self {
+            CatalogAuditOp::CreateDatabase => "create_database",
+            CatalogAuditOp::DropDatabase => "drop_database",
+            CatalogAuditOp::UndropDatabase => "undrop_database",
+            CatalogAuditOp::RenameDatabase => "rename_database",
+            CatalogAuditOp::AlterDatabase => "alter_database",
+            CatalogAuditOp::CreateTable => "create_table",
+            CatalogAuditOp::DropTable => "drop_table",
+            CatalogAuditOp::UndropTable => "undrop_table",
+            CatalogAuditOp::RenameTable => "rename_table",
+            CatalogAuditOp::AlterTable => "alter_table",
+        };
+        write!(f, "{}", s)
+    }
+}
+
+/// A DDL that has been applied through a catalog.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CatalogAuditEvent {
+    pub op: CatalogAuditOp,
+    pub tenant: String,
+
+    /// `None` if the request does not refer to the database by name, e.g., drop a table by id.
+    pub db_name: Option<String>,
+
+    /// `None` for a database operation.
+    pub table_name: Option<String>,
+
+    /// Who sent the DDL, `None` if it is not applied in the scope of [`with_audit_principal`].
+    pub principal: Option<String>,
+
+    /// When the DDL is applied.
+    pub timestamp: DateTime<Utc>,
+
+    /// The id of the database or table the meta-service returns, if the reply contains one.
+    pub object_id: Option<MetaId>,
+}
+
+/// Receives an event for every DDL a catalog applies, e.g., to write an audit log.
+///
+/// `record()` is called after the DDL succeeds, a failed DDL is not recorded.
+/// It is awaited before the DDL returns, thus a slow sink slows down every DDL.
+#[async_trait::async_trait]
+pub trait AuditSink: Send + Sync + Debug {
+    async fn record(&self, event: CatalogAuditEvent);
+}
@@ -0,0 +1,134 @@
+// Copyright 2021 Datafuse Labs
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::fmt;
+use std::fmt::Display;
+use std::fmt::Formatter;
+
+use databend_common_meta_app::schema::DBIdTableName;
+use databend_common_meta_app::schema::DatabaseNameIdent;
+use databend_common_meta_types::MetaId;
+
+/// A record in the meta-service that refers to an object that does not exist,
+/// or an object that misses a record referring to it.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Inconsistency {
+    /// A database name maps to a database id that has no meta.
+    /// Repaired by removing the name.
+    DanglingDatabaseName {
+        name: DatabaseNameIdent,
+        db_id: MetaId,
+    },
+
+    /// The `db_id -> name` index of a database that has no meta.
+    /// Repaired by removing the index entry.
+    DanglingDatabaseIdToName { db_id: MetaId },
+
+    /// A table name belongs to a database id that has no meta.
+    /// Repaired by removing the name.
+    OrphanTableName {
+        name: DBIdTableName,
+        table_id: MetaId,
+    },
+
+    /// A table name maps to a table id that has no meta.
+    /// Repaired by removing the name.
+    DanglingTableName {
+        name: DBIdTableName,
+        table_id: MetaId,
+    },
+
+    /// The `table_id -> name` index of a table that has no meta.
+    /// Repaired by removing the index entry.
+    DanglingTableIdToName {
+        table_id: MetaId,
+        name: DBIdTableName,
+    },
+
+    /// A table is resolvable by its name but not by its id.
+    /// Repaired by adding the `table_id -> name` index entry.
+    MissingTableIdToName {
+        table_id: MetaId,
+        name: DBIdTableName,
+    },
+}
+
+impl Display for Inconsistency {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Inconsistency::DanglingDatabaseName { name, db_id } => {
+                write!(f, "database {} refers to absent db id {}", name, db_id)
+            }
+            Inconsistency::DanglingDatabaseIdToName { db_id } => {
+                write!(f, "id-to-name index of absent db id {}", db_id)
+            }
+            Inconsistency::OrphanTableName { name, table_id } => {
+                write!(
+                    f,
+                    "table {} (table id {}) is in absent db id {}",
+                    name, table_id, name.db_id
+                )
+            }
+            Inconsistency::DanglingTableName { name, table_id } => {
+                write!(f, "table {} refers to absent table id {}", name, table_id)
+            }
+            Inconsistency::DanglingTableIdToName { table_id, name } => {
+                write!(
+                    f,
+                    "id-to-name index of absent table id {} refers to {}",
+                    table_id, name
+                )
+            }
+            Inconsistency::MissingTableIdToName { table_id, name } => {
+                write!(
+                    f,
+                    "table {} has no id-to-name index for table id {}",
+                    name, table_id
+                )
+            }
+        }
+    }
+}
+
+/// An [`Inconsistency`] found by `Catalog::check_consistency()`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ConsistencyIssue {
+    pub inconsistency: Inconsistency,
+
+    /// Whether it is repaired.
+    ///
+    /// It is `false` if a repair is not asked for,
+    /// or if the involved records are changed by others since they are checked.
+    pub repaired: bool,
+}
+
+/// The result of `Catalog::check_consistency()`.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ConsistencyReport {
+    pub issues: Vec<ConsistencyIssue>,
+}
+
+impl ConsistencyReport {
+    pub fn is_consistent(&self) -> bool {
+        self.issues.is_empty()
+    }
+
+    /// The inconsistencies that are left in the meta-service.
+    pub fn unrepaired(&self) -> impl Iterator<Item = &Inconsistency> {
+        self.issues
+            .iter()
+            .filter(|x| !x.repaired)
+            .map(|x| &x.inconsistency)
+    }
+}
@@ -0,0 +1,102 @@
+// Copyright 2021 Datafuse Labs
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use databend_common_expression::TableField;
+use databend_common_meta_app::schema::TableInfo;
+use databend_common_meta_types::MetaId;
+use serde::Deserialize;
+use serde::Serialize;
+
+/// The description of a table for tools outside of databend-query.
+///
+/// Unlike `TableMeta`, it is a contract: a field is never renamed or removed within the same
+/// [`TableDescription::FORMAT_VERSION`], a type is rendered as its SQL name.
+/// A consumer should check `format_version` before reading the other fields.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TableDescription {
+    pub format_version: u32,
+
+    pub table_id: MetaId,
+    pub db_name: String,
+    pub table_name: String,
+    pub engine: String,
+
+    /// Bumped every time the schema is altered.
+    pub schema_version: u64,
+
+    pub comment: String,
+    pub columns: Vec<ColumnDescription>,
+}
+
+/// A column in a [`TableDescription`].
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ColumnDescription {
+    pub name: String,
+
+    /// The SQL name of the type without nullability, e.g., `BIGINT` or `ARRAY(STRING)`.
+    pub data_type: String,
+    pub nullable: bool,
+
+    /// The default value expression, `None` if the column has none.
+    pub default_expr: Option<String>,
+
+    /// The expression of a computed column.
+    pub computed_expr: Option<String>,
+
+    pub comment: String,
+}
+
+impl TableDescription {
+    /// The version of the layout of a description; it is bumped on an incompatible change.
+    pub const FORMAT_VERSION: u32 = 1;
+
+    pub fn new(db_name: impl ToString, table_info: &TableInfo) -> Self {
+        let meta = &table_info.meta;
+
+        let columns = meta
+            .schema
+            .fields()
+            .iter()
+            .enumerate()
+            .map(|(i, field)| {
+                let comment = meta.field_comments.get(i).cloned().unwrap_or_default();
+                ColumnDescription::new(field, comment)
+            })
+            .collect();
+
+        Self {
+            format_version: Self::FORMAT_VERSION,
+            table_id: table_info.ident.table_id,
+            db_name: db_name.to_string(),
+            table_name: table_info.name.clone(),
+            engine: meta.engine.clone(),
+            schema_version: meta.schema_version,
+            comment: meta.comment.clone(),
+            columns,
+        }
+    }
+}
+
+impl ColumnDescription {
+    pub fn new(field: &TableField, comment: String) -> Self {
+        Self {
+            name: field.name().clone(),
+            data_type: field.data_type().remove_nullable().sql_name(),
+            nullable: field.is_nullable(),
+            default_expr: field.default_expr().cloned(),
+            computed_expr: field.computed_expr().map(|e| e.expr().clone()),
+            comment,
+        }
+    }
+}
@@ -0,0 +1,69 @@
+// Copyright 2021 Datafuse Labs
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use databend_common_meta_types::MetaId;
+
+use crate::catalog::CatalogAuditEvent;
+use crate::catalog::CatalogAuditOp;
+
+/// A DDL change broadcast to the subscribers of a catalog, e.g., to invalidate a cache.
+///
+/// An event is built either by the catalog that applies the DDL,
+/// or from a change pushed by the meta-service, which may be applied by another query node.
+/// In the latter case only the id and the seq of the changed object are known, not its names.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CatalogEvent {
+    pub op: CatalogAuditOp,
+
+    /// `None` if the event is pushed by the meta-service.
+    pub tenant: Option<String>,
+
+    pub db_name: Option<String>,
+
+    /// `None` for a database operation.
+    pub table_name: Option<String>,
+
+    /// The id of the database or table, if it is known.
+    pub object_id: Option<MetaId>,
+
+    /// The seq of the changed record in the meta-service, if it is known.
+    pub seq: Option<u64>,
+}
+
+impl From<&CatalogAuditEvent> for CatalogEvent {
+    fn from(ev: &CatalogAuditEvent) -> Self {
+        Self {
+            op: ev.op,
+            tenant: Some(ev.tenant.clone()),
+            db_name: ev.db_name.clone(),
+            table_name: ev.table_name.clone(),
+            object_id: ev.object_id,
+            seq: None,
+        }
+    }
+}
+
+/// An item broadcast to the subscribers of a catalog.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum CatalogNotification {
+    /// A DDL change.
+    Event(CatalogEvent),
+
+    /// This many changes pushed by the meta-service are lost because the catalog did not keep up,
+    /// or `0` if the number is unknown, e.g., after the watch stream is re-established.
+    ///
+    /// A subscriber should discard everything it derives from the catalog, e.g., a cache,
+    /// since it does not know which objects are changed.
+    Lagged(u64),
+}
@@ -14,10 +14,17 @@
 
 use std::any::Any;
 use std::fmt::Debug;
+use std::ops::Range;
 use std::sync::Arc;
+use std::time::Duration;
 
+use chrono::DateTime;
+use chrono::Utc;
 use databend_common_exception::ErrorCode;
 use databend_common_exception::Result;
+use databend_common_expression::Scalar;
+use databend_common_expression::TableField;
+use databend_common_meta_api::txn_backoff::txn_backoff;
 use databend_common_meta_app::schema::CatalogInfo;
 use databend_common_meta_app::schema::CountTablesReply;
 use databend_common_meta_app::schema::CountTablesReq;
@@ -31,6 +38,7 @@ use databend_common_meta_app::schema::CreateTableReply;
 use databend_common_meta_app::schema::CreateTableReq;
 use databend_common_meta_app::schema::CreateVirtualColumnReply;
 use databend_common_meta_app::schema::CreateVirtualColumnReq;
+use databend_common_meta_app::schema::DatabaseNameIdent;
 use databend_common_meta_app::schema::DeleteLockRevReq;
 use databend_common_meta_app::schema::DropDatabaseReply;
 use databend_common_meta_app::schema::DropDatabaseReq;
@@ -61,11 +69,16 @@ use databend_common_meta_app::schema::RenameDatabaseReply;
 use databend_common_meta_app::schema::RenameDatabaseReq;
 use databend_common_meta_app::schema::RenameTableReply;
 use databend_common_meta_app::schema::RenameTableReq;
+use databend_common_meta_app::schema::SetDatabaseQuotaReply;
+use databend_common_meta_app::schema::SetDatabaseQuotaReq;
 use databend_common_meta_app::schema::SetTableColumnMaskPolicyReply;
 use databend_common_meta_app::schema::SetTableColumnMaskPolicyReq;
 use databend_common_meta_app::schema::TableIdent;
 use databend_common_meta_app::schema::TableInfo;
+use databend_common_meta_app::schema::TableInfoFilter;
 use databend_common_meta_app::schema::TableMeta;
+use databend_common_meta_app::schema::TableNameIdent;
+use databend_common_meta_app::schema::TableStatistics;
 use databend_common_meta_app::schema::TruncateTableReply;
 use databend_common_meta_app::schema::TruncateTableReq;
 use databend_common_meta_app::schema::UndropDatabaseReply;
@@ -81,13 +94,21 @@ use databend_common_meta_app::schema::UpdateVirtualColumnReq;
 use databend_common_meta_app::schema::UpsertTableOptionReply;
 use databend_common_meta_app::schema::UpsertTableOptionReq;
 use databend_common_meta_app::schema::VirtualColumnMeta;
+use databend_common_meta_kvapi::kvapi;
+use databend_common_meta_types::MatchSeq;
 use databend_common_meta_types::MetaId;
+use databend_common_meta_types::TxnRequest;
+use databend_storages_common_table_meta::table::OPT_KEY_LEGACY_SNAPSHOT_LOC;
+use databend_storages_common_table_meta::table::OPT_KEY_SNAPSHOT_LOCATION;
 use dyn_clone::DynClone;
 
+use crate::catalog::ConsistencyReport;
+use crate::catalog::TableDescription;
 use crate::database::Database;
 use crate::table::Table;
 use crate::table_args::TableArgs;
 use crate::table_function::TableFunction;
+use crate::table_function::TableFunctionMeta;
 
 #[derive(Default, Clone)]
 pub struct StorageDescription {
@@ -96,6 +117,49 @@ pub struct StorageDescription {
     pub support_cluster_key: bool,
 }
 
+/// What a `drop_table_by_id` would do, without doing it. See [`Catalog::plan_drop_table_by_id`].
+#[derive(Clone, Debug)]
+pub struct DropTablePlan {
+    /// The catalog that the drop is routed to, e.g., `MutableCatalog` in the default catalog.
+    pub routed_to: String,
+
+    /// The transaction to submit to the meta-service.
+    /// `None` if there is nothing to drop.
+    pub txn: Option<TxnRequest>,
+}
+
+/// The statistics of a database aggregated from the statistics of its tables.
+/// See [`Catalog::get_database_stats`].
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct DatabaseStats {
+    pub number_of_tables: u64,
+    pub number_of_rows: u64,
+    pub data_bytes: u64,
+    pub compressed_data_bytes: u64,
+    pub index_data_bytes: u64,
+
+    /// The latest time any of the tables was updated.
+    /// `None` if there is no table.
+    pub updated_on: Option<DateTime<Utc>>,
+}
+
+/// How up to date a read must be. See [`Catalog::get_table_consistent`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ReadConsistency {
+    /// A value cached by the catalog may be returned, it may be stale.
+    #[default]
+    Cached,
+
+    /// Bypass the caches of the catalog, and read from the backend.
+    ///
+    /// It still may not see a change that is just committed, if the backend serves the read before applying it.
+    Fresh,
+
+    /// Read from the backend, after a round trip through a quorum of it,
+    /// so that the read sees every change committed before it starts.
+    Linearizable,
+}
+
 pub trait CatalogCreator: Send + Sync + Debug {
     fn try_create(&self, info: &CatalogInfo) -> Result<Arc<dyn Catalog>>;
 }
@@ -117,6 +181,60 @@ pub trait Catalog: DynClone + Send + Sync + Debug {
     // Get all the databases.
     async fn list_databases(&self, tenant: &str) -> Result<Vec<Arc<dyn Database>>>;
 
+    /// List at most `limit` databases sorted by name.
+    ///
+    /// Only databases whose name is greater than `after` and starts with `name_prefix` are returned,
+    /// so that the last name of a page is the cursor `after` for the next page.
+    #[async_backtrace::framed]
+    async fn list_databases_paged(
+        &self,
+        tenant: &str,
+        after: Option<&str>,
+        limit: usize,
+        name_prefix: Option<&str>,
+    ) -> Result<Vec<Arc<dyn Database>>> {
+        let range = name_prefix.map(kvapi::prefix_to_open_range);
+
+        let mut dbs = self.list_databases(tenant).await?;
+
+        dbs.retain(|db| {
+            let name = db.name();
+            let in_range = match &range {
+                Some((start, end)) => {
+                    start.as_str() <= name && end.as_ref().map_or(true, |end| name < end.as_str())
+                }
+                None => true,
+            };
+            in_range && after.map_or(true, |after| name > after)
+        });
+        dbs.sort_by(|a, b| a.name().cmp(b.name()));
+        dbs.truncate(limit);
+
+        Ok(dbs)
+    }
+
+    /// Sum up the statistics of every table in a database, as they are recorded in the table meta.
+    ///
+    /// The tables are listed with one prefix scan, without reading any table data.
+    #[async_backtrace::framed]
+    async fn get_database_stats(&self, tenant: &str, db_name: &str) -> Result<DatabaseStats> {
+        let tables = self.list_tables(tenant, db_name).await?;
+
+        let mut stats = DatabaseStats::default();
+        for table in tables {
+            let meta = &table.get_table_info().meta;
+
+            stats.number_of_tables += 1;
+            stats.number_of_rows += meta.statistics.number_of_rows;
+            stats.data_bytes += meta.statistics.data_bytes;
+            stats.compressed_data_bytes += meta.statistics.compressed_data_bytes;
+            stats.index_data_bytes += meta.statistics.index_data_bytes;
+            stats.updated_on = stats.updated_on.max(Some(meta.updated_on));
+        }
+
+        Ok(stats)
+    }
+
     // Operation with database.
     async fn create_database(&self, req: CreateDatabaseReq) -> Result<CreateDatabaseReply>;
 
@@ -177,6 +295,32 @@ pub trait Catalog: DynClone + Send + Sync + Debug {
 
     async fn rename_database(&self, req: RenameDatabaseReq) -> Result<RenameDatabaseReply>;
 
+    /// Rename the database `from` to `to`, it fails if `from` does not exist or `to` exists.
+    ///
+    /// Only the name of the database is changed, in a single meta-service transaction:
+    /// the tables refer to the database by its id, thus they are resolvable under `to`
+    /// with the same ids right after it returns.
+    #[async_backtrace::framed]
+    async fn rename_database_by_name(&self, tenant: &str, from: &str, to: &str) -> Result<()> {
+        self.rename_database(RenameDatabaseReq {
+            if_exists: false,
+            name_ident: DatabaseNameIdent {
+                tenant: tenant.to_string(),
+                db_name: from.to_string(),
+            },
+            new_db_name: to.to_string(),
+        })
+        .await?;
+        Ok(())
+    }
+
+    /// Set or remove the max number of tables a database can hold.
+    async fn set_database_quota(&self, _req: SetDatabaseQuotaReq) -> Result<SetDatabaseQuotaReply> {
+        Err(ErrorCode::Unimplemented(
+            "'set_database_quota' not implemented",
+        ))
+    }
+
     /// Table.
 
     // Build a `Arc<dyn Table>` from `TableInfo`.
@@ -188,6 +332,24 @@ pub trait Catalog: DynClone + Send + Sync + Debug {
     // Get the table name by meta id.
     async fn get_table_name_by_id(&self, table_id: MetaId) -> Result<String>;
 
+    // Get one table by meta id.
+    //
+    // The id is resolved with the `table_id -> name` index that is kept up to date by
+    // create, drop and rename, thus a renamed table is still found by its id.
+    #[async_backtrace::framed]
+    async fn get_table_by_id(&self, table_id: MetaId) -> Result<Arc<dyn Table>> {
+        let (ident, meta) = self.get_table_meta_by_id(table_id).await?;
+        let name = self.get_table_name_by_id(table_id).await?;
+        let table_info = TableInfo {
+            ident,
+            desc: "".to_owned(),
+            name,
+            meta: meta.as_ref().clone(),
+            ..TableInfo::default()
+        };
+        self.get_table_by_info(&table_info)
+    }
+
     // Get the db name by meta id.
     async fn get_db_name_by_id(&self, db_id: MetaId) -> databend_common_exception::Result<String>;
 
@@ -199,10 +361,86 @@ pub trait Catalog: DynClone + Send + Sync + Debug {
         table_name: &str,
     ) -> Result<Arc<dyn Table>>;
 
+    /// Get one table by db and table name, at least as up to date as `level` requires.
+    ///
+    /// The default implementation serves `Cached` and `Fresh` with `get_table()`, i.e., it has no cache.
+    #[async_backtrace::framed]
+    async fn get_table_consistent(
+        &self,
+        tenant: &str,
+        db_name: &str,
+        table_name: &str,
+        level: ReadConsistency,
+    ) -> Result<Arc<dyn Table>> {
+        match level {
+            ReadConsistency::Cached | ReadConsistency::Fresh => {
+                self.get_table(tenant, db_name, table_name).await
+            }
+            ReadConsistency::Linearizable => Err(ErrorCode::Unimplemented(
+                "'get_table_consistent' with Linearizable not implemented",
+            )),
+        }
+    }
+
+    /// Describe a table in the stable format of [`TableDescription`], for tools that introspect schemas.
+    #[async_backtrace::framed]
+    async fn describe_table(
+        &self,
+        tenant: &str,
+        db_name: &str,
+        table_name: &str,
+    ) -> Result<TableDescription> {
+        let table = self.get_table(tenant, db_name, table_name).await?;
+        Ok(TableDescription::new(db_name, table.get_table_info()))
+    }
+
     async fn list_tables(&self, tenant: &str, db_name: &str) -> Result<Vec<Arc<dyn Table>>>;
     async fn list_tables_history(&self, tenant: &str, db_name: &str)
     -> Result<Vec<Arc<dyn Table>>>;
 
+    /// List the tables in a db whose names start with `name_prefix`, sorted by name.
+    ///
+    /// An empty `name_prefix` lists all tables.
+    /// The prefix is passed down to the database, which may scan only the tables with it.
+    #[async_backtrace::framed]
+    async fn list_tables_by_prefix(
+        &self,
+        tenant: &str,
+        db_name: &str,
+        name_prefix: &str,
+    ) -> Result<Vec<Arc<dyn Table>>> {
+        let db = self.get_database(tenant, db_name).await?;
+        db.list_tables_by_prefix(name_prefix).await
+    }
+
+    // Get the tables of the given names in a db, a table that does not exist is skipped.
+    #[async_backtrace::framed]
+    async fn mget_tables(
+        &self,
+        tenant: &str,
+        db_name: &str,
+        table_names: &[String],
+    ) -> Result<Vec<Arc<dyn Table>>> {
+        let mut tables = Vec::with_capacity(table_names.len());
+        for table_name in table_names {
+            match self.get_table(tenant, db_name, table_name).await {
+                Ok(table) => tables.push(table),
+                Err(e) if e.code() == ErrorCode::UNKNOWN_TABLE => {}
+                Err(e) => return Err(e),
+            }
+        }
+        Ok(tables)
+    }
+
+    /// Load the tables of the given names in a db in a batch, so that a following `get_table` of them is served from cache.
+    ///
+    /// A table that does not exist is skipped.
+    #[async_backtrace::framed]
+    async fn warm_tables(&self, tenant: &str, db_name: &str, tables: &[String]) -> Result<()> {
+        self.mget_tables(tenant, db_name, tables).await?;
+        Ok(())
+    }
+
     async fn get_drop_table_infos(
         &self,
         _req: ListDroppedTableReq,
@@ -216,10 +454,149 @@ pub trait Catalog: DynClone + Send + Sync + Debug {
         Err(ErrorCode::Unimplemented("'gc_drop_tables' not implemented"))
     }
 
+    // Scan the database and table records in the meta-service of all tenants,
+    // and report the names and indexes that refer to absent objects,
+    // or the tables that miss an index, e.g., left by a crash of an old version.
+    //
+    // With `repair`, every inconsistency is fixed in its own transaction, which is only
+    // applied if the involved records are not changed since they are scanned.
+    async fn check_consistency(&self, _repair: bool) -> Result<ConsistencyReport> {
+        Err(ErrorCode::Unimplemented(
+            "'check_consistency' not implemented",
+        ))
+    }
+
+    // Remove the meta of the tables that are dropped more than `older_than` ago,
+    // in all databases of `tenant`. Returns the number of purged tables.
+    //
+    // A dropped table is kept until it is purged, and can be restored with `undrop_table()`
+    // before that. Unlike `VACUUM DROP TABLE`, the data files of the tables are not removed.
+    #[async_backtrace::framed]
+    async fn purge_dropped_tables(&self, tenant: &str, older_than: Duration) -> Result<u64> {
+        let older_than = chrono::Duration::from_std(older_than)
+            .map_err(|e| ErrorCode::BadArguments(format!("invalid older_than: {}", e)))?;
+        let retention_time = chrono::Utc::now() - older_than;
+
+        let (tables, drop_ids) = self
+            .get_drop_table_infos(ListDroppedTableReq {
+                inner: DatabaseNameIdent {
+                    tenant: tenant.to_string(),
+                    db_name: "".to_string(),
+                },
+                filter: TableInfoFilter::AllDroppedTables(Some(retention_time)),
+                limit: None,
+            })
+            .await?;
+
+        if drop_ids.is_empty() {
+            return Ok(0);
+        }
+
+        self.gc_drop_tables(GcDroppedTableReq {
+            tenant: tenant.to_string(),
+            drop_ids,
+        })
+        .await?;
+
+        Ok(tables.len() as u64)
+    }
+
     async fn create_table(&self, req: CreateTableReq) -> Result<CreateTableReply>;
 
+    /// Reserve `n` contiguous table ids in one meta-service round trip, for the tables created afterwards.
+    ///
+    /// The returned ids are used only by the caller, as the `table_id` of a `CreateTableReq`.
+    /// It also makes a `create_table()` without a `table_id` take an id from a block of `n` ids,
+    /// which is reserved in one round trip when the previous block is exhausted,
+    /// thus a bulk creation of tables does not need a round trip per table to allocate an id.
+    /// An id taken from a block by a failed creation is given back, and taken by the next one.
+    /// The block size is kept until the next call changes `n`: `n == 0` reserves nothing and stops reserving.
+    #[async_backtrace::framed]
+    async fn reserve_table_ids(&self, _n: u64) -> Result<Range<u64>> {
+        Err(ErrorCode::Unimplemented(
+            "'reserve_table_ids' not implemented",
+        ))
+    }
+
+    /// Create a table unless a table with the same name exists, in a single meta-service transaction.
+    ///
+    /// It returns `true` if the table is created, or `false` if it already exists, which is left untouched.
+    #[async_backtrace::framed]
+    async fn create_table_if_not_exists(
+        &self,
+        name_ident: TableNameIdent,
+        table_meta: TableMeta,
+    ) -> Result<bool> {
+        let reply = self
+            .create_table(CreateTableReq {
+                if_not_exists: true,
+                table_id: None,
+                name_ident,
+                table_meta,
+            })
+            .await?;
+        Ok(reply.new_table)
+    }
+
+    /// Create table `dst_table_name` with a copy of the meta of `src_table_name`, in the same database.
+    ///
+    /// The new table gets a new table id. It fails if `dst_table_name` exists.
+    /// The copy is created in a single meta-service transaction, thus a failed copy leaves nothing behind.
+    ///
+    /// With `with_data`, the new table refers to the snapshot of the source table, and shares its data files,
+    /// otherwise it is created empty.
+    /// The data of an engine that does not keep it in the table meta, such as `MEMORY`, is never copied.
+    #[async_backtrace::framed]
+    async fn copy_table(
+        &self,
+        tenant: &str,
+        db_name: &str,
+        src_table_name: &str,
+        dst_table_name: &str,
+        with_data: bool,
+    ) -> Result<()> {
+        let src = self.get_table(tenant, db_name, src_table_name).await?;
+
+        let mut table_meta = src.get_table_info().meta.clone();
+
+        let now = Utc::now();
+        table_meta.created_on = now;
+        table_meta.updated_on = now;
+        table_meta.drop_on = None;
+        // A share grants access to the source table only.
+        table_meta.shared_by.clear();
+
+        if !with_data {
+            table_meta.options.remove(OPT_KEY_SNAPSHOT_LOCATION);
+            table_meta.options.remove(OPT_KEY_LEGACY_SNAPSHOT_LOC);
+            table_meta.statistics = TableStatistics::default();
+        }
+
+        self.create_table(CreateTableReq {
+            if_not_exists: false,
+            table_id: None,
+            name_ident: TableNameIdent {
+                tenant: tenant.to_string(),
+                db_name: db_name.to_string(),
+                table_name: dst_table_name.to_string(),
+            },
+            table_meta,
+        })
+        .await?;
+
+        Ok(())
+    }
+
     async fn drop_table_by_id(&self, req: DropTableByIdReq) -> Result<DropTableReply>;
 
+    /// Dry-run of `drop_table_by_id`: returns the transaction it would submit, without submitting it.
+    #[async_backtrace::framed]
+    async fn plan_drop_table_by_id(&self, _req: DropTableByIdReq) -> Result<DropTablePlan> {
+        Err(ErrorCode::Unimplemented(
+            "'plan_drop_table_by_id' not implemented",
+        ))
+    }
+
     async fn undrop_table(&self, req: UndropTableReq) -> Result<UndropTableReply>;
 
     async fn rename_table(&self, req: RenameTableReq) -> Result<RenameTableReply>;
@@ -230,7 +607,9 @@ pub trait Catalog: DynClone + Send + Sync + Debug {
         match self.get_table(tenant, db_name, table_name).await {
             Ok(_) => Ok(true),
             Err(err) => {
-                if err.code() == ErrorCode::UNKNOWN_TABLE {
+                if err.code() == ErrorCode::UNKNOWN_TABLE
+                    || err.code() == ErrorCode::UNKNOWN_DATABASE
+                {
                     Ok(false)
                 } else {
                     Err(err)
@@ -239,6 +618,17 @@ pub trait Catalog: DynClone + Send + Sync + Debug {
         }
     }
 
+    /// Get the `schema_version` of a table, which is bumped every time its columns are altered.
+    async fn get_table_schema_version(
+        &self,
+        tenant: &str,
+        db_name: &str,
+        table_name: &str,
+    ) -> Result<u64> {
+        let table = self.get_table(tenant, db_name, table_name).await?;
+        Ok(table.get_table_info().meta.schema_version)
+    }
+
     async fn upsert_table_option(
         &self,
         tenant: &str,
@@ -252,6 +642,112 @@ pub trait Catalog: DynClone + Send + Sync + Debug {
         req: UpdateTableMetaReq,
     ) -> Result<UpdateTableMetaReply>;
 
+    /// Add a column to the end of the schema of a table, and bump the `schema_version`.
+    ///
+    /// The new table meta is written only if the table meta has not changed since it was read,
+    /// otherwise it is read again and retried, thus no concurrent schema change is lost.
+    /// `default` is the default value of the column, which replaces the `default_expr` of `field` if it is `Some`.
+    ///
+    /// Adding a column with the name of an existing column fails with `AddColumnExistError`.
+    #[async_backtrace::framed]
+    async fn alter_table_add_column(
+        &self,
+        tenant: &str,
+        db_name: &str,
+        table_name: &str,
+        field: TableField,
+        default: Option<Scalar>,
+    ) -> Result<()> {
+        let field = match default {
+            Some(v) => field.with_default_expr(Some(v.to_string())),
+            None => field,
+        };
+
+        let mut trials = txn_backoff(None, "alter_table_add_column");
+        loop {
+            trials.next().unwrap()?.await;
+
+            let table = self.get_table(tenant, db_name, table_name).await?;
+            let table_info = table.get_table_info();
+
+            // `get_table()` may be served from cache, read the latest meta to check the seq against.
+            let (ident, meta) = self.get_table_meta_by_id(table_info.ident.table_id).await?;
+
+            let mut new_table_meta = meta.as_ref().clone();
+            let index = new_table_meta.schema.num_fields();
+            new_table_meta.add_column(&field, "", index)?;
+
+            let req = UpdateTableMetaReq {
+                table_id: ident.table_id,
+                seq: MatchSeq::Exact(ident.seq),
+                new_table_meta,
+                copied_files: None,
+                update_stream_meta: vec![],
+                deduplicated_label: None,
+            };
+
+            match self.update_table_meta(table_info, req).await {
+                Ok(_) => return Ok(()),
+                Err(e) if e.code() == ErrorCode::TABLE_VERSION_MISMATCHED => continue,
+                Err(e) => return Err(e),
+            }
+        }
+    }
+
+    /// Recompute the statistics of a table from its data and write them back to the table meta.
+    ///
+    /// It is for correcting the statistics after they drift from the data,
+    /// e.g., the data is changed by an operation that bypasses the normal write path.
+    /// The table meta is updated only if it is still the version the statistics are computed from,
+    /// otherwise they are computed again.
+    ///
+    /// It returns the refreshed statistics,
+    /// or `Unimplemented` if the table engine does not support recomputing them.
+    #[async_backtrace::framed]
+    async fn refresh_statistics(
+        &self,
+        tenant: &str,
+        db_name: &str,
+        table_name: &str,
+    ) -> Result<TableStatistics> {
+        let mut trials = txn_backoff(None, "refresh_statistics");
+        loop {
+            trials.next().unwrap()?.await;
+
+            let table = self.get_table(tenant, db_name, table_name).await?;
+            let table_info = table.get_table_info();
+
+            let statistics = table.recompute_statistics().await?.ok_or_else(|| {
+                ErrorCode::Unimplemented(format!(
+                    "Refreshing statistics is not supported for the table '{}', which uses the '{}' engine.",
+                    table_name,
+                    table.engine()
+                ))
+            })?;
+
+            // `get_table()` may be served from cache, read the latest meta to check the seq against.
+            let (ident, meta) = self.get_table_meta_by_id(table_info.ident.table_id).await?;
+
+            let mut new_table_meta = meta.as_ref().clone();
+            new_table_meta.statistics = statistics.clone();
+
+            let req = UpdateTableMetaReq {
+                table_id: ident.table_id,
+                seq: MatchSeq::Exact(ident.seq),
+                new_table_meta,
+                copied_files: None,
+                update_stream_meta: vec![],
+                deduplicated_label: None,
+            };
+
+            match self.update_table_meta(table_info, req).await {
+                Ok(_) => return Ok(statistics),
+                Err(e) if e.code() == ErrorCode::TABLE_VERSION_MISMATCHED => continue,
+                Err(e) => return Err(e),
+            }
+        }
+    }
+
     async fn set_table_column_mask_policy(
         &self,
         req: SetTableColumnMaskPolicyReq,
@@ -299,9 +795,9 @@ pub trait Catalog: DynClone + Send + Sync + Debug {
         false
     }
 
-    // List all table functions' names.
-    fn list_table_functions(&self) -> Vec<String> {
-        unimplemented!()
+    // List all table functions with their signatures, sorted by name.
+    fn list_table_functions(&self) -> Vec<TableFunctionMeta> {
+        vec![]
     }
 
     fn as_any(&self) -> &dyn Any;
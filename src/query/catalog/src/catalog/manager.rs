@@ -38,6 +38,8 @@ use databend_common_meta_store::MetaStoreProvider;
 
 use super::Catalog;
 use super::CatalogCreator;
+use crate::database::Database;
+use crate::table::Table;
 
 pub const CATALOG_DEFAULT: &str = "default";
 
@@ -170,6 +172,46 @@ impl CatalogManager {
         self.build_catalog(&info)
     }
 
+    /// Get a catalog by an optional name, the DEFAULT catalog is returned if it is `None`.
+    #[async_backtrace::framed]
+    pub async fn get_catalog_or_default(
+        &self,
+        tenant: &str,
+        catalog_name: Option<&str>,
+    ) -> Result<Arc<dyn Catalog>> {
+        match catalog_name {
+            None => self.get_default_catalog(),
+            Some(name) => self.get_catalog(tenant, name).await,
+        }
+    }
+
+    /// Get a database addressed by `[catalog.]db`.
+    ///
+    /// Databases with the same name in different catalogs are told apart by the catalog name.
+    #[async_backtrace::framed]
+    pub async fn get_database(
+        &self,
+        tenant: &str,
+        catalog_name: Option<&str>,
+        db_name: &str,
+    ) -> Result<Arc<dyn Database>> {
+        let catalog = self.get_catalog_or_default(tenant, catalog_name).await?;
+        catalog.get_database(tenant, db_name).await
+    }
+
+    /// Get a table addressed by `[catalog.]db.table`.
+    #[async_backtrace::framed]
+    pub async fn get_table(
+        &self,
+        tenant: &str,
+        catalog_name: Option<&str>,
+        db_name: &str,
+        table_name: &str,
+    ) -> Result<Arc<dyn Table>> {
+        let catalog = self.get_catalog_or_default(tenant, catalog_name).await?;
+        catalog.get_table(tenant, db_name, table_name).await
+    }
+
     /// Create a new catalog.
     ///
     /// # NOTES
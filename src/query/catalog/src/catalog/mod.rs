@@ -13,12 +13,31 @@
 // limitations under the License.
 
 /// catalog_interface is the module defining `Catalog` trait
+mod audit;
+mod consistency;
+mod description;
+mod event;
 mod interface;
 /// the catalog manager implementation
 mod manager;
 
+pub use audit::audit_principal;
+pub use audit::with_audit_principal;
+pub use audit::AuditSink;
+pub use audit::CatalogAuditEvent;
+pub use audit::CatalogAuditOp;
+pub use consistency::ConsistencyIssue;
+pub use consistency::ConsistencyReport;
+pub use consistency::Inconsistency;
+pub use description::ColumnDescription;
+pub use description::TableDescription;
+pub use event::CatalogEvent;
+pub use event::CatalogNotification;
 pub use interface::Catalog;
 pub use interface::CatalogCreator;
+pub use interface::DatabaseStats;
+pub use interface::DropTablePlan;
+pub use interface::ReadConsistency;
 pub use interface::StorageDescription;
 pub use manager::CatalogManager;
 pub use manager::CATALOG_DEFAULT;
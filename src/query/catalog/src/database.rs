@@ -38,6 +38,9 @@ use databend_common_meta_app::schema::UpdateTableMetaReq;
 use databend_common_meta_app::schema::UpsertTableOptionReply;
 use databend_common_meta_app::schema::UpsertTableOptionReq;
 use dyn_clone::DynClone;
+use futures::stream::BoxStream;
+use futures::StreamExt;
+use futures::TryStreamExt;
 
 use crate::table::Table;
 
@@ -91,6 +94,20 @@ pub trait Database: DynClone + Sync + Send {
         )))
     }
 
+    // Get the tables of the given names, a table that does not exist is skipped.
+    #[async_backtrace::framed]
+    async fn mget_tables(&self, table_names: &[String]) -> Result<Vec<Arc<dyn Table>>> {
+        let mut tables = Vec::with_capacity(table_names.len());
+        for table_name in table_names {
+            match self.get_table(table_name).await {
+                Ok(table) => tables.push(table),
+                Err(e) if e.code() == ErrorCode::UNKNOWN_TABLE => {}
+                Err(e) => return Err(e),
+            }
+        }
+        Ok(tables)
+    }
+
     #[async_backtrace::framed]
     async fn list_tables(&self) -> Result<Vec<Arc<dyn Table>>> {
         Err(ErrorCode::Unimplemented(format!(
@@ -99,6 +116,36 @@ pub trait Database: DynClone + Sync + Send {
         )))
     }
 
+    // List the tables whose names start with `name_prefix`, sorted by name.
+    //
+    // The default implementation filters the tables returned by `list_tables()`.
+    #[async_backtrace::framed]
+    async fn list_tables_by_prefix(&self, name_prefix: &str) -> Result<Vec<Arc<dyn Table>>> {
+        let mut tables = self.list_tables().await?;
+        tables.retain(|t| t.name().starts_with(name_prefix));
+        tables.sort_by(|a, b| a.name().cmp(b.name()));
+        Ok(tables)
+    }
+
+    // List the tables lazily, in the same order as `list_tables()`.
+    //
+    // The default implementation yields the tables returned by `list_tables()`.
+    fn list_tables_stream(&self) -> BoxStream<'_, Result<Arc<dyn Table>>> {
+        futures::stream::once(self.list_tables())
+            .map_ok(|tables| futures::stream::iter(tables.into_iter().map(Ok)))
+            .try_flatten()
+            .boxed()
+    }
+
+    // Get the number of tables, not including the dropped ones.
+    //
+    // The default implementation counts the tables returned by `list_tables()`.
+    #[async_backtrace::framed]
+    async fn table_count(&self) -> Result<u64> {
+        let tables = self.list_tables().await?;
+        Ok(tables.len() as u64)
+    }
+
     #[async_backtrace::framed]
     async fn list_tables_history(&self) -> Result<Vec<Arc<dyn Table>>> {
         Err(ErrorCode::Unimplemented(format!(
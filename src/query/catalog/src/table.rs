@@ -281,6 +281,17 @@ pub trait Table: Sync + Send {
         Ok(None)
     }
 
+    /// Compute the statistics that are recorded in the table meta from the data of the table,
+    /// instead of returning the recorded ones.
+    ///
+    /// It returns `None` if the engine does not support it.
+    #[async_backtrace::framed]
+    async fn recompute_statistics(
+        &self,
+    ) -> Result<Option<databend_common_meta_app::schema::TableStatistics>> {
+        Ok(None)
+    }
+
     #[async_backtrace::framed]
     async fn column_statistics_provider(
         &self,
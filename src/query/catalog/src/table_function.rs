@@ -14,11 +14,46 @@
 
 use std::sync::Arc;
 
+use databend_common_exception::Result;
+use databend_common_expression::TableSchemaRef;
+
 use crate::table::Table;
+use crate::table_args::TableArgs;
+
+/// A registered table function, as listed by `Catalog::list_table_functions()`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TableFunctionMeta {
+    pub name: String,
+
+    /// The call syntax of the function, e.g. `fuse_segment(database, table [, snapshot_id])`.
+    pub signature: String,
+}
 
 pub trait TableFunction: Sync + Send + Table {
     fn function_name(&self) -> &str;
 
+    /// Check the arity and types of the arguments, before the function is executed.
+    ///
+    /// The planner calls it after the table function is built. By default any args are accepted.
+    fn validate_args(&self, _args: &TableArgs) -> Result<()> {
+        Ok(())
+    }
+
+    /// The schema of the rows the function produces when it is called with `args`.
+    ///
+    /// It lets the planner type-check the columns referred to by the surrounding query
+    /// before the function is executed.
+    /// By default the args are validated and the schema of this function instance is returned,
+    /// a function whose columns depend on the args should derive them from `args` instead.
+    ///
+    /// It takes the bound `TableArgs` rather than the AST expressions of the call,
+    /// because the binder evaluates the args to constants before the function is built,
+    /// and it returns a `TableSchemaRef` because the binder builds the columns of a table from a `TableSchema`.
+    fn output_schema(&self, args: &TableArgs) -> Result<TableSchemaRef> {
+        self.validate_args(args)?;
+        Ok(self.schema())
+    }
+
     fn as_table<'a>(self: Arc<Self>) -> Arc<dyn Table + 'a>
     where Self: 'a;
 }
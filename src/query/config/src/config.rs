@@ -2318,6 +2318,24 @@ pub struct MetaConfig {
     #[clap(long = "auto-sync-interval", value_name = "VALUE", default_value = "0")]
     pub auto_sync_interval: u64,
 
+    /// The interval to send an HTTP/2 PING on an idle connection to the meta-service, in seconds.
+    /// It keeps the connection from being dropped by a load balancer or a NAT.
+    /// 0 disables keep-alive.
+    #[clap(
+        long = "meta-keep-alive-interval-in-second",
+        value_name = "VALUE",
+        default_value = "30"
+    )]
+    pub keep_alive_interval_in_second: u64,
+
+    /// The connection to the meta-service is closed if a PING is not acknowledged in it, in seconds.
+    #[clap(
+        long = "meta-keep-alive-timeout-in-second",
+        value_name = "VALUE",
+        default_value = "10"
+    )]
+    pub keep_alive_timeout_in_second: u64,
+
     #[clap(
         long = "unhealth-endpoint-evict-time",
         value_name = "VALUE",
@@ -2380,6 +2398,8 @@ impl TryInto<InnerMetaConfig> for MetaConfig {
             password: self.password,
             client_timeout_in_second: self.client_timeout_in_second,
             auto_sync_interval: self.auto_sync_interval,
+            keep_alive_interval_in_second: self.keep_alive_interval_in_second,
+            keep_alive_timeout_in_second: self.keep_alive_timeout_in_second,
             unhealth_endpoint_evict_time: self.unhealth_endpoint_evict_time,
             rpc_tls_meta_server_root_ca_cert: self.rpc_tls_meta_server_root_ca_cert,
             rpc_tls_meta_service_domain_name: self.rpc_tls_meta_service_domain_name,
@@ -2396,6 +2416,8 @@ impl From<InnerMetaConfig> for MetaConfig {
             password: inner.password,
             client_timeout_in_second: inner.client_timeout_in_second,
             auto_sync_interval: inner.auto_sync_interval,
+            keep_alive_interval_in_second: inner.keep_alive_interval_in_second,
+            keep_alive_timeout_in_second: inner.keep_alive_timeout_in_second,
             unhealth_endpoint_evict_time: inner.unhealth_endpoint_evict_time,
             rpc_tls_meta_server_root_ca_cert: inner.rpc_tls_meta_server_root_ca_cert,
             rpc_tls_meta_service_domain_name: inner.rpc_tls_meta_service_domain_name,
@@ -2418,6 +2440,14 @@ impl Debug for MetaConfig {
             .field("embedded_dir", &self.embedded_dir)
             .field("client_timeout_in_second", &self.client_timeout_in_second)
             .field("auto_sync_interval", &self.auto_sync_interval)
+            .field(
+                "keep_alive_interval_in_second",
+                &self.keep_alive_interval_in_second,
+            )
+            .field(
+                "keep_alive_timeout_in_second",
+                &self.keep_alive_timeout_in_second,
+            )
             .field(
                 "unhealth_endpoint_evict_time",
                 &self.unhealth_endpoint_evict_time,
@@ -24,6 +24,7 @@ use databend_common_base::base::mask_string;
 use databend_common_base::base::GlobalUniqName;
 use databend_common_exception::ErrorCode;
 use databend_common_exception::Result;
+use databend_common_grpc::KeepAliveConf;
 use databend_common_grpc::RpcClientConf;
 use databend_common_grpc::RpcClientTlsConfig;
 use databend_common_meta_app::tenant::TenantQuota;
@@ -299,6 +300,7 @@ impl QueryConfig {
         RpcClientTlsConfig {
             rpc_tls_server_root_ca_cert: self.rpc_tls_query_server_root_ca_cert.clone(),
             domain_name: self.rpc_tls_query_service_domain_name.clone(),
+            ..Default::default()
         }
     }
 
@@ -328,6 +330,11 @@ pub struct MetaConfig {
     /// AutoSyncInterval is the interval to update endpoints with its latest members.
     /// 0 disables auto-sync. By default auto-sync is disabled.
     pub auto_sync_interval: u64,
+    /// The interval to send an HTTP/2 PING on an idle connection to the meta-service, in seconds.
+    /// 0 disables keep-alive.
+    pub keep_alive_interval_in_second: u64,
+    /// The connection is closed if a PING is not acknowledged in it, in seconds.
+    pub keep_alive_timeout_in_second: u64,
     pub unhealth_endpoint_evict_time: u64,
     /// Certificate for client to identify meta rpc serve
     pub rpc_tls_meta_server_root_ca_cert: String,
@@ -343,6 +350,8 @@ impl Default for MetaConfig {
             password: "".to_string(),
             client_timeout_in_second: 10,
             auto_sync_interval: 0,
+            keep_alive_interval_in_second: 30,
+            keep_alive_timeout_in_second: 10,
             unhealth_endpoint_evict_time: 120,
             rpc_tls_meta_server_root_ca_cert: "".to_string(),
             rpc_tls_meta_service_domain_name: "localhost".to_string(),
@@ -382,6 +391,7 @@ impl MetaConfig {
         RpcClientTlsConfig {
             rpc_tls_server_root_ca_cert: self.rpc_tls_meta_server_root_ca_cert.to_string(),
             domain_name: self.rpc_tls_meta_service_domain_name.to_string(),
+            ..Default::default()
         }
     }
 
@@ -402,6 +412,14 @@ impl MetaConfig {
             } else {
                 None
             },
+            keep_alive: if self.keep_alive_interval_in_second > 0 {
+                Some(KeepAliveConf::new(
+                    Duration::from_secs(self.keep_alive_interval_in_second),
+                    Duration::from_secs(self.keep_alive_timeout_in_second),
+                ))
+            } else {
+                None
+            },
             unhealthy_endpoint_evict_time: Duration::from_secs(self.unhealth_endpoint_evict_time),
         }
     }
@@ -416,6 +434,14 @@ impl Debug for MetaConfig {
             .field("embedded_dir", &self.embedded_dir)
             .field("client_timeout_in_second", &self.client_timeout_in_second)
             .field("auto_sync_interval", &self.auto_sync_interval)
+            .field(
+                "keep_alive_interval_in_second",
+                &self.keep_alive_interval_in_second,
+            )
+            .field(
+                "keep_alive_timeout_in_second",
+                &self.keep_alive_timeout_in_second,
+            )
             .field(
                 "unhealth_endpoint_evict_time",
                 &self.unhealth_endpoint_evict_time,
@@ -127,6 +127,7 @@ impl StreamHandler for RealStreamHandler {
 
         let req = CreateTableReq {
             if_not_exists: plan.if_not_exists,
+            table_id: None,
             name_ident: TableNameIdent {
                 tenant: plan.tenant.clone(),
                 db_name: plan.database.clone(),
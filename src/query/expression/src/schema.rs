@@ -25,6 +25,7 @@ use serde::Deserialize;
 use serde::Serialize;
 
 use crate::types::decimal::DecimalDataType;
+use crate::types::decimal::MAX_DECIMAL128_PRECISION;
 use crate::types::DataType;
 use crate::types::NumberDataType;
 use crate::BlockMetaInfo;
@@ -937,6 +938,39 @@ impl TableSchema {
             next_column_id: self.next_column_id,
         }
     }
+
+    /// Check if the schema can be the schema of a table.
+    ///
+    /// A table must have at least one column, the column names must be unique,
+    /// and the type of every column must be well-formed.
+    pub fn validate(&self) -> Result<()> {
+        if self.fields.is_empty() {
+            return Err(ErrorCode::BadArguments(
+                "A table must have at least one column",
+            ));
+        }
+
+        let mut names = HashSet::new();
+        for field in self.fields() {
+            if !names.insert(field.name()) {
+                return Err(ErrorCode::BadArguments(format!(
+                    "Duplicated column name: {}",
+                    field.name()
+                )));
+            }
+
+            if let Err(reason) = field.data_type().check_declaration() {
+                return Err(ErrorCode::IllegalDataType(format!(
+                    "Invalid type {} of column {}: {}",
+                    field.data_type(),
+                    field.name(),
+                    reason
+                )));
+            }
+        }
+
+        Ok(())
+    }
 }
 
 impl DataField {
@@ -1184,6 +1218,56 @@ impl TableDataType {
         !self.is_nullable_or_null()
     }
 
+    /// Check if the type is well-formed as the type of a column, return the reason if it is not.
+    ///
+    /// A type built from a SQL type name is always well-formed,
+    /// but a `TableMeta` can be built in any way by a caller of the catalog.
+    pub fn check_declaration(&self) -> std::result::Result<(), String> {
+        match self {
+            TableDataType::Nullable(box inner_ty) => {
+                if inner_ty.is_nullable() {
+                    return Err("a nullable type can not be nested in a nullable type".to_string());
+                }
+                inner_ty.check_declaration()
+            }
+            TableDataType::Decimal(decimal_ty) => match decimal_ty {
+                DecimalDataType::Decimal128(size) if size.precision > MAX_DECIMAL128_PRECISION => {
+                    Err(format!(
+                        "the precision of a 128-bit decimal must not be greater than {}",
+                        MAX_DECIMAL128_PRECISION
+                    ))
+                }
+                _ => DecimalDataType::from_size(decimal_ty.size())
+                    .map(|_| ())
+                    .map_err(|e| e.message()),
+            },
+            TableDataType::Array(box inner_ty) => inner_ty.check_declaration(),
+            TableDataType::Map(box inner_ty) => match inner_ty {
+                TableDataType::Tuple { fields_type, .. } if fields_type.len() == 2 => {
+                    fields_type.iter().try_for_each(|ty| ty.check_declaration())
+                }
+                _ => Err("the inner type of a map must be a tuple of key and value".to_string()),
+            },
+            TableDataType::Tuple {
+                fields_name,
+                fields_type,
+            } => {
+                if fields_type.is_empty() {
+                    return Err("a tuple must have at least one field".to_string());
+                }
+                if fields_name.len() != fields_type.len() {
+                    return Err(format!(
+                        "a tuple has {} field names but {} field types",
+                        fields_name.len(),
+                        fields_type.len()
+                    ));
+                }
+                fields_type.iter().try_for_each(|ty| ty.check_declaration())
+            }
+            _ => Ok(()),
+        }
+    }
+
     pub fn remove_nullable(&self) -> Self {
         match self {
             TableDataType::Nullable(ty) => (**ty).clone(),
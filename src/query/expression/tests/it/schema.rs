@@ -16,8 +16,11 @@ use std::collections::BTreeMap;
 
 use databend_common_arrow::arrow::datatypes::DataType as ArrowDataType;
 use databend_common_arrow::arrow::datatypes::Field as ArrowField;
+use databend_common_exception::ErrorCode;
 use databend_common_exception::Result;
 use databend_common_expression::create_test_complex_schema;
+use databend_common_expression::types::decimal::DecimalDataType;
+use databend_common_expression::types::decimal::DecimalSize;
 use databend_common_expression::types::NumberDataType;
 use databend_common_expression::ColumnId;
 use databend_common_expression::Scalar;
@@ -613,3 +616,71 @@ fn test_leaf_columns_of() -> Result<()> {
     assert_eq!(schema.leaf_columns_of(&"e".to_string()), vec![7]);
     Ok(())
 }
+
+#[test]
+fn test_schema_validate() -> Result<()> {
+    let u64_ty = TableDataType::Number(NumberDataType::UInt64);
+
+    // A valid schema
+    create_test_complex_schema().validate()?;
+
+    // No column
+    {
+        let res = TableSchema::empty().validate();
+        assert_eq!(ErrorCode::BAD_ARGUMENTS, res.unwrap_err().code());
+    }
+
+    // Duplicated column name
+    {
+        let schema = TableSchema::new(vec![
+            TableField::new("a", u64_ty.clone()),
+            TableField::new("b", u64_ty.clone()),
+            TableField::new("a", TableDataType::String),
+        ]);
+        let err = schema.validate().unwrap_err();
+        assert_eq!(ErrorCode::BAD_ARGUMENTS, err.code());
+        assert_eq!("Duplicated column name: a", err.message());
+    }
+
+    // Invalid types, the error names the column
+    let invalid_types = vec![
+        TableDataType::Nullable(Box::new(TableDataType::Nullable(Box::new(u64_ty.clone())))),
+        TableDataType::Decimal(DecimalDataType::Decimal128(DecimalSize {
+            precision: 0,
+            scale: 0,
+        })),
+        TableDataType::Decimal(DecimalDataType::Decimal128(DecimalSize {
+            precision: 10,
+            scale: 11,
+        })),
+        TableDataType::Decimal(DecimalDataType::Decimal128(DecimalSize {
+            precision: 50,
+            scale: 1,
+        })),
+        TableDataType::Map(Box::new(u64_ty.clone())),
+        TableDataType::Tuple {
+            fields_name: vec![],
+            fields_type: vec![],
+        },
+        TableDataType::Tuple {
+            fields_name: vec!["x".to_string()],
+            fields_type: vec![u64_ty.clone(), u64_ty.clone()],
+        },
+        TableDataType::Array(Box::new(TableDataType::Tuple {
+            fields_name: vec![],
+            fields_type: vec![],
+        })),
+    ];
+
+    for ty in invalid_types {
+        let schema = TableSchema::new(vec![
+            TableField::new("a", u64_ty.clone()),
+            TableField::new("bad", ty.clone()),
+        ]);
+        let err = schema.validate().unwrap_err();
+        assert_eq!(ErrorCode::ILLEGAL_DATA_TYPE, err.code(), "type: {:?}", ty);
+        assert!(err.message().contains("column bad"), "type: {:?}", ty);
+    }
+
+    Ok(())
+}
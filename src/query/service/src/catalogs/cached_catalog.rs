@@ -0,0 +1,833 @@
+// Copyright 2021 Datafuse Labs
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::any::Any;
+use std::collections::HashMap;
+use std::fmt::Debug;
+use std::fmt::Formatter;
+use std::ops::Range;
+use std::sync::Arc;
+use std::time::Duration;
+use std::time::Instant;
+
+use databend_common_catalog::catalog::Catalog;
+use databend_common_catalog::catalog::ConsistencyReport;
+use databend_common_catalog::catalog::DropTablePlan;
+use databend_common_catalog::catalog::ReadConsistency;
+use databend_common_catalog::catalog::StorageDescription;
+use databend_common_catalog::database::Database;
+use databend_common_catalog::table_args::TableArgs;
+use databend_common_catalog::table_function::TableFunction;
+use databend_common_catalog::table_function::TableFunctionMeta;
+use databend_common_exception::ErrorCode;
+use databend_common_exception::Result;
+use databend_common_meta_app::schema::CatalogInfo;
+use databend_common_meta_app::schema::CountTablesReply;
+use databend_common_meta_app::schema::CountTablesReq;
+use databend_common_meta_app::schema::CreateDatabaseReply;
+use databend_common_meta_app::schema::CreateDatabaseReq;
+use databend_common_meta_app::schema::CreateIndexReply;
+use databend_common_meta_app::schema::CreateIndexReq;
+use databend_common_meta_app::schema::CreateLockRevReply;
+use databend_common_meta_app::schema::CreateLockRevReq;
+use databend_common_meta_app::schema::CreateTableReply;
+use databend_common_meta_app::schema::CreateTableReq;
+use databend_common_meta_app::schema::CreateVirtualColumnReply;
+use databend_common_meta_app::schema::CreateVirtualColumnReq;
+use databend_common_meta_app::schema::DeleteLockRevReq;
+use databend_common_meta_app::schema::DropDatabaseReply;
+use databend_common_meta_app::schema::DropDatabaseReq;
+use databend_common_meta_app::schema::DropIndexReply;
+use databend_common_meta_app::schema::DropIndexReq;
+use databend_common_meta_app::schema::DropTableByIdReq;
+use databend_common_meta_app::schema::DropTableReply;
+use databend_common_meta_app::schema::DropVirtualColumnReply;
+use databend_common_meta_app::schema::DropVirtualColumnReq;
+use databend_common_meta_app::schema::DroppedId;
+use databend_common_meta_app::schema::ExtendLockRevReq;
+use databend_common_meta_app::schema::GcDroppedTableReq;
+use databend_common_meta_app::schema::GcDroppedTableResp;
+use databend_common_meta_app::schema::GetIndexReply;
+use databend_common_meta_app::schema::GetIndexReq;
+use databend_common_meta_app::schema::GetTableCopiedFileReply;
+use databend_common_meta_app::schema::GetTableCopiedFileReq;
+use databend_common_meta_app::schema::IndexMeta;
+use databend_common_meta_app::schema::ListDroppedTableReq;
+use databend_common_meta_app::schema::ListIndexesByIdReq;
+use databend_common_meta_app::schema::ListIndexesReq;
+use databend_common_meta_app::schema::ListLockRevReq;
+use databend_common_meta_app::schema::ListLocksReq;
+use databend_common_meta_app::schema::ListVirtualColumnsReq;
+use databend_common_meta_app::schema::LockInfo;
+use databend_common_meta_app::schema::LockMeta;
+use databend_common_meta_app::schema::RenameDatabaseReply;
+use databend_common_meta_app::schema::RenameDatabaseReq;
+use databend_common_meta_app::schema::RenameTableReply;
+use databend_common_meta_app::schema::RenameTableReq;
+use databend_common_meta_app::schema::SetDatabaseQuotaReply;
+use databend_common_meta_app::schema::SetDatabaseQuotaReq;
+use databend_common_meta_app::schema::SetTableColumnMaskPolicyReply;
+use databend_common_meta_app::schema::SetTableColumnMaskPolicyReq;
+use databend_common_meta_app::schema::TableIdent;
+use databend_common_meta_app::schema::TableInfo;
+use databend_common_meta_app::schema::TableMeta;
+use databend_common_meta_app::schema::TruncateTableReply;
+use databend_common_meta_app::schema::TruncateTableReq;
+use databend_common_meta_app::schema::UndropDatabaseReply;
+use databend_common_meta_app::schema::UndropDatabaseReq;
+use databend_common_meta_app::schema::UndropTableReply;
+use databend_common_meta_app::schema::UndropTableReq;
+use databend_common_meta_app::schema::UpdateIndexReply;
+use databend_common_meta_app::schema::UpdateIndexReq;
+use databend_common_meta_app::schema::UpdateTableMetaReply;
+use databend_common_meta_app::schema::UpdateTableMetaReq;
+use databend_common_meta_app::schema::UpdateVirtualColumnReply;
+use databend_common_meta_app::schema::UpdateVirtualColumnReq;
+use databend_common_meta_app::schema::UpsertTableOptionReply;
+use databend_common_meta_app::schema::UpsertTableOptionReq;
+use databend_common_meta_app::schema::VirtualColumnMeta;
+use databend_common_meta_types::MetaId;
+use log::warn;
+use parking_lot::Mutex;
+
+use crate::storages::Table;
+
+/// `(tenant, db_name, table_name)`
+type TableKey = (String, String, String);
+
+struct CachedTable {
+    table: Arc<dyn Table>,
+    cached_at: Instant,
+}
+
+#[derive(Default)]
+struct Tables {
+    entries: HashMap<TableKey, CachedTable>,
+
+    /// Increased by every invalidation.
+    ///
+    /// A table read from the inner catalog is cached only if no invalidation happens since the read starts:
+    /// otherwise the read may return the version before the DDL that invalidates it.
+    generation: u64,
+}
+
+impl Tables {
+    /// Retain the entries for which `f` returns true, and start a new generation.
+    fn invalidate(&mut self, f: impl FnMut(&TableKey, &mut CachedTable) -> bool) {
+        self.entries.retain(f);
+        self.generation += 1;
+    }
+}
+
+/// A table returned by [`CachedCatalog::get_table_maybe_stale()`].
+#[derive(Clone)]
+pub struct ServedTable {
+    pub table: Arc<dyn Table>,
+
+    /// The table is served from an expired cache entry because the inner catalog is unavailable.
+    pub stale: bool,
+}
+
+/// A catalog that memoizes the result of `get_table()` of the inner catalog.
+///
+/// - A cached table is served until it lives longer than `ttl`.
+/// - At most `max_entries` tables are cached; the oldest one is evicted when it is full.
+/// - Every DDL issued through this catalog that renames, drops or alters a table
+///   invalidates the affected entries, so that the next read goes to the inner catalog.
+///   Changes made by other catalog instances are only visible after `ttl`.
+///   A table read from the inner catalog concurrently with an invalidation is not cached.
+/// - If `max_staleness` is not zero, `get_table()` still serves a table expired for at most `max_staleness`
+///   when the inner catalog fails to reach the meta-service, see [`Self::get_table_maybe_stale()`].
+#[derive(Clone)]
+pub struct CachedCatalog<C: Catalog + Clone> {
+    inner: C,
+    ttl: Duration,
+    max_staleness: Duration,
+    max_entries: usize,
+    tables: Arc<Mutex<Tables>>,
+}
+
+impl<C: Catalog + Clone> Debug for CachedCatalog<C> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("CachedCatalog")
+            .field("inner", &self.inner)
+            .field("ttl", &self.ttl)
+            .field("max_staleness", &self.max_staleness)
+            .field("max_entries", &self.max_entries)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<C: Catalog + Clone> CachedCatalog<C> {
+    pub fn create(inner: C, ttl: Duration, max_entries: usize) -> Self {
+        Self {
+            inner,
+            ttl,
+            max_staleness: Duration::ZERO,
+            max_entries,
+            tables: Arc::new(Mutex::new(Tables::default())),
+        }
+    }
+
+    /// Serve a table expired for at most `max_staleness` when the inner catalog is unavailable.
+    ///
+    /// It is disabled by default, i.e., `max_staleness` is zero.
+    pub fn with_max_staleness(mut self, max_staleness: Duration) -> Self {
+        self.max_staleness = max_staleness;
+        self
+    }
+
+    pub fn inner(&self) -> &C {
+        &self.inner
+    }
+
+    /// Remove the cached table `db_name.table_name` of `tenant`.
+    pub fn invalidate(&self, tenant: &str, db_name: &str, table_name: &str) {
+        self.tables
+            .lock()
+            .invalidate(|(t, db, tbl), _| !(t == tenant && db == db_name && tbl == table_name));
+    }
+
+    /// Remove every cached table in database `db_name` of `tenant`.
+    fn invalidate_database(&self, tenant: &str, db_name: &str) {
+        self.tables
+            .lock()
+            .invalidate(|(t, db, _), _| !(t == tenant && db == db_name));
+    }
+
+    /// Remove the cached table with id `table_id`, whatever name it is cached by.
+    fn invalidate_table_id(&self, table_id: MetaId) {
+        self.tables
+            .lock()
+            .invalidate(|_, cached| cached.table.get_id() != table_id);
+    }
+
+    /// The generation to pass to [`Self::put_cached()`], taken before reading from the inner catalog.
+    fn generation(&self) -> u64 {
+        self.tables.lock().generation
+    }
+
+    /// How long an entry is retained: an expired one is kept for serving it stale.
+    fn retention(&self) -> Duration {
+        self.ttl.saturating_add(self.max_staleness)
+    }
+
+    fn get_cached(&self, key: &TableKey) -> Option<Arc<dyn Table>> {
+        let mut tables = self.tables.lock();
+
+        let cached = tables.entries.get(key)?;
+        let elapsed = cached.cached_at.elapsed();
+        if elapsed < self.ttl {
+            return Some(cached.table.clone());
+        }
+
+        if elapsed >= self.retention() {
+            tables.entries.remove(key);
+        }
+        None
+    }
+
+    /// Get a cached table that is expired for at most `max_staleness`.
+    fn get_stale(&self, key: &TableKey) -> Option<Arc<dyn Table>> {
+        let tables = self.tables.lock();
+
+        let cached = tables.entries.get(key)?;
+        if cached.cached_at.elapsed() < self.retention() {
+            return Some(cached.table.clone());
+        }
+        None
+    }
+
+    /// Whether the inner catalog fails because the meta-service can not be reached.
+    fn is_unavailable(e: &ErrorCode) -> bool {
+        e.code() == ErrorCode::META_SERVICE_ERROR
+    }
+
+    /// Get a table as `get_table()` does, but if the inner catalog is unavailable,
+    /// serve a cached table that is expired for at most `max_staleness`, flagged as stale.
+    ///
+    /// The error of the inner catalog is returned if there is no such cached table.
+    #[async_backtrace::framed]
+    pub async fn get_table_maybe_stale(
+        &self,
+        tenant: &str,
+        db_name: &str,
+        table_name: &str,
+    ) -> Result<ServedTable> {
+        let key = (
+            tenant.to_string(),
+            db_name.to_string(),
+            table_name.to_string(),
+        );
+
+        if let Some(table) = self.get_cached(&key) {
+            return Ok(ServedTable {
+                table,
+                stale: false,
+            });
+        }
+
+        let generation = self.generation();
+
+        match self.inner.get_table(tenant, db_name, table_name).await {
+            Ok(table) => {
+                self.put_cached(key, table.clone(), generation);
+                Ok(ServedTable {
+                    table,
+                    stale: false,
+                })
+            }
+            Err(e) if Self::is_unavailable(&e) => match self.get_stale(&key) {
+                Some(table) => {
+                    warn!(
+                        "CachedCatalog: serve stale table {}.{} of tenant {}, inner catalog error: {}",
+                        db_name, table_name, tenant, e
+                    );
+                    Ok(ServedTable { table, stale: true })
+                }
+                None => Err(e),
+            },
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Cache a table read from the inner catalog,
+    /// unless an invalidation happens after `generation` is taken.
+    fn put_cached(&self, key: TableKey, table: Arc<dyn Table>, generation: u64) {
+        if self.max_entries == 0 {
+            return;
+        }
+
+        let mut tables = self.tables.lock();
+
+        if tables.generation != generation {
+            // The table may be older than the cached one, which is not trusted either.
+            tables.entries.remove(&key);
+            return;
+        }
+
+        let entries = &mut tables.entries;
+
+        if !entries.contains_key(&key) && entries.len() >= self.max_entries {
+            entries.retain(|_, cached| cached.cached_at.elapsed() < self.retention());
+        }
+
+        while !entries.contains_key(&key) && entries.len() >= self.max_entries {
+            let oldest = entries
+                .iter()
+                .min_by_key(|(_, cached)| cached.cached_at)
+                .map(|(k, _)| k.clone());
+
+            match oldest {
+                Some(k) => entries.remove(&k),
+                None => break,
+            };
+        }
+
+        entries.insert(key, CachedTable {
+            table,
+            cached_at: Instant::now(),
+        });
+    }
+}
+
+#[async_trait::async_trait]
+impl<C: Catalog + Clone + 'static> Catalog for CachedCatalog<C> {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn name(&self) -> String {
+        self.inner.name()
+    }
+
+    fn info(&self) -> CatalogInfo {
+        self.inner.info()
+    }
+
+    #[async_backtrace::framed]
+    async fn get_database(&self, tenant: &str, db_name: &str) -> Result<Arc<dyn Database>> {
+        self.inner.get_database(tenant, db_name).await
+    }
+
+    #[async_backtrace::framed]
+    async fn list_databases(&self, tenant: &str) -> Result<Vec<Arc<dyn Database>>> {
+        self.inner.list_databases(tenant).await
+    }
+
+    #[async_backtrace::framed]
+    async fn list_databases_paged(
+        &self,
+        tenant: &str,
+        after: Option<&str>,
+        limit: usize,
+        name_prefix: Option<&str>,
+    ) -> Result<Vec<Arc<dyn Database>>> {
+        self.inner
+            .list_databases_paged(tenant, after, limit, name_prefix)
+            .await
+    }
+
+    #[async_backtrace::framed]
+    async fn create_database(&self, req: CreateDatabaseReq) -> Result<CreateDatabaseReply> {
+        self.inner.create_database(req).await
+    }
+
+    #[async_backtrace::framed]
+    async fn drop_database(&self, req: DropDatabaseReq) -> Result<DropDatabaseReply> {
+        let (tenant, db_name) = (
+            req.name_ident.tenant.clone(),
+            req.name_ident.db_name.clone(),
+        );
+
+        let res = self.inner.drop_database(req).await;
+        self.invalidate_database(&tenant, &db_name);
+        res
+    }
+
+    #[async_backtrace::framed]
+    async fn undrop_database(&self, req: UndropDatabaseReq) -> Result<UndropDatabaseReply> {
+        let (tenant, db_name) = (req.tenant().to_string(), req.db_name().to_string());
+
+        let res = self.inner.undrop_database(req).await;
+        self.invalidate_database(&tenant, &db_name);
+        res
+    }
+
+    #[async_backtrace::framed]
+    async fn exists_database(&self, tenant: &str, db_name: &str) -> Result<bool> {
+        self.inner.exists_database(tenant, db_name).await
+    }
+
+    #[async_backtrace::framed]
+    async fn rename_database(&self, req: RenameDatabaseReq) -> Result<RenameDatabaseReply> {
+        let tenant = req.name_ident.tenant.clone();
+        let db_name = req.name_ident.db_name.clone();
+        let new_db_name = req.new_db_name.clone();
+
+        let res = self.inner.rename_database(req).await;
+        self.invalidate_database(&tenant, &db_name);
+        self.invalidate_database(&tenant, &new_db_name);
+        res
+    }
+
+    #[async_backtrace::framed]
+    async fn set_database_quota(&self, req: SetDatabaseQuotaReq) -> Result<SetDatabaseQuotaReply> {
+        let (tenant, db_name) = (
+            req.name_ident.tenant.clone(),
+            req.name_ident.db_name.clone(),
+        );
+
+        let res = self.inner.set_database_quota(req).await;
+        self.invalidate_database(&tenant, &db_name);
+        res
+    }
+
+    fn get_table_by_info(&self, table_info: &TableInfo) -> Result<Arc<dyn Table>> {
+        self.inner.get_table_by_info(table_info)
+    }
+
+    #[async_backtrace::framed]
+    async fn get_table_meta_by_id(&self, table_id: MetaId) -> Result<(TableIdent, Arc<TableMeta>)> {
+        self.inner.get_table_meta_by_id(table_id).await
+    }
+
+    #[async_backtrace::framed]
+    async fn get_table_name_by_id(&self, table_id: MetaId) -> Result<String> {
+        self.inner.get_table_name_by_id(table_id).await
+    }
+
+    #[async_backtrace::framed]
+    async fn get_table_by_id(&self, table_id: MetaId) -> Result<Arc<dyn Table>> {
+        self.inner.get_table_by_id(table_id).await
+    }
+
+    #[async_backtrace::framed]
+    async fn get_db_name_by_id(&self, db_id: MetaId) -> Result<String> {
+        self.inner.get_db_name_by_id(db_id).await
+    }
+
+    #[async_backtrace::framed]
+    async fn get_table(
+        &self,
+        tenant: &str,
+        db_name: &str,
+        table_name: &str,
+    ) -> Result<Arc<dyn Table>> {
+        let served = self
+            .get_table_maybe_stale(tenant, db_name, table_name)
+            .await?;
+        Ok(served.table)
+    }
+
+    /// `Cached` is served as `get_table()`.
+    /// The others bypass the cache, and the table read from the inner catalog replaces the cached one.
+    #[async_backtrace::framed]
+    async fn get_table_consistent(
+        &self,
+        tenant: &str,
+        db_name: &str,
+        table_name: &str,
+        level: ReadConsistency,
+    ) -> Result<Arc<dyn Table>> {
+        if level == ReadConsistency::Cached {
+            return self.get_table(tenant, db_name, table_name).await;
+        }
+
+        let generation = self.generation();
+
+        let table = self
+            .inner
+            .get_table_consistent(tenant, db_name, table_name, level)
+            .await?;
+
+        let key = (
+            tenant.to_string(),
+            db_name.to_string(),
+            table_name.to_string(),
+        );
+        self.put_cached(key, table.clone(), generation);
+        Ok(table)
+    }
+
+    /// Get the cached tables, and the others from the inner catalog in one batch, which are then cached.
+    #[async_backtrace::framed]
+    async fn mget_tables(
+        &self,
+        tenant: &str,
+        db_name: &str,
+        table_names: &[String],
+    ) -> Result<Vec<Arc<dyn Table>>> {
+        let mut tables = Vec::with_capacity(table_names.len());
+        let mut missing = vec![];
+
+        for table_name in table_names {
+            let key = (tenant.to_string(), db_name.to_string(), table_name.clone());
+            match self.get_cached(&key) {
+                Some(table) => tables.push(table),
+                None => missing.push(table_name.clone()),
+            }
+        }
+
+        if missing.is_empty() {
+            return Ok(tables);
+        }
+
+        let generation = self.generation();
+
+        let fetched = self.inner.mget_tables(tenant, db_name, &missing).await?;
+        for table in fetched {
+            let key = (
+                tenant.to_string(),
+                db_name.to_string(),
+                table.name().to_string(),
+            );
+            self.put_cached(key, table.clone(), generation);
+            tables.push(table);
+        }
+
+        Ok(tables)
+    }
+
+    #[async_backtrace::framed]
+    async fn list_tables(&self, tenant: &str, db_name: &str) -> Result<Vec<Arc<dyn Table>>> {
+        self.inner.list_tables(tenant, db_name).await
+    }
+
+    #[async_backtrace::framed]
+    async fn list_tables_history(
+        &self,
+        tenant: &str,
+        db_name: &str,
+    ) -> Result<Vec<Arc<dyn Table>>> {
+        self.inner.list_tables_history(tenant, db_name).await
+    }
+
+    async fn get_drop_table_infos(
+        &self,
+        req: ListDroppedTableReq,
+    ) -> Result<(Vec<Arc<dyn Table>>, Vec<DroppedId>)> {
+        self.inner.get_drop_table_infos(req).await
+    }
+
+    async fn gc_drop_tables(&self, req: GcDroppedTableReq) -> Result<GcDroppedTableResp> {
+        self.inner.gc_drop_tables(req).await
+    }
+
+    #[async_backtrace::framed]
+    async fn check_consistency(&self, repair: bool) -> Result<ConsistencyReport> {
+        let res = self.inner.check_consistency(repair).await;
+        if repair {
+            // A repair may remove a name that a cached table is resolved by.
+            self.tables.lock().invalidate(|_, _| false);
+        }
+        res
+    }
+
+    #[async_backtrace::framed]
+    async fn create_table(&self, req: CreateTableReq) -> Result<CreateTableReply> {
+        let (tenant, db_name, table_name) = (
+            req.tenant().to_string(),
+            req.db_name().to_string(),
+            req.table_name().to_string(),
+        );
+
+        let res = self.inner.create_table(req).await;
+        self.invalidate(&tenant, &db_name, &table_name);
+        res
+    }
+
+    #[async_backtrace::framed]
+    async fn reserve_table_ids(&self, n: u64) -> Result<Range<u64>> {
+        self.inner.reserve_table_ids(n).await
+    }
+
+    #[async_backtrace::framed]
+    async fn drop_table_by_id(&self, req: DropTableByIdReq) -> Result<DropTableReply> {
+        let table_id = req.tb_id;
+
+        let res = self.inner.drop_table_by_id(req).await;
+        self.invalidate_table_id(table_id);
+        res
+    }
+
+    #[async_backtrace::framed]
+    async fn plan_drop_table_by_id(&self, req: DropTableByIdReq) -> Result<DropTablePlan> {
+        // Nothing is changed, no need to invalidate.
+        self.inner.plan_drop_table_by_id(req).await
+    }
+
+    #[async_backtrace::framed]
+    async fn undrop_table(&self, req: UndropTableReq) -> Result<UndropTableReply> {
+        let (tenant, db_name, table_name) = (
+            req.tenant().to_string(),
+            req.db_name().to_string(),
+            req.table_name().to_string(),
+        );
+
+        let res = self.inner.undrop_table(req).await;
+        self.invalidate(&tenant, &db_name, &table_name);
+        res
+    }
+
+    #[async_backtrace::framed]
+    async fn rename_table(&self, req: RenameTableReq) -> Result<RenameTableReply> {
+        let (tenant, db_name, table_name) = (
+            req.tenant().to_string(),
+            req.db_name().to_string(),
+            req.table_name().to_string(),
+        );
+        let (new_db_name, new_table_name) = (req.new_db_name.clone(), req.new_table_name.clone());
+
+        let res = self.inner.rename_table(req).await;
+        self.invalidate(&tenant, &db_name, &table_name);
+        self.invalidate(&tenant, &new_db_name, &new_table_name);
+        res
+    }
+
+    #[async_backtrace::framed]
+    async fn exists_table(&self, tenant: &str, db_name: &str, table_name: &str) -> Result<bool> {
+        self.inner.exists_table(tenant, db_name, table_name).await
+    }
+
+    #[async_backtrace::framed]
+    async fn upsert_table_option(
+        &self,
+        tenant: &str,
+        db_name: &str,
+        req: UpsertTableOptionReq,
+    ) -> Result<UpsertTableOptionReply> {
+        let table_id = req.table_id;
+
+        let res = self.inner.upsert_table_option(tenant, db_name, req).await;
+        self.invalidate_table_id(table_id);
+        res
+    }
+
+    #[async_backtrace::framed]
+    async fn update_table_meta(
+        &self,
+        table_info: &TableInfo,
+        req: UpdateTableMetaReq,
+    ) -> Result<UpdateTableMetaReply> {
+        let table_id = req.table_id;
+
+        let res = self.inner.update_table_meta(table_info, req).await;
+        self.invalidate_table_id(table_id);
+        res
+    }
+
+    #[async_backtrace::framed]
+    async fn set_table_column_mask_policy(
+        &self,
+        req: SetTableColumnMaskPolicyReq,
+    ) -> Result<SetTableColumnMaskPolicyReply> {
+        let table_id = req.table_id;
+
+        let res = self.inner.set_table_column_mask_policy(req).await;
+        self.invalidate_table_id(table_id);
+        res
+    }
+
+    #[async_backtrace::framed]
+    async fn count_tables(&self, req: CountTablesReq) -> Result<CountTablesReply> {
+        self.inner.count_tables(req).await
+    }
+
+    #[async_backtrace::framed]
+    async fn get_table_copied_file_info(
+        &self,
+        tenant: &str,
+        db_name: &str,
+        req: GetTableCopiedFileReq,
+    ) -> Result<GetTableCopiedFileReply> {
+        self.inner
+            .get_table_copied_file_info(tenant, db_name, req)
+            .await
+    }
+
+    #[async_backtrace::framed]
+    async fn truncate_table(
+        &self,
+        table_info: &TableInfo,
+        req: TruncateTableReq,
+    ) -> Result<TruncateTableReply> {
+        let table_id = req.table_id;
+
+        let res = self.inner.truncate_table(table_info, req).await;
+        self.invalidate_table_id(table_id);
+        res
+    }
+
+    // Table index
+
+    #[async_backtrace::framed]
+    async fn create_index(&self, req: CreateIndexReq) -> Result<CreateIndexReply> {
+        self.inner.create_index(req).await
+    }
+
+    #[async_backtrace::framed]
+    async fn drop_index(&self, req: DropIndexReq) -> Result<DropIndexReply> {
+        self.inner.drop_index(req).await
+    }
+
+    #[async_backtrace::framed]
+    async fn get_index(&self, req: GetIndexReq) -> Result<GetIndexReply> {
+        self.inner.get_index(req).await
+    }
+
+    #[async_backtrace::framed]
+    async fn update_index(&self, req: UpdateIndexReq) -> Result<UpdateIndexReply> {
+        self.inner.update_index(req).await
+    }
+
+    #[async_backtrace::framed]
+    async fn list_indexes(&self, req: ListIndexesReq) -> Result<Vec<(u64, String, IndexMeta)>> {
+        self.inner.list_indexes(req).await
+    }
+
+    #[async_backtrace::framed]
+    async fn list_index_ids_by_table_id(&self, req: ListIndexesByIdReq) -> Result<Vec<u64>> {
+        self.inner.list_index_ids_by_table_id(req).await
+    }
+
+    #[async_backtrace::framed]
+    async fn list_indexes_by_table_id(
+        &self,
+        req: ListIndexesByIdReq,
+    ) -> Result<Vec<(u64, String, IndexMeta)>> {
+        self.inner.list_indexes_by_table_id(req).await
+    }
+
+    // Virtual column
+
+    #[async_backtrace::framed]
+    async fn create_virtual_column(
+        &self,
+        req: CreateVirtualColumnReq,
+    ) -> Result<CreateVirtualColumnReply> {
+        self.inner.create_virtual_column(req).await
+    }
+
+    #[async_backtrace::framed]
+    async fn update_virtual_column(
+        &self,
+        req: UpdateVirtualColumnReq,
+    ) -> Result<UpdateVirtualColumnReply> {
+        self.inner.update_virtual_column(req).await
+    }
+
+    #[async_backtrace::framed]
+    async fn drop_virtual_column(
+        &self,
+        req: DropVirtualColumnReq,
+    ) -> Result<DropVirtualColumnReply> {
+        self.inner.drop_virtual_column(req).await
+    }
+
+    #[async_backtrace::framed]
+    async fn list_virtual_columns(
+        &self,
+        req: ListVirtualColumnsReq,
+    ) -> Result<Vec<VirtualColumnMeta>> {
+        self.inner.list_virtual_columns(req).await
+    }
+
+    // Table lock
+
+    #[async_backtrace::framed]
+    async fn list_lock_revisions(&self, req: ListLockRevReq) -> Result<Vec<(u64, LockMeta)>> {
+        self.inner.list_lock_revisions(req).await
+    }
+
+    #[async_backtrace::framed]
+    async fn create_lock_revision(&self, req: CreateLockRevReq) -> Result<CreateLockRevReply> {
+        self.inner.create_lock_revision(req).await
+    }
+
+    #[async_backtrace::framed]
+    async fn extend_lock_revision(&self, req: ExtendLockRevReq) -> Result<()> {
+        self.inner.extend_lock_revision(req).await
+    }
+
+    #[async_backtrace::framed]
+    async fn delete_lock_revision(&self, req: DeleteLockRevReq) -> Result<()> {
+        self.inner.delete_lock_revision(req).await
+    }
+
+    #[async_backtrace::framed]
+    async fn list_locks(&self, req: ListLocksReq) -> Result<Vec<LockInfo>> {
+        self.inner.list_locks(req).await
+    }
+
+    fn get_table_function(
+        &self,
+        func_name: &str,
+        tbl_args: TableArgs,
+    ) -> Result<Arc<dyn TableFunction>> {
+        self.inner.get_table_function(func_name, tbl_args)
+    }
+
+    fn exists_table_function(&self, func_name: &str) -> bool {
+        self.inner.exists_table_function(func_name)
+    }
+
+    fn list_table_functions(&self) -> Vec<TableFunctionMeta> {
+        self.inner.list_table_functions()
+    }
+
+    fn get_table_engines(&self) -> Vec<StorageDescription> {
+        self.inner.get_table_engines()
+    }
+}
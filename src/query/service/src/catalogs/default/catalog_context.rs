@@ -16,7 +16,9 @@ use std::sync::Arc;
 
 use databend_common_meta_store::MetaStore;
 
+use crate::catalogs::default::table_id_allocator::TableIdAllocator;
 use crate::databases::DatabaseFactory;
+use crate::databases::TableCountCache;
 use crate::storages::StorageFactory;
 
 #[derive(Clone)]
@@ -24,4 +26,7 @@ pub struct CatalogContext {
     pub meta: MetaStore,
     pub storage_factory: Arc<StorageFactory>,
     pub database_factory: Arc<DatabaseFactory>,
+    pub table_counts: Arc<TableCountCache>,
+    /// The table ids reserved by `reserve_table_ids()` and not taken yet.
+    pub table_ids: Arc<TableIdAllocator>,
 }
@@ -0,0 +1,244 @@
+// Copyright 2021 Datafuse Labs
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Cross-check the database and table records in the meta-service.
+//!
+//! The records involved are:
+//! - `DatabaseNameIdent -> db_id` and `DatabaseIdToName -> DatabaseNameIdent`, refer to `DatabaseId -> DatabaseMeta`;
+//! - `DBIdTableName -> table_id` and `TableIdToName -> DBIdTableName`, refer to `TableId -> TableMeta`.
+
+use std::collections::BTreeMap;
+use std::collections::BTreeSet;
+
+use databend_common_catalog::catalog::ConsistencyIssue;
+use databend_common_catalog::catalog::ConsistencyReport;
+use databend_common_catalog::catalog::Inconsistency;
+use databend_common_exception::Result;
+use databend_common_meta_api::codec::MetaCodec;
+use databend_common_meta_api::deserialize_u64;
+use databend_common_meta_api::txn_cond_seq;
+use databend_common_meta_api::txn_op_del;
+use databend_common_meta_api::txn_op_put;
+use databend_common_meta_app::schema::DBIdTableName;
+use databend_common_meta_app::schema::DatabaseId;
+use databend_common_meta_app::schema::DatabaseIdToName;
+use databend_common_meta_app::schema::DatabaseNameIdent;
+use databend_common_meta_app::schema::TableId;
+use databend_common_meta_app::schema::TableIdToName;
+use databend_common_meta_kvapi::kvapi;
+use databend_common_meta_kvapi::kvapi::Key;
+use databend_common_meta_types::ConditionResult;
+use databend_common_meta_types::InvalidReply;
+use databend_common_meta_types::MetaError;
+use databend_common_meta_types::MetaNetworkError;
+use databend_common_meta_types::TxnRequest;
+use log::info;
+use log::warn;
+
+/// List every record of key type `K`, with the seq and the raw value.
+async fn list_records<K: Key>(
+    kv: &(impl kvapi::KVApi<Error = MetaError> + ?Sized),
+) -> Result<Vec<(K, u64, Vec<u8>)>, MetaError> {
+    let res = kv.prefix_list_kv(&format!("{}/", K::PREFIX)).await?;
+
+    let mut records = Vec::with_capacity(res.len());
+    for (str_key, seqv) in res {
+        let key = K::from_str_key(&str_key).map_err(|e| {
+            let inv = InvalidReply::new(format!("fail to parse key {}", str_key), &e);
+            MetaNetworkError::InvalidReply(inv)
+        })?;
+        records.push((key, seqv.seq, seqv.data));
+    }
+
+    Ok(records)
+}
+
+/// List the keys of every record of key type `K`, without fetching the values.
+async fn list_keys<K: Key>(
+    kv: &(impl kvapi::KVApi<Error = MetaError> + ?Sized),
+) -> Result<Vec<K>, MetaError> {
+    let str_keys = kv.prefix_list_keys(&format!("{}/", K::PREFIX)).await?;
+
+    let mut keys = Vec::with_capacity(str_keys.len());
+    for str_key in str_keys {
+        let key = K::from_str_key(&str_key).map_err(|e| {
+            let inv = InvalidReply::new(format!("fail to parse key {}", str_key), &e);
+            MetaNetworkError::InvalidReply(inv)
+        })?;
+        keys.push(key);
+    }
+
+    Ok(keys)
+}
+
+/// An inconsistency and the transaction that repairs it.
+struct Finding {
+    inconsistency: Inconsistency,
+    repair: TxnRequest,
+}
+
+impl Finding {
+    /// Repair by removing `key` if it is not changed since it is scanned with `seq`,
+    /// and the record `absent` it refers to is still absent.
+    ///
+    /// Without the second condition, a record created concurrently, e.g., by `create_table()`,
+    /// after the scan would be broken by the repair.
+    fn remove(inconsistency: Inconsistency, key: &impl Key, seq: u64, absent: &impl Key) -> Self {
+        Self {
+            inconsistency,
+            repair: TxnRequest {
+                condition: vec![
+                    txn_cond_seq(key, ConditionResult::Eq, seq),
+                    txn_cond_seq(absent, ConditionResult::Eq, 0),
+                ],
+                if_then: vec![txn_op_del(key)],
+                else_then: vec![],
+            },
+        }
+    }
+}
+
+/// Scan the meta-service for inconsistencies, and repair them if `repair` is true.
+pub(crate) async fn check_consistency(
+    kv: &(impl kvapi::KVApi<Error = MetaError> + ?Sized),
+    codec: &impl MetaCodec,
+    repair: bool,
+) -> Result<ConsistencyReport> {
+    let findings = scan(kv, codec).await?;
+
+    let mut report = ConsistencyReport::default();
+
+    for finding in findings {
+        let repaired = if repair {
+            let reply = kv.transaction(finding.repair).await?;
+            if reply.success {
+                info!("check_consistency: repaired: {}", finding.inconsistency);
+            } else {
+                warn!(
+                    "check_consistency: not repaired, changed since scanned: {}",
+                    finding.inconsistency
+                );
+            }
+            reply.success
+        } else {
+            warn!("check_consistency: found: {}", finding.inconsistency);
+            false
+        };
+
+        report.issues.push(ConsistencyIssue {
+            inconsistency: finding.inconsistency,
+            repaired,
+        });
+    }
+
+    Ok(report)
+}
+
+async fn scan(
+    kv: &(impl kvapi::KVApi<Error = MetaError> + ?Sized),
+    codec: &impl MetaCodec,
+) -> Result<Vec<Finding>> {
+    let db_ids = list_keys::<DatabaseId>(kv)
+        .await?
+        .into_iter()
+        .map(|k| k.db_id)
+        .collect::<BTreeSet<_>>();
+
+    let table_ids = list_keys::<TableId>(kv)
+        .await?
+        .into_iter()
+        .map(|k| k.table_id)
+        .collect::<BTreeSet<_>>();
+
+    let mut findings = vec![];
+
+    for (name, seq, data) in list_records::<DatabaseNameIdent>(kv).await? {
+        let db_id = *deserialize_u64(&data).map_err(MetaError::from)?;
+        if !db_ids.contains(&db_id) {
+            let inconsistency = Inconsistency::DanglingDatabaseName {
+                name: name.clone(),
+                db_id,
+            };
+            findings.push(Finding::remove(inconsistency, &name, seq, &DatabaseId {
+                db_id,
+            }));
+        }
+    }
+
+    for (key, seq, _) in list_records::<DatabaseIdToName>(kv).await? {
+        if !db_ids.contains(&key.db_id) {
+            let inconsistency = Inconsistency::DanglingDatabaseIdToName { db_id: key.db_id };
+            let absent = DatabaseId { db_id: key.db_id };
+            findings.push(Finding::remove(inconsistency, &key, seq, &absent));
+        }
+    }
+
+    let mut id_to_names = BTreeMap::new();
+    for (key, seq, data) in list_records::<TableIdToName>(kv).await? {
+        let name: DBIdTableName = codec.decode(&data).map_err(MetaError::from)?;
+        if table_ids.contains(&key.table_id) {
+            id_to_names.insert(key.table_id, name);
+        } else {
+            let inconsistency = Inconsistency::DanglingTableIdToName {
+                table_id: key.table_id,
+                name,
+            };
+            let absent = TableId {
+                table_id: key.table_id,
+            };
+            findings.push(Finding::remove(inconsistency, &key, seq, &absent));
+        }
+    }
+
+    for (name, seq, data) in list_records::<DBIdTableName>(kv).await? {
+        let table_id = *deserialize_u64(&data).map_err(MetaError::from)?;
+
+        if !db_ids.contains(&name.db_id) {
+            let inconsistency = Inconsistency::OrphanTableName {
+                name: name.clone(),
+                table_id,
+            };
+            let absent = DatabaseId { db_id: name.db_id };
+            findings.push(Finding::remove(inconsistency, &name, seq, &absent));
+        } else if !table_ids.contains(&table_id) {
+            let inconsistency = Inconsistency::DanglingTableName {
+                name: name.clone(),
+                table_id,
+            };
+            findings.push(Finding::remove(inconsistency, &name, seq, &TableId {
+                table_id,
+            }));
+        } else if !id_to_names.contains_key(&table_id) {
+            let index_key = TableIdToName { table_id };
+            let repair = TxnRequest {
+                condition: vec![
+                    txn_cond_seq(&name, ConditionResult::Eq, seq),
+                    txn_cond_seq(&index_key, ConditionResult::Eq, 0),
+                    txn_cond_seq(&TableId { table_id }, ConditionResult::Gt, 0),
+                ],
+                if_then: vec![txn_op_put(
+                    &index_key,
+                    codec.encode(&name).map_err(MetaError::from)?,
+                )],
+                else_then: vec![],
+            };
+            findings.push(Finding {
+                inconsistency: Inconsistency::MissingTableIdToName { table_id, name },
+                repair,
+            });
+        }
+    }
+
+    Ok(findings)
+}
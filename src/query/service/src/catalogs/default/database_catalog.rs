@@ -15,16 +15,37 @@
 use std::any::Any;
 use std::fmt::Debug;
 use std::fmt::Formatter;
+use std::ops::Range;
+use std::sync::atomic::AtomicBool;
+use std::sync::atomic::Ordering;
 use std::sync::Arc;
-
+use std::time::Duration;
+
+use chrono::Utc;
+use databend_common_base::base::tokio;
+use databend_common_base::base::tokio::sync::broadcast;
+use databend_common_base::runtime::GlobalIORuntime;
+use databend_common_base::runtime::TrySpawn;
+use databend_common_base::GLOBAL_TASK;
+use databend_common_catalog::catalog::audit_principal;
+use databend_common_catalog::catalog::AuditSink;
 use databend_common_catalog::catalog::Catalog;
+use databend_common_catalog::catalog::CatalogAuditEvent;
+use databend_common_catalog::catalog::CatalogAuditOp;
+use databend_common_catalog::catalog::CatalogEvent;
+use databend_common_catalog::catalog::CatalogNotification;
+use databend_common_catalog::catalog::ConsistencyReport;
+use databend_common_catalog::catalog::DropTablePlan;
+use databend_common_catalog::catalog::ReadConsistency;
 use databend_common_catalog::catalog::StorageDescription;
 use databend_common_catalog::database::Database;
 use databend_common_catalog::table_args::TableArgs;
 use databend_common_catalog::table_function::TableFunction;
+use databend_common_catalog::table_function::TableFunctionMeta;
 use databend_common_config::InnerConfig;
 use databend_common_exception::ErrorCode;
 use databend_common_exception::Result;
+use databend_common_meta_api::codec::decode_any;
 use databend_common_meta_app::schema::CatalogInfo;
 use databend_common_meta_app::schema::CountTablesReply;
 use databend_common_meta_app::schema::CountTablesReq;
@@ -68,8 +89,11 @@ use databend_common_meta_app::schema::RenameDatabaseReply;
 use databend_common_meta_app::schema::RenameDatabaseReq;
 use databend_common_meta_app::schema::RenameTableReply;
 use databend_common_meta_app::schema::RenameTableReq;
+use databend_common_meta_app::schema::SetDatabaseQuotaReply;
+use databend_common_meta_app::schema::SetDatabaseQuotaReq;
 use databend_common_meta_app::schema::SetTableColumnMaskPolicyReply;
 use databend_common_meta_app::schema::SetTableColumnMaskPolicyReq;
+use databend_common_meta_app::schema::TableId;
 use databend_common_meta_app::schema::TableIdent;
 use databend_common_meta_app::schema::TableInfo;
 use databend_common_meta_app::schema::TableMeta;
@@ -88,9 +112,24 @@ use databend_common_meta_app::schema::UpdateVirtualColumnReq;
 use databend_common_meta_app::schema::UpsertTableOptionReply;
 use databend_common_meta_app::schema::UpsertTableOptionReq;
 use databend_common_meta_app::schema::VirtualColumnMeta;
+use databend_common_meta_kvapi::kvapi;
+use databend_common_meta_kvapi::kvapi::BackoffPolicy;
+use databend_common_meta_kvapi::kvapi::Key;
+use databend_common_meta_kvapi::kvapi::WatchEvent;
+use databend_common_meta_kvapi::kvapi::WatchEventKind;
+use databend_common_meta_store::MetaStore;
+use databend_common_meta_store::WatchStream;
+use databend_common_meta_types::protobuf::watch_request::FilterType;
+use databend_common_meta_types::protobuf::WatchRequest;
 use databend_common_meta_types::MetaId;
+use databend_common_storages_stream::stream_table::STREAM_ENGINE;
+use databend_common_storages_view::view_table::VIEW_ENGINE;
+use futures::StreamExt;
 use log::info;
+use log::warn;
 
+use crate::catalogs::default::name_index::NameIndex;
+use crate::catalogs::default::name_index::NameIndexKey;
 use crate::catalogs::default::ImmutableCatalog;
 use crate::catalogs::default::MutableCatalog;
 use crate::storages::Table;
@@ -108,6 +147,18 @@ pub struct DatabaseCatalog {
     mutable_catalog: Arc<dyn Catalog>,
     /// table function engine factories
     table_function_factory: Arc<TableFunctionFactory>,
+    /// if true, every mutation is rejected with `ReadOnly`; shared by all clones
+    read_only: Arc<AtomicBool>,
+    /// if true, db and table names are resolved ignoring case; shared by all clones
+    case_insensitive: Arc<AtomicBool>,
+    /// db and table names by their lower case forms, to resolve names ignoring case; shared by all clones
+    name_index: Arc<NameIndex>,
+    /// receives an event for every DDL
+    audit_sink: Option<Arc<dyn AuditSink>>,
+    /// broadcasts DDL changes to the subscribers; shared by all clones
+    events: broadcast::Sender<CatalogNotification>,
+    /// if true, events are forwarded from the meta-service instead of being emitted locally; shared by all clones
+    watching_meta: Arc<AtomicBool>,
 }
 
 impl Debug for DatabaseCatalog {
@@ -117,6 +168,17 @@ impl Debug for DatabaseCatalog {
 }
 
 impl DatabaseCatalog {
+    /// The max number of events a subscriber can fall behind before it starts losing events.
+    const EVENT_CHANNEL_CAPACITY: usize = 1024;
+
+    /// The delay before re-establishing a failed watch stream of the meta-service, doubled after every failure.
+    const WATCH_RETRY_BASE_DELAY: Duration = Duration::from_millis(500);
+    const WATCH_RETRY_MAX_DELAY: Duration = Duration::from_secs(30);
+
+    /// How long the names listed to resolve a name ignoring case are used,
+    /// before they are listed again to find the names created through other query nodes.
+    const NAME_INDEX_TTL: Duration = Duration::from_secs(10);
+
     pub fn create(
         immutable_catalog: Arc<dyn Catalog>,
         mutable_catalog: Arc<dyn Catalog>,
@@ -126,6 +188,370 @@ impl DatabaseCatalog {
             immutable_catalog,
             mutable_catalog,
             table_function_factory,
+            read_only: Arc::new(AtomicBool::new(false)),
+            case_insensitive: Arc::new(AtomicBool::new(false)),
+            name_index: Arc::new(NameIndex::new(Self::NAME_INDEX_TTL)),
+            audit_sink: None,
+            events: broadcast::channel(Self::EVENT_CHANNEL_CAPACITY).0,
+            watching_meta: Arc::new(AtomicBool::new(false)),
+        }
+    }
+
+    /// Send an event to `sink` for every DDL applied through this catalog.
+    pub fn with_audit_sink(mut self, sink: Arc<dyn AuditSink>) -> Self {
+        self.audit_sink = Some(sink);
+        self
+    }
+
+    /// Subscribe to the DDL changes, such as creating, dropping, renaming or altering a table.
+    ///
+    /// A DDL never waits for a subscriber:
+    /// a subscriber that falls behind by more than the channel capacity loses the oldest events,
+    /// and its next `recv()` returns a `Lagged` error.
+    /// When the catalog itself loses the changes pushed by the meta-service,
+    /// the subscribers receive a [`CatalogNotification::Lagged`].
+    pub fn subscribe(&self) -> broadcast::Receiver<CatalogNotification> {
+        self.events.subscribe()
+    }
+
+    /// Forward the table changes pushed by the meta-service to the subscribers,
+    /// instead of emitting an event for every table DDL applied through this catalog.
+    ///
+    /// Thus a subscriber also receives the table changes applied by other query nodes,
+    /// but such an event contains only the table id and the seq, not the names.
+    /// The DDL that do not change a table meta, i.e., database DDL and renaming a table,
+    /// are still emitted locally.
+    ///
+    /// It returns `false` and does nothing if `meta` is an embedded store, which does not push changes.
+    /// If the watch stream fails or is closed, the catalog emits table events locally until it is re-established,
+    /// and then the subscribers receive a [`CatalogNotification::Lagged`],
+    /// since the changes applied by other nodes in between are lost.
+    pub async fn watch_meta(&self, meta: &MetaStore) -> Result<bool> {
+        if meta.is_local() {
+            return Ok(false);
+        }
+
+        let mut strm = Self::open_watch(meta).await?;
+
+        self.watching_meta.store(true, Ordering::SeqCst);
+
+        let meta = meta.clone();
+        let events = self.events.clone();
+        let watching_meta = self.watching_meta.clone();
+        GlobalIORuntime::instance().spawn(GLOBAL_TASK, async move {
+            let mut backoff = BackoffPolicy::new(
+                Self::WATCH_RETRY_BASE_DELAY,
+                2.0,
+                Self::WATCH_RETRY_MAX_DELAY,
+            )
+            .with_jitter(true);
+
+            loop {
+                if Self::forward_meta_events(&mut strm, &events).await {
+                    backoff.reset();
+                }
+
+                info!("catalog stops watching meta-service, fall back to emitting events locally");
+                watching_meta.store(false, Ordering::SeqCst);
+
+                strm = loop {
+                    tokio::time::sleep(backoff.next_delay()).await;
+
+                    match Self::open_watch(&meta).await {
+                        Ok(strm) => break strm,
+                        Err(e) => {
+                            warn!("catalog fails to watch meta-service, retry later: {}", e);
+                        }
+                    }
+                };
+
+                info!("catalog watches meta-service again");
+                watching_meta.store(true, Ordering::SeqCst);
+
+                // The number of changes lost while the stream is down is unknown.
+                let _ = events.send(CatalogNotification::Lagged(0));
+            }
+        });
+
+        Ok(true)
+    }
+
+    async fn open_watch(meta: &MetaStore) -> Result<WatchStream> {
+        let prefix = format!("{}/", TableId::PREFIX);
+        let (key, key_end) =
+            kvapi::prefix_to_range(&prefix).map_err(|e| ErrorCode::Internal(e.to_string()))?;
+
+        let strm = meta
+            .watch(WatchRequest {
+                key,
+                key_end: Some(key_end),
+                filter_type: FilterType::All.into(),
+                report_lag: true,
+            })
+            .await?;
+
+        Ok(strm)
+    }
+
+    /// Send the changes in `strm` to the subscribers until it fails or is closed.
+    ///
+    /// It returns `true` if any response is received.
+    async fn forward_meta_events(
+        strm: &mut WatchStream,
+        events: &broadcast::Sender<CatalogNotification>,
+    ) -> bool {
+        let mut received = false;
+
+        while let Some(res) = strm.next().await {
+            let resp = match res {
+                Ok(resp) => resp,
+                Err(e) => {
+                    warn!("catalog watch stream of meta-service fails: {}", e);
+                    return received;
+                }
+            };
+            received = true;
+
+            let notification = if resp.lagged > 0 {
+                warn!(
+                    "catalog lags behind meta-service, {} changes are lost",
+                    resp.lagged
+                );
+                Some(CatalogNotification::Lagged(resp.lagged))
+            } else {
+                resp.event
+                    .and_then(|ev| Self::table_event(ev.into()))
+                    .map(CatalogNotification::Event)
+            };
+
+            if let Some(notification) = notification {
+                // It is not an error if there is no subscriber.
+                let _ = events.send(notification);
+            }
+        }
+
+        info!("catalog watch stream of meta-service is closed");
+        received
+    }
+
+    /// Build an event from a change of a `__fd_table_by_id/<table_id>` record.
+    ///
+    /// A drop updates the record by setting `drop_on`, and the record is deleted when it is garbage collected.
+    fn table_event(ev: WatchEvent) -> Option<CatalogEvent> {
+        let table_id = TableId::from_str_key(&ev.key).ok()?.table_id;
+
+        let op = match (ev.kind, &ev.value) {
+            (WatchEventKind::Create, _) => CatalogAuditOp::CreateTable,
+            (WatchEventKind::Delete, _) => CatalogAuditOp::DropTable,
+            (WatchEventKind::Update, Some(v)) => {
+                let table_meta: TableMeta = decode_any(&v.data).ok()?;
+                if table_meta.drop_on.is_some() {
+                    CatalogAuditOp::DropTable
+                } else {
+                    CatalogAuditOp::AlterTable
+                }
+            }
+            (WatchEventKind::Update, None) => return None,
+            (WatchEventKind::Lagged(_), _) => return None,
+        };
+
+        Some(CatalogEvent {
+            op,
+            tenant: None,
+            db_name: None,
+            table_name: None,
+            object_id: Some(table_id),
+            seq: ev.value.map(|v| v.seq),
+        })
+    }
+
+    /// If true, an event is emitted locally for a DDL `op` applied through this catalog.
+    ///
+    /// While watching the meta-service, a table meta change is forwarded from it instead.
+    fn emits_local_event(&self, op: CatalogAuditOp) -> bool {
+        if self.events.receiver_count() == 0 {
+            return false;
+        }
+
+        !(self.watching_meta.load(Ordering::SeqCst) && Self::is_watched(op))
+    }
+
+    /// If true, the DDL `op` changes a table meta, thus it is seen by [`Self::watch_meta`].
+    fn is_watched(op: CatalogAuditOp) -> bool {
+        matches!(
+            op,
+            CatalogAuditOp::CreateTable
+                | CatalogAuditOp::DropTable
+                | CatalogAuditOp::UndropTable
+                | CatalogAuditOp::AlterTable
+        )
+    }
+
+    /// Build an event for a DDL to send after it succeeds,
+    /// or `None` if there is neither an audit sink nor a local subscriber.
+    fn audit_event(
+        &self,
+        op: CatalogAuditOp,
+        tenant: &str,
+        db_name: Option<&str>,
+        table_name: Option<&str>,
+    ) -> Option<CatalogAuditEvent> {
+        if self.audit_sink.is_none() && !self.emits_local_event(op) {
+            return None;
+        }
+
+        Some(CatalogAuditEvent {
+            op,
+            tenant: tenant.to_string(),
+            db_name: db_name.map(|x| x.to_string()),
+            table_name: table_name.map(|x| x.to_string()),
+            principal: audit_principal(),
+            timestamp: Utc::now(),
+            object_id: None,
+        })
+    }
+
+    async fn audit(&self, event: Option<CatalogAuditEvent>, object_id: Option<MetaId>) {
+        let Some(mut event) = event else {
+            return;
+        };
+
+        event.timestamp = Utc::now();
+        event.object_id = object_id;
+
+        if self.emits_local_event(event.op) {
+            // A lagging subscriber loses events instead of blocking the DDL.
+            let _ = self
+                .events
+                .send(CatalogNotification::Event(CatalogEvent::from(&event)));
+        }
+
+        if let Some(sink) = &self.audit_sink {
+            sink.record(event).await;
+        }
+    }
+
+    /// Reject or accept mutations of the meta, such as DDL, e.g., when the meta-service is in maintenance.
+    ///
+    /// Reads are not affected.
+    pub fn set_read_only(&self, read_only: bool) {
+        self.read_only.store(read_only, Ordering::SeqCst);
+    }
+
+    pub fn is_read_only(&self) -> bool {
+        self.read_only.load(Ordering::SeqCst)
+    }
+
+    fn check_writable(&self, op: &str) -> Result<()> {
+        if self.is_read_only() {
+            return Err(ErrorCode::ReadOnly(format!(
+                "Catalog is read only, can not {}",
+                op
+            )));
+        }
+        Ok(())
+    }
+
+    /// Resolve db and table names ignoring case, e.g., `MyTable` and `mytable` refer to the same table.
+    ///
+    /// A name is stored as it is created, thus it is displayed as it is.
+    /// A lookup that does not find the exact name falls back to comparing the lower case forms,
+    /// with the names listed by a previous lookup in [`Self::NAME_INDEX_TTL`],
+    /// thus a name created through another query node may not be found in this time.
+    /// Creating a db or table whose name differs from an existing one only in case is rejected.
+    pub fn set_case_insensitive(&self, case_insensitive: bool) {
+        self.case_insensitive
+            .store(case_insensitive, Ordering::SeqCst);
+    }
+
+    pub fn is_case_insensitive(&self) -> bool {
+        self.case_insensitive.load(Ordering::SeqCst)
+    }
+
+    /// Returns the name of the existing db that `db_name` refers to when ignoring case.
+    ///
+    /// It returns `None` if there is no such db, or names are case-sensitive.
+    async fn resolve_db_name(&self, tenant: &str, db_name: &str) -> Result<Option<String>> {
+        if !self.is_case_insensitive() {
+            return Ok(None);
+        }
+
+        let key = NameIndexKey::dbs(tenant);
+        if let Some(found) = self.name_index.lookup(&key, db_name) {
+            return Ok(found);
+        }
+
+        let dbs = self.list_databases(tenant).await?;
+        self.name_index
+            .insert(key.clone(), dbs.iter().map(|db| db.name()));
+        Ok(self.name_index.lookup(&key, db_name).flatten())
+    }
+
+    /// Returns the name of the existing table that `table_name` refers to when ignoring case.
+    ///
+    /// It returns `None` if there is no such table or db, or names are case-sensitive.
+    async fn resolve_table_name(
+        &self,
+        tenant: &str,
+        db_name: &str,
+        table_name: &str,
+    ) -> Result<Option<String>> {
+        if !self.is_case_insensitive() {
+            return Ok(None);
+        }
+
+        let key = NameIndexKey::tables(tenant, db_name);
+        if let Some(found) = self.name_index.lookup(&key, table_name) {
+            return Ok(found);
+        }
+
+        let tables = match self.list_tables(tenant, db_name).await {
+            Ok(tables) => tables,
+            Err(e) if e.code() == ErrorCode::UNKNOWN_DATABASE => return Ok(None),
+            Err(e) => return Err(e),
+        };
+        self.name_index
+            .insert(key.clone(), tables.iter().map(|t| t.name()));
+        Ok(self.name_index.lookup(&key, table_name).flatten())
+    }
+
+    async fn get_database_exact(&self, tenant: &str, db_name: &str) -> Result<Arc<dyn Database>> {
+        let r = self.immutable_catalog.get_database(tenant, db_name).await;
+        match r {
+            Err(e) => {
+                if e.code() == ErrorCode::UNKNOWN_DATABASE {
+                    self.mutable_catalog.get_database(tenant, db_name).await
+                } else {
+                    Err(e)
+                }
+            }
+            Ok(db) => Ok(db),
+        }
+    }
+
+    /// The system tables in the immutable catalog are in memory, thus `level` only applies to the mutable catalog.
+    async fn get_table_exact(
+        &self,
+        tenant: &str,
+        db_name: &str,
+        table_name: &str,
+        level: ReadConsistency,
+    ) -> Result<Arc<dyn Table>> {
+        let res = self
+            .immutable_catalog
+            .get_table(tenant, db_name, table_name)
+            .await;
+        match res {
+            Ok(v) => Ok(v),
+            Err(e) => {
+                if e.code() == ErrorCode::UNKNOWN_DATABASE {
+                    self.mutable_catalog
+                        .get_table_consistent(tenant, db_name, table_name, level)
+                        .await
+                } else {
+                    Err(e)
+                }
+            }
         }
     }
 
@@ -165,16 +591,14 @@ impl Catalog for DatabaseCatalog {
             ));
         }
 
-        let r = self.immutable_catalog.get_database(tenant, db_name).await;
-        match r {
-            Err(e) => {
-                if e.code() == ErrorCode::UNKNOWN_DATABASE {
-                    self.mutable_catalog.get_database(tenant, db_name).await
-                } else {
-                    Err(e)
+        match self.get_database_exact(tenant, db_name).await {
+            Err(e) if e.code() == ErrorCode::UNKNOWN_DATABASE => {
+                match self.resolve_db_name(tenant, db_name).await? {
+                    Some(name) => self.get_database_exact(tenant, &name).await,
+                    None => Err(e),
                 }
             }
-            Ok(db) => Ok(db),
+            res => res,
         }
     }
 
@@ -194,6 +618,7 @@ impl Catalog for DatabaseCatalog {
 
     #[async_backtrace::framed]
     async fn create_database(&self, req: CreateDatabaseReq) -> Result<CreateDatabaseReply> {
+        self.check_writable("create_database")?;
         if req.name_ident.tenant.is_empty() {
             return Err(ErrorCode::TenantIsEmpty(
                 "Tenant can not empty(while create database)",
@@ -201,6 +626,22 @@ impl Catalog for DatabaseCatalog {
         }
         info!("Create database from req:{:?}", req);
 
+        // Check the conflicts with the latest names.
+        self.name_index.invalidate(&req.name_ident.tenant);
+
+        let db_name = &req.name_ident.db_name;
+        if let Some(existing) = self
+            .resolve_db_name(&req.name_ident.tenant, db_name)
+            .await?
+        {
+            if &existing != db_name {
+                return Err(ErrorCode::DatabaseAlreadyExists(format!(
+                    "{} database conflicts with existing database {}, names are case-insensitive",
+                    db_name, existing
+                )));
+            }
+        }
+
         if self
             .immutable_catalog
             .exists_database(&req.name_ident.tenant, &req.name_ident.db_name)
@@ -211,12 +652,26 @@ impl Catalog for DatabaseCatalog {
                 req.name_ident.db_name
             )));
         }
+
+        let event = self.audit_event(
+            CatalogAuditOp::CreateDatabase,
+            &req.name_ident.tenant,
+            Some(&req.name_ident.db_name),
+            None,
+        );
+
+        let tenant = req.name_ident.tenant.clone();
+
         // create db in BOTTOM layer only
-        self.mutable_catalog.create_database(req).await
+        let reply = self.mutable_catalog.create_database(req).await?;
+        self.name_index.invalidate(&tenant);
+        self.audit(event, Some(reply.db_id)).await;
+        Ok(reply)
     }
 
     #[async_backtrace::framed]
     async fn drop_database(&self, req: DropDatabaseReq) -> Result<DropDatabaseReply> {
+        self.check_writable("drop_database")?;
         if req.name_ident.tenant.is_empty() {
             return Err(ErrorCode::TenantIsEmpty(
                 "Tenant can not empty(while drop database)",
@@ -224,19 +679,37 @@ impl Catalog for DatabaseCatalog {
         }
         info!("Drop database from req:{:?}", req);
 
+        let event = self.audit_event(
+            CatalogAuditOp::DropDatabase,
+            &req.name_ident.tenant,
+            Some(&req.name_ident.db_name),
+            None,
+        );
+
+        let tenant = req.name_ident.tenant.clone();
+
         // drop db in BOTTOM layer only
-        if self
+        let reply = if self
             .immutable_catalog
             .exists_database(&req.name_ident.tenant, &req.name_ident.db_name)
             .await?
         {
-            return self.immutable_catalog.drop_database(req).await;
+            self.immutable_catalog.drop_database(req).await?
+        } else {
+            self.mutable_catalog.drop_database(req).await?
+        };
+        self.name_index.invalidate(&tenant);
+
+        // Nothing is dropped if the database does not exist and `if_exists` is set.
+        if reply.db_id.is_some() {
+            self.audit(event, reply.db_id).await;
         }
-        self.mutable_catalog.drop_database(req).await
+        Ok(reply)
     }
 
     #[async_backtrace::framed]
     async fn rename_database(&self, req: RenameDatabaseReq) -> Result<RenameDatabaseReply> {
+        self.check_writable("rename_database")?;
         if req.name_ident.tenant.is_empty() {
             return Err(ErrorCode::TenantIsEmpty(
                 "Tenant can not empty(while rename database)",
@@ -256,7 +729,49 @@ impl Catalog for DatabaseCatalog {
             return self.immutable_catalog.rename_database(req).await;
         }
 
-        self.mutable_catalog.rename_database(req).await
+        let event = self.audit_event(
+            CatalogAuditOp::RenameDatabase,
+            &req.name_ident.tenant,
+            Some(&req.name_ident.db_name),
+            None,
+        );
+
+        let tenant = req.name_ident.tenant.clone();
+
+        let reply = self.mutable_catalog.rename_database(req).await?;
+        self.name_index.invalidate(&tenant);
+        self.audit(event, None).await;
+        Ok(reply)
+    }
+
+    #[async_backtrace::framed]
+    async fn set_database_quota(&self, req: SetDatabaseQuotaReq) -> Result<SetDatabaseQuotaReply> {
+        self.check_writable("set_database_quota")?;
+        if req.name_ident.tenant.is_empty() {
+            return Err(ErrorCode::TenantIsEmpty(
+                "Tenant can not empty(while set database quota)",
+            ));
+        }
+        info!("Set database quota from req:{:?}", req);
+
+        if self
+            .immutable_catalog
+            .exists_database(&req.name_ident.tenant, &req.name_ident.db_name)
+            .await?
+        {
+            return self.immutable_catalog.set_database_quota(req).await;
+        }
+
+        let event = self.audit_event(
+            CatalogAuditOp::AlterDatabase,
+            &req.name_ident.tenant,
+            Some(&req.name_ident.db_name),
+            None,
+        );
+
+        let reply = self.mutable_catalog.set_database_quota(req).await?;
+        self.audit(event, None).await;
+        Ok(reply)
     }
 
     fn get_table_by_info(&self, table_info: &TableInfo) -> Result<Arc<dyn Table>> {
@@ -295,6 +810,17 @@ impl Catalog for DatabaseCatalog {
         }
     }
 
+    #[async_backtrace::framed]
+    async fn get_table_by_id(&self, table_id: MetaId) -> Result<Arc<dyn Table>> {
+        let res = self.immutable_catalog.get_table_by_id(table_id).await;
+
+        if let Ok(x) = res {
+            Ok(x)
+        } else {
+            self.mutable_catalog.get_table_by_id(table_id).await
+        }
+    }
+
     #[async_backtrace::framed]
     async fn get_db_name_by_id(&self, db_id: MetaId) -> Result<String> {
         let res = self.immutable_catalog.get_db_name_by_id(db_id).await;
@@ -312,6 +838,18 @@ impl Catalog for DatabaseCatalog {
         tenant: &str,
         db_name: &str,
         table_name: &str,
+    ) -> Result<Arc<dyn Table>> {
+        self.get_table_consistent(tenant, db_name, table_name, ReadConsistency::Cached)
+            .await
+    }
+
+    #[async_backtrace::framed]
+    async fn get_table_consistent(
+        &self,
+        tenant: &str,
+        db_name: &str,
+        table_name: &str,
+        level: ReadConsistency,
     ) -> Result<Arc<dyn Table>> {
         if tenant.is_empty() {
             return Err(ErrorCode::TenantIsEmpty(
@@ -319,17 +857,78 @@ impl Catalog for DatabaseCatalog {
             ));
         }
 
-        let res = self
+        match self
+            .get_table_exact(tenant, db_name, table_name, level)
+            .await
+        {
+            Err(e)
+                if self.is_case_insensitive()
+                    && (e.code() == ErrorCode::UNKNOWN_DATABASE
+                        || e.code() == ErrorCode::UNKNOWN_TABLE) =>
+            {
+                let db_name = self
+                    .resolve_db_name(tenant, db_name)
+                    .await?
+                    .unwrap_or_else(|| db_name.to_string());
+
+                match self
+                    .resolve_table_name(tenant, &db_name, table_name)
+                    .await?
+                {
+                    Some(name) => self.get_table_exact(tenant, &db_name, &name, level).await,
+                    None => Err(e),
+                }
+            }
+            res => res,
+        }
+    }
+
+    #[async_backtrace::framed]
+    async fn exists_table(&self, tenant: &str, db_name: &str, table_name: &str) -> Result<bool> {
+        if tenant.is_empty() {
+            return Err(ErrorCode::TenantIsEmpty(
+                "Tenant can not empty(while check table exists)",
+            ));
+        }
+
+        if self.is_case_insensitive() {
+            return match self.get_table(tenant, db_name, table_name).await {
+                Ok(_) => Ok(true),
+                Err(e) if e.code() == ErrorCode::UNKNOWN_TABLE => Ok(false),
+                Err(e) => Err(e),
+            };
+        }
+
+        if self
             .immutable_catalog
-            .get_table(tenant, db_name, table_name)
-            .await;
-        match res {
-            Ok(v) => Ok(v),
+            .exists_database(tenant, db_name)
+            .await?
+        {
+            return self
+                .immutable_catalog
+                .exists_table(tenant, db_name, table_name)
+                .await;
+        }
+
+        self.mutable_catalog
+            .exists_table(tenant, db_name, table_name)
+            .await
+    }
+
+    #[async_backtrace::framed]
+    async fn list_tables(&self, tenant: &str, db_name: &str) -> Result<Vec<Arc<dyn Table>>> {
+        if tenant.is_empty() {
+            return Err(ErrorCode::TenantIsEmpty(
+                "Tenant can not empty(while list tables)",
+            ));
+        }
+
+        let r = self.immutable_catalog.list_tables(tenant, db_name).await;
+        match r {
+            Ok(x) => Ok(x),
             Err(e) => {
                 if e.code() == ErrorCode::UNKNOWN_DATABASE {
-                    self.mutable_catalog
-                        .get_table(tenant, db_name, table_name)
-                        .await
+                    self.mutable_catalog.list_tables(tenant, db_name).await
                 } else {
                     Err(e)
                 }
@@ -338,19 +937,42 @@ impl Catalog for DatabaseCatalog {
     }
 
     #[async_backtrace::framed]
-    async fn list_tables(&self, tenant: &str, db_name: &str) -> Result<Vec<Arc<dyn Table>>> {
+    async fn mget_tables(
+        &self,
+        tenant: &str,
+        db_name: &str,
+        table_names: &[String],
+    ) -> Result<Vec<Arc<dyn Table>>> {
         if tenant.is_empty() {
             return Err(ErrorCode::TenantIsEmpty(
-                "Tenant can not empty(while list tables)",
+                "Tenant can not empty(while mget tables)",
             ));
         }
 
-        let r = self.immutable_catalog.list_tables(tenant, db_name).await;
+        // A name may need to be resolved ignoring case, one by one.
+        if self.is_case_insensitive() {
+            let mut tables = Vec::with_capacity(table_names.len());
+            for table_name in table_names {
+                match self.get_table(tenant, db_name, table_name).await {
+                    Ok(table) => tables.push(table),
+                    Err(e) if e.code() == ErrorCode::UNKNOWN_TABLE => {}
+                    Err(e) => return Err(e),
+                }
+            }
+            return Ok(tables);
+        }
+
+        let r = self
+            .immutable_catalog
+            .mget_tables(tenant, db_name, table_names)
+            .await;
         match r {
             Ok(x) => Ok(x),
             Err(e) => {
                 if e.code() == ErrorCode::UNKNOWN_DATABASE {
-                    self.mutable_catalog.list_tables(tenant, db_name).await
+                    self.mutable_catalog
+                        .mget_tables(tenant, db_name, table_names)
+                        .await
                 } else {
                     Err(e)
                 }
@@ -389,7 +1011,8 @@ impl Catalog for DatabaseCatalog {
     }
 
     #[async_backtrace::framed]
-    async fn create_table(&self, req: CreateTableReq) -> Result<CreateTableReply> {
+    async fn create_table(&self, mut req: CreateTableReq) -> Result<CreateTableReply> {
+        self.check_writable("create_table")?;
         if req.tenant().is_empty() {
             return Err(ErrorCode::TenantIsEmpty(
                 "Tenant can not empty(while create table)",
@@ -397,24 +1020,84 @@ impl Catalog for DatabaseCatalog {
         }
         info!("Create table from req:{:?}", req);
 
-        if self
+        // A view or a stream does not store data, its schema is derived from the query or the base table.
+        if !matches!(req.table_meta.engine.as_str(), VIEW_ENGINE | STREAM_ENGINE) {
+            req.table_meta.schema.validate()?;
+        }
+
+        // Check the conflicts with the latest names.
+        self.name_index.invalidate(req.tenant());
+
+        if let Some(db_name) = self.resolve_db_name(req.tenant(), req.db_name()).await? {
+            req.name_ident.db_name = db_name;
+        }
+
+        let table_name = req.table_name();
+        if let Some(existing) = self
+            .resolve_table_name(req.tenant(), req.db_name(), table_name)
+            .await?
+        {
+            if existing != table_name {
+                return Err(ErrorCode::TableAlreadyExists(format!(
+                    "{} table conflicts with existing table {}, names are case-insensitive",
+                    table_name, existing
+                )));
+            }
+        }
+
+        let event = self.audit_event(
+            CatalogAuditOp::CreateTable,
+            req.tenant(),
+            Some(req.db_name()),
+            Some(req.table_name()),
+        );
+
+        let tenant = req.tenant().to_string();
+
+        let reply = if self
             .immutable_catalog
             .exists_database(req.tenant(), req.db_name())
             .await?
         {
-            return self.immutable_catalog.create_table(req).await;
-        }
-        self.mutable_catalog.create_table(req).await
+            self.immutable_catalog.create_table(req).await?
+        } else {
+            self.mutable_catalog.create_table(req).await?
+        };
+        self.name_index.invalidate(&tenant);
+
+        self.audit(event, Some(reply.table_id)).await;
+        Ok(reply)
     }
 
     #[async_backtrace::framed]
     async fn drop_table_by_id(&self, req: DropTableByIdReq) -> Result<DropTableReply> {
+        self.check_writable("drop_table_by_id")?;
+
+        let event = self.audit_event(
+            CatalogAuditOp::DropTable,
+            &req.tenant,
+            None,
+            Some(&req.table_name),
+        );
+        let table_id = req.tb_id;
+        let tenant = req.tenant.clone();
+
         let res = self.mutable_catalog.drop_table_by_id(req).await?;
+        self.name_index.invalidate(&tenant);
+        self.audit(event, Some(table_id)).await;
         Ok(res)
     }
 
+    #[async_backtrace::framed]
+    async fn plan_drop_table_by_id(&self, req: DropTableByIdReq) -> Result<DropTablePlan> {
+        // Routed the same way as `drop_table_by_id`.
+        self.check_writable("drop_table_by_id")?;
+        self.mutable_catalog.plan_drop_table_by_id(req).await
+    }
+
     #[async_backtrace::framed]
     async fn undrop_table(&self, req: UndropTableReq) -> Result<UndropTableReply> {
+        self.check_writable("undrop_table")?;
         if req.tenant().is_empty() {
             return Err(ErrorCode::TenantIsEmpty(
                 "Tenant can not empty(while undrop table)",
@@ -422,18 +1105,33 @@ impl Catalog for DatabaseCatalog {
         }
         info!("Undrop table from req:{:?}", req);
 
-        if self
+        let event = self.audit_event(
+            CatalogAuditOp::UndropTable,
+            req.tenant(),
+            Some(req.db_name()),
+            Some(req.table_name()),
+        );
+
+        let tenant = req.tenant().to_string();
+
+        let reply = if self
             .immutable_catalog
             .exists_database(req.tenant(), req.db_name())
             .await?
         {
-            return self.immutable_catalog.undrop_table(req).await;
-        }
-        self.mutable_catalog.undrop_table(req).await
+            self.immutable_catalog.undrop_table(req).await?
+        } else {
+            self.mutable_catalog.undrop_table(req).await?
+        };
+        self.name_index.invalidate(&tenant);
+
+        self.audit(event, None).await;
+        Ok(reply)
     }
 
     #[async_backtrace::framed]
     async fn undrop_database(&self, req: UndropDatabaseReq) -> Result<UndropDatabaseReply> {
+        self.check_writable("undrop_database")?;
         if req.tenant().is_empty() {
             return Err(ErrorCode::TenantIsEmpty(
                 "Tenant can not empty(while undrop database)",
@@ -441,18 +1139,33 @@ impl Catalog for DatabaseCatalog {
         }
         info!("Undrop database from req:{:?}", req);
 
-        if self
+        let event = self.audit_event(
+            CatalogAuditOp::UndropDatabase,
+            req.tenant(),
+            Some(req.db_name()),
+            None,
+        );
+
+        let tenant = req.tenant().to_string();
+
+        let reply = if self
             .immutable_catalog
             .exists_database(req.tenant(), req.db_name())
             .await?
         {
-            return self.immutable_catalog.undrop_database(req).await;
-        }
-        self.mutable_catalog.undrop_database(req).await
+            self.immutable_catalog.undrop_database(req).await?
+        } else {
+            self.mutable_catalog.undrop_database(req).await?
+        };
+        self.name_index.invalidate(&tenant);
+
+        self.audit(event, None).await;
+        Ok(reply)
     }
 
     #[async_backtrace::framed]
     async fn rename_table(&self, req: RenameTableReq) -> Result<RenameTableReply> {
+        self.check_writable("rename_table")?;
         if req.tenant().is_empty() {
             return Err(ErrorCode::TenantIsEmpty(
                 "Tenant can not empty(while rename table)",
@@ -474,7 +1187,19 @@ impl Catalog for DatabaseCatalog {
             ));
         }
 
-        self.mutable_catalog.rename_table(req).await
+        let event = self.audit_event(
+            CatalogAuditOp::RenameTable,
+            req.tenant(),
+            Some(req.db_name()),
+            Some(req.table_name()),
+        );
+
+        let tenant = req.tenant().to_string();
+
+        let reply = self.mutable_catalog.rename_table(req).await?;
+        self.name_index.invalidate(&tenant);
+        self.audit(event, Some(reply.table_id)).await;
+        Ok(reply)
     }
 
     #[async_backtrace::framed]
@@ -508,6 +1233,7 @@ impl Catalog for DatabaseCatalog {
         table_info: &TableInfo,
         req: TruncateTableReq,
     ) -> Result<TruncateTableReply> {
+        self.check_writable("truncate_table")?;
         self.mutable_catalog.truncate_table(table_info, req).await
     }
 
@@ -518,9 +1244,17 @@ impl Catalog for DatabaseCatalog {
         db_name: &str,
         req: UpsertTableOptionReq,
     ) -> Result<UpsertTableOptionReply> {
-        self.mutable_catalog
+        self.check_writable("upsert_table_option")?;
+
+        let event = self.audit_event(CatalogAuditOp::AlterTable, tenant, Some(db_name), None);
+        let table_id = req.table_id;
+
+        let reply = self
+            .mutable_catalog
             .upsert_table_option(tenant, db_name, req)
-            .await
+            .await?;
+        self.audit(event, Some(table_id)).await;
+        Ok(reply)
     }
 
     #[async_backtrace::framed]
@@ -529,9 +1263,22 @@ impl Catalog for DatabaseCatalog {
         table_info: &TableInfo,
         req: UpdateTableMetaReq,
     ) -> Result<UpdateTableMetaReply> {
-        self.mutable_catalog
+        self.check_writable("update_table_meta")?;
+
+        let event = self.audit_event(
+            CatalogAuditOp::AlterTable,
+            &table_info.tenant,
+            None,
+            Some(&table_info.name),
+        );
+        let table_id = req.table_id;
+
+        let reply = self
+            .mutable_catalog
             .update_table_meta(table_info, req)
-            .await
+            .await?;
+        self.audit(event, Some(table_id)).await;
+        Ok(reply)
     }
 
     #[async_backtrace::framed]
@@ -539,18 +1286,30 @@ impl Catalog for DatabaseCatalog {
         &self,
         req: SetTableColumnMaskPolicyReq,
     ) -> Result<SetTableColumnMaskPolicyReply> {
-        self.mutable_catalog.set_table_column_mask_policy(req).await
+        self.check_writable("set_table_column_mask_policy")?;
+
+        let event = self.audit_event(CatalogAuditOp::AlterTable, &req.tenant, None, None);
+        let table_id = req.table_id;
+
+        let reply = self
+            .mutable_catalog
+            .set_table_column_mask_policy(req)
+            .await?;
+        self.audit(event, Some(table_id)).await;
+        Ok(reply)
     }
 
     // Table index
 
     #[async_backtrace::framed]
     async fn create_index(&self, req: CreateIndexReq) -> Result<CreateIndexReply> {
+        self.check_writable("create_index")?;
         self.mutable_catalog.create_index(req).await
     }
 
     #[async_backtrace::framed]
     async fn drop_index(&self, req: DropIndexReq) -> Result<DropIndexReply> {
+        self.check_writable("drop_index")?;
         self.mutable_catalog.drop_index(req).await
     }
 
@@ -561,6 +1320,7 @@ impl Catalog for DatabaseCatalog {
 
     #[async_backtrace::framed]
     async fn update_index(&self, req: UpdateIndexReq) -> Result<UpdateIndexReply> {
+        self.check_writable("update_index")?;
         self.mutable_catalog.update_index(req).await
     }
 
@@ -589,6 +1349,7 @@ impl Catalog for DatabaseCatalog {
         &self,
         req: CreateVirtualColumnReq,
     ) -> Result<CreateVirtualColumnReply> {
+        self.check_writable("create_virtual_column")?;
         self.mutable_catalog.create_virtual_column(req).await
     }
 
@@ -597,6 +1358,7 @@ impl Catalog for DatabaseCatalog {
         &self,
         req: UpdateVirtualColumnReq,
     ) -> Result<UpdateVirtualColumnReply> {
+        self.check_writable("update_virtual_column")?;
         self.mutable_catalog.update_virtual_column(req).await
     }
 
@@ -605,6 +1367,7 @@ impl Catalog for DatabaseCatalog {
         &self,
         req: DropVirtualColumnReq,
     ) -> Result<DropVirtualColumnReply> {
+        self.check_writable("drop_virtual_column")?;
         self.mutable_catalog.drop_virtual_column(req).await
     }
 
@@ -628,7 +1391,7 @@ impl Catalog for DatabaseCatalog {
         self.table_function_factory.exists(func_name)
     }
 
-    fn list_table_functions(&self) -> Vec<String> {
+    fn list_table_functions(&self) -> Vec<TableFunctionMeta> {
         self.table_function_factory.list()
     }
 
@@ -644,16 +1407,19 @@ impl Catalog for DatabaseCatalog {
 
     #[async_backtrace::framed]
     async fn create_lock_revision(&self, req: CreateLockRevReq) -> Result<CreateLockRevReply> {
+        self.check_writable("create_lock_revision")?;
         self.mutable_catalog.create_lock_revision(req).await
     }
 
     #[async_backtrace::framed]
     async fn extend_lock_revision(&self, req: ExtendLockRevReq) -> Result<()> {
+        self.check_writable("extend_lock_revision")?;
         self.mutable_catalog.extend_lock_revision(req).await
     }
 
     #[async_backtrace::framed]
     async fn delete_lock_revision(&self, req: DeleteLockRevReq) -> Result<()> {
+        self.check_writable("delete_lock_revision")?;
         self.mutable_catalog.delete_lock_revision(req).await
     }
 
@@ -670,6 +1436,22 @@ impl Catalog for DatabaseCatalog {
     }
 
     async fn gc_drop_tables(&self, req: GcDroppedTableReq) -> Result<GcDroppedTableResp> {
+        self.check_writable("gc_drop_tables")?;
         self.mutable_catalog.gc_drop_tables(req).await
     }
+
+    #[async_backtrace::framed]
+    async fn reserve_table_ids(&self, n: u64) -> Result<Range<u64>> {
+        self.check_writable("reserve_table_ids")?;
+        self.mutable_catalog.reserve_table_ids(n).await
+    }
+
+    #[async_backtrace::framed]
+    async fn check_consistency(&self, repair: bool) -> Result<ConsistencyReport> {
+        if repair {
+            self.check_writable("check_consistency")?;
+        }
+        // The system databases are built in memory at startup, only the mutable catalog is persisted.
+        self.mutable_catalog.check_consistency(repair).await
+    }
 }
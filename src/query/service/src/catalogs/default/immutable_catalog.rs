@@ -21,6 +21,8 @@ use databend_common_catalog::catalog::Catalog;
 use databend_common_config::InnerConfig;
 use databend_common_exception::ErrorCode;
 use databend_common_exception::Result;
+use databend_common_meta_app::app_error::AppError;
+use databend_common_meta_app::app_error::UnknownDatabase;
 use databend_common_meta_app::schema::CatalogInfo;
 use databend_common_meta_app::schema::CountTablesReply;
 use databend_common_meta_app::schema::CountTablesReq;
@@ -143,10 +145,7 @@ impl Catalog for ImmutableCatalog {
         match db_name {
             "system" => Ok(self.sys_db.clone()),
             "information_schema" => Ok(self.info_schema_db.clone()),
-            _ => Err(ErrorCode::UnknownDatabase(format!(
-                "Unknown database {}",
-                db_name
-            ))),
+            _ => Err(AppError::from(UnknownDatabase::new(db_name, "get_database")).into()),
         }
     }
 
@@ -198,6 +197,13 @@ impl Catalog for ImmutableCatalog {
         Ok(table.name().to_string())
     }
 
+    #[async_backtrace::framed]
+    async fn get_table_by_id(&self, table_id: MetaId) -> Result<Arc<dyn Table>> {
+        self.sys_db_meta
+            .get_by_id(&table_id)
+            .ok_or_else(|| ErrorCode::UnknownTable(format!("Unknown table id: '{}'", table_id)))
+    }
+
     async fn get_db_name_by_id(&self, db_id: MetaId) -> databend_common_exception::Result<String> {
         if self.sys_db.get_db_info().ident.db_id == db_id {
             Ok("system".to_string())
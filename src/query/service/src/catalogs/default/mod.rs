@@ -13,9 +13,12 @@
 // limitations under the License.
 
 mod catalog_context;
+mod consistency_check;
 mod database_catalog;
 mod immutable_catalog;
 mod mutable_catalog;
+pub mod name_index;
+mod table_id_allocator;
 pub mod table_id_ranges;
 pub mod table_memory_meta;
 
@@ -15,11 +15,19 @@
 use std::any::Any;
 use std::fmt::Debug;
 use std::fmt::Formatter;
+use std::ops::Range;
 use std::sync::Arc;
 
 use databend_common_catalog::catalog::Catalog;
+use databend_common_catalog::catalog::ConsistencyReport;
+use databend_common_catalog::catalog::DropTablePlan;
+use databend_common_catalog::catalog::ReadConsistency;
 use databend_common_config::InnerConfig;
+use databend_common_exception::ErrorCode;
 use databend_common_exception::Result;
+use databend_common_meta_api::codec::ProtobufCodec;
+use databend_common_meta_api::get_pb_value;
+use databend_common_meta_api::get_u64_value;
 use databend_common_meta_api::SchemaApi;
 use databend_common_meta_app::schema::CatalogInfo;
 use databend_common_meta_app::schema::CountTablesReply;
@@ -34,6 +42,8 @@ use databend_common_meta_app::schema::CreateTableReply;
 use databend_common_meta_app::schema::CreateTableReq;
 use databend_common_meta_app::schema::CreateVirtualColumnReply;
 use databend_common_meta_app::schema::CreateVirtualColumnReq;
+use databend_common_meta_app::schema::DBIdTableName;
+use databend_common_meta_app::schema::DatabaseId;
 use databend_common_meta_app::schema::DatabaseIdent;
 use databend_common_meta_app::schema::DatabaseInfo;
 use databend_common_meta_app::schema::DatabaseMeta;
@@ -71,6 +81,8 @@ use databend_common_meta_app::schema::RenameDatabaseReply;
 use databend_common_meta_app::schema::RenameDatabaseReq;
 use databend_common_meta_app::schema::RenameTableReply;
 use databend_common_meta_app::schema::RenameTableReq;
+use databend_common_meta_app::schema::SetDatabaseQuotaReply;
+use databend_common_meta_app::schema::SetDatabaseQuotaReq;
 use databend_common_meta_app::schema::SetTableColumnMaskPolicyReply;
 use databend_common_meta_app::schema::SetTableColumnMaskPolicyReq;
 use databend_common_meta_app::schema::TableIdent;
@@ -91,14 +103,21 @@ use databend_common_meta_app::schema::UpdateVirtualColumnReq;
 use databend_common_meta_app::schema::UpsertTableOptionReply;
 use databend_common_meta_app::schema::UpsertTableOptionReq;
 use databend_common_meta_app::schema::VirtualColumnMeta;
+use databend_common_meta_kvapi::kvapi::KVApi;
+use databend_common_meta_kvapi::kvapi::Key;
 use databend_common_meta_store::MetaStoreProvider;
 use databend_common_meta_types::MetaId;
+use databend_common_meta_types::TxnRequest;
+use databend_common_meta_types::FEATURE_RESERVE_SEQ;
 use log::info;
 
 use crate::catalogs::default::catalog_context::CatalogContext;
+use crate::catalogs::default::consistency_check;
+use crate::catalogs::default::table_id_allocator::TableIdAllocator;
 use crate::databases::Database;
 use crate::databases::DatabaseContext;
 use crate::databases::DatabaseFactory;
+use crate::databases::TableCountCache;
 use crate::storages::StorageDescription;
 use crate::storages::StorageFactory;
 use crate::storages::Table;
@@ -168,6 +187,8 @@ impl MutableCatalog {
             meta,
             storage_factory: Arc::new(storage_factory),
             database_factory: Arc::new(database_factory),
+            table_counts: Arc::new(TableCountCache::default()),
+            table_ids: Arc::new(TableIdAllocator::default()),
         };
         Ok(MutableCatalog {
             ctx,
@@ -180,9 +201,43 @@ impl MutableCatalog {
             meta: self.ctx.meta.clone(),
             storage_factory: self.ctx.storage_factory.clone(),
             tenant: self.tenant.clone(),
+            table_counts: self.ctx.table_counts.clone(),
         };
         self.ctx.database_factory.get_database(ctx, db_info)
     }
+
+    /// Wait until the following reads see every change committed to the meta-service before this call.
+    ///
+    /// An empty transaction is committed through a quorum, like the `sync` of ZooKeeper.
+    /// The reads are served by the leader, which has applied the transaction when it replies.
+    pub async fn sync_meta(&self) -> Result<()> {
+        self.ctx.meta.transaction(TxnRequest::default()).await?;
+        Ok(())
+    }
+
+    /// Take a reserved table id, reserve another block if they are exhausted.
+    ///
+    /// `None` if no id is reserved, the meta-service allocates one when creating the table.
+    async fn take_table_id(&self) -> Result<Option<MetaId>> {
+        let table_ids = &self.ctx.table_ids;
+
+        if let Some(id) = table_ids.take() {
+            return Ok(Some(id));
+        }
+
+        let n = table_ids.block_size();
+        if n == 0 {
+            return Ok(None);
+        }
+
+        let Some(ids) = self.ctx.meta.reserve_table_ids(n).await? else {
+            // Not supported any more, e.g., the feature is disabled.
+            table_ids.set_block_size(0);
+            return Ok(None);
+        };
+        table_ids.add(ids.start + 1..ids.end);
+        Ok(Some(ids.start))
+    }
 }
 
 #[async_trait::async_trait]
@@ -339,6 +394,12 @@ impl Catalog for MutableCatalog {
         Ok(res)
     }
 
+    #[async_backtrace::framed]
+    async fn set_database_quota(&self, req: SetDatabaseQuotaReq) -> Result<SetDatabaseQuotaReply> {
+        let res = self.ctx.meta.set_database_quota(req).await?;
+        Ok(res)
+    }
+
     fn get_table_by_info(&self, table_info: &TableInfo) -> Result<Arc<dyn Table>> {
         let storage = self.ctx.storage_factory.clone();
         storage.get_table(table_info)
@@ -379,12 +440,74 @@ impl Catalog for MutableCatalog {
         db.get_table(table_name).await
     }
 
+    /// There is no cache in this catalog: `Cached` and `Fresh` both read from the meta-service.
+    #[async_backtrace::framed]
+    async fn get_table_consistent(
+        &self,
+        tenant: &str,
+        db_name: &str,
+        table_name: &str,
+        level: ReadConsistency,
+    ) -> Result<Arc<dyn Table>> {
+        if level == ReadConsistency::Linearizable {
+            self.sync_meta().await?;
+        }
+        self.get_table(tenant, db_name, table_name).await
+    }
+
+    /// Check the existence of the table by its name key, without loading the `TableMeta`.
+    #[async_backtrace::framed]
+    async fn exists_table(&self, tenant: &str, db_name: &str, table_name: &str) -> Result<bool> {
+        let name_ident = DatabaseNameIdent {
+            tenant: tenant.to_string(),
+            db_name: db_name.to_string(),
+        };
+        let (db_id_seq, db_id) = get_u64_value(&self.ctx.meta, &name_ident).await?;
+        if db_id_seq == 0 {
+            return Ok(false);
+        }
+
+        let (_, db_meta): (_, Option<DatabaseMeta>) =
+            get_pb_value(&self.ctx.meta, &DatabaseId { db_id }).await?;
+        let Some(db_meta) = db_meta else {
+            return Ok(false);
+        };
+
+        // The tables of a shared database are not stored under its db id.
+        if db_meta.from_share.is_some() {
+            let db = self.get_database(tenant, db_name).await?;
+            return match db.get_table(table_name).await {
+                Ok(_) => Ok(true),
+                Err(e) if e.code() == ErrorCode::UNKNOWN_TABLE => Ok(false),
+                Err(e) => Err(e),
+            };
+        }
+
+        let key = DBIdTableName {
+            db_id,
+            table_name: table_name.to_string(),
+        };
+        let exists = self.ctx.meta.has_kv(&key.to_string_key()).await?;
+        Ok(exists)
+    }
+
     #[async_backtrace::framed]
     async fn list_tables(&self, tenant: &str, db_name: &str) -> Result<Vec<Arc<dyn Table>>> {
         let db = self.get_database(tenant, db_name).await?;
         db.list_tables().await
     }
 
+    #[async_backtrace::framed]
+    async fn mget_tables(
+        &self,
+        tenant: &str,
+        db_name: &str,
+        table_names: &[String],
+    ) -> Result<Vec<Arc<dyn Table>>> {
+        let db = self.get_database(tenant, db_name).await?;
+        db.mget_tables(table_names).await
+    }
+
     #[async_backtrace::framed]
     async fn list_tables_history(
         &self,
@@ -403,6 +526,7 @@ impl Catalog for MutableCatalog {
             meta: self.ctx.meta.clone(),
             storage_factory: self.ctx.storage_factory.clone(),
             tenant: self.tenant.clone(),
+            table_counts: self.ctx.table_counts.clone(),
         };
 
         let resp = ctx.meta.get_drop_table_infos(req).await?;
@@ -426,11 +550,60 @@ impl Catalog for MutableCatalog {
     }
 
     #[async_backtrace::framed]
-    async fn create_table(&self, req: CreateTableReq) -> Result<CreateTableReply> {
+    async fn check_consistency(&self, repair: bool) -> Result<ConsistencyReport> {
+        consistency_check::check_consistency(&self.ctx.meta, &ProtobufCodec, repair).await
+    }
+
+    #[async_backtrace::framed]
+    async fn create_table(&self, mut req: CreateTableReq) -> Result<CreateTableReply> {
         let db = self
             .get_database(&req.name_ident.tenant, &req.name_ident.db_name)
             .await?;
-        db.create_table(req).await
+
+        let taken = if req.table_id.is_none() {
+            self.take_table_id().await?
+        } else {
+            None
+        };
+        req.table_id = req.table_id.or(taken);
+
+        let res = db.create_table(req).await;
+
+        if let Some(id) = taken {
+            // The id is not used if the table exists, or the creation is rejected.
+            // It is not given back if the meta-service is not reachable,
+            // because the table may have been created with it.
+            let unused = match &res {
+                Ok(reply) => !reply.new_table,
+                Err(e) => e.code() != ErrorCode::META_SERVICE_ERROR,
+            };
+            if unused {
+                self.ctx.table_ids.give_back(id);
+            }
+        }
+
+        res
+    }
+
+    #[async_backtrace::framed]
+    async fn reserve_table_ids(&self, n: u64) -> Result<Range<u64>> {
+        let table_ids = &self.ctx.table_ids;
+
+        if n == 0 {
+            table_ids.set_block_size(0);
+            return Ok(0..0);
+        }
+
+        let Some(ids) = self.ctx.meta.reserve_table_ids(n).await? else {
+            return Err(ErrorCode::Unimplemented(format!(
+                "reserving table ids requires the meta-service feature '{}'",
+                FEATURE_RESERVE_SEQ
+            )));
+        };
+        // The returned ids belong to the caller,
+        // they are not added to the free ids, which are taken by a creation without a table id.
+        table_ids.set_block_size(n);
+        Ok(ids)
     }
 
     #[async_backtrace::framed]
@@ -439,6 +612,15 @@ impl Catalog for MutableCatalog {
         Ok(res)
     }
 
+    #[async_backtrace::framed]
+    async fn plan_drop_table_by_id(&self, req: DropTableByIdReq) -> Result<DropTablePlan> {
+        let txn = self.ctx.meta.plan_drop_table_by_id(req).await?;
+        Ok(DropTablePlan {
+            routed_to: "MutableCatalog".to_string(),
+            txn,
+        })
+    }
+
     #[async_backtrace::framed]
     async fn undrop_table(&self, req: UndropTableReq) -> Result<UndropTableReply> {
         let db = self
@@ -0,0 +1,107 @@
+// Copyright 2021 Datafuse Labs
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::HashMap;
+use std::time::Duration;
+use std::time::Instant;
+
+use parking_lot::Mutex;
+
+/// The dbs of a tenant, or the tables of a db, whose names are indexed by a [`NameIndex`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct NameIndexKey {
+    pub tenant: String,
+    /// `None` for the dbs of the tenant, otherwise the tables of this db.
+    pub db_name: Option<String>,
+}
+
+impl NameIndexKey {
+    pub fn dbs(tenant: &str) -> Self {
+        Self {
+            tenant: tenant.to_string(),
+            db_name: None,
+        }
+    }
+
+    pub fn tables(tenant: &str, db_name: &str) -> Self {
+        Self {
+            tenant: tenant.to_string(),
+            db_name: Some(db_name.to_string()),
+        }
+    }
+}
+
+/// Lower case db and table names mapped to the names as they are created,
+/// to resolve a name ignoring case without listing all of the dbs or tables for every lookup.
+///
+/// The names of a key are indexed from a listing when they are first looked up.
+/// They are dropped by [`NameIndex::invalidate`] after a DDL through the owner catalog,
+/// and expire after the `ttl`, so that the names created through another query node are found eventually.
+#[derive(Debug)]
+pub struct NameIndex {
+    ttl: Duration,
+    entries: Mutex<HashMap<NameIndexKey, Entry>>,
+}
+
+#[derive(Debug)]
+struct Entry {
+    indexed_at: Instant,
+    names: HashMap<String, String>,
+}
+
+impl NameIndex {
+    pub fn new(ttl: Duration) -> Self {
+        Self {
+            ttl,
+            entries: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Returns the name that `name` refers to ignoring case, among the indexed names of `key`.
+    ///
+    /// The outer `None` means the names of `key` are not indexed or are expired,
+    /// and the caller should index them with [`NameIndex::insert`].
+    pub fn lookup(&self, key: &NameIndexKey, name: &str) -> Option<Option<String>> {
+        let entries = self.entries.lock();
+        let entry = entries.get(key)?;
+
+        if entry.indexed_at.elapsed() >= self.ttl {
+            return None;
+        }
+
+        Some(entry.names.get(&name.to_lowercase()).cloned())
+    }
+
+    /// Index the names of `key`, replacing the previous ones.
+    ///
+    /// If several names differ only in case, the first one is indexed.
+    pub fn insert<'a>(&self, key: NameIndexKey, names: impl IntoIterator<Item = &'a str>) {
+        let mut indexed = HashMap::new();
+        for name in names {
+            indexed
+                .entry(name.to_lowercase())
+                .or_insert_with(|| name.to_string());
+        }
+
+        self.entries.lock().insert(key, Entry {
+            indexed_at: Instant::now(),
+            names: indexed,
+        });
+    }
+
+    /// Drop the indexed db and table names of `tenant`, after a DDL that may change them.
+    pub fn invalidate(&self, tenant: &str) {
+        self.entries.lock().retain(|k, _| k.tenant != tenant);
+    }
+}
@@ -0,0 +1,68 @@
+// Copyright 2021 Datafuse Labs
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::BTreeSet;
+use std::ops::Range;
+
+use databend_common_meta_types::MetaId;
+use parking_lot::Mutex;
+
+/// The table ids reserved from the meta-service by one catalog, that are not taken by a table yet.
+///
+/// The smallest id is taken first, and an id given back by a failed creation is taken by the next one,
+/// thus the reserved ids are used in order, and are not skipped because of an error.
+/// The ids left when the process exits are never used, a gap in the table ids is harmless.
+#[derive(Debug, Default)]
+pub struct TableIdAllocator {
+    inner: Mutex<Inner>,
+}
+
+#[derive(Debug, Default)]
+struct Inner {
+    free: BTreeSet<MetaId>,
+    /// The number of ids to reserve when the free ids are exhausted: the size of the last explicit reservation.
+    ///
+    /// 0 if no id is reserved, or the meta-service does not support reserving,
+    /// then an id is allocated by the meta-service for every table.
+    block_size: u64,
+}
+
+impl TableIdAllocator {
+    /// Add the ids just reserved.
+    pub fn add(&self, ids: Range<MetaId>) {
+        self.inner.lock().free.extend(ids);
+    }
+
+    /// Set the number of ids to reserve when the free ids are exhausted, 0 to stop reserving.
+    ///
+    /// The free ids are kept, a smaller block size takes effect from the next reservation.
+    pub fn set_block_size(&self, n: u64) {
+        self.inner.lock().block_size = n;
+    }
+
+    /// Take the smallest free id.
+    pub fn take(&self) -> Option<MetaId> {
+        self.inner.lock().free.pop_first()
+    }
+
+    /// Give back an id that is taken but not used by a table.
+    pub fn give_back(&self, id: MetaId) {
+        self.inner.lock().free.insert(id);
+    }
+
+    /// The number of ids to reserve when there is no free id, 0 if not to reserve.
+    pub fn block_size(&self) -> u64 {
+        self.inner.lock().block_size
+    }
+}
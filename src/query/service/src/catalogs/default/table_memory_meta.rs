@@ -12,13 +12,17 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::collections::BTreeMap;
 use std::sync::atomic::AtomicU64;
 use std::sync::atomic::Ordering;
 use std::sync::Arc;
 
 use dashmap::DashMap;
-use databend_common_exception::ErrorCode;
 use databend_common_exception::Result;
+use databend_common_meta_app::app_error::AppError;
+use databend_common_meta_app::app_error::UnknownDatabase;
+use databend_common_meta_app::app_error::UnknownTable;
+use databend_common_meta_app::schema::TableInfo;
 use databend_common_meta_types::MetaId;
 
 use crate::storages::Table;
@@ -32,6 +36,34 @@ pub struct InMemoryMetas {
     next_table_id: AtomicU64,
     next_db_id: AtomicU64,
     db_tables: DashMap<String, DbTables>,
+    stats: CacheCounters,
+}
+
+#[derive(Default)]
+struct CacheCounters {
+    hits: AtomicU64,
+    misses: AtomicU64,
+    replacements: AtomicU64,
+}
+
+/// Statistics of table lookups in an [`InMemoryMetas`], since it is created.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct MetaCacheStats {
+    /// Number of lookups by name or by id, always `hits + misses`.
+    pub lookups: u64,
+    pub hits: u64,
+    pub misses: u64,
+    /// Number of tables replaced by inserting another table with the same name.
+    pub replacements: u64,
+}
+
+/// A serializable copy of the state of [`InMemoryMetas`].
+#[derive(serde::Serialize, serde::Deserialize, Clone, Debug, Default, PartialEq, Eq)]
+pub struct MetaSnapshot {
+    pub next_db_id: u64,
+    pub next_table_id: u64,
+    /// The `TableInfo` of every table, by db name, sorted by table name.
+    pub db_tables: BTreeMap<String, Vec<TableInfo>>,
 }
 
 impl InMemoryMetas {
@@ -40,6 +72,7 @@ impl InMemoryMetas {
             next_table_id: AtomicU64::new(next_table_id),
             next_db_id: AtomicU64::new(next_db_id),
             db_tables: DashMap::new(),
+            stats: CacheCounters::default(),
         }
     }
 
@@ -65,7 +98,10 @@ impl InMemoryMetas {
     pub fn insert(&self, db: &str, tbl_ref: Arc<dyn Table>) {
         if let Some(db_tables) = self.db_tables.get_mut(db) {
             let name = tbl_ref.name().to_owned();
-            db_tables.name_to_table.insert(name, tbl_ref.clone());
+            if let Some(prev) = db_tables.name_to_table.insert(name, tbl_ref.clone()) {
+                db_tables.id_to_table.remove(&prev.get_id());
+                self.stats.replacements.fetch_add(1, Ordering::Relaxed);
+            }
             db_tables.id_to_table.insert(tbl_ref.get_id(), tbl_ref);
         } else {
             panic!("Logical Error: Need create database `{}` first", db)
@@ -73,34 +109,60 @@ impl InMemoryMetas {
     }
 
     pub fn get_by_name(&self, db: &str, name: &str) -> Result<Arc<dyn Table>> {
-        if let Some(db_tables) = self.db_tables.get(db) {
+        let res = if let Some(db_tables) = self.db_tables.get(db) {
             db_tables
                 .value()
                 .name_to_table
                 .get(name)
                 .map(|entry| entry.value().clone())
                 .ok_or_else(|| {
-                    ErrorCode::UnknownTable(format!("`{}.{}` table is unknown", db, name))
+                    AppError::from(UnknownTable::new(name, format!("get_by_name: {}", db))).into()
                 })
         } else {
-            Err(ErrorCode::UnknownDatabase(format!(
-                "`{}` database is unknown",
-                db
-            )))
-        }
+            Err(AppError::from(UnknownDatabase::new(db, "get_by_name")).into())
+        };
+
+        self.count_lookup(res.is_ok());
+        res
     }
 
     pub fn get_by_id(&self, id: &MetaId) -> Option<Arc<dyn Table>> {
-        for entry in self.db_tables.iter() {
-            let db_tables = entry.value();
-            if db_tables.id_to_table.contains_key(id) {
-                return db_tables
-                    .id_to_table
-                    .get(id)
-                    .map(|entry| entry.value().clone());
-            }
+        let found = self.db_tables.iter().find_map(|entry| {
+            entry
+                .value()
+                .id_to_table
+                .get(id)
+                .map(|entry| entry.value().clone())
+        });
+
+        self.count_lookup(found.is_some());
+        found
+    }
+
+    fn count_lookup(&self, hit: bool) {
+        let counter = if hit {
+            &self.stats.hits
+        } else {
+            &self.stats.misses
+        };
+        counter.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Returns a snapshot of the lookup statistics.
+    ///
+    /// The counters are read one by one without a lock,
+    /// thus a snapshot taken during concurrent lookups may lag behind some of them.
+    /// `lookups` is derived from `hits` and `misses`, so that they always add up.
+    pub fn stats(&self) -> MetaCacheStats {
+        let hits = self.stats.hits.load(Ordering::Relaxed);
+        let misses = self.stats.misses.load(Ordering::Relaxed);
+
+        MetaCacheStats {
+            lookups: hits + misses,
+            hits,
+            misses,
+            replacements: self.stats.replacements.load(Ordering::Relaxed),
         }
-        None
     }
 
     pub fn get_all_tables(&self, db: &str) -> Result<Vec<Arc<dyn Table>>> {
@@ -112,10 +174,60 @@ impl InMemoryMetas {
                 .map(|entry| entry.value().clone())
                 .collect())
         } else {
-            Err(ErrorCode::UnknownDatabase(format!(
-                "{} database is unknown",
-                db
-            )))
+            Err(AppError::from(UnknownDatabase::new(db, "get_all_tables")).into())
+        }
+    }
+
+    /// Take a snapshot of the ids and the `TableInfo` of every table.
+    pub fn snapshot(&self) -> MetaSnapshot {
+        let db_tables = self
+            .db_tables
+            .iter()
+            .map(|entry| {
+                let mut tables = entry
+                    .value()
+                    .name_to_table
+                    .iter()
+                    .map(|t| t.value().get_table_info().clone())
+                    .collect::<Vec<_>>();
+                tables.sort_by(|a, b| a.name.cmp(&b.name));
+                (entry.key().clone(), tables)
+            })
+            .collect();
+
+        MetaSnapshot {
+            next_db_id: self.next_db_id.load(Ordering::Relaxed),
+            next_table_id: self.next_table_id.load(Ordering::Relaxed),
+            db_tables,
+        }
+    }
+
+    /// Replace all of the content with the one in `snap`.
+    ///
+    /// A snapshot only contains `TableInfo`,
+    /// thus `build_table` is called to build the table instance from every `TableInfo`.
+    /// If it fails, `self` is left unchanged.
+    pub fn restore(
+        &mut self,
+        snap: MetaSnapshot,
+        build_table: impl Fn(&TableInfo) -> Result<Arc<dyn Table>>,
+    ) -> Result<()> {
+        let mut dbs = Vec::with_capacity(snap.db_tables.len());
+        for (db, infos) in snap.db_tables.iter() {
+            let tables = infos.iter().map(&build_table).collect::<Result<Vec<_>>>()?;
+            dbs.push((db, tables));
+        }
+
+        self.next_db_id = AtomicU64::new(snap.next_db_id);
+        self.next_table_id = AtomicU64::new(snap.next_table_id);
+        self.db_tables.clear();
+
+        for (db, tables) in dbs {
+            self.init_db(db);
+            for table in tables {
+                self.insert(db, table);
+            }
         }
+        Ok(())
     }
 }
@@ -12,9 +12,14 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+mod cached_catalog;
 pub mod default;
+pub use cached_catalog::CachedCatalog;
+pub use cached_catalog::ServedTable;
 pub use databend_common_catalog::catalog::Catalog;
 pub use databend_common_storages_hive as hive;
 pub use default::table_id_ranges::*;
 pub use default::table_memory_meta::InMemoryMetas;
+pub use default::table_memory_meta::MetaCacheStats;
+pub use default::table_memory_meta::MetaSnapshot;
 pub use default::DatabaseCatalog;
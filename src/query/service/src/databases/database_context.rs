@@ -16,6 +16,7 @@ use std::sync::Arc;
 
 use databend_common_meta_store::MetaStore;
 
+use crate::databases::TableCountCache;
 use crate::storages::StorageFactory;
 
 /// Database Context.
@@ -24,4 +25,5 @@ pub struct DatabaseContext {
     pub meta: MetaStore,
     pub storage_factory: Arc<StorageFactory>,
     pub tenant: String,
+    pub table_counts: Arc<TableCountCache>,
 }
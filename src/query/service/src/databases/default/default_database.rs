@@ -19,6 +19,8 @@ use databend_common_exception::Result;
 use databend_common_meta_api::SchemaApi;
 use databend_common_meta_app::schema::CreateTableReply;
 use databend_common_meta_app::schema::CreateTableReq;
+use databend_common_meta_app::schema::DBIdTableName;
+use databend_common_meta_app::schema::DatabaseId;
 use databend_common_meta_app::schema::DatabaseInfo;
 use databend_common_meta_app::schema::DropTableByIdReq;
 use databend_common_meta_app::schema::DropTableReply;
@@ -26,6 +28,7 @@ use databend_common_meta_app::schema::GetTableCopiedFileReply;
 use databend_common_meta_app::schema::GetTableCopiedFileReq;
 use databend_common_meta_app::schema::GetTableReq;
 use databend_common_meta_app::schema::ListTableReq;
+use databend_common_meta_app::schema::MGetTableReq;
 use databend_common_meta_app::schema::RenameTableReply;
 use databend_common_meta_app::schema::RenameTableReq;
 use databend_common_meta_app::schema::SetTableColumnMaskPolicyReply;
@@ -39,6 +42,11 @@ use databend_common_meta_app::schema::UpdateTableMetaReply;
 use databend_common_meta_app::schema::UpdateTableMetaReq;
 use databend_common_meta_app::schema::UpsertTableOptionReply;
 use databend_common_meta_app::schema::UpsertTableOptionReq;
+use databend_common_meta_kvapi::kvapi::KVApi;
+use databend_common_meta_kvapi::kvapi::Key;
+use futures::stream::BoxStream;
+use futures::StreamExt;
+use futures::TryStreamExt;
 
 use crate::databases::Database;
 use crate::databases::DatabaseContext;
@@ -53,6 +61,9 @@ pub struct DefaultDatabase {
 impl DefaultDatabase {
     pub const NAME: &'static str = "DEFAULT";
 
+    /// The number of tables loaded from the meta at a time by `list_tables_stream()`.
+    const LIST_TABLES_PAGE_SIZE: usize = 256;
+
     pub fn try_create(ctx: DatabaseContext, db_info: DatabaseInfo) -> Result<Box<dyn Database>> {
         Ok(Box::new(Self { ctx, db_info }))
     }
@@ -72,6 +83,31 @@ impl DefaultDatabase {
             .list_tables(ListTableReq::new(self.get_tenant(), self.get_db_name()))
             .await?;
 
+        self.refresh_table_infos(table_infos).await
+    }
+
+    async fn list_tables_page(
+        &self,
+        after: Option<String>,
+    ) -> Result<(Vec<Arc<dyn Table>>, Option<String>)> {
+        let (table_infos, next) = self
+            .ctx
+            .meta
+            .list_tables_paged(
+                ListTableReq::new(self.get_tenant(), self.get_db_name()),
+                after,
+                Self::LIST_TABLES_PAGE_SIZE,
+            )
+            .await?;
+
+        let table_infos = self.refresh_table_infos(table_infos).await?;
+        Ok((self.load_tables(table_infos)?, next))
+    }
+
+    async fn refresh_table_infos(
+        &self,
+        table_infos: Vec<Arc<TableInfo>>,
+    ) -> Result<Vec<Arc<TableInfo>>> {
         let mut refreshed = Vec::with_capacity(table_infos.len());
         for table_info in table_infos {
             refreshed.push(
@@ -127,6 +163,87 @@ impl Database for DefaultDatabase {
         self.load_tables(table_infos)
     }
 
+    // Get the tables of the given names with one `mget_tables` request to the meta.
+    #[async_backtrace::framed]
+    async fn mget_tables(&self, table_names: &[String]) -> Result<Vec<Arc<dyn Table>>> {
+        let table_infos = self
+            .ctx
+            .meta
+            .mget_tables(MGetTableReq::new(
+                self.get_tenant(),
+                self.get_db_name(),
+                table_names.to_vec(),
+            ))
+            .await?;
+
+        let table_infos = self.refresh_table_infos(table_infos).await?;
+        self.load_tables(table_infos)
+    }
+
+    // List the tables with the name prefix with one scoped prefix scan in the meta.
+    #[async_backtrace::framed]
+    async fn list_tables_by_prefix(&self, name_prefix: &str) -> Result<Vec<Arc<dyn Table>>> {
+        let table_infos = self
+            .ctx
+            .meta
+            .list_tables_by_prefix(
+                ListTableReq::new(self.get_tenant(), self.get_db_name()),
+                name_prefix,
+            )
+            .await?;
+
+        let table_infos = self.refresh_table_infos(table_infos).await?;
+        self.load_tables(table_infos)
+    }
+
+    // List the tables page by page, a page is loaded when the previous one is consumed.
+    fn list_tables_stream(&self) -> BoxStream<'_, Result<Arc<dyn Table>>> {
+        // The state is the cursor of the next page, or `None` if the last page is loaded.
+        let pages = futures::stream::try_unfold(Some(None), move |after| async move {
+            let Some(after) = after else {
+                return Ok(None);
+            };
+            self.list_tables_page(after)
+                .await
+                .map(|(tables, next)| Some((tables, next.map(Some))))
+        });
+
+        pages
+            .map_ok(|tables| futures::stream::iter(tables.into_iter().map(Ok)))
+            .try_flatten()
+            .boxed()
+    }
+
+    // Count the table names in the meta, and cache the count until the database meta is changed,
+    // which is the case whenever a table is created, dropped, undropped or renamed in this database.
+    #[async_backtrace::framed]
+    async fn table_count(&self) -> Result<u64> {
+        let db_id = self.db_info.ident.db_id;
+
+        // Read the seq before counting, so that a count is never cached with a newer seq than it is counted at.
+        let db_key = DatabaseId { db_id }.to_string_key();
+        let db_meta_seq = self.ctx.meta.get_kv(&db_key).await?.map(|x| x.seq);
+
+        if let Some(seq) = db_meta_seq {
+            if let Some(count) = self.ctx.table_counts.get(db_id, seq) {
+                return Ok(count);
+            }
+        }
+
+        let prefix = DBIdTableName {
+            db_id,
+            // Use empty name to scan all tables
+            table_name: "".to_string(),
+        }
+        .to_string_key();
+        let count = self.ctx.meta.count_prefix_kv(&prefix).await?;
+
+        if let Some(seq) = db_meta_seq {
+            self.ctx.table_counts.set(db_id, seq, count);
+        }
+        Ok(count)
+    }
+
     #[async_backtrace::framed]
     async fn list_tables_history(&self) -> Result<Vec<Arc<dyn Table>>> {
         // `get_table_history` will not fetch the tables that created before the
@@ -19,9 +19,11 @@ mod default;
 mod information_schema;
 mod share;
 mod system;
+mod table_count_cache;
 
 pub use database::Database;
 pub use database_context::DatabaseContext;
 pub use database_factory::DatabaseFactory;
 pub use information_schema::InformationSchemaDatabase;
 pub use system::SystemDatabase;
+pub use table_count_cache::TableCountCache;
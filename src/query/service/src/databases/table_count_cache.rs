@@ -0,0 +1,54 @@
+// Copyright 2021 Datafuse Labs
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::HashMap;
+
+use databend_common_meta_types::MetaId;
+use parking_lot::Mutex;
+
+/// The table count of every database, shared by the `Database` instances built by one catalog.
+///
+/// A count is recorded along with the seq of the `DatabaseMeta` it is counted at.
+/// Creating, dropping, undropping or renaming a table updates the `DatabaseMeta` in the same transaction,
+/// thus a count is valid only if the seq of the `DatabaseMeta` is unchanged,
+/// whichever query node the DDL is issued by.
+#[derive(Debug, Default)]
+pub struct TableCountCache {
+    /// `db_id -> (db_meta_seq, table_count)`
+    counts: Mutex<HashMap<MetaId, (u64, u64)>>,
+}
+
+impl TableCountCache {
+    /// Get the table count of `db_id` if it is counted at `db_meta_seq`.
+    pub fn get(&self, db_id: MetaId, db_meta_seq: u64) -> Option<u64> {
+        let counts = self.counts.lock();
+
+        match counts.get(&db_id) {
+            Some((seq, count)) if *seq == db_meta_seq => Some(*count),
+            _ => None,
+        }
+    }
+
+    /// Record the table count of `db_id` counted at `db_meta_seq`.
+    ///
+    /// A count at an older seq does not replace one at a newer seq.
+    pub fn set(&self, db_id: MetaId, db_meta_seq: u64, count: u64) {
+        let mut counts = self.counts.lock();
+
+        let entry = counts.entry(db_id).or_insert((db_meta_seq, count));
+        if entry.0 <= db_meta_seq {
+            *entry = (db_meta_seq, count);
+        }
+    }
+}
@@ -15,6 +15,7 @@
 use std::sync::Arc;
 use std::time::SystemTime;
 
+use databend_common_catalog::catalog::with_audit_principal;
 use databend_common_catalog::table_context::TableContext;
 use databend_common_exception::ErrorCode;
 use databend_common_exception::Result;
@@ -55,7 +56,13 @@ pub trait Interpreter: Sync + Send {
             log_query_finished(&ctx, Some(err.clone()), false);
             return Err(err);
         }
-        let mut build_res = match self.execute2().await {
+        // The DDLs of the plan are audited with the user of the session.
+        let build = match ctx.get_current_user() {
+            Ok(user) => with_audit_principal(user.identity().to_string(), self.execute2()).await,
+            Err(_) => self.execute2().await,
+        };
+
+        let mut build_res = match build {
             Ok(build_res) => build_res,
             Err(build_error) => {
                 InterpreterMetrics::record_query_error(&ctx);
@@ -340,6 +340,7 @@ impl CreateTableInterpreter {
 
         let req = CreateTableReq {
             if_not_exists: self.plan.if_not_exists,
+            table_id: None,
             name_ident: TableNameIdent {
                 tenant: self.plan.tenant.to_string(),
                 db_name: self.plan.database.to_string(),
@@ -406,6 +407,7 @@ impl CreateTableInterpreter {
         };
         let req = CreateTableReq {
             if_not_exists: self.plan.if_not_exists,
+            table_id: None,
             name_ident: TableNameIdent {
                 tenant: self.plan.tenant.to_string(),
                 db_name: self.plan.database.to_string(),
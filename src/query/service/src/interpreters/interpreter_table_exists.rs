@@ -51,7 +51,13 @@ impl Interpreter for ExistsTableInterpreter {
         let catalog = self.plan.catalog.as_str();
         let database = self.plan.database.as_str();
         let table = self.plan.table.as_str();
-        let exists = self.ctx.get_table(catalog, database, table).await.is_ok();
+        let tenant = self.ctx.get_tenant();
+        let exists = self
+            .ctx
+            .get_catalog(catalog)
+            .await?
+            .exists_table(&tenant, database, table)
+            .await?;
         let result = match exists {
             true => 1u8,
             false => 0u8,
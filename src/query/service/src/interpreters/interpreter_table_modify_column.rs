@@ -339,7 +339,7 @@ impl ModifyTableColumnInterpreter {
                             || is_string_to_binary(&old_field.data_type, &new_field.data_type))
                 });
         if is_alter_column_string_to_binary {
-            table_info.meta.schema = new_schema.into();
+            table_info.meta.update_schema(new_schema.into());
 
             let table_id = table_info.ident.table_id;
             let table_version = table_info.ident.seq;
@@ -412,7 +412,7 @@ impl ModifyTableColumnInterpreter {
         };
 
         // 4. define select schema and insert schema of DistributedInsertSelect plan
-        table_info.meta.schema = new_schema.clone().into();
+        table_info.meta.update_schema(new_schema.clone().into());
         let new_table = FuseTable::try_create(table_info)?;
 
         // 5. build DistributedInsertSelect plan
@@ -482,7 +482,7 @@ impl ModifyTableColumnInterpreter {
         };
 
         let mut new_table_meta = table_meta;
-        new_table_meta.schema = new_schema.into();
+        new_table_meta.update_schema(new_schema.into());
 
         let table_id = table_info.ident.table_id;
         let table_version = table_info.ident.seq;
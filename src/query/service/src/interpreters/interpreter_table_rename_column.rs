@@ -102,7 +102,7 @@ impl Interpreter for RenameTableColumnInterpreter {
                 )?;
             }
 
-            new_table_meta.schema = Arc::new(self.plan.schema.clone());
+            new_table_meta.update_schema(Arc::new(self.plan.schema.clone()));
 
             // update table options
             let opts = &mut new_table_meta.options;
@@ -93,6 +93,7 @@ impl Interpreter for AlterViewInterpreter {
 
             let plan = CreateTableReq {
                 if_not_exists: true,
+                table_id: None,
                 name_ident: TableNameIdent {
                     tenant: self.plan.tenant.clone(),
                     db_name: self.plan.database.clone(),
@@ -65,7 +65,7 @@ impl Interpreter for CreateViewInterpreter {
                     if !catalog
                         .exists_table(tenant.as_str(), database_name, table_name)
                         .await?
-                        && !table_function.contains(&table_name.to_string())
+                        && !table_function.iter().any(|f| f.name == table_name)
                         && !table.table().is_stage_table()
                     {
                         return Err(databend_common_exception::ErrorCode::UnknownTable(format!(
@@ -104,6 +104,7 @@ impl Interpreter for CreateViewInterpreter {
 
         let plan = CreateTableReq {
             if_not_exists: self.plan.if_not_exists,
+            table_id: None,
             name_ident: TableNameIdent {
                 tenant: self.plan.tenant.clone(),
                 db_name: self.plan.database.clone(),
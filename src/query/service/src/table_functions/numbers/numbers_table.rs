@@ -26,6 +26,7 @@ use databend_common_catalog::plan::Partitions;
 use databend_common_catalog::plan::PushDownInfo;
 use databend_common_catalog::table::TableStatistics;
 use databend_common_catalog::table_args::TableArgs;
+use databend_common_exception::ErrorCode;
 use databend_common_exception::Result;
 use databend_common_expression::type_check::check_number;
 use databend_common_expression::types::number::NumberScalar;
@@ -38,6 +39,7 @@ use databend_common_expression::FunctionContext;
 use databend_common_expression::Scalar;
 use databend_common_expression::TableDataType;
 use databend_common_expression::TableField;
+use databend_common_expression::TableSchemaRef;
 use databend_common_expression::TableSchemaRefExt;
 use databend_common_functions::BUILTIN_FUNCTIONS;
 use databend_common_meta_app::schema::TableIdent;
@@ -69,7 +71,7 @@ impl NumbersTable {
         table_id: u64,
         table_args: TableArgs,
     ) -> Result<Arc<dyn TableFunction>> {
-        let args = table_args.expect_all_positioned(table_func_name, Some(1))?;
+        let args = check_args(table_func_name, &table_args)?;
         let total = check_number::<_, u64>(
             None,
             &FunctionContext::default(),
@@ -92,10 +94,7 @@ impl NumbersTable {
             desc: format!("'{}'.'{}'", database_name, table_func_name),
             name: table_func_name.to_string(),
             meta: TableMeta {
-                schema: TableSchemaRefExt::create(vec![TableField::new(
-                    "number",
-                    TableDataType::Number(NumberDataType::UInt64),
-                )]),
+                schema: numbers_schema(),
                 engine: engine.to_string(),
                 // Assuming that created_on is unnecessary for function table,
                 // we could make created_on fixed to pass test_shuffle_action_try_into.
@@ -112,6 +111,26 @@ impl NumbersTable {
     }
 }
 
+/// The schema of `numbers`, `numbers_mt` and `numbers_local`: a single `number UInt64` column.
+fn numbers_schema() -> TableSchemaRef {
+    TableSchemaRefExt::create(vec![TableField::new(
+        "number",
+        TableDataType::Number(NumberDataType::UInt64),
+    )])
+}
+
+/// The only argument of `numbers(N)` is the number of rows, which must not be negative.
+fn check_args(table_func_name: &str, table_args: &TableArgs) -> Result<Vec<Scalar>> {
+    let args = table_args.expect_all_positioned(table_func_name, Some(1))?;
+    if args[0].get_i64().is_some_and(|n| n < 0) {
+        return Err(ErrorCode::BadArguments(format!(
+            "{} expects a non-negative number of rows, but got {}",
+            table_func_name, args[0]
+        )));
+    }
+    Ok(args)
+}
+
 #[async_trait::async_trait]
 impl Table for NumbersTable {
     fn is_local(&self) -> bool {
@@ -274,6 +293,16 @@ impl TableFunction for NumbersTable {
         self.name()
     }
 
+    fn validate_args(&self, args: &TableArgs) -> Result<()> {
+        check_args(self.name(), args)?;
+        Ok(())
+    }
+
+    fn output_schema(&self, args: &TableArgs) -> Result<TableSchemaRef> {
+        check_args(self.name(), args)?;
+        Ok(numbers_schema())
+    }
+
     fn as_table<'a>(self: Arc<Self>) -> Arc<dyn Table + 'a>
     where Self: 'a {
         self
@@ -37,6 +37,7 @@ use databend_common_expression::FunctionContext;
 use databend_common_expression::Scalar;
 use databend_common_expression::TableField;
 use databend_common_expression::TableSchema;
+use databend_common_expression::TableSchemaRef;
 use databend_common_functions::BUILTIN_FUNCTIONS;
 use databend_common_meta_app::schema::TableIdent;
 use databend_common_meta_app::schema::TableInfo;
@@ -68,22 +69,8 @@ impl RangeTable {
     ) -> Result<Arc<dyn TableFunction>> {
         validate_args(&table_args.positioned, table_func_name)?;
 
-        let data_type = match &table_args.positioned[0] {
-            Scalar::Number(_) => DataType::Number(NumberDataType::Int64),
-            Scalar::Timestamp(_) => DataType::Timestamp,
-            Scalar::Date(_) => DataType::Date,
-            other => {
-                return Err(ErrorCode::BadArguments(format!(
-                    "Unsupported data type for generate_series: {:?}",
-                    other
-                )));
-            }
-        };
-
-        let table_type = infer_schema_type(&data_type)?;
-
-        // The data types of start and end have been checked for consistency, and the input types are returned
-        let schema = TableSchema::new(vec![TableField::new(table_func_name, table_type)]);
+        let data_type = output_data_type(&table_args.positioned[0])?;
+        let schema = output_schema(table_func_name, &data_type)?;
 
         let start = table_args.positioned[0].clone();
         let end = table_args.positioned[1].clone();
@@ -137,6 +124,17 @@ impl TableFunction for RangeTable {
         self.name()
     }
 
+    fn validate_args(&self, args: &TableArgs) -> Result<()> {
+        validate_args(&args.positioned, self.name())
+    }
+
+    fn output_schema(&self, args: &TableArgs) -> Result<TableSchemaRef> {
+        validate_args(&args.positioned, self.name())?;
+
+        let data_type = output_data_type(&args.positioned[0])?;
+        Ok(Arc::new(output_schema(self.name(), &data_type)?))
+    }
+
     fn as_table<'a>(self: Arc<Self>) -> Arc<dyn Table + 'a>
     where Self: 'a {
         self
@@ -338,6 +336,29 @@ impl<const INCLUSIVE: bool> SyncSource for RangeSource<INCLUSIVE> {
     }
 }
 
+/// The type of the output column, which is the type of `start`.
+fn output_data_type(start: &Scalar) -> Result<DataType> {
+    match start {
+        Scalar::Number(_) => Ok(DataType::Number(NumberDataType::Int64)),
+        Scalar::Timestamp(_) => Ok(DataType::Timestamp),
+        Scalar::Date(_) => Ok(DataType::Date),
+        other => Err(ErrorCode::BadArguments(format!(
+            "Unsupported data type for generate_series: {:?}",
+            other
+        ))),
+    }
+}
+
+fn output_schema(table_func_name: &str, data_type: &DataType) -> Result<TableSchema> {
+    let table_type = infer_schema_type(data_type)?;
+
+    // The data types of start and end have been checked for consistency, and the input types are returned
+    Ok(TableSchema::new(vec![TableField::new(
+        table_func_name,
+        table_type,
+    )]))
+}
+
 fn validate_args(args: &Vec<Scalar>, table_func_name: &str) -> Result<()> {
     // Check args len.
     validate_function_arg(table_func_name, args.len(), Some((2, 3)), 2)?;
@@ -16,6 +16,7 @@ use std::collections::HashMap;
 use std::sync::Arc;
 
 use databend_common_catalog::table_args::TableArgs;
+use databend_common_catalog::table_function::TableFunctionMeta;
 use databend_common_exception::ErrorCode;
 use databend_common_exception::Result;
 use databend_common_meta_types::MetaId;
@@ -46,7 +47,9 @@ use crate::table_functions::sync_crash_me::SyncCrashMeTable;
 use crate::table_functions::GPT2SQLTable;
 use crate::table_functions::TableFunction;
 
-type TableFunctionCreators = RwLock<HashMap<String, (MetaId, Arc<dyn TableFunctionCreator>)>>;
+/// The creator of each table function by name, with its table id and the args of its signature.
+type TableFunctionCreators =
+    RwLock<HashMap<String, (MetaId, Arc<dyn TableFunctionCreator>, &'static str)>>;
 
 pub trait TableFunctionCreator: Send + Sync {
     fn try_create(
@@ -92,7 +95,7 @@ impl TableFunctionFactory {
             }
         };
 
-        let mut creators: HashMap<String, (MetaId, Arc<dyn TableFunctionCreator>)> =
+        let mut creators: HashMap<String, (MetaId, Arc<dyn TableFunctionCreator>, &'static str)> =
             Default::default();
 
         let number_table_func_creator: Arc<dyn TableFunctionCreator> =
@@ -100,110 +103,158 @@ impl TableFunctionFactory {
 
         creators.insert(
             "numbers".to_string(),
-            (next_id(), number_table_func_creator.clone()),
+            (next_id(), number_table_func_creator.clone(), "n"),
         );
         creators.insert(
             "numbers_mt".to_string(),
-            (next_id(), number_table_func_creator.clone()),
+            (next_id(), number_table_func_creator.clone(), "n"),
         );
         creators.insert(
             "numbers_local".to_string(),
-            (next_id(), number_table_func_creator),
+            (next_id(), number_table_func_creator, "n"),
         );
 
         creators.insert(
             "fuse_snapshot".to_string(),
-            (next_id(), Arc::new(FuseSnapshotTable::create)),
+            (
+                next_id(),
+                Arc::new(FuseSnapshotTable::create),
+                "database, table",
+            ),
         );
         creators.insert(
             "fuse_segment".to_string(),
-            (next_id(), Arc::new(FuseSegmentTable::create)),
+            (
+                next_id(),
+                Arc::new(FuseSegmentTable::create),
+                "database, table [, snapshot_id]",
+            ),
         );
         creators.insert(
             "fuse_block".to_string(),
-            (next_id(), Arc::new(FuseBlockTable::create)),
+            (
+                next_id(),
+                Arc::new(FuseBlockTable::create),
+                "database, table [, snapshot_id]",
+            ),
         );
         creators.insert(
             "fuse_column".to_string(),
-            (next_id(), Arc::new(FuseColumnTable::create)),
+            (
+                next_id(),
+                Arc::new(FuseColumnTable::create),
+                "database, table [, snapshot_id]",
+            ),
         );
         creators.insert(
             "fuse_statistic".to_string(),
-            (next_id(), Arc::new(FuseStatisticTable::create)),
+            (
+                next_id(),
+                Arc::new(FuseStatisticTable::create),
+                "database, table",
+            ),
         );
 
         creators.insert(
             "clustering_information".to_string(),
-            (next_id(), Arc::new(ClusteringInformationTable::create)),
+            (
+                next_id(),
+                Arc::new(ClusteringInformationTable::create),
+                "database, table",
+            ),
         );
 
         creators.insert(
             "stream_status".to_string(),
-            (next_id(), Arc::new(StreamStatusTable::create)),
+            (
+                next_id(),
+                Arc::new(StreamStatusTable::create),
+                "stream_name",
+            ),
         );
 
         creators.insert(
             "sync_crash_me".to_string(),
-            (next_id(), Arc::new(SyncCrashMeTable::create)),
+            (next_id(), Arc::new(SyncCrashMeTable::create), "[message]"),
         );
 
         creators.insert(
             "async_crash_me".to_string(),
-            (next_id(), Arc::new(AsyncCrashMeTable::create)),
+            (next_id(), Arc::new(AsyncCrashMeTable::create), "[message]"),
         );
 
         creators.insert(
             "infer_schema".to_string(),
-            (next_id(), Arc::new(InferSchemaTable::create)),
+            (next_id(), Arc::new(InferSchemaTable::create), "location => ... [, connection_name => ...] [, pattern => ...] [, file_format => ...]"),
         );
         creators.insert(
             "inspect_parquet".to_string(),
-            (next_id(), Arc::new(InspectParquetTable::create)),
+            (next_id(), Arc::new(InspectParquetTable::create), "file"),
         );
 
         creators.insert(
             "list_stage".to_string(),
-            (next_id(), Arc::new(ListStageTable::create)),
+            (
+                next_id(),
+                Arc::new(ListStageTable::create),
+                "location => ... [, pattern => ...]",
+            ),
         );
 
         creators.insert(
             "generate_series".to_string(),
-            (next_id(), Arc::new(RangeTable::create)),
+            (
+                next_id(),
+                Arc::new(RangeTable::create),
+                "start, stop [, step]",
+            ),
         );
 
         creators.insert(
             "range".to_string(),
-            (next_id(), Arc::new(RangeTable::create)),
+            (
+                next_id(),
+                Arc::new(RangeTable::create),
+                "start, stop [, step]",
+            ),
         );
 
         creators.insert(
             "ai_to_sql".to_string(),
-            (next_id(), Arc::new(GPT2SQLTable::create)),
+            (next_id(), Arc::new(GPT2SQLTable::create), "prompt"),
         );
 
         creators.insert(
             "execute_background_job".to_string(),
-            (next_id(), Arc::new(ExecuteBackgroundJobTable::create)),
+            (
+                next_id(),
+                Arc::new(ExecuteBackgroundJobTable::create),
+                "job_name",
+            ),
         );
 
         creators.insert(
             "license_info".to_string(),
-            (next_id(), Arc::new(LicenseInfoTable::create)),
+            (next_id(), Arc::new(LicenseInfoTable::create), ""),
         );
 
         creators.insert(
             "suggested_background_tasks".to_string(),
-            (next_id(), Arc::new(SuggestedBackgroundTasksTable::create)),
+            (
+                next_id(),
+                Arc::new(SuggestedBackgroundTasksTable::create),
+                "",
+            ),
         );
 
         creators.insert(
             "tenant_quota".to_string(),
-            (next_id(), Arc::new(TenantQuotaTable::create)),
+            (next_id(), Arc::new(TenantQuotaTable::create), "[tenant [, max_databases [, max_tables_per_database [, max_stages [, max_files_per_stage]]]]]"),
         );
 
         creators.insert(
             "fuse_encoding".to_string(),
-            (next_id(), Arc::new(FuseEncodingTable::create)),
+            (next_id(), Arc::new(FuseEncodingTable::create), "database"),
         );
 
         TableFunctionFactory {
@@ -214,7 +265,7 @@ impl TableFunctionFactory {
     pub fn get(&self, func_name: &str, tbl_args: TableArgs) -> Result<Arc<dyn TableFunction>> {
         let lock = self.creators.read();
         let func_name = func_name.to_lowercase();
-        let (id, factory) = lock.get(&func_name).ok_or_else(|| {
+        let (id, factory, _) = lock.get(&func_name).ok_or_else(|| {
             ErrorCode::UnknownTable(format!("Unknown table function {}", func_name))
         })?;
         let func = factory.try_create("", &func_name, *id, tbl_args)?;
@@ -227,13 +278,16 @@ impl TableFunctionFactory {
         lock.contains_key(&func_name)
     }
 
-    pub fn list(&self) -> Vec<String> {
+    /// List all registered table functions with their signatures, sorted by name.
+    pub fn list(&self) -> Vec<TableFunctionMeta> {
         self.creators
             .read()
             .iter()
-            .map(|(name, (id, _))| (name, id))
-            .sorted_by(|a, b| Ord::cmp(a.1, b.1))
-            .map(|(name, _)| name.clone())
+            .sorted_by(|a, b| Ord::cmp(a.0, b.0))
+            .map(|(name, (_, _, args))| TableFunctionMeta {
+                name: name.clone(),
+                signature: format!("{}({})", name, args),
+            })
             .collect()
     }
 }
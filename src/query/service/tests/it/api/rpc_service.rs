@@ -48,6 +48,7 @@ async fn test_tls_rpc_server() -> Result<()> {
     let tls_conf = Some(RpcClientTlsConfig {
         rpc_tls_server_root_ca_cert: TEST_CA_CERT.to_string(),
         domain_name: TEST_CN_NAME.to_string(),
+        ..Default::default()
     });
 
     // normal case
@@ -91,6 +92,7 @@ async fn test_tls_rpc_server_invalid_client_config() -> Result<()> {
     let client_conf = RpcClientTlsConfig {
         rpc_tls_server_root_ca_cert: "../tests/data/certs/nowhere.pem".to_string(),
         domain_name: TEST_CN_NAME.to_string(),
+        ..Default::default()
     };
 
     let r = ConnectionFactory::create_rpc_channel("fake:1234", None, Some(client_conf)).await;
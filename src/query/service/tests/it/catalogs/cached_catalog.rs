@@ -0,0 +1,767 @@
+// Copyright 2021 Datafuse Labs
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::any::Any;
+use std::sync::atomic::AtomicBool;
+use std::sync::atomic::Ordering;
+use std::sync::Arc;
+use std::time::Duration;
+
+use databend_common_base::base::tokio;
+use databend_common_base::base::tokio::sync::Notify;
+use databend_common_catalog::catalog::ReadConsistency;
+use databend_common_catalog::database::Database;
+use databend_common_catalog::table::Table;
+use databend_common_exception::ErrorCode;
+use databend_common_exception::Result;
+use databend_common_expression::types::NumberDataType;
+use databend_common_expression::TableDataType;
+use databend_common_expression::TableField;
+use databend_common_expression::TableSchema;
+use databend_common_meta_app::schema::CatalogInfo;
+use databend_common_meta_app::schema::CountTablesReply;
+use databend_common_meta_app::schema::CountTablesReq;
+use databend_common_meta_app::schema::CreateDatabaseReply;
+use databend_common_meta_app::schema::CreateDatabaseReq;
+use databend_common_meta_app::schema::CreateIndexReply;
+use databend_common_meta_app::schema::CreateIndexReq;
+use databend_common_meta_app::schema::CreateLockRevReply;
+use databend_common_meta_app::schema::CreateLockRevReq;
+use databend_common_meta_app::schema::CreateTableReply;
+use databend_common_meta_app::schema::CreateTableReq;
+use databend_common_meta_app::schema::CreateVirtualColumnReply;
+use databend_common_meta_app::schema::CreateVirtualColumnReq;
+use databend_common_meta_app::schema::DatabaseMeta;
+use databend_common_meta_app::schema::DatabaseNameIdent;
+use databend_common_meta_app::schema::DeleteLockRevReq;
+use databend_common_meta_app::schema::DropDatabaseReply;
+use databend_common_meta_app::schema::DropDatabaseReq;
+use databend_common_meta_app::schema::DropIndexReply;
+use databend_common_meta_app::schema::DropIndexReq;
+use databend_common_meta_app::schema::DropTableByIdReq;
+use databend_common_meta_app::schema::DropTableReply;
+use databend_common_meta_app::schema::DropVirtualColumnReply;
+use databend_common_meta_app::schema::DropVirtualColumnReq;
+use databend_common_meta_app::schema::ExtendLockRevReq;
+use databend_common_meta_app::schema::GetIndexReply;
+use databend_common_meta_app::schema::GetIndexReq;
+use databend_common_meta_app::schema::GetTableCopiedFileReply;
+use databend_common_meta_app::schema::GetTableCopiedFileReq;
+use databend_common_meta_app::schema::IndexMeta;
+use databend_common_meta_app::schema::ListIndexesByIdReq;
+use databend_common_meta_app::schema::ListIndexesReq;
+use databend_common_meta_app::schema::ListLockRevReq;
+use databend_common_meta_app::schema::ListLocksReq;
+use databend_common_meta_app::schema::ListVirtualColumnsReq;
+use databend_common_meta_app::schema::LockInfo;
+use databend_common_meta_app::schema::LockMeta;
+use databend_common_meta_app::schema::RenameDatabaseReply;
+use databend_common_meta_app::schema::RenameDatabaseReq;
+use databend_common_meta_app::schema::RenameTableReply;
+use databend_common_meta_app::schema::RenameTableReq;
+use databend_common_meta_app::schema::SetDatabaseQuotaReq;
+use databend_common_meta_app::schema::SetTableColumnMaskPolicyReply;
+use databend_common_meta_app::schema::SetTableColumnMaskPolicyReq;
+use databend_common_meta_app::schema::TableIdent;
+use databend_common_meta_app::schema::TableInfo;
+use databend_common_meta_app::schema::TableMeta;
+use databend_common_meta_app::schema::TableNameIdent;
+use databend_common_meta_app::schema::TruncateTableReply;
+use databend_common_meta_app::schema::TruncateTableReq;
+use databend_common_meta_app::schema::UndropDatabaseReply;
+use databend_common_meta_app::schema::UndropDatabaseReq;
+use databend_common_meta_app::schema::UndropTableReply;
+use databend_common_meta_app::schema::UndropTableReq;
+use databend_common_meta_app::schema::UpdateIndexReply;
+use databend_common_meta_app::schema::UpdateIndexReq;
+use databend_common_meta_app::schema::UpdateTableMetaReply;
+use databend_common_meta_app::schema::UpdateTableMetaReq;
+use databend_common_meta_app::schema::UpdateVirtualColumnReply;
+use databend_common_meta_app::schema::UpdateVirtualColumnReq;
+use databend_common_meta_app::schema::UpsertTableOptionReply;
+use databend_common_meta_app::schema::UpsertTableOptionReq;
+use databend_common_meta_app::schema::VirtualColumnMeta;
+use databend_common_meta_types::MatchSeq;
+use databend_common_meta_types::MetaId;
+use databend_query::catalogs::CachedCatalog;
+use databend_query::catalogs::Catalog;
+
+use crate::tests::create_catalog;
+
+fn create_table_req(tenant: &str, table_name: &str) -> CreateTableReq {
+    CreateTableReq {
+        if_not_exists: false,
+        table_id: None,
+        name_ident: TableNameIdent {
+            tenant: tenant.to_string(),
+            db_name: "default".to_string(),
+            table_name: table_name.to_string(),
+        },
+        table_meta: TableMeta {
+            schema: Arc::new(TableSchema::new(vec![TableField::new(
+                "number",
+                TableDataType::Number(NumberDataType::UInt64),
+            )])),
+            engine: "MEMORY".to_string(),
+            ..TableMeta::default()
+        },
+    }
+}
+
+/// Pause a `get_table()` after it reads the table, until `release` is notified.
+#[derive(Debug, Default)]
+struct Hold {
+    /// Pause the next `get_table()` only.
+    armed: AtomicBool,
+    /// Notified when the paused `get_table()` has read the table.
+    read: Notify,
+    release: Notify,
+}
+
+/// A catalog that delegates every call to `cat`, except that `get_table()` fails
+/// as if the meta-service is unreachable when `unavailable` is set.
+#[derive(Clone, Debug)]
+struct FaultyCatalog {
+    cat: Arc<dyn Catalog>,
+    unavailable: Arc<AtomicBool>,
+    hold: Arc<Hold>,
+}
+
+#[async_trait::async_trait]
+impl Catalog for FaultyCatalog {
+    fn name(&self) -> String {
+        "FaultyCatalog".to_string()
+    }
+
+    fn info(&self) -> CatalogInfo {
+        self.cat.info()
+    }
+
+    async fn get_database(&self, tenant: &str, db_name: &str) -> Result<Arc<dyn Database>> {
+        self.cat.get_database(tenant, db_name).await
+    }
+
+    async fn list_databases(&self, tenant: &str) -> Result<Vec<Arc<dyn Database>>> {
+        self.cat.list_databases(tenant).await
+    }
+
+    async fn create_database(&self, req: CreateDatabaseReq) -> Result<CreateDatabaseReply> {
+        self.cat.create_database(req).await
+    }
+
+    async fn drop_database(&self, req: DropDatabaseReq) -> Result<DropDatabaseReply> {
+        self.cat.drop_database(req).await
+    }
+
+    async fn undrop_database(&self, req: UndropDatabaseReq) -> Result<UndropDatabaseReply> {
+        self.cat.undrop_database(req).await
+    }
+
+    async fn rename_database(&self, req: RenameDatabaseReq) -> Result<RenameDatabaseReply> {
+        self.cat.rename_database(req).await
+    }
+
+    fn get_table_by_info(&self, table_info: &TableInfo) -> Result<Arc<dyn Table>> {
+        self.cat.get_table_by_info(table_info)
+    }
+
+    async fn get_table_meta_by_id(&self, table_id: MetaId) -> Result<(TableIdent, Arc<TableMeta>)> {
+        self.cat.get_table_meta_by_id(table_id).await
+    }
+
+    async fn get_table_name_by_id(&self, table_id: MetaId) -> Result<String> {
+        self.cat.get_table_name_by_id(table_id).await
+    }
+
+    async fn get_db_name_by_id(&self, db_id: MetaId) -> Result<String> {
+        self.cat.get_db_name_by_id(db_id).await
+    }
+
+    async fn get_table(
+        &self,
+        tenant: &str,
+        db_name: &str,
+        table_name: &str,
+    ) -> Result<Arc<dyn Table>> {
+        if self.unavailable.load(Ordering::Relaxed) {
+            return Err(ErrorCode::MetaServiceError("meta-service is unreachable"));
+        }
+
+        let table = self.cat.get_table(tenant, db_name, table_name).await?;
+
+        if self.hold.armed.swap(false, Ordering::SeqCst) {
+            self.hold.read.notify_one();
+            self.hold.release.notified().await;
+        }
+        Ok(table)
+    }
+
+    async fn list_tables(&self, tenant: &str, db_name: &str) -> Result<Vec<Arc<dyn Table>>> {
+        self.cat.list_tables(tenant, db_name).await
+    }
+
+    async fn list_tables_history(
+        &self,
+        tenant: &str,
+        db_name: &str,
+    ) -> Result<Vec<Arc<dyn Table>>> {
+        self.cat.list_tables_history(tenant, db_name).await
+    }
+
+    async fn create_table(&self, req: CreateTableReq) -> Result<CreateTableReply> {
+        self.cat.create_table(req).await
+    }
+
+    async fn drop_table_by_id(&self, req: DropTableByIdReq) -> Result<DropTableReply> {
+        self.cat.drop_table_by_id(req).await
+    }
+
+    async fn undrop_table(&self, req: UndropTableReq) -> Result<UndropTableReply> {
+        self.cat.undrop_table(req).await
+    }
+
+    async fn rename_table(&self, req: RenameTableReq) -> Result<RenameTableReply> {
+        self.cat.rename_table(req).await
+    }
+
+    async fn upsert_table_option(
+        &self,
+        tenant: &str,
+        db_name: &str,
+        req: UpsertTableOptionReq,
+    ) -> Result<UpsertTableOptionReply> {
+        self.cat.upsert_table_option(tenant, db_name, req).await
+    }
+
+    async fn update_table_meta(
+        &self,
+        table_info: &TableInfo,
+        req: UpdateTableMetaReq,
+    ) -> Result<UpdateTableMetaReply> {
+        self.cat.update_table_meta(table_info, req).await
+    }
+
+    async fn set_table_column_mask_policy(
+        &self,
+        req: SetTableColumnMaskPolicyReq,
+    ) -> Result<SetTableColumnMaskPolicyReply> {
+        self.cat.set_table_column_mask_policy(req).await
+    }
+
+    async fn count_tables(&self, req: CountTablesReq) -> Result<CountTablesReply> {
+        self.cat.count_tables(req).await
+    }
+
+    async fn get_table_copied_file_info(
+        &self,
+        tenant: &str,
+        db_name: &str,
+        req: GetTableCopiedFileReq,
+    ) -> Result<GetTableCopiedFileReply> {
+        self.cat
+            .get_table_copied_file_info(tenant, db_name, req)
+            .await
+    }
+
+    async fn truncate_table(
+        &self,
+        table_info: &TableInfo,
+        req: TruncateTableReq,
+    ) -> Result<TruncateTableReply> {
+        self.cat.truncate_table(table_info, req).await
+    }
+
+    #[async_backtrace::framed]
+    async fn create_index(&self, req: CreateIndexReq) -> Result<CreateIndexReply> {
+        self.cat.create_index(req).await
+    }
+
+    #[async_backtrace::framed]
+    async fn drop_index(&self, req: DropIndexReq) -> Result<DropIndexReply> {
+        self.cat.drop_index(req).await
+    }
+
+    #[async_backtrace::framed]
+    async fn get_index(&self, req: GetIndexReq) -> Result<GetIndexReply> {
+        self.cat.get_index(req).await
+    }
+
+    #[async_backtrace::framed]
+    async fn update_index(&self, req: UpdateIndexReq) -> Result<UpdateIndexReply> {
+        self.cat.update_index(req).await
+    }
+
+    #[async_backtrace::framed]
+    async fn list_indexes(&self, req: ListIndexesReq) -> Result<Vec<(u64, String, IndexMeta)>> {
+        self.cat.list_indexes(req).await
+    }
+
+    #[async_backtrace::framed]
+    async fn list_index_ids_by_table_id(&self, req: ListIndexesByIdReq) -> Result<Vec<u64>> {
+        self.cat.list_index_ids_by_table_id(req).await
+    }
+
+    #[async_backtrace::framed]
+    async fn list_indexes_by_table_id(
+        &self,
+        req: ListIndexesByIdReq,
+    ) -> Result<Vec<(u64, String, IndexMeta)>> {
+        self.cat.list_indexes_by_table_id(req).await
+    }
+
+    #[async_backtrace::framed]
+    async fn create_virtual_column(
+        &self,
+        req: CreateVirtualColumnReq,
+    ) -> Result<CreateVirtualColumnReply> {
+        self.cat.create_virtual_column(req).await
+    }
+
+    #[async_backtrace::framed]
+    async fn update_virtual_column(
+        &self,
+        req: UpdateVirtualColumnReq,
+    ) -> Result<UpdateVirtualColumnReply> {
+        self.cat.update_virtual_column(req).await
+    }
+
+    #[async_backtrace::framed]
+    async fn drop_virtual_column(
+        &self,
+        req: DropVirtualColumnReq,
+    ) -> Result<DropVirtualColumnReply> {
+        self.cat.drop_virtual_column(req).await
+    }
+
+    #[async_backtrace::framed]
+    async fn list_virtual_columns(
+        &self,
+        req: ListVirtualColumnsReq,
+    ) -> Result<Vec<VirtualColumnMeta>> {
+        self.cat.list_virtual_columns(req).await
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    async fn list_lock_revisions(&self, req: ListLockRevReq) -> Result<Vec<(u64, LockMeta)>> {
+        self.cat.list_lock_revisions(req).await
+    }
+
+    async fn create_lock_revision(&self, req: CreateLockRevReq) -> Result<CreateLockRevReply> {
+        self.cat.create_lock_revision(req).await
+    }
+
+    async fn extend_lock_revision(&self, req: ExtendLockRevReq) -> Result<()> {
+        self.cat.extend_lock_revision(req).await
+    }
+
+    async fn delete_lock_revision(&self, req: DeleteLockRevReq) -> Result<()> {
+        self.cat.delete_lock_revision(req).await
+    }
+
+    async fn list_locks(&self, req: ListLocksReq) -> Result<Vec<LockInfo>> {
+        self.cat.list_locks(req).await
+    }
+}
+
+/// Add a column to `table_name` through `catalog`.
+async fn add_column(
+    catalog: &dyn Catalog,
+    tenant: &str,
+    table_name: &str,
+    column: &str,
+) -> Result<()> {
+    let table = catalog.get_table(tenant, "default", table_name).await?;
+    let table_info = table.get_table_info();
+
+    let mut new_table_meta = table_info.meta.clone();
+    let index = new_table_meta.schema.num_fields();
+    new_table_meta.add_column(
+        &TableField::new(column, TableDataType::Number(NumberDataType::UInt64)),
+        "",
+        index,
+    )?;
+
+    let req = UpdateTableMetaReq {
+        table_id: table_info.ident.table_id,
+        seq: MatchSeq::Exact(table_info.ident.seq),
+        new_table_meta,
+        copied_files: None,
+        update_stream_meta: vec![],
+        deduplicated_label: None,
+    };
+    catalog.update_table_meta(table_info, req).await?;
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_cached_catalog_invalidate_on_ddl() -> Result<()> {
+    let tenant = "test";
+    let inner = create_catalog().await?;
+    let catalog = CachedCatalog::create(inner.clone(), Duration::from_secs(3600), 16);
+
+    catalog.create_table(create_table_req(tenant, "t1")).await?;
+
+    let t1 = catalog.get_table(tenant, "default", "t1").await?;
+    assert_eq!(1, t1.schema().num_fields());
+
+    // A change made behind the cache is not visible until the entry is invalidated.
+    {
+        add_column(&inner, tenant, "t1", "a").await?;
+
+        let t1 = catalog.get_table(tenant, "default", "t1").await?;
+        assert_eq!(1, t1.schema().num_fields(), "served from cache");
+
+        catalog.invalidate(tenant, "default", "t1");
+
+        let t1 = catalog.get_table(tenant, "default", "t1").await?;
+        assert_eq!(2, t1.schema().num_fields());
+    }
+
+    // A schema change through the cached catalog invalidates the entry.
+    {
+        add_column(&catalog, tenant, "t1", "b").await?;
+
+        let t1 = catalog.get_table(tenant, "default", "t1").await?;
+        assert_eq!(3, t1.schema().num_fields());
+        assert_eq!(2, t1.get_table_info().meta.schema_version);
+    }
+
+    // Rename invalidates the entry of the old name.
+    {
+        let req = RenameTableReq {
+            if_exists: false,
+            name_ident: TableNameIdent {
+                tenant: tenant.to_string(),
+                db_name: "default".to_string(),
+                table_name: "t1".to_string(),
+            },
+            new_db_name: "default".to_string(),
+            new_table_name: "t2".to_string(),
+        };
+        catalog.rename_table(req).await?;
+
+        let res = catalog.get_table(tenant, "default", "t1").await;
+        assert!(res.is_err());
+
+        let t2 = catalog.get_table(tenant, "default", "t2").await?;
+        assert_eq!(3, t2.schema().num_fields());
+    }
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_cached_catalog_ttl_and_max_entries() -> Result<()> {
+    let tenant = "test";
+    let inner = create_catalog().await?;
+
+    inner.create_table(create_table_req(tenant, "t1")).await?;
+    inner.create_table(create_table_req(tenant, "t2")).await?;
+
+    // An expired entry is not served.
+    {
+        let catalog = CachedCatalog::create(inner.clone(), Duration::from_millis(0), 16);
+
+        catalog.get_table(tenant, "default", "t1").await?;
+        add_column(&inner, tenant, "t1", "a").await?;
+
+        let t1 = catalog.get_table(tenant, "default", "t1").await?;
+        assert_eq!(2, t1.schema().num_fields());
+    }
+
+    // Caching `t2` evicts `t1` when there is room for only one entry.
+    {
+        let catalog = CachedCatalog::create(inner.clone(), Duration::from_secs(3600), 1);
+
+        catalog.get_table(tenant, "default", "t1").await?;
+        catalog.get_table(tenant, "default", "t2").await?;
+        add_column(&inner, tenant, "t1", "b").await?;
+
+        let t1 = catalog.get_table(tenant, "default", "t1").await?;
+        assert_eq!(3, t1.schema().num_fields());
+    }
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_cached_catalog_warm_tables() -> Result<()> {
+    let tenant = "test";
+    let inner = create_catalog().await?;
+
+    inner.create_table(create_table_req(tenant, "t1")).await?;
+    inner.create_table(create_table_req(tenant, "t2")).await?;
+
+    let names = vec!["t1".to_string(), "t2".to_string(), "t3".to_string()];
+
+    // A table that does not exist is skipped.
+    let tables = inner.mget_tables(tenant, "default", &names).await?;
+    let mut got = tables
+        .iter()
+        .map(|t| t.name().to_string())
+        .collect::<Vec<_>>();
+    got.sort();
+    assert_eq!(vec!["t1".to_string(), "t2".to_string()], got);
+
+    let res = inner.mget_tables(tenant, "not_exist", &names).await;
+    assert!(res.is_err());
+
+    // Warmed tables are served from cache.
+    {
+        let catalog = CachedCatalog::create(inner.clone(), Duration::from_secs(3600), 16);
+        catalog.warm_tables(tenant, "default", &names).await?;
+
+        add_column(&inner, tenant, "t1", "a").await?;
+        add_column(&inner, tenant, "t2", "a").await?;
+
+        let t1 = catalog.get_table(tenant, "default", "t1").await?;
+        assert_eq!(1, t1.schema().num_fields(), "served from cache");
+
+        let t2 = catalog.get_table(tenant, "default", "t2").await?;
+        assert_eq!(1, t2.schema().num_fields(), "served from cache");
+
+        let res = catalog.get_table(tenant, "default", "t3").await;
+        assert!(res.is_err());
+    }
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_cached_catalog_serve_stale() -> Result<()> {
+    let tenant = "test";
+    let inner = create_catalog().await?;
+    inner.create_table(create_table_req(tenant, "t1")).await?;
+
+    let unavailable = Arc::new(AtomicBool::new(false));
+    let faulty = FaultyCatalog {
+        cat: Arc::new(inner.clone()),
+        unavailable: unavailable.clone(),
+        hold: Arc::new(Hold::default()),
+    };
+
+    // Disabled by default: an expired table is not served.
+    {
+        let catalog = CachedCatalog::create(faulty.clone(), Duration::from_millis(0), 16);
+        catalog.get_table(tenant, "default", "t1").await?;
+
+        unavailable.store(true, Ordering::Relaxed);
+        let res = catalog.get_table(tenant, "default", "t1").await;
+        assert_eq!(ErrorCode::META_SERVICE_ERROR, res.err().unwrap().code());
+        unavailable.store(false, Ordering::Relaxed);
+    }
+
+    // An expired table within `max_staleness` is served when the inner catalog is unavailable.
+    {
+        let catalog = CachedCatalog::create(faulty.clone(), Duration::from_millis(0), 16)
+            .with_max_staleness(Duration::from_secs(3600));
+
+        let served = catalog
+            .get_table_maybe_stale(tenant, "default", "t1")
+            .await?;
+        assert!(!served.stale);
+
+        unavailable.store(true, Ordering::Relaxed);
+
+        let served = catalog
+            .get_table_maybe_stale(tenant, "default", "t1")
+            .await?;
+        assert!(served.stale);
+        assert_eq!("t1", served.table.name());
+
+        let t1 = catalog.get_table(tenant, "default", "t1").await?;
+        assert_eq!("t1", t1.name());
+
+        // Nothing cached to serve.
+        let res = catalog.get_table(tenant, "default", "t2").await;
+        assert_eq!(ErrorCode::META_SERVICE_ERROR, res.err().unwrap().code());
+
+        // A fresh table is served once the inner catalog is back.
+        unavailable.store(false, Ordering::Relaxed);
+        add_column(&inner, tenant, "t1", "a").await?;
+
+        let served = catalog
+            .get_table_maybe_stale(tenant, "default", "t1")
+            .await?;
+        assert!(!served.stale);
+        assert_eq!(2, served.table.schema().num_fields());
+    }
+
+    // An entry expired for longer than `max_staleness` is not served.
+    {
+        let catalog = CachedCatalog::create(faulty.clone(), Duration::from_millis(0), 16)
+            .with_max_staleness(Duration::from_millis(50));
+        catalog.get_table(tenant, "default", "t1").await?;
+
+        tokio::time::sleep(Duration::from_millis(100)).await;
+
+        unavailable.store(true, Ordering::Relaxed);
+        let res = catalog.get_table(tenant, "default", "t1").await;
+        assert_eq!(ErrorCode::META_SERVICE_ERROR, res.err().unwrap().code());
+        unavailable.store(false, Ordering::Relaxed);
+    }
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_cached_catalog_get_table_consistent() -> Result<()> {
+    let tenant = "test_consistent";
+    let inner = create_catalog().await?;
+    let catalog = CachedCatalog::create(inner.clone(), Duration::from_secs(3600), 16);
+
+    inner.create_table(create_table_req(tenant, "t1")).await?;
+
+    catalog.get_table(tenant, "default", "t1").await?;
+
+    // Make the cached entry stale.
+    add_column(&inner, tenant, "t1", "a").await?;
+
+    let t1 = catalog
+        .get_table_consistent(tenant, "default", "t1", ReadConsistency::Cached)
+        .await?;
+    assert_eq!(1, t1.schema().num_fields(), "served from cache");
+
+    let t1 = catalog
+        .get_table_consistent(tenant, "default", "t1", ReadConsistency::Fresh)
+        .await?;
+    assert_eq!(2, t1.schema().num_fields(), "bypass the cache");
+
+    // The fresh table replaces the stale entry.
+    let t1 = catalog.get_table(tenant, "default", "t1").await?;
+    assert_eq!(2, t1.schema().num_fields());
+
+    add_column(&inner, tenant, "t1", "b").await?;
+
+    let t1 = catalog
+        .get_table_consistent(tenant, "default", "t1", ReadConsistency::Linearizable)
+        .await?;
+    assert_eq!(3, t1.schema().num_fields());
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_cached_catalog_refresh_statistics_stale() -> Result<()> {
+    let tenant = "test_refresh_stale";
+    let inner = create_catalog().await?;
+    let catalog = CachedCatalog::create(inner.clone(), Duration::from_secs(3600), 16);
+
+    inner.create_table(create_table_req(tenant, "t1")).await?;
+
+    catalog.get_table(tenant, "default", "t1").await?;
+
+    // Make the cached entry stale.
+    add_column(&inner, tenant, "t1", "a").await?;
+
+    // The update is checked against the latest seq, not the cached one,
+    // and does not overwrite the change made behind the cache.
+    catalog.refresh_statistics(tenant, "default", "t1").await?;
+
+    let t1 = inner.get_table(tenant, "default", "t1").await?;
+    assert_eq!(2, t1.schema().num_fields());
+    assert_eq!(0, t1.get_table_info().meta.statistics.number_of_rows);
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_cached_catalog_set_database_quota() -> Result<()> {
+    let tenant = "test";
+    let db_name = "db_cached_quota";
+    let inner = create_catalog().await?;
+    let catalog = CachedCatalog::create(inner.clone(), Duration::from_secs(3600), 16);
+
+    catalog
+        .create_database(CreateDatabaseReq {
+            if_not_exists: false,
+            name_ident: DatabaseNameIdent {
+                tenant: tenant.to_string(),
+                db_name: db_name.to_string(),
+            },
+            meta: DatabaseMeta {
+                engine: "".to_string(),
+                ..Default::default()
+            },
+        })
+        .await?;
+
+    let set_quota = |max_tables: Option<u64>| SetDatabaseQuotaReq {
+        name_ident: DatabaseNameIdent {
+            tenant: tenant.to_string(),
+            db_name: db_name.to_string(),
+        },
+        max_tables,
+    };
+
+    // Forwarded to the inner catalog.
+    catalog.set_database_quota(set_quota(Some(3))).await?;
+
+    let db = inner.get_database(tenant, db_name).await?;
+    assert_eq!(Some(3), db.get_db_info().meta.max_tables);
+
+    catalog.set_database_quota(set_quota(None)).await?;
+
+    let db = catalog.get_database(tenant, db_name).await?;
+    assert_eq!(None, db.get_db_info().meta.max_tables);
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_cached_catalog_invalidate_during_read() -> Result<()> {
+    let tenant = "test";
+    let inner = create_catalog().await?;
+    inner
+        .create_table(create_table_req(tenant, "t_inv_race"))
+        .await?;
+
+    let faulty = FaultyCatalog {
+        cat: Arc::new(inner.clone()),
+        unavailable: Arc::new(AtomicBool::new(false)),
+        hold: Arc::new(Hold::default()),
+    };
+    let catalog = CachedCatalog::create(faulty.clone(), Duration::from_secs(3600), 16);
+
+    // A read starts before a DDL and returns after the DDL invalidates the entry.
+    faulty.hold.armed.store(true, Ordering::SeqCst);
+    let read = {
+        let catalog = catalog.clone();
+        tokio::spawn(async move { catalog.get_table(tenant, "default", "t_inv_race").await })
+    };
+    faulty.hold.read.notified().await;
+
+    add_column(&inner, tenant, "t_inv_race", "a").await?;
+    catalog.invalidate(tenant, "default", "t_inv_race");
+
+    faulty.hold.release.notify_one();
+    let t = read.await.unwrap()?;
+    assert_eq!(1, t.schema().num_fields(), "read before the DDL");
+
+    // The table of the read is not cached.
+    let t = catalog.get_table(tenant, "default", "t_inv_race").await?;
+    assert_eq!(2, t.schema().num_fields());
+
+    // Invalidating the same name of another tenant does not remove the entry.
+    add_column(&inner, tenant, "t_inv_race", "b").await?;
+    catalog.invalidate("another_tenant", "default", "t_inv_race");
+
+    let t = catalog.get_table(tenant, "default", "t_inv_race").await?;
+    assert_eq!(2, t.schema().num_fields(), "served from cache");
+
+    Ok(())
+}
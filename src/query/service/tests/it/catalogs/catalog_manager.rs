@@ -0,0 +1,50 @@
+// Copyright 2021 Datafuse Labs
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use databend_common_base::base::tokio;
+use databend_common_catalog::catalog::CatalogManager;
+use databend_common_exception::Result;
+use databend_query::test_kits::TestFixture;
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_catalog_manager_qualified_names() -> Result<()> {
+    let fixture = TestFixture::setup().await?;
+    fixture.create_default_database().await?;
+    fixture.create_default_table().await?;
+
+    let tenant = fixture.default_tenant();
+    let db_name = fixture.default_db_name();
+    let table_name = fixture.default_table_name();
+    let manager = CatalogManager::instance();
+
+    // Without a catalog name, the DEFAULT catalog is used.
+    let unqualified = manager
+        .get_table(&tenant, None, &db_name, &table_name)
+        .await?;
+    let qualified = manager
+        .get_table(&tenant, Some("default"), &db_name, &table_name)
+        .await?;
+    assert_eq!(unqualified.get_id(), qualified.get_id());
+
+    let db = manager.get_database(&tenant, None, &db_name).await?;
+    assert_eq!(db_name, db.name());
+
+    // An unknown catalog is an error, rather than falling back to the DEFAULT catalog.
+    let res = manager
+        .get_table(&tenant, Some("catalog_not_exist"), &db_name, &table_name)
+        .await;
+    assert!(res.is_err());
+
+    Ok(())
+}
@@ -13,24 +13,61 @@
 // limitations under the License.
 
 use std::sync::Arc;
+use std::sync::Mutex;
+use std::time::Duration;
 
 use chrono::Utc;
 use databend_common_base::base::tokio;
+use databend_common_base::base::tokio::sync::broadcast;
+use databend_common_catalog::catalog::with_audit_principal;
+use databend_common_catalog::catalog::AuditSink;
+use databend_common_catalog::catalog::CatalogAuditEvent;
+use databend_common_catalog::catalog::CatalogAuditOp;
+use databend_common_catalog::catalog::CatalogEvent;
+use databend_common_catalog::catalog::CatalogNotification;
+use databend_common_catalog::catalog::ColumnDescription;
+use databend_common_catalog::catalog::ConsistencyReport;
+use databend_common_catalog::catalog::DatabaseStats;
+use databend_common_catalog::catalog::Inconsistency;
+use databend_common_catalog::catalog::TableDescription;
+use databend_common_catalog::database::Database;
+use databend_common_exception::ErrorCode;
 use databend_common_exception::Result;
 use databend_common_expression::types::NumberDataType;
+use databend_common_expression::types::NumberScalar;
+use databend_common_expression::Scalar;
 use databend_common_expression::TableDataType;
 use databend_common_expression::TableField;
 use databend_common_expression::TableSchema;
+use databend_common_meta_api::serialize_struct;
+use databend_common_meta_api::serialize_u64;
 use databend_common_meta_app::schema::CreateDatabaseReq;
 use databend_common_meta_app::schema::CreateTableReq;
+use databend_common_meta_app::schema::DBIdTableName;
 use databend_common_meta_app::schema::DatabaseMeta;
 use databend_common_meta_app::schema::DatabaseNameIdent;
 use databend_common_meta_app::schema::DropDatabaseReq;
 use databend_common_meta_app::schema::DropTableByIdReq;
 use databend_common_meta_app::schema::RenameDatabaseReq;
+use databend_common_meta_app::schema::RenameTableReq;
+use databend_common_meta_app::schema::SetDatabaseQuotaReq;
+use databend_common_meta_app::schema::TableId;
+use databend_common_meta_app::schema::TableIdToName;
 use databend_common_meta_app::schema::TableMeta;
 use databend_common_meta_app::schema::TableNameIdent;
+use databend_common_meta_app::schema::TableStatistics;
+use databend_common_meta_app::schema::UndropTableReq;
+use databend_common_meta_app::schema::UpdateTableMetaReq;
+use databend_common_meta_kvapi::kvapi::KVApi;
+use databend_common_meta_kvapi::kvapi::Key;
+use databend_common_meta_kvapi::kvapi::UpsertKVReq;
+use databend_common_meta_store::MetaStoreProvider;
+use databend_common_meta_types::txn_op;
+use databend_common_meta_types::MatchSeq;
+use databend_common_meta_types::MetaError;
 use databend_query::catalogs::Catalog;
+use databend_storages_common_table_meta::table::OPT_KEY_SNAPSHOT_LOCATION;
+use futures::TryStreamExt;
 
 use crate::tests::create_catalog;
 
@@ -180,6 +217,7 @@ async fn test_catalogs_table() -> Result<()> {
 
         let mut req = CreateTableReq {
             if_not_exists: false,
+            table_id: None,
             name_ident: TableNameIdent {
                 tenant: tenant.to_string(),
                 db_name: "default".to_string(),
@@ -230,3 +268,2125 @@ async fn test_catalogs_table() -> Result<()> {
 
     Ok(())
 }
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_catalogs_rename_table() -> Result<()> {
+    let tenant = "test";
+    let catalog = create_catalog().await?;
+
+    let create_req = |table_name: &str| CreateTableReq {
+        if_not_exists: false,
+        table_id: None,
+        name_ident: TableNameIdent {
+            tenant: tenant.to_string(),
+            db_name: "default".to_string(),
+            table_name: table_name.to_string(),
+        },
+        table_meta: TableMeta {
+            schema: Arc::new(TableSchema::new(vec![TableField::new(
+                "number",
+                TableDataType::Number(NumberDataType::UInt64),
+            )])),
+            engine: "MEMORY".to_string(),
+            ..TableMeta::default()
+        },
+    };
+
+    let rename_req = |from: &str, to: &str| RenameTableReq {
+        if_exists: false,
+        name_ident: TableNameIdent {
+            tenant: tenant.to_string(),
+            db_name: "default".to_string(),
+            table_name: from.to_string(),
+        },
+        new_db_name: "default".to_string(),
+        new_table_name: to.to_string(),
+    };
+
+    catalog.create_table(create_req("t1")).await?;
+    catalog.create_table(create_req("t2")).await?;
+
+    let t1 = catalog.get_table(tenant, "default", "t1").await?;
+    let t1_id = t1.get_table_info().ident.table_id;
+
+    // Rename keeps the table id.
+    {
+        catalog.rename_table(rename_req("t1", "t3")).await?;
+
+        assert!(!catalog.exists_table(tenant, "default", "t1").await?);
+        let t3 = catalog.get_table(tenant, "default", "t3").await?;
+        assert_eq!(t1_id, t3.get_table_info().ident.table_id);
+    }
+
+    // Rename to an existing table fails and changes nothing.
+    {
+        let res = catalog.rename_table(rename_req("t3", "t2")).await;
+        assert!(res.is_err());
+
+        let t3 = catalog.get_table(tenant, "default", "t3").await?;
+        assert_eq!(t1_id, t3.get_table_info().ident.table_id);
+        assert!(catalog.exists_table(tenant, "default", "t2").await?);
+    }
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_catalogs_rename_database_with_tables() -> Result<()> {
+    let tenant = "test";
+    let catalog = create_catalog().await?;
+
+    let create_db_req = |db_name: &str| CreateDatabaseReq {
+        if_not_exists: false,
+        name_ident: DatabaseNameIdent {
+            tenant: tenant.to_string(),
+            db_name: db_name.to_string(),
+        },
+        meta: DatabaseMeta::default(),
+    };
+
+    let create_req = |table_name: &str| CreateTableReq {
+        if_not_exists: false,
+        table_id: None,
+        name_ident: TableNameIdent {
+            tenant: tenant.to_string(),
+            db_name: "db1".to_string(),
+            table_name: table_name.to_string(),
+        },
+        table_meta: TableMeta {
+            schema: Arc::new(TableSchema::new(vec![TableField::new(
+                "number",
+                TableDataType::Number(NumberDataType::UInt64),
+            )])),
+            engine: "MEMORY".to_string(),
+            ..TableMeta::default()
+        },
+    };
+
+    catalog.create_database(create_db_req("db1")).await?;
+    catalog.create_database(create_db_req("db3")).await?;
+
+    let mut table_ids = vec![];
+    for table_name in ["t1", "t2", "t3"] {
+        catalog.create_table(create_req(table_name)).await?;
+        let t = catalog.get_table(tenant, "db1", table_name).await?;
+        table_ids.push(t.get_id());
+    }
+
+    let db_id = catalog
+        .get_database(tenant, "db1")
+        .await?
+        .get_db_info()
+        .ident
+        .db_id;
+
+    // All tables move with the database and keep their ids.
+    {
+        catalog
+            .rename_database_by_name(tenant, "db1", "db2")
+            .await?;
+
+        assert!(!catalog.exists_database(tenant, "db1").await?);
+
+        let db = catalog.get_database(tenant, "db2").await?;
+        assert_eq!(db_id, db.get_db_info().ident.db_id);
+
+        for (table_name, table_id) in ["t1", "t2", "t3"].into_iter().zip(&table_ids) {
+            assert!(!catalog.exists_table(tenant, "db1", table_name).await?);
+
+            let t = catalog.get_table(tenant, "db2", table_name).await?;
+            assert_eq!(*table_id, t.get_id());
+        }
+
+        let tables = catalog.list_tables(tenant, "db2").await?;
+        assert_eq!(3, tables.len());
+    }
+
+    // Rename to an existing database fails and changes nothing.
+    {
+        let res = catalog.rename_database_by_name(tenant, "db2", "db3").await;
+        assert_eq!(ErrorCode::DATABASE_ALREADY_EXISTS, res.unwrap_err().code());
+
+        let tables = catalog.list_tables(tenant, "db2").await?;
+        assert_eq!(3, tables.len());
+
+        let tables = catalog.list_tables(tenant, "db3").await?;
+        assert!(tables.is_empty());
+    }
+
+    // Rename an unknown database fails.
+    {
+        let res = catalog.rename_database_by_name(tenant, "db1", "db4").await;
+        assert_eq!(ErrorCode::UNKNOWN_DATABASE, res.unwrap_err().code());
+    }
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_catalogs_exists_table() -> Result<()> {
+    let tenant = "test";
+    let catalog = create_catalog().await?;
+
+    let create_table_req = CreateTableReq {
+        if_not_exists: false,
+        table_id: None,
+        name_ident: TableNameIdent {
+            tenant: tenant.to_string(),
+            db_name: "default".to_string(),
+            table_name: "t1".to_string(),
+        },
+        table_meta: TableMeta {
+            schema: Arc::new(TableSchema::new(vec![TableField::new(
+                "number",
+                TableDataType::Number(NumberDataType::UInt64),
+            )])),
+            engine: "MEMORY".to_string(),
+            ..TableMeta::default()
+        },
+    };
+    catalog.create_table(create_table_req).await?;
+
+    // Present
+    assert!(catalog.exists_table(tenant, "default", "t1").await?);
+
+    // Absent table
+    assert!(!catalog.exists_table(tenant, "default", "t2").await?);
+
+    // Absent database
+    assert!(!catalog.exists_table(tenant, "db_not_exist", "t1").await?);
+
+    // Tables of system databases
+    assert!(catalog.exists_table(tenant, "system", "clusters").await?);
+    assert!(!catalog.exists_table(tenant, "system", "t1").await?);
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_catalogs_table_schema_version() -> Result<()> {
+    let tenant = "test";
+    let catalog = create_catalog().await?;
+
+    let create_table_req = CreateTableReq {
+        if_not_exists: false,
+        table_id: None,
+        name_ident: TableNameIdent {
+            tenant: tenant.to_string(),
+            db_name: "default".to_string(),
+            table_name: "t1".to_string(),
+        },
+        table_meta: TableMeta {
+            schema: Arc::new(TableSchema::new(vec![TableField::new(
+                "number",
+                TableDataType::Number(NumberDataType::UInt64),
+            )])),
+            engine: "MEMORY".to_string(),
+            ..TableMeta::default()
+        },
+    };
+    catalog.create_table(create_table_req).await?;
+
+    let version = catalog
+        .get_table_schema_version(tenant, "default", "t1")
+        .await?;
+    assert_eq!(0, version);
+
+    // Adding a column bumps the schema version.
+    {
+        let table = catalog.get_table(tenant, "default", "t1").await?;
+        let table_info = table.get_table_info();
+
+        let mut new_table_meta = table_info.meta.clone();
+        new_table_meta.add_column(
+            &TableField::new("c", TableDataType::Number(NumberDataType::UInt64)),
+            "",
+            1,
+        )?;
+
+        let req = UpdateTableMetaReq {
+            table_id: table_info.ident.table_id,
+            seq: MatchSeq::Exact(table_info.ident.seq),
+            new_table_meta,
+            copied_files: None,
+            update_stream_meta: vec![],
+            deduplicated_label: None,
+        };
+        catalog.update_table_meta(table_info, req).await?;
+
+        let version = catalog
+            .get_table_schema_version(tenant, "default", "t1")
+            .await?;
+        assert_eq!(1, version);
+    }
+
+    // Unknown table
+    {
+        let res = catalog
+            .get_table_schema_version(tenant, "default", "t2")
+            .await;
+        assert!(res.is_err());
+    }
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_catalogs_alter_table_add_column() -> Result<()> {
+    let tenant = "test";
+    let catalog = create_catalog().await?;
+
+    let create_table_req = CreateTableReq {
+        if_not_exists: false,
+        table_id: None,
+        name_ident: TableNameIdent {
+            tenant: tenant.to_string(),
+            db_name: "default".to_string(),
+            table_name: "t1".to_string(),
+        },
+        table_meta: TableMeta {
+            schema: Arc::new(TableSchema::new(vec![TableField::new(
+                "number",
+                TableDataType::Number(NumberDataType::UInt64),
+            )])),
+            engine: "MEMORY".to_string(),
+            ..TableMeta::default()
+        },
+    };
+    catalog.create_table(create_table_req).await?;
+
+    let uint64 = TableDataType::Number(NumberDataType::UInt64);
+
+    // Concurrent alters do not lose any column.
+    {
+        let futs = (0..5).map(|i| {
+            catalog.alter_table_add_column(
+                tenant,
+                "default",
+                "t1",
+                TableField::new(&format!("c{}", i), uint64.clone()),
+                None,
+            )
+        });
+        futures::future::try_join_all(futs).await?;
+
+        let table = catalog.get_table(tenant, "default", "t1").await?;
+        let schema = table.schema();
+        assert_eq!(6, schema.num_fields());
+        for i in 0..5 {
+            schema.field_with_name(&format!("c{}", i))?;
+        }
+
+        let version = catalog
+            .get_table_schema_version(tenant, "default", "t1")
+            .await?;
+        assert_eq!(5, version);
+    }
+
+    // With a default value
+    {
+        catalog
+            .alter_table_add_column(
+                tenant,
+                "default",
+                "t1",
+                TableField::new("d", uint64.clone()),
+                Some(Scalar::Number(NumberScalar::UInt64(1))),
+            )
+            .await?;
+
+        let table = catalog.get_table(tenant, "default", "t1").await?;
+        let schema = table.schema();
+        let field = schema.field_with_name("d")?;
+        assert_eq!(Some(&"1".to_string()), field.default_expr());
+    }
+
+    // Duplicated column name
+    {
+        let res = catalog
+            .alter_table_add_column(
+                tenant,
+                "default",
+                "t1",
+                TableField::new("c0", uint64.clone()),
+                None,
+            )
+            .await;
+        assert_eq!(ErrorCode::ADD_COLUMN_EXIST_ERROR, res.unwrap_err().code());
+
+        let version = catalog
+            .get_table_schema_version(tenant, "default", "t1")
+            .await?;
+        assert_eq!(6, version);
+    }
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_catalogs_get_database_stats() -> Result<()> {
+    let tenant = "test";
+    let catalog = create_catalog().await?;
+
+    catalog
+        .create_database(CreateDatabaseReq {
+            if_not_exists: false,
+            name_ident: DatabaseNameIdent {
+                tenant: tenant.to_string(),
+                db_name: "db1".to_string(),
+            },
+            meta: DatabaseMeta {
+                engine: "".to_string(),
+                ..Default::default()
+            },
+        })
+        .await?;
+
+    // Empty database
+    {
+        let stats = catalog.get_database_stats(tenant, "db1").await?;
+        assert_eq!(DatabaseStats::default(), stats);
+    }
+
+    for i in 1..=3u64 {
+        let create_table_req = CreateTableReq {
+            if_not_exists: false,
+            table_id: None,
+            name_ident: TableNameIdent {
+                tenant: tenant.to_string(),
+                db_name: "db1".to_string(),
+                table_name: format!("t{}", i),
+            },
+            table_meta: TableMeta {
+                schema: Arc::new(TableSchema::new(vec![TableField::new(
+                    "number",
+                    TableDataType::Number(NumberDataType::UInt64),
+                )])),
+                engine: "MEMORY".to_string(),
+                statistics: TableStatistics {
+                    number_of_rows: i * 10,
+                    data_bytes: i * 100,
+                    compressed_data_bytes: i * 50,
+                    index_data_bytes: i,
+                    ..TableStatistics::default()
+                },
+                ..TableMeta::default()
+            },
+        };
+        catalog.create_table(create_table_req).await?;
+    }
+
+    let stats = catalog.get_database_stats(tenant, "db1").await?;
+    assert_eq!(3, stats.number_of_tables);
+    assert_eq!(60, stats.number_of_rows);
+    assert_eq!(600, stats.data_bytes);
+    assert_eq!(300, stats.compressed_data_bytes);
+    assert_eq!(6, stats.index_data_bytes);
+    assert!(stats.updated_on.is_some());
+
+    // Unknown database
+    {
+        let res = catalog.get_database_stats(tenant, "not_exists").await;
+        assert_eq!(ErrorCode::UNKNOWN_DATABASE, res.unwrap_err().code());
+    }
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_catalogs_list_databases_paged() -> Result<()> {
+    let tenant = "test";
+    let catalog = create_catalog().await?;
+
+    for db_name in ["pg_c", "pg_a", "pg_b", "other"] {
+        catalog
+            .create_database(CreateDatabaseReq {
+                if_not_exists: false,
+                name_ident: DatabaseNameIdent {
+                    tenant: tenant.to_string(),
+                    db_name: db_name.to_string(),
+                },
+                meta: DatabaseMeta::default(),
+            })
+            .await?;
+    }
+
+    let names = |dbs: Vec<Arc<dyn Database>>| {
+        dbs.iter()
+            .map(|db| db.name().to_string())
+            .collect::<Vec<_>>()
+    };
+
+    let page = catalog
+        .list_databases_paged(tenant, None, 2, Some("pg_"))
+        .await?;
+    assert_eq!(vec!["pg_a", "pg_b"], names(page));
+
+    let page = catalog
+        .list_databases_paged(tenant, Some("pg_b"), 2, Some("pg_"))
+        .await?;
+    assert_eq!(vec!["pg_c"], names(page));
+
+    let page = catalog
+        .list_databases_paged(tenant, Some("pg_c"), 2, Some("pg_"))
+        .await?;
+    assert!(page.is_empty());
+
+    // Without prefix, also lists system databases, sorted by name.
+    let page = catalog
+        .list_databases_paged(tenant, None, 100, None)
+        .await?;
+    let all = names(page);
+    let mut sorted = all.clone();
+    sorted.sort();
+    assert_eq!(sorted, all);
+    assert!(all.contains(&"other".to_string()));
+    assert!(all.contains(&"system".to_string()));
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_catalogs_list_tables_stream() -> Result<()> {
+    let tenant = "test";
+    let catalog = create_catalog().await?;
+
+    for table_name in ["t_c", "t_a", "t_b"] {
+        catalog
+            .create_table(CreateTableReq {
+                if_not_exists: false,
+                table_id: None,
+                name_ident: TableNameIdent {
+                    tenant: tenant.to_string(),
+                    db_name: "default".to_string(),
+                    table_name: table_name.to_string(),
+                },
+                table_meta: TableMeta {
+                    schema: Arc::new(TableSchema::new(vec![TableField::new(
+                        "number",
+                        TableDataType::Number(NumberDataType::UInt64),
+                    )])),
+                    engine: "MEMORY".to_string(),
+                    ..TableMeta::default()
+                },
+            })
+            .await?;
+    }
+
+    // The same tables in the same order as the eager list_tables().
+    for db_name in ["default", "system"] {
+        let db = catalog.get_database(tenant, db_name).await?;
+
+        let eager = db.list_tables().await?;
+        let eager = eager
+            .iter()
+            .map(|t| t.name().to_string())
+            .collect::<Vec<_>>();
+
+        let streamed = db.list_tables_stream().try_collect::<Vec<_>>().await?;
+        let streamed = streamed
+            .iter()
+            .map(|t| t.name().to_string())
+            .collect::<Vec<_>>();
+
+        assert_eq!(eager, streamed, "db: {}", db_name);
+    }
+
+    let db = catalog.get_database(tenant, "default").await?;
+    let streamed = db.list_tables_stream().try_collect::<Vec<_>>().await?;
+    let streamed = streamed.iter().map(|t| t.name()).collect::<Vec<_>>();
+    assert_eq!(vec!["t_a", "t_b", "t_c"], streamed);
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_catalogs_get_table_not_found() -> Result<()> {
+    let tenant = "test";
+    let catalog = create_catalog().await?;
+
+    // Both the system databases and the databases in meta-service
+    // tell a missing database from a missing table by error code, with the name in the message.
+    for (db_name, table_name, code) in [
+        ("db_not_exist", "t1", ErrorCode::UNKNOWN_DATABASE),
+        ("default", "t_not_exist", ErrorCode::UNKNOWN_TABLE),
+        ("system", "t_not_exist", ErrorCode::UNKNOWN_TABLE),
+    ] {
+        let err = catalog
+            .get_table(tenant, db_name, table_name)
+            .await
+            .err()
+            .unwrap();
+        assert_eq!(code, err.code(), "{}.{}", db_name, table_name);
+
+        let name = if code == ErrorCode::UNKNOWN_DATABASE {
+            db_name
+        } else {
+            table_name
+        };
+        assert!(err.message().contains(&format!("'{}'", name)), "{}", err);
+    }
+
+    let err = catalog
+        .get_database(tenant, "db_not_exist")
+        .await
+        .err()
+        .unwrap();
+    assert_eq!(ErrorCode::UNKNOWN_DATABASE, err.code());
+    assert!(err.message().contains("'db_not_exist'"), "{}", err);
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_catalogs_get_table_by_id() -> Result<()> {
+    let tenant = "test";
+    let catalog = create_catalog().await?;
+
+    catalog
+        .create_table(CreateTableReq {
+            if_not_exists: false,
+            table_id: None,
+            name_ident: TableNameIdent {
+                tenant: tenant.to_string(),
+                db_name: "default".to_string(),
+                table_name: "t1".to_string(),
+            },
+            table_meta: TableMeta {
+                schema: Arc::new(TableSchema::new(vec![TableField::new(
+                    "number",
+                    TableDataType::Number(NumberDataType::UInt64),
+                )])),
+                engine: "MEMORY".to_string(),
+                ..TableMeta::default()
+            },
+        })
+        .await?;
+
+    let t1_id = catalog
+        .get_table(tenant, "default", "t1")
+        .await?
+        .get_table_info()
+        .ident
+        .table_id;
+
+    let got = catalog.get_table_by_id(t1_id).await?;
+    assert_eq!("t1", got.name());
+    assert_eq!(t1_id, got.get_id());
+
+    // The id resolves to the new name after rename.
+    {
+        catalog
+            .rename_table(RenameTableReq {
+                if_exists: false,
+                name_ident: TableNameIdent {
+                    tenant: tenant.to_string(),
+                    db_name: "default".to_string(),
+                    table_name: "t1".to_string(),
+                },
+                new_db_name: "default".to_string(),
+                new_table_name: "t2".to_string(),
+            })
+            .await?;
+
+        let got = catalog.get_table_by_id(t1_id).await?;
+        assert_eq!("t2", got.name());
+        assert_eq!(t1_id, got.get_id());
+    }
+
+    // A table in a system database is found by id too.
+    {
+        let one = catalog.get_table(tenant, "system", "one").await?;
+        let got = catalog.get_table_by_id(one.get_id()).await?;
+        assert_eq!("one", got.name());
+    }
+
+    let res = catalog.get_table_by_id(u64::MAX).await;
+    assert!(res.is_err());
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_catalogs_undrop_and_purge_dropped_tables() -> Result<()> {
+    let tenant = "test";
+    let catalog = create_catalog().await?;
+
+    let name_ident = TableNameIdent {
+        tenant: tenant.to_string(),
+        db_name: "default".to_string(),
+        table_name: "t1".to_string(),
+    };
+
+    catalog
+        .create_table(CreateTableReq {
+            if_not_exists: false,
+            table_id: None,
+            name_ident: name_ident.clone(),
+            table_meta: TableMeta {
+                schema: Arc::new(TableSchema::new(vec![TableField::new(
+                    "number",
+                    TableDataType::Number(NumberDataType::UInt64),
+                )])),
+                engine: "MEMORY".to_string(),
+                ..TableMeta::default()
+            },
+        })
+        .await?;
+
+    let db = catalog.get_database(tenant, "default").await?;
+    let drop_t1 = |tb_id: u64| DropTableByIdReq {
+        if_exists: false,
+        tenant: tenant.to_string(),
+        table_name: "t1".to_string(),
+        tb_id,
+        db_id: db.get_db_info().ident.db_id,
+    };
+    let undrop_t1 = || UndropTableReq {
+        name_ident: name_ident.clone(),
+    };
+
+    let t1_id = catalog.get_table(tenant, "default", "t1").await?.get_id();
+
+    // A dropped table is invisible, until it is undropped.
+    {
+        catalog.drop_table_by_id(drop_t1(t1_id)).await?;
+
+        assert!(!catalog.exists_table(tenant, "default", "t1").await?);
+        let tables = catalog.list_tables(tenant, "default").await?;
+        assert!(tables.iter().all(|t| t.name() != "t1"));
+
+        catalog.undrop_table(undrop_t1()).await?;
+
+        let t1 = catalog.get_table(tenant, "default", "t1").await?;
+        assert_eq!(t1_id, t1.get_id());
+    }
+
+    // A table dropped recently is not purged.
+    {
+        catalog.drop_table_by_id(drop_t1(t1_id)).await?;
+
+        let n = catalog
+            .purge_dropped_tables(tenant, Duration::from_secs(3600))
+            .await?;
+        assert_eq!(0, n);
+    }
+
+    // A purged table can not be undropped.
+    {
+        let n = catalog
+            .purge_dropped_tables(tenant, Duration::from_secs(0))
+            .await?;
+        assert_eq!(1, n);
+
+        let res = catalog.undrop_table(undrop_t1()).await;
+        assert!(res.is_err());
+        assert!(!catalog.exists_table(tenant, "default", "t1").await?);
+    }
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_catalogs_read_only() -> Result<()> {
+    let tenant = "test";
+    let catalog = create_catalog().await?;
+
+    let create_req = |table_name: &str| CreateTableReq {
+        if_not_exists: false,
+        table_id: None,
+        name_ident: TableNameIdent {
+            tenant: tenant.to_string(),
+            db_name: "default".to_string(),
+            table_name: table_name.to_string(),
+        },
+        table_meta: TableMeta {
+            schema: Arc::new(TableSchema::new(vec![TableField::new(
+                "number",
+                TableDataType::Number(NumberDataType::UInt64),
+            )])),
+            engine: "MEMORY".to_string(),
+            ..TableMeta::default()
+        },
+    };
+
+    catalog.create_table(create_req("t1")).await?;
+    let t1 = catalog.get_table(tenant, "default", "t1").await?;
+    let db = catalog.get_database(tenant, "default").await?;
+
+    assert!(!catalog.is_read_only());
+    catalog.set_read_only(true);
+    assert!(catalog.is_read_only());
+
+    // Clones share the flag.
+    assert!(catalog.clone().is_read_only());
+
+    // Reads still work.
+    {
+        let got = catalog.get_table(tenant, "default", "t1").await?;
+        assert_eq!(t1.get_id(), got.get_id());
+
+        let tables = catalog.list_tables(tenant, "default").await?;
+        assert!(tables.iter().any(|t| t.name() == "t1"));
+
+        catalog.get_database(tenant, "default").await?;
+    }
+
+    // Mutations are rejected.
+    {
+        let err = catalog.create_table(create_req("t2")).await.unwrap_err();
+        assert_eq!(ErrorCode::READ_ONLY, err.code());
+
+        let err = catalog
+            .rename_table(RenameTableReq {
+                if_exists: false,
+                name_ident: TableNameIdent {
+                    tenant: tenant.to_string(),
+                    db_name: "default".to_string(),
+                    table_name: "t1".to_string(),
+                },
+                new_db_name: "default".to_string(),
+                new_table_name: "t3".to_string(),
+            })
+            .await
+            .unwrap_err();
+        assert_eq!(ErrorCode::READ_ONLY, err.code());
+
+        let err = catalog
+            .drop_table_by_id(DropTableByIdReq {
+                if_exists: false,
+                tenant: tenant.to_string(),
+                table_name: "t1".to_string(),
+                tb_id: t1.get_id(),
+                db_id: db.get_db_info().ident.db_id,
+            })
+            .await
+            .unwrap_err();
+        assert_eq!(ErrorCode::READ_ONLY, err.code());
+
+        let err = catalog
+            .create_database(CreateDatabaseReq {
+                if_not_exists: false,
+                name_ident: DatabaseNameIdent {
+                    tenant: tenant.to_string(),
+                    db_name: "db1".to_string(),
+                },
+                meta: DatabaseMeta {
+                    engine: "".to_string(),
+                    ..DatabaseMeta::default()
+                },
+            })
+            .await
+            .unwrap_err();
+        assert_eq!(ErrorCode::READ_ONLY, err.code());
+
+        // Nothing has been changed.
+        assert!(catalog.exists_table(tenant, "default", "t1").await?);
+        assert!(!catalog.exists_table(tenant, "default", "t2").await?);
+    }
+
+    catalog.set_read_only(false);
+    catalog.create_table(create_req("t2")).await?;
+    assert!(catalog.exists_table(tenant, "default", "t2").await?);
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_catalogs_plan_drop_table() -> Result<()> {
+    let tenant = "test";
+    let catalog = create_catalog().await?;
+
+    catalog
+        .create_table(CreateTableReq {
+            if_not_exists: false,
+            table_id: None,
+            name_ident: TableNameIdent {
+                tenant: tenant.to_string(),
+                db_name: "default".to_string(),
+                table_name: "t1".to_string(),
+            },
+            table_meta: TableMeta {
+                schema: Arc::new(TableSchema::new(vec![TableField::new(
+                    "number",
+                    TableDataType::Number(NumberDataType::UInt64),
+                )])),
+                engine: "MEMORY".to_string(),
+                ..TableMeta::default()
+            },
+        })
+        .await?;
+
+    let db = catalog.get_database(tenant, "default").await?;
+    let t1_id = catalog.get_table(tenant, "default", "t1").await?.get_id();
+    let drop_t1 = |if_exists: bool| DropTableByIdReq {
+        if_exists,
+        tenant: tenant.to_string(),
+        table_name: "t1".to_string(),
+        tb_id: t1_id,
+        db_id: db.get_db_info().ident.db_id,
+    };
+
+    let plan = catalog.plan_drop_table_by_id(drop_t1(false)).await?;
+    assert_eq!("MutableCatalog", plan.routed_to);
+
+    let txn = plan.txn.unwrap();
+    assert!(!txn.condition.is_empty());
+
+    let table_key = TableId { table_id: t1_id }.to_string_key();
+    let puts_table_meta = txn
+        .if_then
+        .iter()
+        .any(|op| matches!(&op.request, Some(txn_op::Request::Put(put)) if put.key == table_key));
+    assert!(puts_table_meta, "the table meta is updated with drop_on");
+
+    // Nothing is dropped by a plan.
+    assert!(catalog.exists_table(tenant, "default", "t1").await?);
+
+    catalog.drop_table_by_id(drop_t1(false)).await?;
+    assert!(!catalog.exists_table(tenant, "default", "t1").await?);
+
+    // Nothing to drop.
+    let plan = catalog.plan_drop_table_by_id(drop_t1(true)).await?;
+    assert!(plan.txn.is_none());
+
+    let res = catalog.plan_drop_table_by_id(drop_t1(false)).await;
+    assert!(res.is_err());
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_catalogs_create_table_if_not_exists() -> Result<()> {
+    let tenant = "test";
+    let catalog = create_catalog().await?;
+
+    let name_ident = TableNameIdent {
+        tenant: tenant.to_string(),
+        db_name: "default".to_string(),
+        table_name: "t1".to_string(),
+    };
+    let table_meta = TableMeta {
+        schema: Arc::new(TableSchema::new(vec![TableField::new(
+            "number",
+            TableDataType::Number(NumberDataType::UInt64),
+        )])),
+        engine: "MEMORY".to_string(),
+        ..TableMeta::default()
+    };
+
+    // Concurrent creators, exactly one of them creates the table.
+    let (a, b) = futures::future::join(
+        catalog.create_table_if_not_exists(name_ident.clone(), table_meta.clone()),
+        catalog.create_table_if_not_exists(name_ident.clone(), table_meta.clone()),
+    )
+    .await;
+    let created = [a?, b?];
+    assert_eq!(1, created.iter().filter(|x| **x).count());
+
+    let t1_id = catalog.get_table(tenant, "default", "t1").await?.get_id();
+
+    // An existing table is left untouched.
+    let created = catalog
+        .create_table_if_not_exists(name_ident.clone(), table_meta.clone())
+        .await?;
+    assert!(!created);
+    assert_eq!(
+        t1_id,
+        catalog.get_table(tenant, "default", "t1").await?.get_id()
+    );
+
+    // Another name is created.
+    let created = catalog
+        .create_table_if_not_exists(
+            TableNameIdent {
+                table_name: "t2".to_string(),
+                ..name_ident
+            },
+            table_meta,
+        )
+        .await?;
+    assert!(created);
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_catalogs_case_insensitive() -> Result<()> {
+    let tenant = "test";
+    let catalog = create_catalog().await?;
+    catalog.set_case_insensitive(true);
+
+    let create_req = |db_name: &str, table_name: &str| CreateTableReq {
+        if_not_exists: false,
+        table_id: None,
+        name_ident: TableNameIdent {
+            tenant: tenant.to_string(),
+            db_name: db_name.to_string(),
+            table_name: table_name.to_string(),
+        },
+        table_meta: TableMeta {
+            schema: Arc::new(TableSchema::new(vec![TableField::new(
+                "number",
+                TableDataType::Number(NumberDataType::UInt64),
+            )])),
+            engine: "MEMORY".to_string(),
+            ..TableMeta::default()
+        },
+    };
+
+    catalog
+        .create_table(create_req("DEFAULT", "MyTable"))
+        .await?;
+
+    // Lookup ignores case, the name is displayed as it is created.
+    {
+        let got = catalog.get_table(tenant, "default", "mytable").await?;
+        assert_eq!("MyTable", got.name());
+
+        let got = catalog.get_table(tenant, "Default", "MYTABLE").await?;
+        assert_eq!("MyTable", got.name());
+
+        assert!(catalog.exists_table(tenant, "default", "MYTABLE").await?);
+        assert!(!catalog.exists_table(tenant, "default", "other").await?);
+
+        let db = catalog.get_database(tenant, "DeFault").await?;
+        assert_eq!("default", db.name());
+
+        let res = catalog.get_table(tenant, "default", "my_table").await;
+        assert_eq!(ErrorCode::UNKNOWN_TABLE, res.unwrap_err().code());
+    }
+
+    // A name that differs only in case is rejected.
+    {
+        let res = catalog.create_table(create_req("default", "mytable")).await;
+        assert_eq!(ErrorCode::TABLE_ALREADY_EXISTS, res.unwrap_err().code());
+
+        let res = catalog
+            .create_database(CreateDatabaseReq {
+                if_not_exists: false,
+                name_ident: DatabaseNameIdent {
+                    tenant: tenant.to_string(),
+                    db_name: "Default".to_string(),
+                },
+                meta: DatabaseMeta {
+                    engine: "".to_string(),
+                    ..DatabaseMeta::default()
+                },
+            })
+            .await;
+        assert_eq!(ErrorCode::DATABASE_ALREADY_EXISTS, res.unwrap_err().code());
+    }
+
+    // Case-sensitive again.
+    {
+        catalog.set_case_insensitive(false);
+
+        let res = catalog.get_table(tenant, "default", "mytable").await;
+        assert_eq!(ErrorCode::UNKNOWN_TABLE, res.unwrap_err().code());
+
+        catalog
+            .create_table(create_req("default", "mytable"))
+            .await?;
+        let got = catalog.get_table(tenant, "default", "mytable").await?;
+        assert_eq!("mytable", got.name());
+    }
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_catalogs_list_tables_by_prefix() -> Result<()> {
+    let tenant = "test";
+    let catalog = create_catalog().await?;
+
+    let create_req = |table_name: &str| CreateTableReq {
+        if_not_exists: false,
+        table_id: None,
+        name_ident: TableNameIdent {
+            tenant: tenant.to_string(),
+            db_name: "default".to_string(),
+            table_name: table_name.to_string(),
+        },
+        table_meta: TableMeta {
+            schema: Arc::new(TableSchema::new(vec![TableField::new(
+                "number",
+                TableDataType::Number(NumberDataType::UInt64),
+            )])),
+            engine: "MEMORY".to_string(),
+            ..TableMeta::default()
+        },
+    };
+
+    for name in ["users", "user_b", "admin", "user_a", "usr"] {
+        catalog.create_table(create_req(name)).await?;
+    }
+
+    let list = |prefix: &'static str| {
+        let catalog = catalog.clone();
+        async move {
+            let tables = catalog
+                .list_tables_by_prefix(tenant, "default", prefix)
+                .await?;
+            Ok::<_, ErrorCode>(
+                tables
+                    .iter()
+                    .map(|t| t.name().to_string())
+                    .collect::<Vec<_>>(),
+            )
+        }
+    };
+
+    assert_eq!(vec!["user_a", "user_b"], list("user_").await?);
+    assert_eq!(vec!["user_a", "user_b", "users"], list("user").await?);
+    assert_eq!(vec!["user_a", "user_b", "users", "usr"], list("us").await?);
+    assert_eq!(vec!["users"], list("users").await?);
+    assert_eq!(Vec::<String>::new(), list("usera").await?);
+    assert_eq!(
+        vec!["admin", "user_a", "user_b", "users", "usr"],
+        list("").await?
+    );
+
+    // Unknown database
+    let res = catalog
+        .list_tables_by_prefix(tenant, "no_such_db", "user")
+        .await;
+    assert_eq!(ErrorCode::UNKNOWN_DATABASE, res.unwrap_err().code());
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_catalogs_refresh_statistics() -> Result<()> {
+    let tenant = "test";
+    let catalog = create_catalog().await?;
+
+    // The recorded statistics do not match the data: the table is empty.
+    let corrupted = TableStatistics {
+        number_of_rows: 100,
+        data_bytes: 1000,
+        compressed_data_bytes: 500,
+        index_data_bytes: 50,
+        number_of_segments: Some(2),
+        number_of_blocks: Some(4),
+    };
+
+    let create_table_req = CreateTableReq {
+        if_not_exists: false,
+        table_id: None,
+        name_ident: TableNameIdent {
+            tenant: tenant.to_string(),
+            db_name: "default".to_string(),
+            table_name: "t1".to_string(),
+        },
+        table_meta: TableMeta {
+            schema: Arc::new(TableSchema::new(vec![TableField::new(
+                "number",
+                TableDataType::Number(NumberDataType::UInt64),
+            )])),
+            engine: "MEMORY".to_string(),
+            statistics: corrupted,
+            ..TableMeta::default()
+        },
+    };
+    catalog.create_table(create_table_req).await?;
+
+    let refreshed = catalog.refresh_statistics(tenant, "default", "t1").await?;
+
+    let want = TableStatistics {
+        number_of_rows: 0,
+        data_bytes: 0,
+        compressed_data_bytes: 0,
+        index_data_bytes: 0,
+        number_of_segments: None,
+        number_of_blocks: Some(0),
+    };
+    assert_eq!(want, refreshed);
+
+    let table = catalog.get_table(tenant, "default", "t1").await?;
+    assert_eq!(want, table.get_table_info().meta.statistics);
+
+    // Unknown table
+    let res = catalog.refresh_statistics(tenant, "default", "t2").await;
+    assert_eq!(ErrorCode::UNKNOWN_TABLE, res.unwrap_err().code());
+
+    Ok(())
+}
+
+#[derive(Debug, Default)]
+struct MemAuditSink {
+    events: Mutex<Vec<CatalogAuditEvent>>,
+}
+
+#[async_trait::async_trait]
+impl AuditSink for MemAuditSink {
+    async fn record(&self, event: CatalogAuditEvent) {
+        self.events.lock().unwrap().push(event);
+    }
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_catalogs_audit_sink() -> Result<()> {
+    let tenant = "test";
+    let sink = Arc::new(MemAuditSink::default());
+    let catalog = create_catalog().await?.with_audit_sink(sink.clone());
+
+    let req = CreateTableReq {
+        if_not_exists: false,
+        table_id: None,
+        name_ident: TableNameIdent {
+            tenant: tenant.to_string(),
+            db_name: "default".to_string(),
+            table_name: "t1".to_string(),
+        },
+        table_meta: TableMeta {
+            schema: Arc::new(TableSchema::new(vec![TableField::new(
+                "number",
+                TableDataType::Number(NumberDataType::UInt64),
+            )])),
+            engine: "MEMORY".to_string(),
+            ..TableMeta::default()
+        },
+    };
+
+    let reply = catalog.create_table(req.clone()).await?;
+
+    // A failed DDL is not recorded.
+    let res = catalog.create_table(req).await;
+    assert!(res.is_err());
+
+    let events = sink.events.lock().unwrap().clone();
+    assert_eq!(1, events.len());
+
+    let ev = &events[0];
+    assert_eq!(CatalogAuditOp::CreateTable, ev.op);
+    assert_eq!(tenant, ev.tenant);
+    assert_eq!(Some("default".to_string()), ev.db_name);
+    assert_eq!(Some("t1".to_string()), ev.table_name);
+    assert_eq!(None, ev.principal);
+    assert_eq!(Some(reply.table_id), ev.object_id);
+
+    // The principal is the one of the scope the DDL is applied in.
+    let create_db = CreateDatabaseReq {
+        if_not_exists: false,
+        name_ident: DatabaseNameIdent {
+            tenant: tenant.to_string(),
+            db_name: "db_audit".to_string(),
+        },
+        meta: DatabaseMeta {
+            engine: "".to_string(),
+            ..DatabaseMeta::default()
+        },
+    };
+    let reply =
+        with_audit_principal("'u1'@'%'".to_string(), catalog.create_database(create_db)).await?;
+
+    let drop_db = DropDatabaseReq {
+        if_exists: true,
+        name_ident: DatabaseNameIdent {
+            tenant: tenant.to_string(),
+            db_name: "db_audit".to_string(),
+        },
+    };
+    catalog.drop_database(drop_db.clone()).await?;
+
+    // Dropping an absent database with `if_exists` drops nothing and is not recorded.
+    catalog.drop_database(drop_db).await?;
+
+    let events = sink.events.lock().unwrap().clone();
+    assert_eq!(3, events.len());
+
+    let ev = &events[1];
+    assert_eq!(CatalogAuditOp::CreateDatabase, ev.op);
+    assert_eq!(Some("'u1'@'%'".to_string()), ev.principal);
+
+    let ev = &events[2];
+    assert_eq!(CatalogAuditOp::DropDatabase, ev.op);
+    assert_eq!(None, ev.principal);
+    assert_eq!(Some(reply.db_id), ev.object_id);
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_catalogs_database_quota() -> Result<()> {
+    let tenant = "test";
+    let db_name = "db_quota";
+    let catalog = create_catalog().await?;
+
+    catalog
+        .create_database(CreateDatabaseReq {
+            if_not_exists: false,
+            name_ident: DatabaseNameIdent {
+                tenant: tenant.to_string(),
+                db_name: db_name.to_string(),
+            },
+            meta: DatabaseMeta {
+                engine: "".to_string(),
+                ..DatabaseMeta::default()
+            },
+        })
+        .await?;
+
+    let set_quota = |max_tables: Option<u64>| SetDatabaseQuotaReq {
+        name_ident: DatabaseNameIdent {
+            tenant: tenant.to_string(),
+            db_name: db_name.to_string(),
+        },
+        max_tables,
+    };
+
+    let create_req = |table_name: String| CreateTableReq {
+        if_not_exists: false,
+        table_id: None,
+        name_ident: TableNameIdent {
+            tenant: tenant.to_string(),
+            db_name: db_name.to_string(),
+            table_name,
+        },
+        table_meta: TableMeta {
+            schema: Arc::new(TableSchema::new(vec![TableField::new(
+                "number",
+                TableDataType::Number(NumberDataType::UInt64),
+            )])),
+            engine: "MEMORY".to_string(),
+            ..TableMeta::default()
+        },
+    };
+
+    catalog.set_database_quota(set_quota(Some(3))).await?;
+
+    let db = catalog.get_database(tenant, db_name).await?;
+    assert_eq!(Some(3), db.get_db_info().meta.max_tables);
+
+    // Concurrent creations can not exceed the quota.
+    let futs = (0..5).map(|i| {
+        let catalog = catalog.clone();
+        let req = create_req(format!("t{}", i));
+        async move { catalog.create_table(req).await }
+    });
+    let results = futures::future::join_all(futs).await;
+
+    let created = results.iter().filter(|r| r.is_ok()).count();
+    assert_eq!(3, created);
+
+    for res in results.iter().filter_map(|r| r.as_ref().err()) {
+        assert_eq!(ErrorCode::DATABASE_QUOTA_EXCEEDED, res.code());
+    }
+
+    let tables = catalog.list_tables(tenant, db_name).await?;
+    assert_eq!(3, tables.len());
+
+    // Removing the quota allows creating more tables.
+    catalog.set_database_quota(set_quota(None)).await?;
+    catalog.create_table(create_req("t5".to_string())).await?;
+
+    // Unknown database
+    let res = catalog
+        .set_database_quota(SetDatabaseQuotaReq {
+            name_ident: DatabaseNameIdent {
+                tenant: tenant.to_string(),
+                db_name: "no_such_db".to_string(),
+            },
+            max_tables: Some(1),
+        })
+        .await;
+    assert_eq!(ErrorCode::UNKNOWN_DATABASE, res.unwrap_err().code());
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_catalogs_copy_table() -> Result<()> {
+    let tenant = "test";
+    let catalog = create_catalog().await?;
+
+    let statistics = TableStatistics {
+        number_of_rows: 10,
+        data_bytes: 100,
+        ..TableStatistics::default()
+    };
+
+    let req = CreateTableReq {
+        if_not_exists: false,
+        table_id: None,
+        name_ident: TableNameIdent {
+            tenant: tenant.to_string(),
+            db_name: "default".to_string(),
+            table_name: "src".to_string(),
+        },
+        table_meta: TableMeta {
+            schema: Arc::new(TableSchema::new(vec![
+                TableField::new("a", TableDataType::Number(NumberDataType::UInt64)),
+                TableField::new("b", TableDataType::String),
+            ])),
+            engine: "MEMORY".to_string(),
+            options: maplit::btreemap! {
+                OPT_KEY_SNAPSHOT_LOCATION.to_string() => "_ss/snapshot".to_string(),
+                "comment".to_string() => "src table".to_string(),
+            },
+            statistics: statistics.clone(),
+            ..TableMeta::default()
+        },
+    };
+    catalog.create_table(req).await?;
+
+    let src = catalog.get_table(tenant, "default", "src").await?;
+    let src_meta = &src.get_table_info().meta;
+
+    // Without data
+    {
+        catalog
+            .copy_table(tenant, "default", "src", "dst_empty", false)
+            .await?;
+
+        let dst = catalog.get_table(tenant, "default", "dst_empty").await?;
+        let dst_meta = &dst.get_table_info().meta;
+
+        assert_ne!(src.get_id(), dst.get_id());
+        assert_eq!(src_meta.schema, dst_meta.schema);
+        assert_eq!(src_meta.engine, dst_meta.engine);
+        assert_eq!(
+            Some(&"src table".to_string()),
+            dst_meta.options.get("comment")
+        );
+        assert_eq!(None, dst_meta.options.get(OPT_KEY_SNAPSHOT_LOCATION));
+        assert_eq!(TableStatistics::default(), dst_meta.statistics);
+    }
+
+    // With data
+    {
+        catalog
+            .copy_table(tenant, "default", "src", "dst_data", true)
+            .await?;
+
+        let dst = catalog.get_table(tenant, "default", "dst_data").await?;
+        let dst_meta = &dst.get_table_info().meta;
+
+        assert_ne!(src.get_id(), dst.get_id());
+        assert_eq!(src_meta.schema, dst_meta.schema);
+        assert_eq!(
+            Some(&"_ss/snapshot".to_string()),
+            dst_meta.options.get(OPT_KEY_SNAPSHOT_LOCATION)
+        );
+        assert_eq!(statistics, dst_meta.statistics);
+    }
+
+    // The destination exists
+    {
+        let res = catalog
+            .copy_table(tenant, "default", "src", "dst_data", false)
+            .await;
+        assert_eq!(ErrorCode::TABLE_ALREADY_EXISTS, res.unwrap_err().code());
+    }
+
+    // The source does not exist
+    {
+        let res = catalog
+            .copy_table(tenant, "default", "no_such_table", "dst2", false)
+            .await;
+        assert_eq!(ErrorCode::UNKNOWN_TABLE, res.unwrap_err().code());
+
+        let res = catalog.get_table(tenant, "default", "dst2").await;
+        assert!(res.is_err());
+    }
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_catalogs_describe_table() -> Result<()> {
+    let tenant = "test";
+    let catalog = create_catalog().await?;
+
+    let req = CreateTableReq {
+        if_not_exists: false,
+        table_id: None,
+        name_ident: TableNameIdent {
+            tenant: tenant.to_string(),
+            db_name: "default".to_string(),
+            table_name: "t_desc".to_string(),
+        },
+        table_meta: TableMeta {
+            schema: Arc::new(TableSchema::new(vec![
+                TableField::new("id", TableDataType::Number(NumberDataType::Int64)),
+                TableField::new(
+                    "name",
+                    TableDataType::Nullable(Box::new(TableDataType::String)),
+                )
+                .with_default_expr(Some("'anonymous'".to_string())),
+            ])),
+            engine: "MEMORY".to_string(),
+            comment: "people".to_string(),
+            field_comments: vec!["the id".to_string(), "".to_string()],
+            ..TableMeta::default()
+        },
+    };
+    catalog.create_table(req).await?;
+
+    let table = catalog.get_table(tenant, "default", "t_desc").await?;
+
+    let desc = catalog.describe_table(tenant, "default", "t_desc").await?;
+    assert_eq!(
+        TableDescription {
+            format_version: TableDescription::FORMAT_VERSION,
+            table_id: table.get_id(),
+            db_name: "default".to_string(),
+            table_name: "t_desc".to_string(),
+            engine: "MEMORY".to_string(),
+            schema_version: 0,
+            comment: "people".to_string(),
+            columns: vec![
+                ColumnDescription {
+                    name: "id".to_string(),
+                    data_type: "BIGINT".to_string(),
+                    nullable: false,
+                    default_expr: None,
+                    computed_expr: None,
+                    comment: "the id".to_string(),
+                },
+                ColumnDescription {
+                    name: "name".to_string(),
+                    data_type: "VARCHAR".to_string(),
+                    nullable: true,
+                    default_expr: Some("'anonymous'".to_string()),
+                    computed_expr: None,
+                    comment: "".to_string(),
+                },
+            ],
+        },
+        desc
+    );
+
+    // Round trip through serde
+    {
+        let json = serde_json::to_string(&desc)?;
+        let got: TableDescription = serde_json::from_str(&json)?;
+        assert_eq!(desc, got);
+    }
+
+    // Unknown table
+    {
+        let res = catalog
+            .describe_table(tenant, "default", "no_such_table")
+            .await;
+        assert_eq!(ErrorCode::UNKNOWN_TABLE, res.unwrap_err().code());
+    }
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_catalogs_column_default_expr() -> Result<()> {
+    // The default expression of a column is persisted with the schema in the table meta,
+    // by both create_table() and alter_table_add_column(), and read back unchanged.
+
+    let tenant = "test";
+    let catalog = create_catalog().await?;
+
+    let created_default = "now()".to_string();
+    let added_default = "concat('id-', to_string((1 + 2) * 3))".to_string();
+
+    let req = CreateTableReq {
+        if_not_exists: false,
+        table_id: None,
+        name_ident: TableNameIdent {
+            tenant: tenant.to_string(),
+            db_name: "default".to_string(),
+            table_name: "t_default_expr".to_string(),
+        },
+        table_meta: TableMeta {
+            schema: Arc::new(TableSchema::new(vec![
+                TableField::new("id", TableDataType::Number(NumberDataType::Int64)),
+                TableField::new("created_at", TableDataType::Timestamp)
+                    .with_default_expr(Some(created_default.clone())),
+            ])),
+            engine: "MEMORY".to_string(),
+            ..TableMeta::default()
+        },
+    };
+    catalog.create_table(req).await?;
+
+    catalog
+        .alter_table_add_column(
+            tenant,
+            "default",
+            "t_default_expr",
+            TableField::new("label", TableDataType::String)
+                .with_default_expr(Some(added_default.clone())),
+            None,
+        )
+        .await?;
+
+    let table = catalog
+        .get_table(tenant, "default", "t_default_expr")
+        .await?;
+    let schema = table.schema();
+    assert_eq!(None, schema.field_with_name("id")?.default_expr());
+    assert_eq!(
+        Some(&created_default),
+        schema.field_with_name("created_at")?.default_expr()
+    );
+    assert_eq!(
+        Some(&added_default),
+        schema.field_with_name("label")?.default_expr()
+    );
+
+    let desc = catalog
+        .describe_table(tenant, "default", "t_default_expr")
+        .await?;
+    let defaults = desc
+        .columns
+        .iter()
+        .map(|c| (c.name.as_str(), c.default_expr.clone()))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        vec![
+            ("id", None),
+            ("created_at", Some(created_default)),
+            ("label", Some(added_default)),
+        ],
+        defaults
+    );
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_catalogs_create_table_validate_schema() -> Result<()> {
+    let tenant = "test";
+    let catalog = create_catalog().await?;
+
+    let req = |table_name: &str, fields: Vec<TableField>| CreateTableReq {
+        if_not_exists: false,
+        table_id: None,
+        name_ident: TableNameIdent {
+            tenant: tenant.to_string(),
+            db_name: "default".to_string(),
+            table_name: table_name.to_string(),
+        },
+        table_meta: TableMeta {
+            schema: Arc::new(TableSchema::new(fields)),
+            engine: "MEMORY".to_string(),
+            ..TableMeta::default()
+        },
+    };
+
+    let uint64 = TableDataType::Number(NumberDataType::UInt64);
+
+    // No column
+    {
+        let res = catalog.create_table(req("t_empty", vec![])).await;
+        assert_eq!(ErrorCode::BAD_ARGUMENTS, res.unwrap_err().code());
+    }
+
+    // Duplicated column name
+    {
+        let res = catalog
+            .create_table(req("t_dup", vec![
+                TableField::new("a", uint64.clone()),
+                TableField::new("a", TableDataType::String),
+            ]))
+            .await;
+        let err = res.unwrap_err();
+        assert_eq!(ErrorCode::BAD_ARGUMENTS, err.code());
+        assert_eq!("Duplicated column name: a", err.message());
+    }
+
+    // Invalid type
+    {
+        let res = catalog
+            .create_table(req("t_bad_type", vec![
+                TableField::new("a", uint64.clone()),
+                TableField::new("m", TableDataType::Map(Box::new(uint64.clone()))),
+            ]))
+            .await;
+        let err = res.unwrap_err();
+        assert_eq!(ErrorCode::ILLEGAL_DATA_TYPE, err.code());
+        assert!(err.message().contains("column m"));
+    }
+
+    // Nothing is persisted for a rejected schema
+    for name in ["t_empty", "t_dup", "t_bad_type"] {
+        let res = catalog.get_table(tenant, "default", name).await;
+        assert_eq!(ErrorCode::UNKNOWN_TABLE, res.unwrap_err().code());
+    }
+
+    // A valid schema
+    {
+        catalog
+            .create_table(req("t_ok", vec![
+                TableField::new("a", uint64.clone()),
+                TableField::new(
+                    "b",
+                    TableDataType::Nullable(Box::new(TableDataType::String)),
+                ),
+            ]))
+            .await?;
+        catalog.get_table(tenant, "default", "t_ok").await?;
+    }
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_catalogs_subscribe() -> Result<()> {
+    let tenant = "test";
+    let catalog = create_catalog().await?;
+    let mut rx = catalog.subscribe();
+
+    let req = CreateTableReq {
+        if_not_exists: false,
+        table_id: None,
+        name_ident: TableNameIdent {
+            tenant: tenant.to_string(),
+            db_name: "default".to_string(),
+            table_name: "t_sub".to_string(),
+        },
+        table_meta: TableMeta {
+            schema: Arc::new(TableSchema::new(vec![TableField::new(
+                "a",
+                TableDataType::Number(NumberDataType::UInt64),
+            )])),
+            engine: "MEMORY".to_string(),
+            ..TableMeta::default()
+        },
+    };
+    let reply = catalog.create_table(req).await?;
+
+    let event = rx.try_recv().expect("an event for create_table");
+    assert_eq!(
+        CatalogNotification::Event(CatalogEvent {
+            op: CatalogAuditOp::CreateTable,
+            tenant: Some(tenant.to_string()),
+            db_name: Some("default".to_string()),
+            table_name: Some("t_sub".to_string()),
+            object_id: Some(reply.table_id),
+            seq: None,
+        }),
+        event
+    );
+
+    // A failed DDL emits no event
+    {
+        let req = CreateTableReq {
+            if_not_exists: false,
+            table_id: None,
+            name_ident: TableNameIdent {
+                tenant: tenant.to_string(),
+                db_name: "default".to_string(),
+                table_name: "t_sub".to_string(),
+            },
+            table_meta: TableMeta {
+                schema: Arc::new(TableSchema::new(vec![TableField::new(
+                    "a",
+                    TableDataType::Number(NumberDataType::UInt64),
+                )])),
+                engine: "MEMORY".to_string(),
+                ..TableMeta::default()
+            },
+        };
+        let res = catalog.create_table(req).await;
+        assert!(res.is_err());
+        assert!(rx.try_recv().is_err());
+    }
+
+    // A subscriber that falls behind loses events, but a DDL is never blocked
+    {
+        let mut lagging = catalog.subscribe();
+
+        let db_req = |i: usize| CreateDatabaseReq {
+            if_not_exists: false,
+            name_ident: DatabaseNameIdent {
+                tenant: tenant.to_string(),
+                db_name: format!("db_sub_{}", i),
+            },
+            meta: DatabaseMeta {
+                engine: "".to_string(),
+                ..Default::default()
+            },
+        };
+
+        for i in 0..1100 {
+            catalog.create_database(db_req(i)).await?;
+        }
+
+        assert!(matches!(
+            lagging.try_recv(),
+            Err(broadcast::error::TryRecvError::Lagged(_))
+        ));
+    }
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_catalogs_check_consistency() -> Result<()> {
+    let tenant = "test";
+    let catalog = create_catalog().await?;
+
+    // The catalog and this meta store share the same embedded meta-service.
+    let conf = databend_query::test_kits::ConfigBuilder::create().config();
+    let meta = MetaStoreProvider::new(conf.meta.to_meta_grpc_client_conf())
+        .create_meta_store()
+        .await?;
+
+    catalog
+        .create_table(CreateTableReq {
+            if_not_exists: false,
+            table_id: None,
+            name_ident: TableNameIdent {
+                tenant: tenant.to_string(),
+                db_name: "default".to_string(),
+                table_name: "t_cc".to_string(),
+            },
+            table_meta: TableMeta {
+                schema: Arc::new(TableSchema::new(vec![TableField::new(
+                    "a",
+                    TableDataType::Number(NumberDataType::UInt64),
+                )])),
+                engine: "MEMORY".to_string(),
+                ..TableMeta::default()
+            },
+        })
+        .await?;
+
+    let db_id = catalog
+        .get_database(tenant, "default")
+        .await?
+        .get_db_info()
+        .ident
+        .db_id;
+    let t_cc_id = catalog.get_table(tenant, "default", "t_cc").await?.get_id();
+    let t_cc_name = DBIdTableName {
+        db_id,
+        table_name: "t_cc".to_string(),
+    };
+
+    // Ids that are never allocated in a test.
+    let absent_id_1 = 1 << 60;
+    let absent_id_2 = (1 << 60) + 1;
+
+    let dangling_name = DBIdTableName {
+        db_id,
+        table_name: "t_cc_dangling".to_string(),
+    };
+    let dangling_index_name = DBIdTableName {
+        db_id,
+        table_name: "t_cc_gone".to_string(),
+    };
+
+    // Break the records
+    {
+        meta.upsert_kv(UpsertKVReq::delete(
+            TableIdToName { table_id: t_cc_id }.to_string_key(),
+        ))
+        .await?;
+
+        meta.upsert_kv(UpsertKVReq::insert(
+            dangling_name.to_string_key(),
+            &serialize_u64(absent_id_1).map_err(MetaError::from)?,
+        ))
+        .await?;
+
+        meta.upsert_kv(UpsertKVReq::insert(
+            TableIdToName {
+                table_id: absent_id_2,
+            }
+            .to_string_key(),
+            &serialize_struct(&dangling_index_name).map_err(MetaError::from)?,
+        ))
+        .await?;
+    }
+
+    let injected = vec![
+        Inconsistency::MissingTableIdToName {
+            table_id: t_cc_id,
+            name: t_cc_name,
+        },
+        Inconsistency::DanglingTableName {
+            name: dangling_name,
+            table_id: absent_id_1,
+        },
+        Inconsistency::DanglingTableIdToName {
+            table_id: absent_id_2,
+            name: dangling_index_name,
+        },
+    ];
+
+    // Other tests share the meta-service, only the injected inconsistencies are checked.
+    let find = |report: &ConsistencyReport, inc: &Inconsistency| {
+        report
+            .issues
+            .iter()
+            .find(|x| &x.inconsistency == inc)
+            .cloned()
+    };
+
+    // Check only
+    {
+        let report = catalog.check_consistency(false).await?;
+        for inc in &injected {
+            let issue = find(&report, inc);
+            assert!(issue.is_some(), "found: {}", inc);
+            assert!(!issue.unwrap().repaired);
+        }
+
+        // Not repaired
+        let report = catalog.check_consistency(false).await?;
+        for inc in &injected {
+            assert!(find(&report, inc).is_some(), "still there: {}", inc);
+        }
+    }
+
+    // Repair
+    {
+        let report = catalog.check_consistency(true).await?;
+        for inc in &injected {
+            let issue = find(&report, inc);
+            assert!(issue.is_some(), "found: {}", inc);
+            assert!(issue.unwrap().repaired, "repaired: {}", inc);
+        }
+
+        let report = catalog.check_consistency(false).await?;
+        for inc in &injected {
+            assert!(find(&report, inc).is_none(), "gone: {}", inc);
+        }
+    }
+
+    // The table is resolvable by id again
+    let name = catalog.get_table_name_by_id(t_cc_id).await?;
+    assert_eq!("t_cc", name);
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_catalogs_table_count() -> Result<()> {
+    let tenant = "test";
+    let catalog = create_catalog().await?;
+
+    let create_db = |db_name: &str| CreateDatabaseReq {
+        if_not_exists: false,
+        name_ident: DatabaseNameIdent {
+            tenant: tenant.to_string(),
+            db_name: db_name.to_string(),
+        },
+        meta: DatabaseMeta {
+            engine: "".to_string(),
+            ..Default::default()
+        },
+    };
+    let create_table = |db_name: &str, table_name: &str| CreateTableReq {
+        if_not_exists: false,
+        table_id: None,
+        name_ident: TableNameIdent {
+            tenant: tenant.to_string(),
+            db_name: db_name.to_string(),
+            table_name: table_name.to_string(),
+        },
+        table_meta: TableMeta {
+            schema: Arc::new(TableSchema::new(vec![TableField::new(
+                "number",
+                TableDataType::Number(NumberDataType::UInt64),
+            )])),
+            engine: "MEMORY".to_string(),
+            ..TableMeta::default()
+        },
+    };
+
+    catalog.create_database(create_db("db_count_1")).await?;
+    catalog.create_database(create_db("db_count_2")).await?;
+
+    let db1 = catalog.get_database(tenant, "db_count_1").await?;
+    let db2 = catalog.get_database(tenant, "db_count_2").await?;
+    assert_eq!(0, db1.table_count().await?);
+
+    // Create
+    for table_name in ["t1", "t2", "t3"] {
+        catalog
+            .create_table(create_table("db_count_1", table_name))
+            .await?;
+    }
+    assert_eq!(3, db1.table_count().await?);
+    assert_eq!(3, db1.table_count().await?, "served from cache");
+
+    // Drop
+    let t1 = catalog.get_table(tenant, "db_count_1", "t1").await?;
+    let drop_t1 = DropTableByIdReq {
+        if_exists: false,
+        tenant: tenant.to_string(),
+        table_name: "t1".to_string(),
+        tb_id: t1.get_id(),
+        db_id: db1.get_db_info().ident.db_id,
+    };
+    catalog.drop_table_by_id(drop_t1).await?;
+    assert_eq!(2, db1.table_count().await?);
+
+    // Undrop
+    catalog
+        .undrop_table(UndropTableReq {
+            name_ident: TableNameIdent {
+                tenant: tenant.to_string(),
+                db_name: "db_count_1".to_string(),
+                table_name: "t1".to_string(),
+            },
+        })
+        .await?;
+    assert_eq!(3, db1.table_count().await?);
+
+    // Rename to another database
+    assert_eq!(0, db2.table_count().await?);
+    catalog
+        .rename_table(RenameTableReq {
+            if_exists: false,
+            name_ident: TableNameIdent {
+                tenant: tenant.to_string(),
+                db_name: "db_count_1".to_string(),
+                table_name: "t2".to_string(),
+            },
+            new_db_name: "db_count_2".to_string(),
+            new_table_name: "t2".to_string(),
+        })
+        .await?;
+    assert_eq!(2, db1.table_count().await?);
+    assert_eq!(1, db2.table_count().await?);
+
+    // A DDL issued by another catalog, e.g., on another query node, is seen too.
+    {
+        let other = create_catalog().await?;
+        other.create_table(create_table("db_count_2", "t4")).await?;
+        assert_eq!(2, db2.table_count().await?);
+    }
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_catalogs_reserve_table_ids() -> Result<()> {
+    let tenant = "test";
+    let catalog = create_catalog().await?;
+
+    catalog
+        .create_database(CreateDatabaseReq {
+            if_not_exists: false,
+            name_ident: DatabaseNameIdent {
+                tenant: tenant.to_string(),
+                db_name: "db_reserve_ids".to_string(),
+            },
+            meta: DatabaseMeta {
+                engine: "".to_string(),
+                ..Default::default()
+            },
+        })
+        .await?;
+
+    let create_table = |table_name: &str| CreateTableReq {
+        if_not_exists: false,
+        table_id: None,
+        name_ident: TableNameIdent {
+            tenant: tenant.to_string(),
+            db_name: "db_reserve_ids".to_string(),
+            table_name: table_name.to_string(),
+        },
+        table_meta: TableMeta {
+            schema: Arc::new(TableSchema::new(vec![TableField::new(
+                "number",
+                TableDataType::Number(NumberDataType::UInt64),
+            )])),
+            engine: "MEMORY".to_string(),
+            ..TableMeta::default()
+        },
+    };
+
+    let reserved = catalog.reserve_table_ids(20).await?;
+    assert_eq!(20, reserved.end - reserved.start);
+
+    // 20 tables with the reserved ids, given by the caller.
+    let mut ids = vec![];
+    for id in reserved.clone() {
+        let mut req = create_table(&format!("t{}", id));
+        req.table_id = Some(id);
+        let reply = catalog.create_table(req).await?;
+        assert!(reply.new_table);
+        assert_eq!(id, reply.table_id);
+        ids.push(id);
+    }
+
+    // 10 tables without a table id, with a block of 20 ids reserved when the first one is created.
+    // A failed creation does not use up an id, and the ids reserved for the caller are not taken.
+    for i in 0..10 {
+        let name = format!("t_auto{}", i);
+        let reply = catalog.create_table(create_table(&name)).await?;
+        assert!(reply.new_table);
+
+        let res = catalog.create_table(create_table(&name)).await;
+        assert_eq!(ErrorCode::TABLE_ALREADY_EXISTS, res.unwrap_err().code());
+
+        let table = catalog.get_table(tenant, "db_reserve_ids", &name).await?;
+        assert_eq!(reply.table_id, table.get_id());
+        ids.push(table.get_id());
+    }
+
+    // The block is contiguous, after the ids reserved for the caller.
+    assert!(ids[20] >= reserved.end);
+    assert_eq!(
+        (ids[20]..ids[20] + 10).collect::<Vec<_>>(),
+        ids[20..].to_vec()
+    );
+
+    // Another catalog does not take the ids reserved by this one.
+    let other = create_catalog().await?;
+    let reply = other.create_table(create_table("t_other")).await?;
+    assert!(!ids.contains(&reply.table_id));
+
+    // A table can not be created with an id that is in use.
+    let mut req = create_table("t_reused");
+    req.table_id = Some(ids[0]);
+    let res = catalog.create_table(req).await;
+    assert_eq!(ErrorCode::TABLE_ID_IN_USE, res.unwrap_err().code());
+    assert!(
+        !catalog
+            .exists_table(tenant, "db_reserve_ids", "t_reused")
+            .await?
+    );
+
+    // A table can not be created with an id that is not issued yet,
+    // otherwise another table may be created with it later.
+    let mut req = create_table("t_not_issued");
+    req.table_id = Some(reply.table_id + 1_000_000);
+    let res = catalog.create_table(req).await;
+    assert_eq!(ErrorCode::UNKNOWN_TABLE_ID, res.unwrap_err().code());
+
+    // A smaller reservation makes the following blocks smaller, the free ids are taken first:
+    // 10 left in the block, then blocks of 2.
+    let reserved = catalog.reserve_table_ids(2).await?;
+    let mut ids = vec![];
+    for i in 0..15 {
+        let reply = catalog
+            .create_table(create_table(&format!("t_small{}", i)))
+            .await?;
+        ids.push(reply.table_id);
+    }
+    assert!(reserved.clone().all(|id| !ids.contains(&id)));
+    assert_eq!(
+        (ids[0]..ids[0] + 10).collect::<Vec<_>>(),
+        ids[..10].to_vec()
+    );
+    assert_eq!(ids[10] + 1, ids[11]);
+    assert_ne!(ids[11] + 1, ids[12]);
+    assert_eq!(ids[12] + 1, ids[13]);
+
+    for id in reserved {
+        let mut req = create_table(&format!("t_small_reserved{}", id));
+        req.table_id = Some(id);
+        assert_eq!(id, catalog.create_table(req).await?.table_id);
+    }
+
+    // Stop reserving, the free id is still taken.
+    assert_eq!(0..0, catalog.reserve_table_ids(0).await?);
+    let reply = catalog.create_table(create_table("t_stop")).await?;
+    assert_eq!(ids[14] + 1, reply.table_id);
+
+    Ok(())
+}
@@ -12,5 +12,9 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+mod cached_catalog;
+mod catalog_manager;
 mod database_catalog;
 mod immutable_catalogs;
+mod name_index;
+mod table_memory_meta;
@@ -0,0 +1,61 @@
+// Copyright 2021 Datafuse Labs
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::time::Duration;
+
+use databend_query::catalogs::default::name_index::NameIndex;
+use databend_query::catalogs::default::name_index::NameIndexKey;
+
+#[test]
+fn test_name_index_lookup_and_invalidate() {
+    let index = NameIndex::new(Duration::from_secs(3600));
+    let dbs = NameIndexKey::dbs("t1");
+    let tables = NameIndexKey::tables("t1", "Db1");
+
+    // Not indexed yet.
+    assert_eq!(None, index.lookup(&dbs, "db1"));
+
+    index.insert(dbs.clone(), ["Db1", "db2"]);
+    index.insert(tables.clone(), ["MyTable"]);
+
+    assert_eq!(Some(Some("Db1".to_string())), index.lookup(&dbs, "DB1"));
+    assert_eq!(Some(None), index.lookup(&dbs, "db3"));
+    assert_eq!(
+        Some(Some("MyTable".to_string())),
+        index.lookup(&tables, "mytable")
+    );
+
+    // Another tenant is not affected.
+    index.insert(NameIndexKey::dbs("t2"), ["Db1"]);
+    index.invalidate("t1");
+
+    assert_eq!(None, index.lookup(&dbs, "db1"));
+    assert_eq!(None, index.lookup(&tables, "mytable"));
+    assert_eq!(
+        Some(Some("Db1".to_string())),
+        index.lookup(&NameIndexKey::dbs("t2"), "db1")
+    );
+}
+
+#[test]
+fn test_name_index_expire() {
+    let index = NameIndex::new(Duration::from_millis(50));
+    let dbs = NameIndexKey::dbs("t1");
+
+    index.insert(dbs.clone(), ["Db1"]);
+    assert_eq!(Some(Some("Db1".to_string())), index.lookup(&dbs, "db1"));
+
+    std::thread::sleep(Duration::from_millis(100));
+    assert_eq!(None, index.lookup(&dbs, "db1"));
+}
@@ -0,0 +1,103 @@
+// Copyright 2021 Datafuse Labs
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use databend_common_catalog::table::Table;
+use databend_common_exception::ErrorCode;
+use databend_common_exception::Result;
+use databend_query::catalogs::InMemoryMetas;
+use databend_query::catalogs::MetaCacheStats;
+use databend_query::catalogs::MetaSnapshot;
+use databend_query::catalogs::SYS_DB_ID_BEGIN;
+use databend_query::catalogs::SYS_TBL_ID_BEGIN;
+use databend_query::databases::SystemDatabase;
+use databend_query::test_kits::*;
+
+#[test]
+fn test_in_memory_metas_snapshot_restore() -> Result<()> {
+    let conf = ConfigBuilder::create().build();
+
+    let mut sys_db_meta = InMemoryMetas::create(SYS_DB_ID_BEGIN, SYS_TBL_ID_BEGIN);
+    sys_db_meta.init_db("system");
+    let _ = SystemDatabase::create(&mut sys_db_meta, &conf);
+
+    let snap = sys_db_meta.snapshot();
+    assert!(!snap.db_tables["system"].is_empty());
+
+    // The snapshot can be written as JSON and loaded back.
+    let json = serde_json::to_string(&snap)?;
+    let loaded: MetaSnapshot = serde_json::from_str(&json)?;
+    assert_eq!(snap, loaded);
+
+    // Restore into an empty instance, reusing the tables of the original one.
+    {
+        let mut restored = InMemoryMetas::create(0, 0);
+        restored.restore(loaded, |info| {
+            sys_db_meta
+                .get_by_id(&info.ident.table_id)
+                .ok_or_else(|| ErrorCode::UnknownTable(info.name.clone()))
+        })?;
+
+        assert_eq!(snap, restored.snapshot());
+        assert!(restored.get_by_name("system", "clusters").is_ok());
+        assert_eq!(sys_db_meta.next_table_id(), restored.next_table_id());
+    }
+
+    // A failed restore changes nothing.
+    {
+        let mut restored = InMemoryMetas::create(0, 0);
+        restored.init_db("default");
+
+        let res = restored.restore(snap, |info| Err(ErrorCode::UnknownTable(info.name.clone())));
+        assert!(res.is_err());
+
+        let got = restored.snapshot();
+        assert_eq!(0, got.next_table_id);
+        assert_eq!(vec!["default"], got.db_tables.keys().collect::<Vec<_>>());
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_in_memory_metas_stats() -> Result<()> {
+    let conf = ConfigBuilder::create().build();
+
+    let mut sys_db_meta = InMemoryMetas::create(SYS_DB_ID_BEGIN, SYS_TBL_ID_BEGIN);
+    sys_db_meta.init_db("system");
+    let _ = SystemDatabase::create(&mut sys_db_meta, &conf);
+
+    assert_eq!(MetaCacheStats::default(), sys_db_meta.stats());
+
+    let one = sys_db_meta.get_by_name("system", "one")?;
+    assert!(sys_db_meta.get_by_name("system", "no_such_table").is_err());
+    assert!(sys_db_meta.get_by_name("no_such_db", "one").is_err());
+    assert!(sys_db_meta.get_by_id(&one.get_id()).is_some());
+    assert!(sys_db_meta.get_by_id(&u64::MAX).is_none());
+
+    assert_eq!(
+        MetaCacheStats {
+            lookups: 5,
+            hits: 2,
+            misses: 3,
+            replacements: 0,
+        },
+        sys_db_meta.stats()
+    );
+
+    // Inserting a table with the name of an existing one is a replacement.
+    sys_db_meta.insert("system", one);
+    assert_eq!(1, sys_db_meta.stats().replacements);
+
+    Ok(())
+}
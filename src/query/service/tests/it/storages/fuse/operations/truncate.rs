@@ -71,8 +71,14 @@ async fn test_fuse_table_truncate() -> databend_common_exception::Result<()> {
 
     // get the latest tbl
     let prev_version = table.get_table_info().ident.seq;
+    let prev_table_id = table.get_id();
+    let prev_schema = table.schema();
     let table = fixture.latest_default_table().await?;
     assert_ne!(prev_version, table.get_table_info().ident.seq);
+
+    // the table itself is kept: same id, same schema
+    assert_eq!(prev_table_id, table.get_id());
+    assert_eq!(prev_schema, table.schema());
     let (stats, parts) = table
         .read_partitions(ctx.clone(), source_plan.push_downs.clone(), true)
         .await?;
@@ -14,3 +14,4 @@
 
 mod ai_to_sql;
 mod numbers_table;
+mod table_function_factory;
@@ -15,9 +15,11 @@
 use databend_common_base::base::tokio;
 use databend_common_catalog::plan::PushDownInfo;
 use databend_common_catalog::table_args::TableArgs;
+use databend_common_exception::ErrorCode;
 use databend_common_exception::Result;
 use databend_common_expression::Scalar;
 use databend_common_sql::executor::table_read_plan::ToReadDataSourcePlan;
+use databend_common_sql::Planner;
 use databend_query::sessions::TableContext;
 use databend_query::stream::ReadDataBlockStream;
 use databend_query::table_functions::generate_numbers_parts;
@@ -69,6 +71,25 @@ async fn test_number_table() -> Result<()> {
     Ok(())
 }
 
+#[test]
+fn test_number_table_args() -> Result<()> {
+    let tbl_args = TableArgs::new_positioned(vec![Scalar::from(8u64)]);
+    let table = NumbersTable::create("system", "numbers", 1, tbl_args.clone())?;
+    table.validate_args(&tbl_args)?;
+
+    // Negative count
+    let tbl_args = TableArgs::new_positioned(vec![Scalar::from(-1i64)]);
+    let res = NumbersTable::create("system", "numbers", 1, tbl_args.clone());
+    assert!(res.is_err());
+    assert!(table.validate_args(&tbl_args).is_err());
+
+    // Wrong arity
+    let tbl_args = TableArgs::new_positioned(vec![Scalar::from(1u64), Scalar::from(2u64)]);
+    assert!(table.validate_args(&tbl_args).is_err());
+
+    Ok(())
+}
+
 #[test]
 fn test_util_generate_parts() -> Result<()> {
     {
@@ -116,3 +137,23 @@ fn test_util_generate_parts() -> Result<()> {
 
     Ok(())
 }
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_numbers_table_bind_columns() -> Result<()> {
+    let fixture = TestFixture::setup().await?;
+    let ctx = fixture.new_query_ctx().await?;
+    let mut planner = Planner::new(ctx.clone());
+
+    // The column is resolved from the output schema of `numbers`.
+    planner.plan_sql("SELECT number FROM numbers(10)").await?;
+
+    // An unknown column is rejected when the query is planned.
+    let res = planner.plan_sql("SELECT col FROM numbers(10)").await;
+    assert_eq!(ErrorCode::SEMANTIC_ERROR, res.unwrap_err().code());
+
+    // So are invalid args.
+    let res = planner.plan_sql("SELECT number FROM numbers(-1)").await;
+    assert_eq!(ErrorCode::BAD_ARGUMENTS, res.unwrap_err().code());
+
+    Ok(())
+}
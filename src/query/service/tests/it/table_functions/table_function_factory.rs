@@ -0,0 +1,138 @@
+//  Copyright 2021 Datafuse Labs.
+//
+//  Licensed under the Apache License, Version 2.0 (the "License");
+//  you may not use this file except in compliance with the License.
+//  You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+//  Unless required by applicable law or agreed to in writing, software
+//  distributed under the License is distributed on an "AS IS" BASIS,
+//  WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//  See the License for the specific language governing permissions and
+//  limitations under the License.
+
+use databend_common_base::base::tokio;
+use databend_common_catalog::table_args::TableArgs;
+use databend_common_exception::Result;
+use databend_common_expression::types::NumberDataType;
+use databend_common_expression::types::NumberScalar;
+use databend_common_expression::Scalar;
+use databend_common_expression::TableDataType;
+use databend_common_expression::TableField;
+use databend_common_expression::TableSchema;
+use databend_query::catalogs::Catalog;
+use databend_query::table_functions::TableFunctionFactory;
+
+use crate::tests::create_catalog;
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_list_table_functions() -> Result<()> {
+    let factory = TableFunctionFactory::create();
+    let funcs = factory.list();
+    let names = funcs.iter().map(|f| f.name.as_str()).collect::<Vec<_>>();
+
+    // Sorted by name, without duplicates.
+    assert!(
+        names.windows(2).all(|w| w[0] < w[1]),
+        "not sorted: {:?}",
+        names
+    );
+
+    for name in ["numbers", "fuse_snapshot", "infer_schema", "list_stage"] {
+        assert!(names.contains(&name), "{} not found", name);
+    }
+    for f in funcs.iter() {
+        assert!(
+            factory.exists(&f.name),
+            "{} is listed but not registered",
+            f.name
+        );
+        assert!(
+            f.signature.starts_with(&format!("{}(", f.name)),
+            "{}: bad signature {}",
+            f.name,
+            f.signature
+        );
+    }
+
+    let signature = |name: &str| {
+        funcs
+            .iter()
+            .find(|f| f.name == name)
+            .map(|f| f.signature.as_str())
+    };
+    assert_eq!(Some("numbers(n)"), signature("numbers"));
+    assert_eq!(
+        Some("fuse_segment(database, table [, snapshot_id])"),
+        signature("fuse_segment")
+    );
+    assert_eq!(Some("license_info()"), signature("license_info"));
+
+    // The default catalog lists the same functions.
+    let catalog = create_catalog().await?;
+    assert_eq!(funcs, catalog.list_table_functions());
+
+    Ok(())
+}
+
+#[test]
+fn test_table_function_output_schema() -> Result<()> {
+    let factory = TableFunctionFactory::create();
+
+    // numbers
+    {
+        let args = TableArgs::new_positioned(vec![Scalar::from(10u64)]);
+        let func = factory.get("numbers", args.clone())?;
+
+        let schema = func.output_schema(&args)?;
+        assert_eq!(
+            TableSchema::new(vec![TableField::new(
+                "number",
+                TableDataType::Number(NumberDataType::UInt64)
+            )]),
+            *schema
+        );
+        assert_eq!(func.as_table().schema(), schema);
+
+        let bad_args = TableArgs::new_positioned(vec![Scalar::from(-1i64)]);
+        let func = factory.get("numbers", args)?;
+        assert!(func.output_schema(&bad_args).is_err());
+    }
+
+    // The column of generate_series depends on the type of the args.
+    {
+        let args = TableArgs::new_positioned(vec![
+            Scalar::Number(NumberScalar::Int64(1)),
+            Scalar::Number(NumberScalar::Int64(5)),
+        ]);
+        let func = factory.get("generate_series", args.clone())?;
+
+        let schema = func.output_schema(&args)?;
+        assert_eq!(
+            TableSchema::new(vec![TableField::new(
+                "generate_series",
+                TableDataType::Number(NumberDataType::Int64)
+            )]),
+            *schema
+        );
+
+        let date_args = TableArgs::new_positioned(vec![Scalar::Date(0), Scalar::Date(10)]);
+        let schema = func.output_schema(&date_args)?;
+        assert_eq!(
+            TableSchema::new(vec![TableField::new(
+                "generate_series",
+                TableDataType::Date
+            )]),
+            *schema
+        );
+
+        let mismatched = TableArgs::new_positioned(vec![
+            Scalar::Date(0),
+            Scalar::Number(NumberScalar::Int64(5)),
+        ]);
+        assert!(func.output_schema(&mismatched).is_err());
+    }
+
+    Ok(())
+}
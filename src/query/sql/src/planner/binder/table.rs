@@ -668,17 +668,23 @@ impl Binder {
             let table_meta: Arc<dyn TableFunction> = self
                 .catalogs
                 .get_default_catalog()?
-                .get_table_function(&func_name.name, table_args)?;
+                .get_table_function(&func_name.name, table_args.clone())?;
+            // The columns referred to by the query are resolved from the output schema,
+            // which validates the args and is derived from them.
+            let output_schema = table_meta
+                .output_schema(&table_args)
+                .map_err(|e| e.set_span(*span))?;
             let table = table_meta.as_table();
             let table_alias_name = if let Some(table_alias) = alias {
                 Some(normalize_identifier(&table_alias.name, &self.name_resolution_ctx).name)
             } else {
                 None
             };
-            let table_index = self.metadata.write().add_table(
+            let table_index = self.metadata.write().add_table_with_schema(
                 CATALOG_DEFAULT.to_string(),
                 "system".to_string(),
                 table.clone(),
+                output_schema,
                 table_alias_name,
                 false,
                 false,
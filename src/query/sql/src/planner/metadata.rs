@@ -28,6 +28,7 @@ use databend_common_expression::ComputedExpr;
 use databend_common_expression::Scalar;
 use databend_common_expression::TableDataType;
 use databend_common_expression::TableField;
+use databend_common_expression::TableSchemaRef;
 use parking_lot::RwLock;
 
 use crate::optimizer::SExpr;
@@ -284,6 +285,35 @@ impl Metadata {
         source_of_view: bool,
         source_of_index: bool,
         source_of_stage: bool,
+    ) -> IndexType {
+        let table_schema = table_meta.schema_with_stream();
+        self.add_table_with_schema(
+            catalog,
+            database,
+            table_meta,
+            table_schema,
+            table_alias_name,
+            source_of_view,
+            source_of_index,
+            source_of_stage,
+        )
+    }
+
+    /// Add a table like [`Metadata::add_table`], whose columns are built from `table_schema`
+    /// instead of the schema of `table_meta`.
+    ///
+    /// A table function uses the output schema derived from its args.
+    #[allow(clippy::too_many_arguments)]
+    pub fn add_table_with_schema(
+        &mut self,
+        catalog: String,
+        database: String,
+        table_meta: Arc<dyn Table>,
+        table_schema: TableSchemaRef,
+        table_alias_name: Option<String>,
+        source_of_view: bool,
+        source_of_index: bool,
+        source_of_stage: bool,
     ) -> IndexType {
         let table_name = table_meta.name().to_string();
 
@@ -301,7 +331,6 @@ impl Metadata {
             source_of_stage,
         };
         self.tables.push(table_entry);
-        let table_schema = table_meta.schema_with_stream();
         let mut index = 0;
         let mut fields = VecDeque::with_capacity(table_schema.fields().len());
         for field in table_schema.fields().iter() {
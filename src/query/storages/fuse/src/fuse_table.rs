@@ -756,6 +756,27 @@ impl Table for FuseTable {
         Ok(Some(stats))
     }
 
+    // The statistics are taken from the summary of the current snapshot,
+    // which is merged from the summaries of its segments.
+    #[async_backtrace::framed]
+    async fn recompute_statistics(
+        &self,
+    ) -> Result<Option<databend_common_meta_app::schema::TableStatistics>> {
+        let Some(snapshot) = self.read_table_snapshot().await? else {
+            return Ok(Some(Default::default()));
+        };
+
+        let summary = &snapshot.summary;
+        Ok(Some(databend_common_meta_app::schema::TableStatistics {
+            number_of_rows: summary.row_count,
+            data_bytes: summary.uncompressed_byte_size,
+            compressed_data_bytes: summary.compressed_byte_size,
+            index_data_bytes: summary.index_size,
+            number_of_segments: Some(snapshot.segments.len() as u64),
+            number_of_blocks: Some(summary.block_count),
+        }))
+    }
+
     #[async_backtrace::framed]
     async fn column_statistics_provider(
         &self,
@@ -643,11 +643,6 @@ impl Catalog for HiveCatalog {
         unimplemented!()
     }
 
-    // List all table functions' names.
-    fn list_table_functions(&self) -> Vec<String> {
-        vec![]
-    }
-
     // Get table engines
     fn get_table_engines(&self) -> Vec<StorageDescription> {
         unimplemented!()
@@ -489,11 +489,6 @@ impl Catalog for IcebergCatalog {
         unimplemented!()
     }
 
-    // List all table functions' names.
-    fn list_table_functions(&self) -> Vec<String> {
-        vec![]
-    }
-
     fn as_any(&self) -> &dyn Any {
         self
     }
@@ -38,6 +38,7 @@ use databend_common_expression::BlockEntry;
 use databend_common_expression::DataBlock;
 use databend_common_expression::Value;
 use databend_common_meta_app::schema::TableInfo;
+use databend_common_meta_app::schema::TableStatistics;
 use databend_common_meta_app::schema::UpdateStreamMetaReq;
 use databend_common_meta_app::schema::UpsertTableCopiedFileReq;
 use databend_common_pipeline_core::processors::InputPort;
@@ -265,6 +266,24 @@ impl Table for MemoryTable {
         })
     }
 
+    #[async_backtrace::framed]
+    async fn recompute_statistics(&self) -> Result<Option<TableStatistics>> {
+        let blocks = self.blocks.read();
+
+        let rows = blocks.iter().map(|block| block.num_rows() as u64).sum();
+        let bytes = blocks.iter().map(|block| block.memory_size() as u64).sum();
+
+        // Data in memory is neither compressed nor indexed.
+        Ok(Some(TableStatistics {
+            number_of_rows: rows,
+            data_bytes: bytes,
+            compressed_data_bytes: bytes,
+            index_data_bytes: 0,
+            number_of_segments: None,
+            number_of_blocks: Some(blocks.len() as u64),
+        }))
+    }
+
     #[async_backtrace::framed]
     async fn truncate(&self, _ctx: Arc<dyn TableContext>) -> Result<()> {
         let mut blocks = self.blocks.write();
@@ -51,6 +51,7 @@ impl ResultCacheMetaManager {
                 seq,
                 value: Operation::Update(value),
                 value_meta: Some(MetaSpec::new_expire(expire_at)),
+                idempotency_token: None,
             })
             .await?;
         Ok(())
@@ -44,8 +44,8 @@ impl SyncSystemTable for TableFunctionsTable {
     }
 
     fn get_full_data(&self, ctx: Arc<dyn TableContext>) -> Result<DataBlock> {
-        let func_names = ctx.get_default_catalog()?.list_table_functions();
-        let mut names = func_names.iter().map(|s| s.as_str()).collect::<Vec<_>>();
+        let funcs = ctx.get_default_catalog()?.list_table_functions();
+        let mut names = funcs.iter().map(|f| f.name.as_str()).collect::<Vec<_>>();
         // srf functions can also used as table functions
         let mut srf_func_names = BUILTIN_FUNCTIONS
             .properties